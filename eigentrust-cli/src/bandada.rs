@@ -3,9 +3,105 @@
 //! Bandada API handling module.
 
 use dotenv::{dotenv, var};
-use eigentrust::error::EigenError;
+use eigentrust::{error::EigenError, storage::ScoreRecord};
+use ethers::types::U256;
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use reqwest::{Client, Response};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Merkle tree depth of the created groups.
+const GROUP_TREE_DEPTH: u8 = 16;
+/// Validity of the created groups' Merkle roots, in seconds.
+const GROUP_FINGERPRINT_DURATION: u64 = 3600;
+
+/// Identity commitment of a participant.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CommitmentRecord {
+	/// Participant address.
+	pub address: String,
+	/// Semaphore identity commitment.
+	pub identity_commitment: String,
+}
+
+/// Bandada group, as returned by the API.
+#[derive(Clone, Debug, Deserialize)]
+struct Group {
+	/// Group members' identity commitments.
+	members: Vec<String>,
+}
+
+/// Bandada group creation request.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NewGroup {
+	/// Group name.
+	name: String,
+	/// Group description.
+	description: String,
+	/// Merkle tree depth.
+	tree_depth: u8,
+	/// Validity of the Merkle roots, in seconds.
+	fingerprint_duration: u64,
+}
+
+/// Bandada group created by the API.
+#[derive(Clone, Debug, Deserialize)]
+struct CreatedGroup {
+	/// Group id.
+	id: String,
+}
+
+/// Changes bringing a group in line with the scores.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MembershipChanges {
+	/// Identity commitments to add.
+	pub add: Vec<String>,
+	/// Identity commitments to remove.
+	pub remove: Vec<String>,
+}
+
+impl MembershipChanges {
+	/// Computes the changes from the scores, the participants' identity commitments and the
+	/// current group members. Participants whose score is at least the threshold must be
+	/// members. Members without a known participant are left untouched.
+	pub fn new(
+		scores: &[ScoreRecord], commitments: &[CommitmentRecord], threshold: u64,
+		members: &[String],
+	) -> Result<Self, EigenError> {
+		let scores: HashMap<String, &ScoreRecord> =
+			scores.iter().map(|record| (record.peer_address().to_lowercase(), record)).collect();
+		let members: BTreeSet<&String> = members.iter().collect();
+
+		let mut changes = Self::default();
+		for commitment in commitments {
+			let eligible = match scores.get(&commitment.address.to_lowercase()) {
+				Some(record) => Self::meets_threshold(record, threshold)?,
+				None => false,
+			};
+			let is_member = members.contains(&commitment.identity_commitment);
+
+			if eligible && !is_member {
+				changes.add.push(commitment.identity_commitment.clone());
+			} else if !eligible && is_member {
+				changes.remove.push(commitment.identity_commitment.clone());
+			}
+		}
+
+		Ok(changes)
+	}
+
+	/// Returns whether the rational score of the record is at least the threshold.
+	fn meets_threshold(record: &ScoreRecord, threshold: u64) -> Result<bool, EigenError> {
+		let parse = |value: &String| {
+			U256::from_dec_str(value)
+				.map_err(|_| EigenError::ParsingError("Failed to parse score.".to_string()))
+		};
+		let (numerator, denominator) = (parse(record.numerator())?, parse(record.denominator())?);
+
+		Ok(numerator >= denominator.saturating_mul(U256::from(threshold)))
+	}
+}
 
 /// Bandada API client.
 pub struct BandadaApi {
@@ -24,6 +120,67 @@ impl BandadaApi {
 		Ok(Self { base_url: base_url.to_string(), client: Client::new(), key })
 	}
 
+	/// Returns the identity commitments of the group members.
+	pub async fn get_members(&self, group_id: &str) -> Result<Vec<String>, EigenError> {
+		let response = self
+			.client
+			.get(&format!("{}/groups/{}", self.base_url, group_id))
+			.send()
+			.await
+			.map_err(|e| EigenError::RequestError(e.to_string()))?;
+		if !response.status().is_success() {
+			return Err(EigenError::RequestError(format!(
+				"Failed to fetch the group: {}",
+				response.status()
+			)));
+		}
+
+		let body = response.text().await.map_err(|e| EigenError::RequestError(e.to_string()))?;
+		let group: Group = serde_json::from_str(&body)?;
+
+		Ok(group.members)
+	}
+
+	/// Creates a group with the given name, returning its id.
+	pub async fn create_group(&self, name: &str, description: &str) -> Result<String, EigenError> {
+		let mut headers = HeaderMap::new();
+		headers.insert(
+			"X-API-KEY",
+			HeaderValue::from_str(&self.key)
+				.map_err(|e| EigenError::ConfigurationError(e.to_string()))?,
+		);
+		headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+		// Groups are created in batches
+		let body = serde_json::to_string(&[NewGroup {
+			name: name.to_string(),
+			description: description.to_string(),
+			tree_depth: GROUP_TREE_DEPTH,
+			fingerprint_duration: GROUP_FINGERPRINT_DURATION,
+		}])?;
+		let response = self
+			.client
+			.post(&format!("{}/groups", self.base_url))
+			.headers(headers)
+			.body(body)
+			.send()
+			.await
+			.map_err(|e| EigenError::RequestError(e.to_string()))?;
+		if !response.status().is_success() {
+			return Err(EigenError::RequestError(format!(
+				"Failed to create the group: {}",
+				response.status()
+			)));
+		}
+
+		let body = response.text().await.map_err(|e| EigenError::RequestError(e.to_string()))?;
+		let groups: Vec<CreatedGroup> = serde_json::from_str(&body)?;
+
+		groups.into_iter().next().map(|group| group.id).ok_or_else(|| {
+			EigenError::RequestError("No group returned by the Bandada API".to_string())
+		})
+	}
+
 	/// Adds Member.
 	pub async fn add_member(
 		&self, group_id: &str, identity_commitment: &str,
@@ -61,3 +218,59 @@ impl BandadaApi {
 			.map_err(|e| EigenError::RequestError(e.to_string()))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_membership_changes() {
+		let score = |address: &str, numerator: &str, denominator: &str| {
+			ScoreRecord::new(
+				address.to_string(),
+				"0x00".to_string(),
+				numerator.to_string(),
+				denominator.to_string(),
+				"0".to_string(),
+			)
+		};
+		let commitment = |address: &str, identity_commitment: &str| CommitmentRecord {
+			address: address.to_string(),
+			identity_commitment: identity_commitment.to_string(),
+		};
+
+		let (a, b, c) = (
+			"0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+			"0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc",
+			"0x90f79bf6eb2c4f870365e785982e1f101e93b906",
+		);
+		let scores = vec![score(a, "1001", "2"), score(b, "999", "2")];
+		let commitments = vec![commitment(a, "1"), commitment(b, "2"), commitment(c, "3")];
+
+		// a is above 500 and joins, b and the unscored c leave, unknown members stay
+		let members = vec!["2".to_string(), "3".to_string(), "4".to_string()];
+		let changes = MembershipChanges::new(&scores, &commitments, 500, &members).unwrap();
+		assert_eq!(changes.add, vec!["1".to_string()]);
+		assert_eq!(changes.remove, vec!["2".to_string(), "3".to_string()]);
+	}
+
+	#[test]
+	fn test_new_group_body() {
+		let group = NewGroup {
+			name: "eigentrust".to_string(),
+			description: "Reputation group".to_string(),
+			tree_depth: GROUP_TREE_DEPTH,
+			fingerprint_duration: GROUP_FINGERPRINT_DURATION,
+		};
+
+		let body = serde_json::to_value([group]).unwrap();
+		assert_eq!(body[0]["treeDepth"], 16);
+		assert_eq!(body[0]["fingerprintDuration"], 3600);
+
+		let groups: Vec<CreatedGroup> = serde_json::from_str(
+			r#"[{"id":"10402173435763029700781503965100","name":"eigentrust"}]"#,
+		)
+		.unwrap();
+		assert_eq!(groups[0].id, "10402173435763029700781503965100");
+	}
+}
@@ -3,25 +3,79 @@
 //! This module contains all CLI related data handling and conversions.
 
 use crate::{
-	bandada::BandadaApi,
-	fs::{get_file_path, load_config, load_mnemonic, EigenFile, FileType},
+	bandada::{BandadaApi, CommitmentRecord, MembershipChanges},
+	fs::{
+		active_profile, aggregation_policy, dedup_policy, encryption_key, epoch_scores_path,
+		get_file_path, load_config, load_domain_registry, load_mnemonic, load_profile,
+		override_config, proof_storage, save_domain_registry, save_profile, EigenFile, FileType,
+	},
+	relayer::RelayerApi,
+	schedule::{jitter, parse_duration, unix_now, Schedule},
+	server::{serve, Relayer, DEFAULT_SERVER_ADDRESS},
 };
 use clap::{Args, Parser, Subcommand};
+use dotenv::{dotenv, var};
 use eigentrust::{
-	attestation::{AttestationRaw, SignedAttestationRaw},
-	circuit::{Circuit, ET_PARAMS_K, TH_PARAMS_K},
+	attestation::{AttestationQuery, AttestationRaw, RelayPayload, SignedAttestationRaw},
+	badge::{Badge, BadgeFormat},
+	circuit::{attestations_hash, params_hash, Circuit, ProofArtifact, ET_PARAMS_K, TH_PARAMS_K},
+	domain::DomainParams,
 	error::EigenError,
-	eth::deploy_as,
+	eth::{
+		attester_identities, deploy_as_confirmed, generate_mnemonic, min_verification_gas,
+		AttesterIdentity,
+	},
+	graph::{DisconnectedPolicy, GraphFormat, SybilReport, DEFAULT_SYBIL_SHARE},
+	manifest::{Provenance, ScoreCertificate, ScoringManifest},
+	simulation::{ScoreDistribution, SyntheticNetwork},
+	snapshot::StateSnapshot,
 	storage::{
-		str_to_20_byte_array, str_to_32_byte_array, AttestationRecord, CSVFileStorage,
-		JSONFileStorage, ScoreRecord, Storage,
+		normalize_scores, parse_normalization, str_to_20_byte_array, str_to_32_byte_array,
+		AttestationRecord, BinFileStorage, CSVFileStorage, DedupPolicy, EncryptedJSONFileStorage,
+		JSONFileStorage, RecordIssue, ReplayRecord, ScoreRecord, Storage, SyncState,
 	},
+	transport::ClientTransport,
 	Client,
 };
-use ethers::{abi::Address, providers::Http, types::H160};
-use log::{debug, info};
+use ethers::{
+	abi::Address,
+	providers::{Http, Middleware},
+	types::{H160, U256},
+	utils::{format_ether, format_units, hex},
+};
 use serde::{Deserialize, Serialize};
-use std::str::FromStr;
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	net::{AddrParseError, SocketAddr},
+	path::PathBuf,
+	str::FromStr,
+	time::{Duration, Instant, UNIX_EPOCH},
+};
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+/// Default number of seconds between scores recalculations of the `watch` command.
+const DEFAULT_WATCH_INTERVAL: u64 = 300;
+/// Number of seconds between polls of the `watch` command.
+const WATCH_POLL_INTERVAL: u64 = 12;
+/// Default number of peers of the `simulate` command.
+const DEFAULT_SIMULATION_PEERS: usize = 100;
+/// Default edge density of the `simulate` command.
+const DEFAULT_SIMULATION_DENSITY: f64 = 0.1;
+/// Attestations file of the `import` command.
+const EAS_ATTESTATIONS_FILE: &str = "eas-attestations";
+/// Attestation indexing state file, see `sync_attestations`.
+const SYNC_STATE_FILE: &str = "sync-state";
+/// Signed attestations file of the `attest --sign-only` command.
+const SIGNED_ATTESTATIONS_FILE: &str = "signed-attestations";
+/// Default number of attestations of the `gas-report` batch estimate.
+const DEFAULT_GAS_REPORT_BATCH_SIZE: u64 = 10;
+/// Default number of confirmations awaited by the `deploy` command.
+const DEFAULT_DEPLOY_CONFIRMATIONS: usize = 1;
+
+/// Fields of the CLI configuration, which can be overridden at invocation time.
+pub const CONFIG_FIELDS: [&str; 7] =
+	["as_address", "band_id", "band_th", "band_url", "chain_id", "domain", "node_url"];
 
 /// CLI configuration settings.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -72,6 +126,26 @@ impl CliConfig {
 pub struct Cli {
 	#[command(subcommand)]
 	pub mode: Mode,
+	/// Output format of the command results (text, json). Defaults to text.
+	#[clap(long = "output", global = true)]
+	pub output: Option<String>,
+	/// Overrides a configuration field for this invocation only (field=value).
+	#[clap(long = "set", global = true)]
+	pub overrides: Vec<String>,
+	/// Log filter (error, warn, info, debug, trace, or tracing directives). Defaults to
+	/// the `LOG_LEVEL` environment variable, or info.
+	#[clap(long = "verbosity", global = true)]
+	pub verbosity: Option<String>,
+	/// File the logs are appended to, along with the timings of the command.
+	#[clap(long = "log-file", global = true)]
+	pub log_file: Option<String>,
+}
+
+impl Cli {
+	/// Returns the selected output format.
+	pub fn output_format(&self) -> Result<OutputFormat, EigenError> {
+		self.output.as_deref().map_or(Ok(OutputFormat::default()), str::parse)
+	}
 }
 
 /// CLI commands.
@@ -79,34 +153,69 @@ pub struct Cli {
 pub enum Mode {
 	/// Submits an attestation. Requires 'AttestData'.
 	Attest(AttestData),
-	/// Retrieves and saves all attestations.
-	Attestations,
+	/// Retrieves and saves the attestations. Requires 'AttestationsData'.
+	Attestations(AttestationsData),
+	/// Renders the reputation badge of a participant. Requires 'BadgeData'.
+	Badge(BadgeData),
 	/// Creates Bandada group.
 	Bandada(BandadaData),
-	/// Deploys the contracts.
-	Deploy,
+	/// Deploys the contracts. Requires 'DeployData'.
+	Deploy(DeployData),
+	/// Compares two score snapshots. Requires 'DiffScoresData'.
+	DiffScores(DiffScoresData),
 	/// Generates EigenTrust circuit proof.
+	#[command(visible_alias = "proof")]
 	ETProof,
 	/// Generates EigenTrust circuit proving key
 	ETProvingKey,
 	/// Verifies the stored eigentrust circuit proof.
 	ETVerify,
+	/// Estimates the cost of submitting attestations and verifying proofs. Requires
+	/// 'GasReportData'.
+	GasReport(GasReportData),
+	/// Imports attestations from an external registry. Requires 'ImportData'.
+	Import(ImportData),
+	/// Manages the attester keys. Requires 'KeyData'.
+	Key(KeyData),
 	/// Generates KZG parameters
 	KZGParams(KZGParamsData),
-	/// Calculates the global scores from the saved attestations.
-	LocalScores,
+	/// Calculates the global scores from the saved attestations. Requires 'ScoresData'.
+	LocalScores(ScoresData),
+	/// Replays the scoring run of a manifest over the saved attestations. Requires 'ReplayData'.
+	Replay(ReplayData),
 	/// Retrieves and saves all attestations and calculates the global scores.
-	Scores,
-	/// Generates a Threshold circuit proof for the selected participant.
+	/// Requires 'ScoresData'.
+	Scores(ScoresData),
+	/// Serves the local HTTP API. Requires 'ServeData'.
+	Serve(ServeData),
+	/// Generates a Threshold circuit proof that the selected participant's score is above
+	/// the threshold. Requires 'ThProofData'.
+	#[command(visible_alias = "prove-threshold")]
 	ThProof(ThProofData),
 	/// Generates Threshold circuit proving key
 	ThProvingKey,
 	/// Verifies the stored Threshold circuit proof.
 	ThVerify,
+	/// Starts the interactive shell.
+	Shell,
 	/// Displays the current configuration.
 	Show,
+	/// Displays the score of a participant. Requires 'ShowScoreData'.
+	ShowScore(ShowScoreData),
+	/// Runs the scoring over a synthetic trust network. Requires 'SimulateData'.
+	Simulate(SimulateData),
+	/// Displays the configuration, the local data and the node status.
+	Status,
+	/// Exports or imports the client state snapshot. Requires 'StateData'.
+	State(StateData),
 	/// Updates the configuration. Requires 'UpdateData'.
 	Update(UpdateData),
+	/// Checks the saved attestations before computing scores. Requires 'ValidateData'.
+	Validate(ValidateData),
+	/// Checks the signed certificates of the saved scores. Requires 'VerifyCertificatesData'.
+	VerifyCertificates(VerifyCertificatesData),
+	/// Keeps the attestations and scores up to date with the contract. Requires 'WatchData'.
+	Watch(WatchData),
 }
 
 /// Attestation subcommand input.
@@ -121,12 +230,73 @@ pub struct AttestData {
 	/// Attestation message (32-byte hex string).
 	#[clap(long = "message")]
 	message: Option<String>,
+	/// Signs the attestation and submits it through a relayer.
+	#[clap(long = "relay")]
+	relay: bool,
+	/// Signs and checks the attestations, printing their payloads and the estimated gas,
+	/// without submitting them.
+	#[clap(long = "dry-run")]
+	dry_run: bool,
+	/// Signs the attestations without node connection, saving their payload to the
+	/// `signed-attestations.json` file to be broadcast from another machine.
+	#[clap(long = "sign-only", conflicts_with_all = ["relay", "dry_run"])]
+	sign_only: bool,
+	/// Signed attestations file of `attest --sign-only`, verified and submitted from the
+	/// configured account.
+	#[clap(
+		long = "from-payload",
+		conflicts_with_all = ["address", "score", "message", "file", "sign_only", "relay", "dry_run"]
+	)]
+	from_payload: Option<String>,
+	/// CSV file of (address, score, message) rows, submitted in a single transaction.
+	#[clap(long = "file", conflicts_with_all = ["address", "score", "message"])]
+	file: Option<String>,
 }
 
-/// Attestation subcommand input.
+/// Attestation row of a batch file.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AttestationRow {
+	/// Attested address (20-byte ethereum address).
+	address: String,
+	/// Given score (0-255).
+	score: String,
+	/// Optional attestation message (32-byte hex string).
+	#[serde(default)]
+	message: Option<String>,
+}
+
+/// Attestations subcommand input.
+#[derive(Args, Debug, Default)]
+pub struct AttestationsData {
+	/// Only fetches the attestations submitted by this address.
+	#[clap(long = "from")]
+	from: Option<String>,
+	/// Only fetches the attestations about this address.
+	#[clap(long = "about")]
+	about: Option<String>,
+	/// First block to fetch, inclusive.
+	#[clap(long = "from-block")]
+	from_block: Option<String>,
+	/// Last block to fetch, inclusive. Defaults to the latest block.
+	#[clap(long = "to-block")]
+	to_block: Option<String>,
+}
+
+/// Badge subcommand input.
+#[derive(Args, Debug)]
+pub struct BadgeData {
+	/// Participant address.
+	#[clap(long = "addr")]
+	address: Option<String>,
+	/// Badge format (json, svg). Defaults to the shields.io endpoint JSON.
+	#[clap(long = "format")]
+	format: Option<String>,
+}
+
+/// Bandada subcommand input.
 #[derive(Args, Debug)]
 pub struct BandadaData {
-	/// Desired action (add, remove).
+	/// Desired action (add, remove, sync, create).
 	#[clap(long = "action")]
 	action: Option<String>,
 	/// Identity commitment.
@@ -135,6 +305,62 @@ pub struct BandadaData {
 	/// Participant address.
 	#[clap(long = "addr")]
 	address: Option<String>,
+	/// Identity commitments file path, used by the sync action. Defaults to the
+	/// `commitments.csv` file of the assets folder.
+	#[clap(long = "file")]
+	file: Option<String>,
+	/// Name of the created group.
+	#[clap(long = "name")]
+	name: Option<String>,
+	/// Score threshold of the created group. Defaults to the configured one.
+	#[clap(long = "threshold")]
+	threshold: Option<String>,
+	/// Adds the participants reaching the threshold to the created group.
+	#[clap(long = "seed")]
+	seed: bool,
+}
+
+/// Deploy subcommand input.
+#[derive(Args, Debug)]
+pub struct DeployData {
+	/// Configuration profile to deploy with and update. Defaults to the `PROFILE`
+	/// environment variable, or the default configuration.
+	#[clap(long = "profile")]
+	profile: Option<String>,
+	/// Number of confirmations to wait for. Defaults to 1.
+	#[clap(long = "confirmations")]
+	confirmations: Option<String>,
+}
+
+/// GasReport subcommand input.
+#[derive(Args, Debug)]
+pub struct GasReportData {
+	/// Number of attestations of the batch estimate. Defaults to 10.
+	#[clap(long = "batch-size")]
+	batch_size: Option<String>,
+}
+
+/// DiffScores subcommand input.
+#[derive(Args, Debug)]
+pub struct DiffScoresData {
+	/// Scores CSV file to compare from.
+	#[clap(long = "old", conflicts_with = "from_block")]
+	old: Option<String>,
+	/// Scores CSV file to compare to. Defaults to the `scores.csv` file of the assets folder.
+	#[clap(long = "new", conflicts_with = "to_block")]
+	new: Option<String>,
+	/// Block to compare from, scoring the saved attestations indexed up to it.
+	#[clap(long = "from-block")]
+	from_block: Option<String>,
+	/// Block to compare to, scoring the saved attestations indexed up to it.
+	#[clap(long = "to-block")]
+	to_block: Option<String>,
+	/// Score change beyond which participants are reported. Defaults to 0.
+	#[clap(long = "threshold")]
+	threshold: Option<String>,
+	/// Rank change beyond which participants are reported. Defaults to 0.
+	#[clap(long = "rank-threshold")]
+	rank_threshold: Option<String>,
 }
 
 /// Configuration update subcommand input.
@@ -161,6 +387,52 @@ pub struct UpdateData {
 	/// Ethereum node URL.
 	#[clap(long = "node")]
 	node_url: Option<String>,
+	/// Number of convergence iterations of the configured domain.
+	#[clap(long = "iterations")]
+	num_iterations: Option<String>,
+	/// Initial participant score of the configured domain.
+	#[clap(long = "initial-score")]
+	initial_score: Option<String>,
+}
+
+/// Validate subcommand input.
+#[derive(Args, Debug)]
+pub struct ValidateData {
+	/// Attestations CSV file path. Defaults to the `attestations.csv` file of the assets
+	/// folder.
+	#[clap(long = "file")]
+	file: Option<String>,
+}
+
+/// Import subcommand input.
+#[derive(Args, Debug)]
+pub struct ImportData {
+	/// Attestations registry (eas).
+	#[clap(long = "source")]
+	source: Option<String>,
+	/// Address of the EAS contract.
+	#[clap(long = "eas-address")]
+	eas_address: Option<String>,
+	/// Unique identifier of the EAS schema (32-byte hex string).
+	#[clap(long = "schema")]
+	schema: Option<String>,
+	/// First block to import from. Defaults to 0.
+	#[clap(long = "from-block")]
+	from_block: Option<String>,
+}
+
+/// Key subcommand input.
+#[derive(Args, Debug)]
+pub struct KeyData {
+	/// Desired action (generate, addresses, export).
+	#[clap(long = "action")]
+	action: Option<String>,
+	/// Comma-separated derivation indices of the mnemonic keys. Defaults to 0.
+	#[clap(long = "indices")]
+	indices: Option<String>,
+	/// Identity file path. Defaults to the `identity.json` file of the assets folder.
+	#[clap(long = "file")]
+	file: Option<String>,
 }
 
 /// KZGParams subcommand input.
@@ -171,12 +443,157 @@ pub struct KZGParamsData {
 	k: Option<String>,
 }
 
+/// Replay subcommand input.
+#[derive(Args, Debug)]
+pub struct ReplayData {
+	/// Scoring manifest file path. Defaults to the manifest of the last scores calculation.
+	#[clap(long = "manifest")]
+	manifest: Option<String>,
+}
+
+/// Scores subcommand input.
+#[derive(Args, Debug, Default)]
+pub struct ScoresData {
+	/// Exports the attestation graph (dot, graphml, json).
+	#[clap(long = "export-graph")]
+	export_graph: Option<String>,
+	/// Displays the leaderboard of the N highest scores.
+	#[clap(long = "top")]
+	top: Option<String>,
+	/// Saves the leaderboard to the `ranking.csv` file.
+	#[clap(long = "save-ranking")]
+	save_ranking: bool,
+	/// Saves the potential sybil rings report to the `sybil-report.json` file.
+	#[clap(long = "sybil-report")]
+	sybil_report: bool,
+	/// Scores the attestations up to the given block, saving them to the
+	/// `scores-<block>.csv` file.
+	#[clap(long = "epoch")]
+	epoch: Option<String>,
+	/// Signs a certificate of every score, saving them to the `score-certificates.json`
+	/// file.
+	#[clap(long = "certify")]
+	certify: bool,
+	/// Shards the participants into several sets when they exceed the set capacity. The
+	/// sharded scores approximate the ones of a single set and can't be proven, certified
+	/// or replayed, so no manifest is saved for them.
+	#[clap(long = "sharded")]
+	sharded: bool,
+	/// Displays the scores normalized as shares of the total (share), percentiles (percentile)
+	/// or logarithms (log).
+	#[clap(long = "normalize")]
+	normalize: Option<String>,
+	/// Comma-separated seed addresses. Only the participants reachable from the seeds
+	/// through positive attestations are scored together.
+	#[clap(long = "seeds")]
+	seeds: Option<String>,
+	/// Handling of the participants disconnected from the seeds (exclude, score-zero or
+	/// separate-run). Defaults to exclude.
+	#[clap(long = "disconnected")]
+	disconnected: Option<String>,
+}
+
+/// Serve subcommand input.
+#[derive(Args, Debug)]
+pub struct ServeData {
+	/// Server address. Defaults to 127.0.0.1:3000.
+	#[clap(long = "addr")]
+	address: Option<String>,
+	/// Serves the `POST /relay` route, relaying the attestations of the callers holding
+	/// the `RELAYER_TOKEN` bearer token from the `RELAYER_MNEMONIC` account.
+	#[clap(long = "relay")]
+	relay: bool,
+}
+
+/// ShowScore subcommand input.
+#[derive(Args, Debug)]
+pub struct ShowScoreData {
+	/// Participant address.
+	#[clap(long = "address")]
+	address: Option<String>,
+}
+
+/// Simulate subcommand input.
+#[derive(Args, Debug)]
+pub struct SimulateData {
+	/// Number of peers. Defaults to 100.
+	#[clap(long = "peers")]
+	peers: Option<String>,
+	/// Probability of every peer attesting to every other one (0-1]. Defaults to 0.1.
+	#[clap(long = "density")]
+	density: Option<String>,
+	/// Distribution of the attestation scores (uniform, constant, skewed).
+	/// Defaults to uniform.
+	#[clap(long = "distribution")]
+	distribution: Option<String>,
+	/// Number of convergence iterations. Defaults to the configured domain ones.
+	#[clap(long = "iterations")]
+	num_iterations: Option<String>,
+	/// Initial participant score. Defaults to the configured domain one.
+	#[clap(long = "initial-score")]
+	initial_score: Option<String>,
+	/// Seed of the network generation, for reproducible runs.
+	#[clap(long = "seed")]
+	seed: Option<String>,
+}
+
+/// State subcommand input.
+#[derive(Args, Debug)]
+pub struct StateData {
+	/// Desired action (export, import).
+	#[clap(long = "action")]
+	action: Option<String>,
+	/// Snapshot file path. Defaults to the `state.json` file of the assets folder.
+	#[clap(long = "file")]
+	file: Option<String>,
+}
+
+/// VerifyCertificates subcommand input.
+#[derive(Args, Debug)]
+pub struct VerifyCertificatesData {
+	/// Score certificates JSON file path. Defaults to the `score-certificates.json` file of
+	/// the assets folder.
+	#[clap(long = "file")]
+	file: Option<String>,
+	/// Scores CSV file path. Defaults to the `scores.csv` file of the assets folder.
+	#[clap(long = "scores")]
+	scores: Option<String>,
+	/// Address of the scorer expected to sign the certificates. Defaults to the scorer of
+	/// the scores provenance.
+	#[clap(long = "scorer")]
+	scorer: Option<String>,
+}
+
 /// ThresholdProof subcommand input.
 #[derive(Args, Debug)]
 pub struct ThProofData {
 	/// Peer.
-	#[clap(long = "peer")]
+	#[clap(long = "peer", visible_alias = "address")]
 	peer: Option<String>,
+	/// Score threshold. Defaults to the Bandada group threshold.
+	#[clap(long = "threshold")]
+	threshold: Option<String>,
+}
+
+/// Watch subcommand input.
+#[derive(Args, Debug)]
+pub struct WatchData {
+	/// Minimum number of seconds between scores recalculations. Defaults to 300.
+	#[clap(long = "interval")]
+	interval: Option<String>,
+	/// Recalculates the scores as soon as new attestations are indexed.
+	#[clap(long = "on-attestation")]
+	on_attestation: bool,
+	/// Recalculation schedule, a period (e.g. '10m') or a cron expression in UTC (e.g.
+	/// '*/15 * * * *').
+	#[clap(long = "every", conflicts_with_all = ["interval", "on_attestation"])]
+	every: Option<String>,
+	/// Maximum random delay added to the scheduled recalculations (e.g. '30s').
+	#[clap(long = "jitter", requires = "every")]
+	jitter: Option<String>,
+	/// Syncs the Bandada group after every scores recalculation.
+	#[clap(long = "bandada-sync")]
+	bandada_sync: bool,
 }
 
 /// Bandada API action.
@@ -185,12 +602,253 @@ pub enum Action {
 	Remove,
 }
 
+/// Key management action.
+pub enum KeyAction {
+	Generate,
+	Addresses,
+	Export,
+}
+
+/// State snapshot action.
+pub enum StateAction {
+	Export,
+	Import,
+}
+
+/// External attestations registry.
+pub enum ImportSource {
+	Eas,
+}
+
 /// Attestations Origin.
 pub enum AttestationsOrigin {
 	Local,
 	Fetch,
 }
 
+/// Output format of the command results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+	/// Human-readable logs.
+	#[default]
+	Text,
+	/// JSON document printed to stdout, logs being sent to stderr.
+	Json,
+}
+
+impl OutputFormat {
+	/// Prints the value as JSON to stdout, if the JSON output was selected.
+	pub fn print<T: Serialize>(&self, value: &T) -> Result<(), EigenError> {
+		if let OutputFormat::Json = self {
+			println!("{}", serde_json::to_string_pretty(value)?);
+		}
+
+		Ok(())
+	}
+}
+
+/// Attestations checked by a dry run.
+#[derive(Debug, Serialize)]
+pub struct AttestDryRun {
+	/// Payloads of the signed attestations.
+	pub payloads: Vec<RelayPayload>,
+	/// Estimated gas of submitting the attestations in a single transaction.
+	pub estimated_gas: String,
+}
+
+/// Attestations signed offline, along with the call data of the transaction submitting them.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SignedPayload {
+	/// Chain ID of the network the attestations are meant for.
+	pub chain_id: u32,
+	/// AttestationStation contract address.
+	pub as_address: String,
+	/// Payloads of the signed attestations.
+	pub payloads: Vec<RelayPayload>,
+	/// Call data of the AttestationStation `attest` transaction.
+	pub call_data: String,
+}
+
+impl SignedPayload {
+	/// Returns the signed attestations of the payload, checking that it targets the
+	/// configured network and contract, that every signature recovers to its attester and
+	/// that the call data submits exactly these attestations.
+	pub fn verify(
+		&self, client: &Client, config: &CliConfig,
+	) -> Result<Vec<SignedAttestationRaw>, EigenError> {
+		if self.chain_id != config.chain_id()? {
+			return Err(EigenError::ValidationError(format!(
+				"Payload signed for chain {}, but the configured chain is {}",
+				self.chain_id, config.chain_id
+			)));
+		}
+		let as_address = str_to_20_byte_array(&self.as_address)?;
+		if as_address != config.as_address()? {
+			return Err(EigenError::ValidationError(format!(
+				"Payload signed for contract {}, but the configured contract is {}",
+				self.as_address, config.as_address
+			)));
+		}
+
+		let signed_attestations =
+			self.payloads.iter().map(RelayPayload::to_signed).collect::<Result<Vec<_>, _>>()?;
+		if signed_attestations.is_empty() {
+			return Err(EigenError::ValidationError(
+				"Payload without attestations".to_string(),
+			));
+		}
+
+		let call_data = client.attest_call_data(signed_attestations.clone())?;
+		if !self.call_data.eq_ignore_ascii_case(&format!("0x{}", hex::encode(call_data))) {
+			return Err(EigenError::ValidationError(
+				"Payload call data doesn't match its attestations".to_string(),
+			));
+		}
+
+		Ok(signed_attestations)
+	}
+}
+
+/// Leaderboard row.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RankingRecord {
+	/// Rank, participants with equal scores sharing the same rank.
+	pub rank: usize,
+	/// Participant address.
+	pub peer_address: String,
+	/// Score.
+	pub score: String,
+	/// Share of the total score, as a percentage with two decimals.
+	pub share: String,
+}
+
+/// Score change of a participant between two score snapshots.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ScoreChange {
+	/// Participant address.
+	pub peer_address: String,
+	/// Previous score, if the participant was scored.
+	pub old_score: Option<String>,
+	/// New score, if the participant is still scored.
+	pub new_score: Option<String>,
+	/// Signed score difference, e.g. `+120` or `-35`.
+	pub change: String,
+	/// Previous rank, if the participant was scored.
+	pub old_rank: Option<usize>,
+	/// New rank, if the participant is still scored.
+	pub new_rank: Option<usize>,
+}
+
+/// Attestation record failing validation.
+#[derive(Debug, Serialize)]
+pub struct InvalidRecord {
+	/// Row of the record in the CSV file, starting at 1.
+	pub row: usize,
+	/// Issues found.
+	pub issues: Vec<RecordIssue>,
+}
+
+/// Gas and cost estimate of submitting attestations.
+#[derive(Debug, Serialize)]
+pub struct AttestationGasEstimate {
+	/// Number of attestations submitted in the transaction.
+	pub attestations: u64,
+	/// Estimated gas of the transaction.
+	pub gas: String,
+	/// Estimated gas per attestation.
+	pub gas_per_attestation: String,
+	/// Cost of the transaction at the current gas price, in ETH.
+	pub cost: String,
+}
+
+/// Gas and cost estimate of verifying a stored proof on-chain.
+#[derive(Debug, Serialize)]
+pub struct VerificationGasEstimate {
+	/// Circuit name.
+	pub circuit: &'static str,
+	/// Lower bound of the verification gas.
+	pub min_gas: String,
+	/// Lower bound of the verification cost at the current gas price, in ETH.
+	pub min_cost: String,
+}
+
+/// Outcome of the `gas-report` command.
+#[derive(Debug, Serialize)]
+pub struct GasReport {
+	/// Current gas price, in gwei.
+	pub gas_price: String,
+	/// Estimate of submitting a single attestation.
+	pub single: AttestationGasEstimate,
+	/// Estimate of submitting a batch of attestations in a single transaction.
+	pub batch: AttestationGasEstimate,
+	/// Estimates of verifying the stored proofs.
+	pub verification: Vec<VerificationGasEstimate>,
+}
+
+/// Availability of the proof artifacts of a circuit.
+#[derive(Debug, Serialize)]
+pub struct ProofStatus {
+	/// Circuit name.
+	pub circuit: &'static str,
+	/// Whether a proof and its public inputs were generated.
+	pub proof: bool,
+	/// Whether the KZG parameters and the proving key, holding the verifying key, were
+	/// generated.
+	pub verifier: bool,
+}
+
+/// Outcome of the `status` command.
+#[derive(Debug, Serialize)]
+pub struct Status {
+	/// Active configuration profile, if any.
+	pub profile: Option<String>,
+	/// AttestationStation contract address.
+	pub as_address: String,
+	/// Bandada group id.
+	pub band_id: String,
+	/// Configured chain ID.
+	pub chain_id: String,
+	/// Attestation domain.
+	pub domain: String,
+	/// Ethereum node URL.
+	pub node_url: String,
+	/// Chain ID reported by the node, if reachable.
+	pub node_chain_id: Option<String>,
+	/// Latest block of the node, if reachable.
+	pub latest_block: Option<u64>,
+	/// Number of locally indexed attestations.
+	pub attestations: usize,
+	/// Number of attestations imported from EAS.
+	pub imported_attestations: usize,
+	/// Last block of the indexed attestations, if known.
+	pub last_synced_block: Option<u64>,
+	/// Time of the last scores computation, in seconds since the Unix epoch.
+	pub scores_updated_at: Option<u64>,
+	/// Proof artifacts of every circuit.
+	pub proofs: Vec<ProofStatus>,
+}
+
+/// Outcome of the `validate` command.
+#[derive(Debug, Serialize)]
+pub struct ValidationReport {
+	/// Number of records checked.
+	pub records: usize,
+	/// Records failing validation.
+	pub invalid: Vec<InvalidRecord>,
+}
+
+/// Score of a participant, along with its rank.
+#[derive(Debug, Serialize)]
+pub struct RankedScore {
+	/// Score record.
+	#[serde(flatten)]
+	pub record: ScoreRecord,
+	/// Rank among the participants, starting at 1.
+	pub rank: usize,
+	/// Number of participants.
+	pub participants: usize,
+}
+
 impl AttestData {
 	pub fn to_attestation_raw(&self, config: &CliConfig) -> Result<AttestationRaw, EigenError> {
 		// Parse Address
@@ -220,6 +878,32 @@ impl AttestData {
 	}
 }
 
+impl AttestationsData {
+	/// Builds the attestation query of the filters.
+	pub fn to_query(&self) -> Result<AttestationQuery, EigenError> {
+		let parse_block = |block: &Option<String>| {
+			block
+				.as_ref()
+				.map(|block| {
+					block.parse::<u64>().map_err(|e| {
+						EigenError::ParsingError(format!("Error parsing block: {}", e))
+					})
+				})
+				.transpose()
+		};
+
+		let query = AttestationQuery {
+			creator: self.from.as_deref().map(str_to_20_byte_array).transpose()?,
+			about: self.about.as_deref().map(str_to_20_byte_array).transpose()?,
+			from_block: parse_block(&self.from_block)?.unwrap_or_default(),
+			to_block: parse_block(&self.to_block)?,
+		};
+		query.validate()?;
+
+		Ok(query)
+	}
+}
+
 impl FromStr for Action {
 	type Err = EigenError;
 
@@ -232,78 +916,514 @@ impl FromStr for Action {
 	}
 }
 
-/// Handles submitting an attestation
-pub async fn handle_attest(attest_data: AttestData) -> Result<(), EigenError> {
+impl FromStr for KeyAction {
+	type Err = EigenError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"generate" => Ok(KeyAction::Generate),
+			"addresses" => Ok(KeyAction::Addresses),
+			"export" => Ok(KeyAction::Export),
+			_ => Err(EigenError::ParsingError("Invalid action.".to_string())),
+		}
+	}
+}
+
+impl FromStr for OutputFormat {
+	type Err = EigenError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"text" => Ok(OutputFormat::Text),
+			"json" => Ok(OutputFormat::Json),
+			_ => Err(EigenError::ParsingError(
+				"Invalid output format.".to_string(),
+			)),
+		}
+	}
+}
+
+impl FromStr for StateAction {
+	type Err = EigenError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"export" => Ok(StateAction::Export),
+			"import" => Ok(StateAction::Import),
+			_ => Err(EigenError::ParsingError("Invalid action.".to_string())),
+		}
+	}
+}
+
+impl FromStr for ImportSource {
+	type Err = EigenError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"eas" => Ok(ImportSource::Eas),
+			_ => Err(EigenError::ParsingError(
+				"Invalid import source.".to_string(),
+			)),
+		}
+	}
+}
+
+/// Handles submitting an attestation, or a batch of them.
+pub async fn handle_attest(
+	attest_data: AttestData, output: OutputFormat,
+) -> Result<(), EigenError> {
 	let config = load_config()?;
 	let mnemonic = load_mnemonic();
 
-	// Build raw attestation
-	let attestation = attest_data.to_attestation_raw(&config)?;
-	debug!("Attesting:{:?}", attestation);
+	if let Some(path) = attest_data.from_payload {
+		return submit_signed_payload(PathBuf::from(path), &config, mnemonic).await;
+	}
+
+	// Build raw attestations
+	let attestations = match &attest_data.file {
+		Some(file) => load_attestation_batch(PathBuf::from(file), &config)?,
+		None => vec![attest_data.to_attestation_raw(&config)?],
+	};
+	debug!("Attesting:{:?}", attestations);
+
+	let chain_id = config.chain_id()?;
+	let as_address = config.as_address()?;
+	let domain = config.domain()?;
+
+	if attest_data.sign_only {
+		let client = Client::offline(mnemonic, chain_id, as_address, domain);
+		let signed_payload = sign_offline(&client, &config, attestations)?;
+		output.print(&signed_payload)?;
+
+		let filepath = get_file_path(SIGNED_ATTESTATIONS_FILE, FileType::Json)?;
+		let mut storage =
+			EncryptedJSONFileStorage::<SignedPayload>::new(filepath, encryption_key()?);
+		storage.save(signed_payload)?;
+		info!(
+			"Signed attestations saved at \"{}\". Nothing was submitted.",
+			storage.filepath().display()
+		);
+		return Ok(());
+	}
+
+	let client = Client::connect(mnemonic, chain_id, as_address, domain, &config.node_url).await?;
+
+	let count = attestations.len();
+	if !attest_data.relay && !attest_data.dry_run {
+		// Submit attestations
+		client.attest_batch(attestations).await?;
+		info!("Submitted {} attestations in a single transaction.", count);
+		return Ok(());
+	}
+
+	// Sign off-chain, checking that the signatures are recoverable
+	let signed_attestations = attestations
+		.into_iter()
+		.map(|attestation| client.sign_attestation(attestation))
+		.collect::<Result<Vec<_>, _>>()?;
+
+	if attest_data.dry_run {
+		let payloads = signed_attestations
+			.iter()
+			.map(RelayPayload::from_signed)
+			.collect::<Result<Vec<_>, _>>()?;
+		let estimated_gas = client.estimate_relay_gas(signed_attestations).await?;
+
+		if let OutputFormat::Json = output {
+			let dry_run = AttestDryRun { payloads, estimated_gas: estimated_gas.to_string() };
+			return output.print(&dry_run);
+		}
+
+		for payload in &payloads {
+			info!(
+				"Attestation about {}:\n  attester: {}\n  key: {}\n  payload: {}",
+				payload.about, payload.attester, payload.key, payload.val
+			);
+		}
+		info!(
+			"Estimated gas of {} attestations: {}. Nothing was submitted.",
+			count, estimated_gas
+		);
+		return Ok(());
+	}
+
+	// Hand the attestations over to the relayer
+	dotenv().ok();
+	if let Ok(relayer_url) = var("RELAYER_URL") {
+		let relayer_api = RelayerApi::new(&relayer_url, var("RELAYER_TOKEN").ok());
+		for signed_attestation in &signed_attestations {
+			let payload = RelayPayload::from_signed(signed_attestation)?;
+			let response = relayer_api.submit(&payload).await?;
+			if !response.status().is_success() {
+				return Err(EigenError::RequestError(format!(
+					"Relayer rejected the attestation: {}",
+					response.status()
+				)));
+			}
+		}
+		info!("Submitted {} attestations to the relayer.", count);
+	} else if let Ok(relayer_mnemonic) = var("RELAYER_MNEMONIC") {
+		let relayer = Client::connect(
+			relayer_mnemonic, chain_id, as_address, domain, &config.node_url,
+		)
+		.await?;
+		relayer.relay(signed_attestations).await?;
+	} else {
+		return Err(EigenError::ConfigurationError(
+			"Relaying requires either RELAYER_URL or RELAYER_MNEMONIC to be set.".to_string(),
+		));
+	}
+
+	Ok(())
+}
+
+/// Signs the attestations, returning their payloads along with the call data of the
+/// transaction submitting them. Doesn't require a node connection.
+fn sign_offline(
+	client: &Client, config: &CliConfig, attestations: Vec<AttestationRaw>,
+) -> Result<SignedPayload, EigenError> {
+	let signed_attestations = attestations
+		.into_iter()
+		.map(|attestation| client.sign_attestation(attestation))
+		.collect::<Result<Vec<_>, _>>()?;
+	let payloads =
+		signed_attestations.iter().map(RelayPayload::from_signed).collect::<Result<Vec<_>, _>>()?;
+	let call_data = client.attest_call_data(signed_attestations)?;
+
+	Ok(SignedPayload {
+		chain_id: config.chain_id()?,
+		as_address: format!("{:?}", Address::from(config.as_address()?)),
+		payloads,
+		call_data: format!("0x{}", hex::encode(call_data)),
+	})
+}
 
-	let client = Client::new(
+/// Verifies the signed attestations of the payload file and submits them from the account of
+/// the given mnemonic.
+async fn submit_signed_payload(
+	filepath: PathBuf, config: &CliConfig, mnemonic: String,
+) -> Result<(), EigenError> {
+	let signed_payload =
+		EncryptedJSONFileStorage::<SignedPayload>::new(filepath, encryption_key()?).load()?;
+	let client = Client::connect(
 		mnemonic,
 		config.chain_id()?,
 		config.as_address()?,
 		config.domain()?,
-		config.node_url,
-	);
+		&config.node_url,
+	)
+	.await?;
+
+	let signed_attestations = signed_payload.verify(&client, config)?;
+	for payload in &signed_payload.payloads {
+		info!(
+			"Attestation about {} signed by {}.",
+			payload.about, payload.attester
+		);
+	}
+
+	let count = signed_attestations.len();
+	client.relay(signed_attestations).await?;
+	info!("Submitted {} signed attestations.", count);
 
-	// Submit attestation
-	client.attest(attestation).await?;
 	Ok(())
 }
 
+/// Loads and validates the attestation rows of a batch file, reporting the invalid ones.
+/// Fails if no row is valid.
+fn load_attestation_batch(
+	filepath: PathBuf, config: &CliConfig,
+) -> Result<Vec<AttestationRaw>, EigenError> {
+	let storage = CSVFileStorage::<AttestationRow>::new(filepath);
+	let rows = storage.load()?;
+	let total = rows.len();
+
+	let (attestations, failures) = validate_attestation_rows(rows, config);
+	for (line, e) in &failures {
+		warn!("Skipping line {}: {}", line, e);
+	}
+	info!(
+		"{} of {} attestations are valid, {} failed validation.",
+		attestations.len(),
+		total,
+		failures.len()
+	);
+
+	if attestations.is_empty() {
+		return Err(EigenError::ValidationError(format!(
+			"No valid attestations in \"{}\"",
+			storage.filepath().display()
+		)));
+	}
+
+	Ok(attestations)
+}
+
+/// Validates the attestation rows of a batch file. Returns the valid attestations, and
+/// the file line and error of every invalid row. Only the first attestation about each
+/// address is kept, as later ones would overwrite it on-chain.
+fn validate_attestation_rows(
+	rows: Vec<AttestationRow>, config: &CliConfig,
+) -> (Vec<AttestationRaw>, Vec<(usize, EigenError)>) {
+	let mut attestations: Vec<AttestationRaw> = Vec::new();
+	let mut failures = Vec::new();
+	for (i, row) in rows.into_iter().enumerate() {
+		// Rows start after the CSV header line
+		let line = i + 2;
+		let data = AttestData {
+			address: Some(row.address),
+			score: Some(row.score),
+			message: row.message.filter(|message| !message.is_empty()),
+			relay: false,
+			dry_run: false,
+			sign_only: false,
+			from_payload: None,
+			file: None,
+		};
+
+		match data.to_attestation_raw(config) {
+			Ok(attestation) if attestations.iter().any(|a| a.about() == attestation.about()) => {
+				failures.push((
+					line,
+					EigenError::ValidationError("Duplicate attested address".to_string()),
+				));
+			},
+			Ok(attestation) => attestations.push(attestation),
+			Err(e) => failures.push((line, e)),
+		}
+	}
+
+	(attestations, failures)
+}
+
 /// Handles `attestations` command.
-pub async fn handle_attestations() -> Result<(), EigenError> {
+/// Filtered fetches are saved apart, since they don't cover the whole history the
+/// incremental indexing of `attestations.csv` relies on.
+pub async fn handle_attestations(
+	data: AttestationsData, output: OutputFormat,
+) -> Result<(), EigenError> {
+	let query = data.to_query()?;
 	let config = load_config()?;
 	let mnemonic = load_mnemonic();
-	let client = Client::new(
+	let client = Client::connect(
 		mnemonic,
 		config.chain_id()?,
 		config.as_address()?,
 		config.domain()?,
-		config.node_url,
-	);
+		&config.node_url,
+	)
+	.await?;
+
+	if query.is_partial() {
+		let attestations = client.query_attestations(&query).await?;
+		if attestations.is_empty() {
+			return Err(EigenError::AttestationError(
+				"No attestations match the filters.".to_string(),
+			));
+		}
+
+		let filepath = get_file_path("filtered-attestations", FileType::Csv)?;
+		let mut storage = CSVFileStorage::<AttestationRecord>::new(filepath);
+		let records: Vec<AttestationRecord> =
+			attestations.into_iter().map(AttestationRecord::from).collect();
+		storage.save(records.clone())?;
+		info!(
+			"{} attestations saved at \"{}\".",
+			records.len(),
+			storage.filepath().display()
+		);
 
-	let attestations = client.get_attestations().await?;
+		return output.print(&records);
+	}
 
-	if attestations.is_empty() {
+	let (_, total) = sync_attestations(&client).await?;
+	if total == 0 {
 		return Err(EigenError::AttestationError(
 			"No attestations found.".to_string(),
 		));
 	}
 
-	let attestation_records: Vec<AttestationRecord> =
-		attestations.into_iter().map(|attestation| attestation.into()).collect();
+	if let OutputFormat::Json = output {
+		let filepath = get_file_path("attestations", FileType::Csv)?;
+		output.print(&CSVFileStorage::<AttestationRecord>::new(filepath).load()?)?;
+	}
 
-	let filepath = get_file_path("attestations", FileType::Csv)?;
+	Ok(())
+}
 
-	let mut storage = CSVFileStorage::<AttestationRecord>::new(filepath);
+/// Handles the `import` command.
+pub async fn handle_import(data: ImportData) -> Result<(), EigenError> {
+	let source: ImportSource = data
+		.source
+		.as_deref()
+		.ok_or(EigenError::ValidationError("Missing source.".to_string()))?
+		.parse()?;
+	let from_block = match data.from_block {
+		Some(from_block) => from_block
+			.parse::<u64>()
+			.map_err(|e| EigenError::ParsingError(format!("Error parsing from block: {}", e)))?,
+		None => 0,
+	};
 
-	storage.save(attestation_records)?;
+	let config = load_config()?;
+	let mnemonic = load_mnemonic();
+	let client = Client::connect(
+		mnemonic,
+		config.chain_id()?,
+		config.as_address()?,
+		config.domain()?,
+		&config.node_url,
+	)
+	.await?;
+
+	let attestations = match source {
+		ImportSource::Eas => {
+			let eas_address = data.eas_address.as_deref().ok_or(EigenError::ValidationError(
+				"Missing EAS address.".to_string(),
+			))?;
+			let schema = data
+				.schema
+				.as_deref()
+				.ok_or(EigenError::ValidationError("Missing schema.".to_string()))?;
+
+			client
+				.get_eas_attestations(
+					str_to_20_byte_array(eas_address)?,
+					str_to_32_byte_array(schema)?,
+					from_block,
+				)
+				.await?
+		},
+	};
 
+	// Imported attestations are kept apart from the indexed ones, which track the blocks
+	// of the AttestationStation contract
+	let filepath = get_file_path(EAS_ATTESTATIONS_FILE, FileType::Csv)?;
+	let mut storage = CSVFileStorage::<AttestationRecord>::new(filepath);
+	let records: Vec<AttestationRecord> =
+		attestations.into_iter().map(AttestationRecord::from).collect();
+	storage.save(records.clone())?;
 	info!(
-		"Attestations saved at \"{}\".",
+		"{} attestations imported at \"{}\".",
+		records.len(),
 		storage.filepath().display()
 	);
 
 	Ok(())
 }
 
-/// Handles the bandada subcommand.
-pub async fn handle_bandada(data: BandadaData) -> Result<(), EigenError> {
-	let config = load_config()?;
-	let action: Action = data
-		.action
-		.as_deref()
-		.ok_or(EigenError::ValidationError("Missing action.".to_string()))?
-		.parse()?;
-	let identity_commitment = data.identity_commitment.as_deref().ok_or(
-		EigenError::ValidationError("Missing identity commitment.".to_string()),
-	)?;
-	let address = data
-		.address
+/// Loads the attestations imported from EAS, if any.
+pub fn load_imported_attestations() -> Result<Vec<AttestationRecord>, EigenError> {
+	let filepath = get_file_path(EAS_ATTESTATIONS_FILE, FileType::Csv)?;
+	if !filepath.exists() {
+		return Ok(Vec::new());
+	}
+
+	CSVFileStorage::<AttestationRecord>::new(filepath).load()
+}
+
+/// Brings the locally stored attestations up to date with the contract, resuming from the
+/// last block scanned by the previous run.
+/// Returns the number of newly indexed attestations and the total number of stored ones.
+pub async fn sync_attestations(client: &Client) -> Result<(usize, usize), EigenError> {
+	let filepath = get_file_path("attestations", FileType::Csv)?;
+	let mut storage = CSVFileStorage::<AttestationRecord>::new(filepath);
+
+	// Load the locally indexed attestations, if any
+	let mut records = storage.load().unwrap_or_default();
+	let stored = records.len();
+
+	// Records without block data can't be checked for reorgs, so they are fully re-indexed
+	let indexed_blocks: Option<Vec<(u64, [u8; 32])>> = records
+		.iter()
+		.map(AttestationRecord::block)
+		.collect::<Result<Vec<_>, _>>()?
+		.into_iter()
+		.collect();
+
+	let mut reorged = false;
+	let from_block = match indexed_blocks {
+		Some(blocks) if !blocks.is_empty() => match client.find_fork_point(&blocks).await? {
+			Some(fork_point) => {
+				reorged = true;
+				info!(
+					"Chain reorganization detected, re-indexing from block {}.",
+					fork_point
+				);
+				records.retain(
+					|record| matches!(record.block(), Ok(Some((number, _))) if number < fork_point),
+				);
+				fork_point
+			},
+			None => blocks.iter().map(|(number, _)| number + 1).max().unwrap_or_default(),
+		},
+		_ => {
+			records.clear();
+			0
+		},
+	};
+
+	// The last scanned block may be past the last stored attestation, unless stored
+	// attestations were dropped or are missing
+	let state_fp = get_file_path(SYNC_STATE_FILE, FileType::Json)?;
+	let mut state_storage = JSONFileStorage::<SyncState>::new(state_fp);
+	let resume_state = match state_storage.load() {
+		Ok(state)
+			if !reorged
+				&& records.len() == stored
+				&& storage.filepath().exists()
+				&& state.last_block() >= from_block =>
+		{
+			Some(state)
+		},
+		_ => from_block.checked_sub(1).map(|last_block| client.sync_state(last_block)),
+	};
+
+	let (attestations, state) = client.get_attestations_since(resume_state.as_ref()).await?;
+	let new_records: Vec<AttestationRecord> =
+		attestations.into_iter().map(AttestationRecord::from).collect();
+	let new_attestations = new_records.len();
+	let kept = records.len();
+
+	// New records are appended, the file is only rewritten if a reorg dropped records or
+	// none could be loaded
+	if new_attestations > 0 || kept != stored {
+		if kept != stored || stored == 0 {
+			records.extend(new_records);
+			storage.save(records)?;
+		} else {
+			storage.append(new_records)?;
+		}
+		info!(
+			"Attestations saved at \"{}\".",
+			storage.filepath().display()
+		);
+	}
+	state_storage.save(state)?;
+
+	Ok((new_attestations, kept + new_attestations))
+}
+
+/// Handles the bandada subcommand.
+pub async fn handle_bandada(data: BandadaData) -> Result<(), EigenError> {
+	let config = load_config()?;
+	let action =
+		data.action.as_deref().ok_or(EigenError::ValidationError("Missing action.".to_string()))?;
+
+	// Syncing handles all the participants at once
+	if action == "sync" {
+		return sync_bandada(&config, data.file).await;
+	}
+	if action == "create" {
+		return create_bandada_group(data).await;
+	}
+	let action: Action = action.parse()?;
+
+	let identity_commitment = data.identity_commitment.as_deref().ok_or(
+		EigenError::ValidationError("Missing identity commitment.".to_string()),
+	)?;
+	let address = data
+		.address
 		.as_deref()
 		.ok_or(EigenError::ValidationError("Missing address.".to_string()))?;
 
@@ -363,21 +1483,109 @@ pub async fn handle_bandada(data: BandadaData) -> Result<(), EigenError> {
 	Ok(())
 }
 
+/// Creates a Bandada group and points the configuration to it, seeding it with the
+/// participants reaching its threshold if requested.
+async fn create_bandada_group(data: BandadaData) -> Result<(), EigenError> {
+	let name =
+		data.name.as_deref().ok_or(EigenError::ValidationError("Missing name.".to_string()))?;
+	let profile = active_profile();
+	let mut file_config = load_profile(profile.as_deref())?;
+	let mut config = override_config(file_config.clone())?;
+	if let Some(threshold) = data.threshold {
+		threshold
+			.parse::<u64>()
+			.map_err(|e| EigenError::ParsingError(format!("Error parsing threshold: {}", e)))?;
+		config.band_th = threshold;
+	}
+
+	let bandada_api = BandadaApi::new(&config.band_url)?;
+	let description = format!(
+		"Participants with an EigenTrust score of at least {}",
+		config.band_th
+	);
+	config.band_id = bandada_api.create_group(name, &description).await?;
+	info!(
+		"Bandada group \"{}\" created with id {}.",
+		name, config.band_id
+	);
+
+	// Overrides only apply to the current invocation
+	file_config.band_id = config.band_id.clone();
+	file_config.band_th = config.band_th.clone();
+	let filepath = save_profile(profile.as_deref(), file_config)?;
+	info!("Configuration updated at \"{}\".", filepath.display());
+
+	if data.seed {
+		sync_bandada(&config, data.file).await?;
+	}
+
+	Ok(())
+}
+
+/// Adds the participants whose score reaches the group threshold to the Bandada group, and
+/// removes the others, according to the saved scores and identity commitments.
+async fn sync_bandada(config: &CliConfig, file: Option<String>) -> Result<(), EigenError> {
+	let threshold = config
+		.band_th
+		.parse()
+		.map_err(|_| EigenError::ParsingError("Failed to parse threshold.".to_string()))?;
+	let scores =
+		CSVFileStorage::<ScoreRecord>::new(get_file_path("scores", FileType::Csv)?).load()?;
+	let commitments_fp = match file {
+		Some(path) => PathBuf::from(path),
+		None => get_file_path("commitments", FileType::Csv)?,
+	};
+	let commitments = CSVFileStorage::<CommitmentRecord>::new(commitments_fp).load()?;
+
+	let bandada_api = BandadaApi::new(&config.band_url)?;
+	let members = bandada_api.get_members(&config.band_id).await?;
+	let changes = MembershipChanges::new(&scores, &commitments, threshold, &members)?;
+
+	for identity_commitment in &changes.add {
+		bandada_api.add_member(&config.band_id, identity_commitment).await?;
+	}
+	for identity_commitment in &changes.remove {
+		bandada_api.remove_member(&config.band_id, identity_commitment).await?;
+	}
+
+	info!(
+		"Bandada group synced: {} members added, {} removed.",
+		changes.add.len(),
+		changes.remove.len()
+	);
+
+	Ok(())
+}
+
 /// Handles the deployment of AS contract.
-pub async fn handle_deploy() -> Result<(), EigenError> {
-	let config = load_config()?;
+pub async fn handle_deploy(data: DeployData) -> Result<(), EigenError> {
+	let confirmations = match data.confirmations {
+		Some(confirmations) => confirmations
+			.parse::<usize>()
+			.map_err(|e| EigenError::ParsingError(format!("Error parsing confirmations: {}", e)))?,
+		None => DEFAULT_DEPLOY_CONFIRMATIONS,
+	};
+	let profile = data.profile.or_else(active_profile);
+	let mut file_config = load_profile(profile.as_deref())?;
+	let config = override_config(file_config.clone())?;
 	let mnemonic = load_mnemonic();
-	let client = Client::new(
+	let client = Client::connect(
 		mnemonic,
 		config.chain_id()?,
 		config.as_address()?,
 		config.domain()?,
-		config.node_url,
-	);
+		&config.node_url,
+	)
+	.await?;
 
-	let as_address = deploy_as(client.get_signer()).await?;
+	let as_address = deploy_as_confirmed(client.get_signer(), confirmations).await?;
 	info!("AttestationStation deployed at {:?}", as_address);
 
+	// Point the profile to the new contract, leaving the overrides out
+	file_config.as_address = format!("{:?}", as_address);
+	let filepath = save_profile(profile.as_deref(), file_config)?;
+	info!("Configuration updated at \"{}\".", filepath.display());
+
 	Ok(())
 }
 
@@ -385,73 +1593,73 @@ pub async fn handle_deploy() -> Result<(), EigenError> {
 pub async fn handle_et_pk() -> Result<(), EigenError> {
 	let config = load_config()?;
 	let mnemonic = load_mnemonic();
-	let client = Client::new(
+	let client = Client::connect(
 		mnemonic,
 		config.chain_id()?,
 		config.as_address()?,
 		config.domain()?,
-		config.node_url,
-	);
+		&config.node_url,
+	)
+	.await?;
 	let attestations = load_or_fetch_attestations().await?;
 	let et_kzg_params = EigenFile::KzgParams(ET_PARAMS_K).load()?;
 
 	let proving_key = client.generate_et_pk(attestations, et_kzg_params)?;
 
-	EigenFile::ProvingKey(Circuit::EigenTrust).save(proving_key)
+	let pk_file = EigenFile::ProvingKey(Circuit::EigenTrust);
+	pk_file.save(proving_key)?;
+	pk_file.save_metadata(&Circuit::EigenTrust)
 }
 
 /// Handles the eigentrust proof generation command.
 pub async fn handle_et_proof() -> Result<(), EigenError> {
 	let config = load_config()?;
 	let mnemonic = load_mnemonic();
-	let client = Client::new(
+	let mut client = Client::connect(
 		mnemonic,
 		config.chain_id()?,
 		config.as_address()?,
 		config.domain()?,
-		config.node_url,
-	);
-
-	let attestations = load_or_fetch_attestations().await?;
-
-	let proving_key = EigenFile::ProvingKey(Circuit::EigenTrust).load()?;
+		&config.node_url,
+	)
+	.await?;
+	client.set_domain_params(load_domain_registry()?.params(config.domain()?))?;
+	client.set_aggregation(aggregation_policy()?);
+
+	let (attestations, contract_attesters) = load_proving_attestations(&client).await?;
+	let attestations_hash = attestations_hash(&attestations);
+
+	let pk_file = EigenFile::ProvingKey(Circuit::EigenTrust);
+	pk_file.validate_metadata(&Circuit::EigenTrust)?;
+	let proving_key = pk_file.load()?;
 	let kzg_params = EigenFile::KzgParams(ET_PARAMS_K).load()?;
+	let params_hash = params_hash(&kzg_params, &proving_key);
 
 	// Generate proof
-	let report = client.generate_et_proof(attestations, kzg_params, proving_key)?;
+	let report =
+		client.generate_et_proof(attestations, &contract_attesters, kzg_params, proving_key)?;
+	let public_inputs = report.pub_inputs.to_bytes();
+
+	proof_storage(&Circuit::EigenTrust)?.save(ProofArtifact::new(
+		&Circuit::EigenTrust,
+		&report.proof,
+		&public_inputs,
+		params_hash,
+		attestations_hash,
+	))?;
+	let proof_file = EigenFile::Proof(Circuit::EigenTrust);
+	proof_file.save(report.proof)?;
+	proof_file.save_metadata(&Circuit::EigenTrust)?;
+	EigenFile::PublicInputs(Circuit::EigenTrust).save(public_inputs)?;
 
-	EigenFile::Proof(Circuit::EigenTrust).save(report.proof)?;
-	EigenFile::PublicInputs(Circuit::EigenTrust).save(report.pub_inputs.to_bytes())?;
+	info!("EigenTrust proof saved.");
 
 	Ok(())
 }
 
 /// Handles the eigentrust proof verification command.
 pub async fn handle_et_verify() -> Result<(), EigenError> {
-	let config = load_config()?;
-	let mnemonic = load_mnemonic();
-	let client = Client::new(
-		mnemonic,
-		config.chain_id()?,
-		config.as_address()?,
-		config.domain()?,
-		config.node_url,
-	);
-
-	// Load data
-	let kzg_params = EigenFile::KzgParams(ET_PARAMS_K).load()?;
-	let public_inputs = EigenFile::PublicInputs(Circuit::EigenTrust).load()?;
-	let proving_key = EigenFile::ProvingKey(Circuit::EigenTrust).load()?;
-	let proof = EigenFile::Proof(Circuit::EigenTrust).load()?;
-
-	// Verify proof
-	client.verify(
-		Circuit::EigenTrust,
-		kzg_params,
-		public_inputs,
-		proving_key,
-		proof,
-	)?;
+	verify_proof(Circuit::EigenTrust).await?;
 
 	info!("EigenTrust proof has been verified.");
 	Ok(())
@@ -473,184 +1681,928 @@ pub fn handle_params(data: KZGParamsData) -> Result<(), EigenError> {
 }
 
 /// Handles `scores` and `local_scores` commands.
-pub async fn handle_scores(origin: AttestationsOrigin) -> Result<(), EigenError> {
+pub async fn handle_scores(
+	origin: AttestationsOrigin, data: ScoresData, output: OutputFormat,
+) -> Result<(), EigenError> {
+	let graph_format: Option<GraphFormat> =
+		data.export_graph.as_deref().map(str::parse).transpose()?;
+	let normalization = data.normalize.as_deref().map(parse_normalization).transpose()?;
+	let top = data
+		.top
+		.as_deref()
+		.map(|top| {
+			top.parse::<usize>()
+				.map_err(|e| EigenError::ParsingError(format!("Error parsing top: {}", e)))
+		})
+		.transpose()?;
+	let epoch_block = data
+		.epoch
+		.as_deref()
+		.map(|epoch| {
+			epoch
+				.parse::<u64>()
+				.map_err(|e| EigenError::ParsingError(format!("Error parsing epoch: {}", e)))
+		})
+		.transpose()?;
+
+	let seeds = data.seeds.as_deref().map(parse_seeds).transpose()?;
+	let disconnected: DisconnectedPolicy =
+		data.disconnected.as_deref().map(str::parse).transpose()?.unwrap_or_default();
+	if data.sharded && seeds.is_some() {
+		return Err(EigenError::ValidationError(
+			"Sharded scores can't be seeded.".to_string(),
+		));
+	}
+
+	// The manifest and certificates don't record sharding or seeds, so they'd misdescribe
+	// the run
+	let reproducible = !data.sharded && seeds.is_none();
+	if !reproducible && data.certify {
+		return Err(EigenError::ValidationError(
+			"Sharded or seeded scores can't be certified.".to_string(),
+		));
+	}
+
 	let config = load_config()?;
 	let mnemonic = load_mnemonic();
-	let client = Client::new(
+	let domain = config.domain()?;
+	let mut client = Client::connect(
 		mnemonic,
 		config.chain_id()?,
 		config.as_address()?,
-		config.domain()?,
-		config.node_url,
-	);
+		domain,
+		&config.node_url,
+	)
+	.await?;
+	client.set_domain_params(load_domain_registry()?.params(domain))?;
+	client.set_aggregation(aggregation_policy()?);
+
+	// Fetch attestations, if requested
+	if let AttestationsOrigin::Fetch = origin {
+		handle_attestations(AttestationsData::default(), OutputFormat::Text).await?;
+	}
 
 	let att_fp = get_file_path("attestations", FileType::Csv)?;
+	let records = scoring_records(
+		CSVFileStorage::<AttestationRecord>::new(att_fp).load()?,
+		load_imported_attestations()?,
+		epoch_block,
+		dedup_policy()?,
+	);
 
-	// Get or Fetch attestations
-	let attestations: Vec<SignedAttestationRaw> = match origin {
-		AttestationsOrigin::Local => {
-			let att_storage = CSVFileStorage::<AttestationRecord>::new(att_fp);
-
-			let records = att_storage.load()?;
-
-			// Verify there are attestations
-			if records.is_empty() {
-				return Err(EigenError::AttestationError(
-					"No attestations found.".to_string(),
-				));
-			}
-
-			let attestations: Result<Vec<SignedAttestationRaw>, EigenError> =
-				records.into_iter().map(|record| record.try_into()).collect();
-
-			attestations?
-		},
-		AttestationsOrigin::Fetch => {
-			handle_attestations().await?;
-
-			let att_storage = CSVFileStorage::<AttestationRecord>::new(att_fp);
-			let attestations: Result<Vec<SignedAttestationRaw>, EigenError> =
-				att_storage.load()?.into_iter().map(|record| record.try_into()).collect();
+	// Verify there are attestations
+	if records.is_empty() {
+		return Err(EigenError::AttestationError(
+			"No attestations found.".to_string(),
+		));
+	}
 
-			attestations?
-		},
+	let epoch = match epoch_block {
+		Some(block) => block,
+		None => attestations_epoch(&records)?,
 	};
+	let creators: Vec<Option<[u8; 20]>> =
+		records.iter().map(AttestationRecord::creator).collect::<Result<_, _>>()?;
+	let attestations: Vec<SignedAttestationRaw> =
+		records.into_iter().map(|record| record.try_into()).collect::<Result<_, _>>()?;
+
+	// Attestations submitted by contract wallets are verified through EIP-1271
+	let contract_attesters = client.contract_attesters(&attestations, &creators).await?;
+
+	// Keep the attestations for the graph export, if requested
+	let graph_attestations = graph_format.map(|_| attestations.clone());
+
+	// Report the potential sybil rings, if requested
+	if data.sybil_report {
+		let report = Client::sybil_report(&attestations, &contract_attesters, DEFAULT_SYBIL_SHARE)?;
+		let (flagged, participants) = (report.clusters.len(), report.participants);
+		let report_fp = get_file_path("sybil-report", FileType::Json)?;
+		JSONFileStorage::<SybilReport>::new(report_fp.clone()).save(report)?;
+
+		if flagged == 0 {
+			info!(
+				"No potential sybil rings among {} participants.",
+				participants
+			);
+		} else {
+			warn!(
+				"{} potential sybil rings among {} participants, see \"{}\".",
+				flagged,
+				participants,
+				report_fp.display()
+			);
+		}
+	}
 
 	// Calculate scores
+	let scores = if data.sharded {
+		client.calculate_sharded_scores(attestations, &contract_attesters)?
+	} else if let Some(seeds) = &seeds {
+		client
+			.calculate_scores_from_seeds(attestations, &contract_attesters, seeds, disconnected)?
+	} else {
+		client.calculate_scores_with_contract_attesters(attestations, &contract_attesters)?
+	};
+	let manifest = reproducible.then(|| client.manifest(&scores));
+	let certificates =
+		if data.certify { Some(client.certify_scores(&scores, epoch)?) } else { None };
 	let score_records: Vec<ScoreRecord> =
-		client.calculate_scores(attestations)?.into_iter().map(ScoreRecord::from_score).collect();
+		scores.into_iter().map(|score| ScoreRecord::from_score(score).with_epoch(epoch)).collect();
+
+	// Export the attestation graph, along with the participants' scores
+	if let (Some(format), Some(attestations)) = (graph_format, graph_attestations) {
+		let graph = Client::attestation_graph(&attestations, &contract_attesters, &score_records)?;
+		let graph_fp = get_file_path("graph", FileType::Graph(format))?;
+		BinFileStorage::new(graph_fp.clone()).save(graph.render(format)?.into_bytes())?;
+
+		info!(
+			"Attestation graph of {} participants saved at \"{}\".",
+			graph.nodes.len(),
+			graph_fp.display()
+		);
+	}
 
-	// Save scores
-	let scores_fp = get_file_path("scores", FileType::Csv)?;
+	// Save scores, along with their provenance. Sharded and seeded runs have no manifest
+	// to point to.
+	let scorer = client.get_signer().address().to_fixed_bytes();
+	let scores_fp = match epoch_block {
+		Some(block) => epoch_scores_path(block)?,
+		None => get_file_path("scores", FileType::Csv)?,
+	};
 	let mut records_storage = CSVFileStorage::<ScoreRecord>::new(scores_fp);
-	records_storage.save(score_records)?;
+	if let Some(manifest) = &manifest {
+		let provenance = Provenance::new(manifest, scorer, None, epoch);
+		records_storage = records_storage.with_header(provenance.to_header());
+	}
+	records_storage.save(score_records.clone())?;
 
 	info!(
 		"Scores saved at \"{}\".",
 		records_storage.filepath().display()
 	);
 
+	// Save the signed score certificates, if requested
+	if let Some(certificates) = certificates {
+		let certificates_fp = get_file_path("score-certificates", FileType::Json)?;
+		JSONFileStorage::<Vec<ScoreCertificate>>::new(certificates_fp.clone())
+			.save(certificates)?;
+
+		info!(
+			"Score certificates saved at \"{}\".",
+			certificates_fp.display()
+		);
+	}
+
+	if let (Some(normalization), None, false) = (normalization, top, data.save_ranking) {
+		let normalized = normalize_scores(&score_records, normalization)?;
+		match output {
+			OutputFormat::Json => output.print(&normalized)?,
+			OutputFormat::Text => {
+				let mut table = format!("{:<44}{:<24}{}", "Address", "Score", "Value");
+				for row in &normalized {
+					table.push_str(&format!(
+						"\n{:<44}{:<24}{:.4}",
+						row.peer_address, row.score, row.value
+					));
+				}
+				info!("Normalized scores:\n{}", table);
+			},
+		}
+	} else if top.is_none() && !data.save_ranking {
+		output.print(&score_records)?;
+	} else {
+		let mut ranking = rank_scores(&score_records)?;
+		if let Some(top) = top {
+			ranking.truncate(top);
+		}
+
+		match output {
+			OutputFormat::Json => output.print(&ranking)?,
+			OutputFormat::Text => {
+				let mut leaderboard =
+					format!("{:<6}{:<44}{:<24}{}", "Rank", "Address", "Score", "Share");
+				for row in &ranking {
+					leaderboard.push_str(&format!(
+						"\n{:<6}{:<44}{:<24}{}%",
+						row.rank, row.peer_address, row.score, row.share
+					));
+				}
+				info!("Leaderboard:\n{}", leaderboard);
+			},
+		}
+
+		if data.save_ranking {
+			let ranking_fp = get_file_path("ranking", FileType::Csv)?;
+			let mut ranking_storage = CSVFileStorage::<RankingRecord>::new(ranking_fp);
+			ranking_storage.save(ranking)?;
+			info!(
+				"Ranking saved at \"{}\".",
+				ranking_storage.filepath().display()
+			);
+		}
+	}
+
+	// Save the manifest of the run, so it can be replayed
+	if let Some(manifest) = manifest {
+		let manifest_fp = get_file_path("manifest", FileType::Json)?;
+		JSONFileStorage::<ScoringManifest>::new(manifest_fp).save(manifest)?;
+	}
+
 	Ok(())
 }
 
-/// Handles threshold circuit proving key generation.
-pub async fn handle_th_pk() -> Result<(), EigenError> {
+/// Handles the `diff-scores` command.
+pub async fn handle_diff_scores(
+	data: DiffScoresData, output: OutputFormat,
+) -> Result<(), EigenError> {
+	let parse_block = |block: &str| {
+		block
+			.parse::<u64>()
+			.map_err(|e| EigenError::ParsingError(format!("Error parsing block: {}", e)))
+	};
+	let from_block = data.from_block.as_deref().map(parse_block).transpose()?;
+	let to_block = data.to_block.as_deref().map(parse_block).transpose()?;
+	let threshold = match data.threshold {
+		Some(threshold) => U256::from_dec_str(&threshold)
+			.map_err(|e| EigenError::ParsingError(format!("Error parsing threshold: {}", e)))?,
+		None => U256::zero(),
+	};
+	let rank_threshold = match data.rank_threshold {
+		Some(rank_threshold) => rank_threshold.parse::<usize>().map_err(|e| {
+			EigenError::ParsingError(format!("Error parsing rank threshold: {}", e))
+		})?,
+		None => 0,
+	};
+
+	// Blocks are scored from the saved attestations
+	let client = match (from_block, to_block) {
+		(None, None) => None,
+		_ => {
+			let config = load_config()?;
+			let domain = config.domain()?;
+			let mut client = Client::connect(
+				load_mnemonic(),
+				config.chain_id()?,
+				config.as_address()?,
+				domain,
+				&config.node_url,
+			)
+			.await?;
+			client.set_domain_params(load_domain_registry()?.params(domain))?;
+			client.set_aggregation(aggregation_policy()?);
+			Some(client)
+		},
+	};
+
+	let old = match (data.old, from_block, &client) {
+		(Some(path), ..) => CSVFileStorage::<ScoreRecord>::new(PathBuf::from(path)).load()?,
+		(None, Some(block), Some(client)) => epoch_scores(client, block).await?,
+		_ => {
+			return Err(EigenError::ValidationError(
+				"Missing scores to compare from, set --old or --from-block.".to_string(),
+			))
+		},
+	};
+	let new = match (data.new, to_block, &client) {
+		(Some(path), ..) => CSVFileStorage::<ScoreRecord>::new(PathBuf::from(path)).load()?,
+		(None, Some(block), Some(client)) => epoch_scores(client, block).await?,
+		_ => CSVFileStorage::<ScoreRecord>::new(get_file_path("scores", FileType::Csv)?).load()?,
+	};
+
+	let changes = diff_scores(&old, &new, threshold, rank_threshold)?;
+
+	match output {
+		OutputFormat::Json => output.print(&changes)?,
+		OutputFormat::Text => {
+			let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+			let mut table = format!(
+				"{:<44}{:<24}{:<24}{:<24}{:<10}{}",
+				"Address", "Old score", "New score", "Change", "Old rank", "New rank"
+			);
+			for change in &changes {
+				table.push_str(&format!(
+					"\n{:<44}{:<24}{:<24}{:<24}{:<10}{}",
+					change.peer_address,
+					optional(change.old_score.clone()),
+					optional(change.new_score.clone()),
+					change.change,
+					optional(change.old_rank.map(|rank| rank.to_string())),
+					optional(change.new_rank.map(|rank| rank.to_string())),
+				));
+			}
+			if !changes.is_empty() {
+				info!("Score changes:\n{}", table);
+			}
+			info!(
+				"{} participants changed beyond the thresholds.",
+				changes.len()
+			);
+		},
+	}
+
+	Ok(())
+}
+
+/// Handles the `gas-report` command.
+pub async fn handle_gas_report(
+	data: GasReportData, output: OutputFormat,
+) -> Result<(), EigenError> {
+	let batch_size = match data.batch_size {
+		Some(batch_size) => batch_size
+			.parse::<u64>()
+			.map_err(|e| EigenError::ParsingError(format!("Error parsing batch size: {}", e)))?,
+		None => DEFAULT_GAS_REPORT_BATCH_SIZE,
+	};
+	if batch_size == 0 {
+		return Err(EigenError::ValidationError(
+			"The batch size must be positive".to_string(),
+		));
+	}
+
 	let config = load_config()?;
-	let mnemonic = load_mnemonic();
-	let client = Client::new(
-		mnemonic,
+	let domain = config.domain()?;
+	let client = Client::connect(
+		load_mnemonic(),
 		config.chain_id()?,
 		config.as_address()?,
-		config.domain()?,
-		config.node_url,
-	);
-	let attestations = load_or_fetch_attestations().await?;
+		domain,
+		&config.node_url,
+	)
+	.await?;
+	let gas_price =
+		client.get_signer().get_gas_price().await.map_err(|e| {
+			EigenError::ConnectionError(format!("Failed to get the gas price: {}", e))
+		})?;
+
+	// Sample attestations about distinct addresses, signed by the configured account
+	let mut signed_attestations = Vec::new();
+	for index in 1..=batch_size {
+		let mut about = [0; 20];
+		about[12..].copy_from_slice(&index.to_be_bytes());
+		let attestation = AttestationRaw::new(about, domain, 5, [0; 32]);
+		signed_attestations.push(client.sign_attestation(attestation)?);
+	}
 
-	// Load KZG params
-	let et_kzg_params = EigenFile::KzgParams(ET_PARAMS_K).load()?;
-	let th_kzg_params = EigenFile::KzgParams(TH_PARAMS_K).load()?;
+	let estimate = |attestations: u64, gas: U256| AttestationGasEstimate {
+		attestations,
+		gas: gas.to_string(),
+		gas_per_attestation: (gas / U256::from(attestations)).to_string(),
+		cost: format_ether(gas.saturating_mul(gas_price)),
+	};
+	let single_gas = client.estimate_relay_gas(signed_attestations[..1].to_vec()).await?;
+	let batch_gas = client.estimate_relay_gas(signed_attestations).await?;
+
+	// There is no verifier contract yet, so only a lower bound of the stored proofs is known
+	let mut verification = Vec::new();
+	for circuit in [Circuit::EigenTrust, Circuit::Threshold] {
+		if !(EigenFile::Proof(circuit).exists()? && EigenFile::PublicInputs(circuit).exists()?) {
+			continue;
+		}
+		let min_gas = U256::from(min_verification_gas(
+			&EigenFile::Proof(circuit).load()?,
+			&EigenFile::PublicInputs(circuit).load()?,
+		));
+		verification.push(VerificationGasEstimate {
+			circuit: circuit.as_str(),
+			min_gas: min_gas.to_string(),
+			min_cost: format_ether(min_gas.saturating_mul(gas_price)),
+		});
+	}
 
-	let proving_key = client.generate_th_pk(attestations, et_kzg_params, th_kzg_params)?;
+	let report = GasReport {
+		gas_price: format_units(gas_price, "gwei")
+			.map_err(|e| EigenError::ConversionError(e.to_string()))?,
+		single: estimate(1, single_gas),
+		batch: estimate(batch_size, batch_gas),
+		verification,
+	};
+
+	match output {
+		OutputFormat::Json => output.print(&report)?,
+		OutputFormat::Text => {
+			info!("Gas price: {} gwei", report.gas_price);
+			for estimate in [&report.single, &report.batch] {
+				info!(
+					"{} attestations in a transaction: {} gas ({} per attestation), {} ETH",
+					estimate.attestations,
+					estimate.gas,
+					estimate.gas_per_attestation,
+					estimate.cost
+				);
+			}
+			for estimate in &report.verification {
+				info!(
+					"{} proof verification: at least {} gas, {} ETH",
+					estimate.circuit, estimate.min_gas, estimate.min_cost
+				);
+			}
+			if report.verification.is_empty() {
+				info!("No stored proofs to estimate the verification of.");
+			}
+		},
+	}
 
-	EigenFile::ProvingKey(Circuit::Threshold).save(proving_key)
+	Ok(())
 }
 
-/// Handles threshold circuit proof generation.
-pub async fn handle_th_proof(data: ThProofData) -> Result<(), EigenError> {
-	// Validate peer argument
-	let peer_id = match data.peer {
-		Some(peer) => H160::from_str(&peer).map_err(|e| EigenError::ParsingError(e.to_string()))?,
-		None => {
-			return Err(EigenError::ValidationError(
-				"Missing argument 'peer': participant address.".to_string(),
-			))
+/// Handles the `key` command.
+pub fn handle_key(data: KeyData) -> Result<(), EigenError> {
+	let action: KeyAction = data
+		.action
+		.as_deref()
+		.ok_or(EigenError::ValidationError("Missing action.".to_string()))?
+		.parse()?;
+	let indices = parse_indices(data.indices.as_deref().unwrap_or("0"))?;
+
+	match action {
+		KeyAction::Generate => {
+			let mnemonic = generate_mnemonic();
+			let identities = attester_identities(&mnemonic, &indices)?;
+
+			info!(
+				"New mnemonic, set it as MNEMONIC in the .env file and keep it secret:\n{}",
+				mnemonic
+			);
+			for identity in identities {
+				info!("Address {}: {}", identity.index, identity.address);
+			}
 		},
-	};
+		KeyAction::Addresses => {
+			for identity in attester_identities(&load_mnemonic(), &indices)? {
+				info!("Address {}: {}", identity.index, identity.address);
+			}
+		},
+		KeyAction::Export => {
+			let identities = attester_identities(&load_mnemonic(), &indices)?;
+			let identity_fp = match data.file {
+				Some(path) => PathBuf::from(path),
+				None => get_file_path("identity", FileType::Json)?,
+			};
+			let mut identity_storage = JSONFileStorage::<Vec<AttesterIdentity>>::new(identity_fp);
+			identity_storage.save(identities)?;
+
+			info!(
+				"Public identity saved at \"{}\".",
+				identity_storage.filepath().display()
+			);
+		},
+	}
+
+	Ok(())
+}
 
+/// Handles the `replay` command.
+pub async fn handle_replay(data: ReplayData) -> Result<(), EigenError> {
 	let config = load_config()?;
 	let mnemonic = load_mnemonic();
-	let client = Client::new(
+	let mut client = Client::connect(
 		mnemonic,
 		config.chain_id()?,
 		config.as_address()?,
 		config.domain()?,
-		config.node_url,
-	);
+		&config.node_url,
+	)
+	.await?;
+	client.set_domain_params(load_domain_registry()?.params(config.domain()?))?;
+	client.set_aggregation(aggregation_policy()?);
+
+	let manifest_fp = match data.manifest {
+		Some(path) => PathBuf::from(path),
+		None => get_file_path("manifest", FileType::Json)?,
+	};
+	let manifest = JSONFileStorage::<ScoringManifest>::new(manifest_fp).load()?;
 
 	let attestations = load_or_fetch_attestations().await?;
+	let att_fp = get_file_path("attestations", FileType::Csv)?;
+	let epoch = attestations_epoch(&CSVFileStorage::<AttestationRecord>::new(att_fp).load()?)?;
 
-	// Load KZG params and proving key
-	let et_kzg_params = EigenFile::KzgParams(ET_PARAMS_K).load()?;
-	let th_kzg_params = EigenFile::KzgParams(TH_PARAMS_K).load()?;
-	let proving_key = EigenFile::ProvingKey(Circuit::Threshold).load()?;
+	// Replay the scoring run
+	let trace = client.replay_scores(attestations, &manifest)?;
+	if !trace.matches_manifest {
+		warn!("The saved attestations don't reproduce the manifest scores.");
+	}
 
-	let report = client.generate_th_proof(
-		attestations,
-		et_kzg_params,
-		th_kzg_params,
-		proving_key,
-		config.band_th.parse().unwrap(),
-		*peer_id.as_fixed_bytes(),
-	)?;
+	// Save the scores of every iteration, along with their provenance
+	let scorer = client.get_signer().address().to_fixed_bytes();
+	let provenance = Provenance::new(&manifest, scorer, None, epoch);
+	let replay_fp = get_file_path("replay", FileType::Csv)?;
+	let mut replay_storage =
+		CSVFileStorage::<ReplayRecord>::new(replay_fp).with_header(provenance.to_header());
+	replay_storage.save(ReplayRecord::from_trace(&trace))?;
 
-	EigenFile::Proof(Circuit::Threshold).save(report.proof)?;
-	EigenFile::PublicInputs(Circuit::Threshold).save(report.pub_inputs.to_bytes())?;
+	info!(
+		"Replay of {} iterations saved at \"{}\".",
+		trace.iterations.len() - 1,
+		replay_storage.filepath().display()
+	);
 
 	Ok(())
 }
 
-/// Handles the eigentrust proof verification command.
-pub async fn handle_th_verify() -> Result<(), EigenError> {
+/// Handles the `state` command.
+pub async fn handle_state(data: StateData) -> Result<(), EigenError> {
 	let config = load_config()?;
 	let mnemonic = load_mnemonic();
-	let client = Client::new(
+	let client = Client::connect(
 		mnemonic,
 		config.chain_id()?,
 		config.as_address()?,
 		config.domain()?,
-		config.node_url,
-	);
+		&config.node_url,
+	)
+	.await?;
 
-	// Load data
-	let kzg_params = EigenFile::KzgParams(TH_PARAMS_K).load()?;
-	let public_inputs = EigenFile::PublicInputs(Circuit::Threshold).load()?;
-	let proving_key = EigenFile::ProvingKey(Circuit::Threshold).load()?;
-	let proof = EigenFile::Proof(Circuit::Threshold).load()?;
+	let action: StateAction = data
+		.action
+		.as_deref()
+		.ok_or(EigenError::ValidationError("Missing action.".to_string()))?
+		.parse()?;
+	let state_fp = match data.file {
+		Some(path) => PathBuf::from(path),
+		None => get_file_path("state", FileType::Json)?,
+	};
+	let mut state_storage =
+		EncryptedJSONFileStorage::<StateSnapshot>::new(state_fp, encryption_key()?);
+	let mut att_storage =
+		CSVFileStorage::<AttestationRecord>::new(get_file_path("attestations", FileType::Csv)?);
+	let mut scores_storage =
+		CSVFileStorage::<ScoreRecord>::new(get_file_path("scores", FileType::Csv)?);
 
-	// Verify proof
-	client.verify(
-		Circuit::Threshold,
-		kzg_params,
-		public_inputs,
-		proving_key,
-		proof,
-	)?;
+	match action {
+		StateAction::Export => {
+			let mut snapshot = client.export_state(&[]).await?;
 
-	info!("Threshold proof has been verified.");
-	Ok(())
-}
+			// The computed scores are the last saved ones
+			snapshot.scores = scores_storage.load().unwrap_or_default();
+			state_storage.save(snapshot)?;
 
-/// Handles the CLI project configuration update.
-pub fn handle_update(data: UpdateData) -> Result<(), EigenError> {
-	let mut config = load_config()?;
-	if let Some(as_address) = data.as_address {
-		config.as_address = Address::from_str(&as_address)
-			.map_err(|e| EigenError::ParsingError(e.to_string()))?
-			.to_string();
-	}
+			info!(
+				"State snapshot saved at \"{}\".",
+				state_storage.filepath().display()
+			);
+		},
+		StateAction::Import => {
+			let snapshot = client.import_state(state_storage.load()?).await?;
 
-	if let Some(band_id) = data.band_id {
-		// TODO: Validate bandada group id type
-		config.band_id = band_id
-	}
+			att_storage.save(snapshot.attestations)?;
+			if snapshot.scores.is_empty() {
+				warn!("The snapshot has no valid scores, run `local-scores` to compute them.");
+			} else {
+				scores_storage.save(snapshot.scores)?;
+			}
 
-	if let Some(band_th) = data.band_th {
-		band_th.parse::<u32>().map_err(|e| EigenError::ParsingError(e.to_string()))?;
-		config.band_th = band_th;
+			info!(
+				"State snapshot imported to \"{}\".",
+				att_storage.filepath().display()
+			);
+		},
 	}
 
-	if let Some(band_url) = data.band_url {
-		Http::from_str(&band_url).map_err(|e| EigenError::ParsingError(e.to_string()))?;
+	Ok(())
+}
+
+/// Handles the `serve` command.
+pub async fn handle_serve(data: ServeData) -> Result<(), EigenError> {
+	let address: SocketAddr = data
+		.address
+		.as_deref()
+		.unwrap_or(DEFAULT_SERVER_ADDRESS)
+		.parse()
+		.map_err(|e: AddrParseError| EigenError::ParsingError(e.to_string()))?;
+	let relayer = if data.relay { Some(Relayer::from_env().await?) } else { None };
+
+	serve(address, relayer).await
+}
+
+/// Handles the `show-score` command. Scores are calculated from the saved attestations
+/// if none were saved yet.
+pub async fn handle_show_score(
+	data: ShowScoreData, output: OutputFormat,
+) -> Result<(), EigenError> {
+	let address = data
+		.address
+		.as_deref()
+		.ok_or(EigenError::ValidationError("Missing address.".to_string()))?;
+	str_to_20_byte_array(address)?;
+
+	let scores_fp = get_file_path("scores", FileType::Csv)?;
+	if !scores_fp.exists() {
+		info!("No scores found, calculating them from the saved attestations.");
+		handle_scores(
+			AttestationsOrigin::Local,
+			ScoresData::default(),
+			OutputFormat::Text,
+		)
+		.await?;
+	}
+	let records = CSVFileStorage::<ScoreRecord>::new(scores_fp).load()?;
+
+	let (rank, record) = score_rank(&records, address)?
+		.ok_or_else(|| EigenError::ResourceUnavailableError(format!("No score for {}", address)))?;
+
+	if let OutputFormat::Json = output {
+		let ranked = RankedScore { record: record.clone(), rank, participants: records.len() };
+		return output.print(&ranked);
+	}
+
+	info!(
+		"Score of {}:\n  score: {}\n  rank: {} of {}\n  numerator: {}\n  denominator: {}",
+		record.peer_address(),
+		record.score(),
+		rank,
+		records.len(),
+		record.numerator(),
+		record.denominator()
+	);
+
+	Ok(())
+}
+
+/// Handles the `simulate` command.
+pub fn handle_simulate(data: SimulateData, output: OutputFormat) -> Result<(), EigenError> {
+	let peers = match data.peers {
+		Some(peers) => peers
+			.parse::<usize>()
+			.map_err(|e| EigenError::ParsingError(format!("Error parsing peers: {}", e)))?,
+		None => DEFAULT_SIMULATION_PEERS,
+	};
+	let density = match data.density {
+		Some(density) => density
+			.parse::<f64>()
+			.map_err(|e| EigenError::ParsingError(format!("Error parsing density: {}", e)))?,
+		None => DEFAULT_SIMULATION_DENSITY,
+	};
+	let distribution: ScoreDistribution =
+		data.distribution.as_deref().map_or(Ok(ScoreDistribution::default()), str::parse)?;
+	let seed = data
+		.seed
+		.map(|seed| seed.parse::<u64>())
+		.transpose()
+		.map_err(|e| EigenError::ParsingError(format!("Error parsing seed: {}", e)))?;
+
+	// Simulations default to the configured domain parameters, if any
+	let current = match load_config() {
+		Ok(config) => load_domain_registry()?.params(config.domain()?),
+		Err(_) => DomainParams::circuit(),
+	};
+	let num_iterations = match data.num_iterations {
+		Some(n) => n.parse::<u64>().map_err(|e| EigenError::ParsingError(e.to_string()))?,
+		None => current.num_iterations,
+	};
+	let initial_score = match data.initial_score {
+		Some(s) => s.parse::<u128>().map_err(|e| EigenError::ParsingError(e.to_string()))?,
+		None => current.initial_score,
+	};
+
+	let network = SyntheticNetwork::generate(peers, density, distribution, seed)?;
+	let report = network.converge(DomainParams::new(num_iterations, initial_score))?;
+
+	if let OutputFormat::Json = output {
+		return output.print(&report);
+	}
+
+	let convergence = match report.converged_at {
+		Some(iteration) => format!("converged at iteration {}", iteration),
+		None => format!(
+			"not converged, last relative change {:.2e}",
+			report.final_delta
+		),
+	};
+	info!(
+		"Simulation of {} peers and {} attestations over {} iterations, {}:\n  min: {:.2}\n  median: {:.2}\n  mean: {:.2}\n  max: {:.2}\n  std dev: {:.2}\n  top 10% share: {:.1}%",
+		report.peers,
+		report.edges,
+		report.iterations,
+		convergence,
+		report.stats.min,
+		report.stats.median,
+		report.stats.mean,
+		report.stats.max,
+		report.stats.std_dev,
+		report.stats.top_decile_share * 100.0
+	);
+
+	Ok(())
+}
+
+/// Handles threshold circuit proving key generation.
+pub async fn handle_th_pk() -> Result<(), EigenError> {
+	let config = load_config()?;
+	let mnemonic = load_mnemonic();
+	let client = Client::connect(
+		mnemonic,
+		config.chain_id()?,
+		config.as_address()?,
+		config.domain()?,
+		&config.node_url,
+	)
+	.await?;
+	let attestations = load_or_fetch_attestations().await?;
+
+	// Load KZG params
+	let et_kzg_params = EigenFile::KzgParams(ET_PARAMS_K).load()?;
+	let th_kzg_params = EigenFile::KzgParams(TH_PARAMS_K).load()?;
+
+	let proving_key = client.generate_th_pk(attestations, et_kzg_params, th_kzg_params)?;
+
+	let pk_file = EigenFile::ProvingKey(Circuit::Threshold);
+	pk_file.save(proving_key)?;
+	pk_file.save_metadata(&Circuit::Threshold)
+}
+
+/// Handles threshold circuit proof generation.
+pub async fn handle_th_proof(data: ThProofData) -> Result<(), EigenError> {
+	// Validate peer argument
+	let peer_id = match data.peer {
+		Some(peer) => H160::from_str(&peer).map_err(|e| EigenError::ParsingError(e.to_string()))?,
+		None => {
+			return Err(EigenError::ValidationError(
+				"Missing argument 'peer': participant address.".to_string(),
+			))
+		},
+	};
+
+	let config = load_config()?;
+	let mnemonic = load_mnemonic();
+	let mut client = Client::connect(
+		mnemonic,
+		config.chain_id()?,
+		config.as_address()?,
+		config.domain()?,
+		&config.node_url,
+	)
+	.await?;
+	client.set_domain_params(load_domain_registry()?.params(config.domain()?))?;
+	client.set_aggregation(aggregation_policy()?);
+
+	let threshold = data
+		.threshold
+		.as_ref()
+		.unwrap_or(&config.band_th)
+		.parse::<u32>()
+		.map_err(|e| EigenError::ParsingError(format!("Error parsing threshold: {}", e)))?;
+
+	let (attestations, contract_attesters) = load_proving_attestations(&client).await?;
+	let attestations_hash = attestations_hash(&attestations);
+
+	// Load KZG params and proving key
+	let et_kzg_params = EigenFile::KzgParams(ET_PARAMS_K).load()?;
+	let th_kzg_params = EigenFile::KzgParams(TH_PARAMS_K).load()?;
+	let pk_file = EigenFile::ProvingKey(Circuit::Threshold);
+	pk_file.validate_metadata(&Circuit::Threshold)?;
+	let proving_key = pk_file.load()?;
+	// The proof is verified with the threshold parameters only
+	let params_hash = params_hash(&th_kzg_params, &proving_key);
+
+	let report = client.generate_th_proof(
+		attestations,
+		&contract_attesters,
+		et_kzg_params,
+		th_kzg_params,
+		proving_key,
+		threshold,
+		*peer_id.as_fixed_bytes(),
+	)?;
+
+	// A proof of a score below the threshold can't back any credential
+	if !report.pub_inputs.is_above_threshold() {
+		return Err(EigenError::ValidationError(format!(
+			"The score of {:?} is not above the threshold {}",
+			peer_id, threshold
+		)));
+	}
+
+	let public_inputs = report.pub_inputs.to_bytes();
+	proof_storage(&Circuit::Threshold)?.save(ProofArtifact::new(
+		&Circuit::Threshold,
+		&report.proof,
+		&public_inputs,
+		params_hash,
+		attestations_hash,
+	))?;
+	let proof_file = EigenFile::Proof(Circuit::Threshold);
+	proof_file.save(report.proof)?;
+	proof_file.save_metadata(&Circuit::Threshold)?;
+	EigenFile::PublicInputs(Circuit::Threshold).save(public_inputs)?;
+
+	info!(
+		"Threshold proof of {:?} above {} saved.",
+		peer_id, threshold
+	);
+
+	Ok(())
+}
+
+/// Handles the threshold proof verification command.
+pub async fn handle_th_verify() -> Result<(), EigenError> {
+	verify_proof(Circuit::Threshold).await?;
+
+	info!("Threshold proof has been verified.");
+	Ok(())
+}
+
+/// Verifies the stored proof of the given circuit.
+pub async fn verify_proof(circuit: Circuit) -> Result<(), EigenError> {
+	let config = load_config()?;
+	let mnemonic = load_mnemonic();
+	let client = Client::connect(
+		mnemonic,
+		config.chain_id()?,
+		config.as_address()?,
+		config.domain()?,
+		&config.node_url,
+	)
+	.await?;
+
+	// Check the artifacts were generated with the current hasher parameters
+	EigenFile::ProvingKey(circuit).validate_metadata(&circuit)?;
+	EigenFile::Proof(circuit).validate_metadata(&circuit)?;
+
+	// Load data
+	let params_k = match circuit {
+		Circuit::EigenTrust => ET_PARAMS_K,
+		Circuit::Threshold => TH_PARAMS_K,
+	};
+	let kzg_params = EigenFile::KzgParams(params_k).load()?;
+	let public_inputs = EigenFile::PublicInputs(circuit).load()?;
+	let proving_key = EigenFile::ProvingKey(circuit).load()?;
+	let proof = EigenFile::Proof(circuit).load()?;
+
+	// Check the proof and public inputs are the ones generated with these parameters
+	let storage = proof_storage(&circuit)?;
+	if storage.filepath().exists() {
+		let artifact =
+			storage.load_checked(&circuit, params_hash(&kzg_params, &proving_key), None)?;
+		if artifact.proof()? != proof || artifact.public_inputs()? != public_inputs {
+			return Err(EigenError::VerificationError(
+				"The proof or public inputs don't match the proof artifact".to_string(),
+			));
+		}
+	} else {
+		warn!(
+			"No proof artifact found for \"{}\", skipping input checks.",
+			circuit.as_str()
+		);
+	}
+
+	// Verify proof
+	client.verify(circuit, kzg_params, public_inputs, proving_key, proof)
+}
+
+/// Handles the badge rendering of a participant, from the saved scores.
+pub fn handle_badge(data: BadgeData) -> Result<(), EigenError> {
+	let address = data
+		.address
+		.as_deref()
+		.ok_or(EigenError::ValidationError("Missing address.".to_string()))?;
+	let format: BadgeFormat = match data.format.as_deref() {
+		Some(format) => format.parse()?,
+		None => BadgeFormat::default(),
+	};
+
+	let scores_fp = get_file_path("scores", FileType::Csv)?;
+	let scores = CSVFileStorage::<ScoreRecord>::new(scores_fp).load()?;
+
+	let badge = Badge::from_records(&scores, address)?;
+	let file_type = match format {
+		BadgeFormat::Json => FileType::Json,
+		BadgeFormat::Svg => FileType::Svg,
+	};
+	let badge_fp = get_file_path("badge", file_type)?;
+	BinFileStorage::new(badge_fp.clone()).save(badge.render(format)?.into_bytes())?;
+
+	info!(
+		"Badge of {} ({} tier) saved at \"{}\".",
+		badge.address(),
+		badge.tier().as_str(),
+		badge_fp.display()
+	);
+
+	Ok(())
+}
+
+/// Handles the CLI project configuration update.
+pub fn handle_update(data: UpdateData) -> Result<(), EigenError> {
+	// Overrides only apply to the current invocation
+	let mut config = load_profile(active_profile().as_deref())?;
+	if let Some(as_address) = data.as_address {
+		config.as_address = Address::from_str(&as_address)
+			.map_err(|e| EigenError::ParsingError(e.to_string()))?
+			.to_string();
+	}
+
+	if let Some(band_id) = data.band_id {
+		// TODO: Validate bandada group id type
+		config.band_id = band_id
+	}
+
+	if let Some(band_th) = data.band_th {
+		band_th.parse::<u32>().map_err(|e| EigenError::ParsingError(e.to_string()))?;
+		config.band_th = band_th;
+	}
+
+	if let Some(band_url) = data.band_url {
+		Http::from_str(&band_url).map_err(|e| EigenError::ParsingError(e.to_string()))?;
 		config.band_url = band_url;
 	}
 
@@ -660,32 +2612,402 @@ pub fn handle_update(data: UpdateData) -> Result<(), EigenError> {
 	}
 
 	if let Some(domain) = data.domain {
-		config.as_address = H160::from_str(&domain)
+		config.domain = H160::from_str(&domain)
 			.map_err(|e| EigenError::ParsingError(e.to_string()))?
 			.to_string();
 	}
 
 	if let Some(node_url) = data.node_url {
-		Http::from_str(&node_url).map_err(|e| EigenError::ParsingError(e.to_string()))?;
+		// IPC endpoints can only be checked when connecting
+		if ClientTransport::ipc_path(&node_url).is_none() {
+			Http::from_str(&node_url).map_err(|e| EigenError::ParsingError(e.to_string()))?;
+		}
 		config.node_url = node_url;
 	}
 
-	let filepath = get_file_path("config", FileType::Json)?;
-	let mut json_storage = JSONFileStorage::<CliConfig>::new(filepath);
+	// Domain parameter overrides are kept in the domain registry
+	if data.num_iterations.is_some() || data.initial_score.is_some() {
+		let mut registry = load_domain_registry()?;
+		let current = registry.params(config.domain()?);
+
+		let num_iterations = match data.num_iterations {
+			Some(n) => n.parse::<u64>().map_err(|e| EigenError::ParsingError(e.to_string()))?,
+			None => current.num_iterations,
+		};
+		let initial_score = match data.initial_score {
+			Some(s) => s.parse::<u128>().map_err(|e| EigenError::ParsingError(e.to_string()))?,
+			None => current.initial_score,
+		};
+
+		let params = DomainParams::new(num_iterations, initial_score);
+		registry.set_params(config.domain()?, params)?;
+		save_domain_registry(registry)?;
+
+		if !params.is_circuit() {
+			warn!(
+				"The domain parameters differ from the circuit ones, its scores can't be proven."
+			);
+		}
+	}
+
+	save_profile(active_profile().as_deref(), config)?;
+
+	Ok(())
+}
+
+/// Handles the `status` command.
+pub async fn handle_status(output: OutputFormat) -> Result<(), EigenError> {
+	let config = load_config()?;
+
+	// Missing local data isn't an error, and large attestation files are streamed
+	let att_fp = get_file_path("attestations", FileType::Csv)?;
+	let mut attestations = 0;
+	let mut last_synced_block = None;
+	if att_fp.exists() {
+		for record in CSVFileStorage::<AttestationRecord>::new(att_fp).load_iter()? {
+			let block = record?.block().ok().flatten().map(|(number, _)| number);
+			last_synced_block = last_synced_block.max(block);
+			attestations += 1;
+		}
+	}
+	let scores_updated_at = get_file_path("scores", FileType::Csv)?
+		.metadata()
+		.and_then(|metadata| metadata.modified())
+		.ok()
+		.and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+		.map(|elapsed| elapsed.as_secs());
+
+	let mut proofs = Vec::new();
+	for (circuit, params_k) in
+		[(Circuit::EigenTrust, ET_PARAMS_K), (Circuit::Threshold, TH_PARAMS_K)]
+	{
+		proofs.push(ProofStatus {
+			circuit: circuit.as_str(),
+			proof: EigenFile::Proof(circuit).exists()?
+				&& EigenFile::PublicInputs(circuit).exists()?,
+			verifier: EigenFile::KzgParams(params_k).exists()?
+				&& EigenFile::ProvingKey(circuit).exists()?,
+		});
+	}
+
+	// An unreachable node is reported, not failed on
+	let node = match Client::connect(
+		load_mnemonic(),
+		config.chain_id()?,
+		config.as_address()?,
+		config.domain()?,
+		&config.node_url,
+	)
+	.await
+	{
+		Ok(client) => {
+			let signer = client.get_signer();
+			match (signer.get_chainid().await, signer.get_block_number().await) {
+				(Ok(chain_id), Ok(block)) => Some((chain_id.to_string(), block.as_u64())),
+				(Err(e), _) | (_, Err(e)) => {
+					warn!("Node unreachable: {}", e);
+					None
+				},
+			}
+		},
+		Err(e) => {
+			warn!("Node unreachable: {}", e);
+			None
+		},
+	};
+
+	let status = Status {
+		profile: active_profile(),
+		as_address: config.as_address.clone(),
+		band_id: config.band_id.clone(),
+		chain_id: config.chain_id.clone(),
+		domain: config.domain.clone(),
+		node_url: config.node_url.clone(),
+		node_chain_id: node.as_ref().map(|(chain_id, _)| chain_id.clone()),
+		latest_block: node.map(|(_, block)| block),
+		attestations,
+		imported_attestations: load_imported_attestations()?.len(),
+		last_synced_block,
+		scores_updated_at,
+		proofs,
+	};
+	if let OutputFormat::Json = output {
+		return output.print(&status);
+	}
+
+	let or_none = |value: Option<String>| value.unwrap_or_else(|| "none".to_string());
+	let proofs: Vec<String> = status
+		.proofs
+		.iter()
+		.map(|proof| {
+			format!(
+				"{}: proof {}, verifier {}",
+				proof.circuit,
+				if proof.proof { "available" } else { "missing" },
+				if proof.verifier { "available" } else { "missing" }
+			)
+		})
+		.collect();
+	info!(
+		"Status:\n  profile: {}\n  AttestationStation: {}\n  bandada group: {}\n  domain: {}\n  \
+		 chain ID: {} (node: {})\n  node: {} (latest block: {})\n  attestations: {} indexed, {} \
+		 imported\n  last synced block: {}\n  scores computed at: {}\n  {}",
+		or_none(status.profile),
+		status.as_address,
+		status.band_id,
+		status.domain,
+		status.chain_id,
+		or_none(status.node_chain_id),
+		status.node_url,
+		or_none(status.latest_block.map(|block| block.to_string())),
+		status.attestations,
+		status.imported_attestations,
+		or_none(status.last_synced_block.map(|block| block.to_string())),
+		or_none(status.scores_updated_at.map(|time| format!("{} (Unix time)", time))),
+		proofs.join("\n  ")
+	);
+
+	Ok(())
+}
+
+/// Handles the `validate` command.
+pub fn handle_validate(data: ValidateData, output: OutputFormat) -> Result<(), EigenError> {
+	let config = load_config()?;
+	let domain = config.domain()?;
+	let filepath = match data.file {
+		Some(path) => PathBuf::from(path),
+		None => get_file_path("attestations", FileType::Csv)?,
+	};
+	let records = CSVFileStorage::<AttestationRecord>::new(filepath.clone()).load()?;
+
+	let invalid: Vec<InvalidRecord> = records
+		.iter()
+		.enumerate()
+		.map(|(i, record)| InvalidRecord { row: i + 1, issues: record.validate(domain) })
+		.filter(|record| !record.issues.is_empty())
+		.collect();
+	let report = ValidationReport { records: records.len(), invalid };
+
+	match output {
+		OutputFormat::Json => output.print(&report)?,
+		OutputFormat::Text => {
+			for record in &report.invalid {
+				let issues: Vec<String> = record.issues.iter().map(ToString::to_string).collect();
+				info!("Row {}: {}", record.row, issues.join(", "));
+			}
+			info!(
+				"{} of {} attestations in \"{}\" are valid.",
+				report.records - report.invalid.len(),
+				report.records,
+				filepath.display()
+			);
+		},
+	}
+
+	if !report.invalid.is_empty() {
+		return Err(EigenError::ValidationError(format!(
+			"{} invalid attestations found",
+			report.invalid.len()
+		)));
+	}
+
+	Ok(())
+}
+
+/// Handles the `verify-certificates` command.
+pub fn handle_verify_certificates(
+	data: VerifyCertificatesData, output: OutputFormat,
+) -> Result<(), EigenError> {
+	let certificates_fp = match data.file {
+		Some(path) => PathBuf::from(path),
+		None => get_file_path("score-certificates", FileType::Json)?,
+	};
+	let scores_fp = match data.scores {
+		Some(path) => PathBuf::from(path),
+		None => get_file_path("scores", FileType::Csv)?,
+	};
+	let certificates = JSONFileStorage::<Vec<ScoreCertificate>>::new(certificates_fp).load()?;
+	let scores_storage = CSVFileStorage::<ScoreRecord>::new(scores_fp.clone());
+	let provenance = Provenance::from_header(&scores_storage.load_header()?)?;
+	let scores = scores_storage.load()?;
+
+	let scorer = str_to_20_byte_array(data.scorer.as_deref().unwrap_or(&provenance.scorer))?;
+	let mut unverified = Vec::new();
+	for record in &scores {
+		let certificate = certificates
+			.iter()
+			.find(|certificate| certificate.member.eq_ignore_ascii_case(record.peer_address()));
+		let verified = certificate.map(|certificate| {
+			certificate.verify_score(
+				scorer,
+				provenance.epoch,
+				record.peer_address(),
+				record.score_fr(),
+			)
+		});
+
+		match verified {
+			Some(Ok(())) => {},
+			Some(Err(e)) => unverified.push(format!("{}: {}", record.peer_address(), e)),
+			None => unverified.push(format!("{}: missing certificate", record.peer_address())),
+		}
+	}
+
+	match output {
+		OutputFormat::Json => output.print(&unverified)?,
+		OutputFormat::Text => {
+			for issue in &unverified {
+				warn!("{}", issue);
+			}
+			info!(
+				"{} of {} scores in \"{}\" are certified by 0x{}.",
+				scores.len() - unverified.len(),
+				scores.len(),
+				scores_fp.display(),
+				hex::encode(scorer)
+			);
+		},
+	}
+
+	if !unverified.is_empty() {
+		return Err(EigenError::VerificationError(format!(
+			"{} scores without a valid certificate",
+			unverified.len()
+		)));
+	}
+
+	Ok(())
+}
+
+/// Handles the `watch` command.
+pub async fn handle_watch(data: WatchData) -> Result<(), EigenError> {
+	let interval = match data.interval {
+		Some(interval) => interval
+			.parse::<u64>()
+			.map_err(|e| EigenError::ParsingError(format!("Error parsing interval: {}", e)))?,
+		None => DEFAULT_WATCH_INTERVAL,
+	};
+	let interval = Duration::from_secs(interval);
+	let schedule = data.every.as_deref().map(Schedule::from_str).transpose()?;
+	let max_jitter = match data.jitter {
+		Some(jitter) => parse_duration(&jitter)?,
+		None => Duration::ZERO,
+	};
+
+	let config = load_config()?;
+	let mnemonic = load_mnemonic();
+	let client = Client::connect(
+		mnemonic,
+		config.chain_id()?,
+		config.as_address()?,
+		config.domain()?,
+		&config.node_url,
+	)
+	.await?;
+
+	info!(
+		"Watching AttestationCreated events of {}, press Ctrl+C to stop.",
+		config.as_address
+	);
+
+	// The scores are brought up to date with the stored attestations on start, unless
+	// they are scheduled
+	let mut pending = true;
+	let mut last_scores: Option<Instant> = None;
+	let mut next_run = match &schedule {
+		Some(schedule) => Some(next_scheduled_run(schedule, max_jitter)?),
+		None => None,
+	};
+	loop {
+		let stored = match sync_attestations(&client).await {
+			Ok((new_attestations, total)) => {
+				if new_attestations > 0 {
+					info!("Indexed {} new attestations.", new_attestations);
+					pending = true;
+				}
+				total
+			},
+			// Keep watching through transient node failures
+			Err(e) => {
+				warn!(code = e.code(), "Failed to sync attestations: {}", e);
+				0
+			},
+		};
+
+		let due = match next_run {
+			Some(next_run) => unix_now() >= next_run,
+			None => scores_due(pending, data.on_attestation, last_scores, interval),
+		};
+		if due && pending && stored > 0 {
+			match handle_scores(
+				AttestationsOrigin::Local,
+				ScoresData::default(),
+				OutputFormat::Text,
+			)
+			.await
+			{
+				Ok(()) => {
+					pending = false;
+					last_scores = Some(Instant::now());
+
+					if data.bandada_sync {
+						if let Err(e) = sync_bandada(&config, None).await {
+							warn!(code = e.code(), "Failed to sync the Bandada group: {}", e);
+						}
+					}
+				},
+				Err(e) => warn!(code = e.code(), "Failed to calculate scores: {}", e),
+			}
+		}
+		// The next run is scheduled once this one is over, so runs never overlap and the
+		// ones missed while running are skipped
+		match &schedule {
+			Some(schedule) if due => next_run = Some(next_scheduled_run(schedule, max_jitter)?),
+			_ => {},
+		}
+
+		sleep(Duration::from_secs(WATCH_POLL_INTERVAL)).await;
+	}
+}
+
+/// Returns the time of the next scheduled run after the current time, delayed by a random
+/// jitter, in seconds since the Unix epoch.
+fn next_scheduled_run(schedule: &Schedule, max_jitter: Duration) -> Result<u64, EigenError> {
+	let next_run = schedule.next_after(unix_now())?.saturating_add(jitter(max_jitter).as_secs());
+	debug!("Next scores recalculation at {} (Unix time).", next_run);
+
+	Ok(next_run)
+}
 
-	json_storage.save(config)
+/// Returns whether the scores must be recalculated. Scores are recalculated when new
+/// attestations were indexed since the last calculation, either right away or once the
+/// interval has elapsed.
+fn scores_due(
+	pending: bool, on_attestation: bool, last_scores: Option<Instant>, interval: Duration,
+) -> bool {
+	pending && (on_attestation || last_scores.map_or(true, |last| last.elapsed() >= interval))
 }
 
 /// Tries to load attestations from local storage. If no attestations are found,
 /// it fetches them from the AS contract.
 pub async fn load_or_fetch_attestations() -> Result<Vec<SignedAttestationRaw>, EigenError> {
+	dedup_policy()?
+		.apply(load_or_fetch_records().await?)
+		.into_iter()
+		.map(|record| record.try_into())
+		.collect()
+}
+
+/// Loads the local attestation records, fetching them from the AS contract if there are
+/// none.
+async fn load_or_fetch_records() -> Result<Vec<AttestationRecord>, EigenError> {
 	let att_file_path = get_file_path("attestations", FileType::Csv)?;
 	let att_storage = CSVFileStorage::<AttestationRecord>::new(att_file_path.clone());
 
 	match att_storage.load() {
 		Ok(local_records) => {
 			if !local_records.is_empty() {
-				return local_records.into_iter().map(|record| record.try_into()).collect();
+				return Ok(local_records);
 			}
 			debug!("No local attestations found. Fetching from AS contract.");
 		},
@@ -695,22 +3017,258 @@ pub async fn load_or_fetch_attestations() -> Result<Vec<SignedAttestationRaw>, E
 	}
 
 	// Fetch attestations from AS contract
-	handle_attestations().await?;
+	handle_attestations(AttestationsData::default(), OutputFormat::Text).await?;
+
+	att_storage.load()
+}
+
+/// Returns the attestation records scored by the `scores` command: the indexed ones merged
+/// with the ones imported from EAS, up to the epoch ending at the given block, if any, and
+/// deduplicated with the given policy.
+fn scoring_records(
+	indexed: Vec<AttestationRecord>, imported: Vec<AttestationRecord>, epoch_block: Option<u64>,
+	policy: DedupPolicy,
+) -> Vec<AttestationRecord> {
+	let mut records = indexed;
+	records.extend(imported);
+	if let Some(block) = epoch_block {
+		records.retain(|record| record.is_in_epoch(block));
+	}
+
+	policy.apply(records)
+}
+
+/// Loads the attestations to prove, the same ones the `scores` command scores, along with
+/// the contract wallet attester of every one, verified through EIP-1271 as when scoring
+/// them. Imported attestations are credited to their creator, so they can't be proven.
+async fn load_proving_attestations(
+	client: &Client,
+) -> Result<(Vec<SignedAttestationRaw>, Vec<Option<[u8; 20]>>), EigenError> {
+	let records = scoring_records(
+		load_or_fetch_records().await?,
+		load_imported_attestations()?,
+		None,
+		dedup_policy()?,
+	);
+	let creators: Vec<Option<[u8; 20]>> =
+		records.iter().map(AttestationRecord::creator).collect::<Result<_, _>>()?;
+	let attestations: Vec<SignedAttestationRaw> =
+		records.into_iter().map(|record| record.try_into()).collect::<Result<_, _>>()?;
+	let contract_attesters = client.contract_attesters(&attestations, &creators).await?;
+
+	Ok((attestations, contract_attesters))
+}
+
+/// Parses comma-separated seed addresses.
+fn parse_seeds(seeds: &str) -> Result<Vec<[u8; 20]>, EigenError> {
+	seeds.split(',').map(|seed| str_to_20_byte_array(seed.trim())).collect()
+}
+
+/// Parses comma-separated derivation indices.
+fn parse_indices(indices: &str) -> Result<Vec<u32>, EigenError> {
+	indices
+		.split(',')
+		.map(|index| {
+			index
+				.trim()
+				.parse::<u32>()
+				.map_err(|e| EigenError::ParsingError(format!("Error parsing index: {}", e)))
+		})
+		.collect()
+}
+
+/// Returns the leaderboard of the scores, from the highest to the lowest. Participants with
+/// equal scores share the same rank and are ordered by address.
+pub fn rank_scores(records: &[ScoreRecord]) -> Result<Vec<RankingRecord>, EigenError> {
+	let mut scores = records
+		.iter()
+		.map(|record| {
+			U256::from_dec_str(record.score())
+				.map(|score| (score, record.peer_address().to_lowercase()))
+				.map_err(|_| EigenError::ParsingError("Failed to parse score.".to_string()))
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	scores.sort_by(|(a_score, a_address), (b_score, b_address)| {
+		b_score.cmp(a_score).then_with(|| a_address.cmp(b_address))
+	});
+
+	let total = scores.iter().fold(U256::zero(), |total, (score, _)| {
+		total.saturating_add(*score)
+	});
+	let mut ranking: Vec<RankingRecord> = Vec::with_capacity(scores.len());
+	for (index, (score, peer_address)) in scores.into_iter().enumerate() {
+		let rank = match ranking.last() {
+			Some(previous) if previous.score == score.to_string() => previous.rank,
+			_ => index + 1,
+		};
+		let basis_points = if total.is_zero() {
+			U256::zero()
+		} else {
+			score.saturating_mul(U256::from(10_000)) / total
+		};
+		let share = format!(
+			"{}.{:02}",
+			basis_points / U256::from(100),
+			(basis_points % U256::from(100)).as_u32()
+		);
+
+		ranking.push(RankingRecord { rank, peer_address, score: score.to_string(), share });
+	}
+
+	Ok(ranking)
+}
+
+/// Returns the participants whose score or rank changed beyond the given thresholds between
+/// the old and the new scores, from the largest score change to the smallest. Participants
+/// only scored in one of them are always reported.
+pub fn diff_scores(
+	old: &[ScoreRecord], new: &[ScoreRecord], threshold: U256, rank_threshold: usize,
+) -> Result<Vec<ScoreChange>, EigenError> {
+	let parse_ranking = |records: &[ScoreRecord]| -> Result<BTreeMap<_, _>, EigenError> {
+		rank_scores(records)?
+			.into_iter()
+			.map(|row| {
+				let score = U256::from_dec_str(&row.score)
+					.map_err(|_| EigenError::ParsingError("Failed to parse score.".to_string()))?;
+				Ok((row.peer_address, (row.rank, score)))
+			})
+			.collect()
+	};
+	let old = parse_ranking(old)?;
+	let new = parse_ranking(new)?;
+	let addresses: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+
+	let mut changes = Vec::new();
+	for address in addresses {
+		let (old_rank, old_score) = old.get(address).copied().unzip();
+		let (new_rank, new_score) = new.get(address).copied().unzip();
+		let (old_value, new_value) = (old_score.unwrap_or_default(), new_score.unwrap_or_default());
+
+		let (difference, sign) = if new_value >= old_value {
+			(new_value - old_value, '+')
+		} else {
+			(old_value - new_value, '-')
+		};
+		let rank_difference = match (old_rank, new_rank) {
+			(Some(old_rank), Some(new_rank)) => Some(old_rank.abs_diff(new_rank)),
+			_ => None,
+		};
+		let changed = match rank_difference {
+			Some(rank_difference) => difference > threshold || rank_difference > rank_threshold,
+			None => true,
+		};
+
+		if changed {
+			let change = ScoreChange {
+				peer_address: address.clone(),
+				old_score: old_score.map(|score| score.to_string()),
+				new_score: new_score.map(|score| score.to_string()),
+				change: format!("{}{}", sign, difference),
+				old_rank,
+				new_rank,
+			};
+			changes.push((difference, change));
+		}
+	}
+	changes.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+	Ok(changes.into_iter().map(|(_, change)| change).collect())
+}
 
-	att_storage.load()?.into_iter().map(|record| record.try_into()).collect()
+/// Returns the scores of the epoch ending at the given block: the saved scores of the epoch,
+/// if any, else the scores of the saved attestations indexed up to the block.
+async fn epoch_scores(client: &Client, block: u64) -> Result<Vec<ScoreRecord>, EigenError> {
+	let epoch_fp = epoch_scores_path(block)?;
+	if epoch_fp.exists() {
+		debug!("Loading the saved scores of epoch {}.", block);
+		return CSVFileStorage::<ScoreRecord>::new(epoch_fp).load();
+	}
+
+	scores_at_block(client, block).await
+}
+
+/// Calculates the scores of the saved attestations indexed up to the given block, inclusive.
+/// Attestations without block data are always included.
+async fn scores_at_block(client: &Client, block: u64) -> Result<Vec<ScoreRecord>, EigenError> {
+	let att_fp = get_file_path("attestations", FileType::Csv)?;
+	let mut records = CSVFileStorage::<AttestationRecord>::new(att_fp).load()?;
+	records.extend(load_imported_attestations()?);
+	records.retain(|record| record.is_in_epoch(block));
+	let indexed = dedup_policy()?.apply(records);
+	if indexed.is_empty() {
+		return Err(EigenError::AttestationError(format!(
+			"No attestations found up to block {}.",
+			block
+		)));
+	}
+
+	let creators: Vec<Option<[u8; 20]>> =
+		indexed.iter().map(AttestationRecord::creator).collect::<Result<_, _>>()?;
+	let attestations: Vec<SignedAttestationRaw> =
+		indexed.into_iter().map(|record| record.try_into()).collect::<Result<_, _>>()?;
+	let contract_attesters = client.contract_attesters(&attestations, &creators).await?;
+	let scores =
+		client.calculate_scores_with_contract_attesters(attestations, &contract_attesters)?;
+
+	Ok(scores.into_iter().map(|score| ScoreRecord::from_score(score).with_epoch(block)).collect())
+}
+
+/// Returns the score record of the given address, along with its rank among all the
+/// participants, starting from 1. Participants with the same score share the same rank.
+pub fn score_rank<'a>(
+	records: &'a [ScoreRecord], address: &str,
+) -> Result<Option<(usize, &'a ScoreRecord)>, EigenError> {
+	let parse_score = |record: &ScoreRecord| {
+		U256::from_dec_str(record.score())
+			.map_err(|_| EigenError::ParsingError("Failed to parse score.".to_string()))
+	};
+
+	let record =
+		match records.iter().find(|record| record.peer_address().eq_ignore_ascii_case(address)) {
+			Some(record) => record,
+			None => return Ok(None),
+		};
+
+	let score = parse_score(record)?;
+	let mut rank = 1;
+	for other in records {
+		if parse_score(other)? > score {
+			rank += 1;
+		}
+	}
+
+	Ok(Some((rank, record)))
+}
+
+/// Returns the last block of the given attestation records, used as the scores epoch.
+/// Records without block data are ignored.
+fn attestations_epoch(records: &[AttestationRecord]) -> Result<u64, EigenError> {
+	let blocks = records.iter().map(AttestationRecord::block).collect::<Result<Vec<_>, _>>()?;
+
+	Ok(blocks.into_iter().flatten().map(|(number, _)| number).max().unwrap_or_default())
 }
 
 #[cfg(test)]
 mod tests {
 	use crate::{
-		cli::{AttestData, Cli},
+		cli::{
+			diff_scores, parse_indices, parse_seeds, rank_scores, score_rank, scores_due,
+			scoring_records, sign_offline, validate_attestation_rows, AttestData, AttestationRow,
+			AttestationsData, Cli, OutputFormat, RankedScore, SignedPayload,
+		},
 		CliConfig,
 	};
 	use clap::CommandFactory;
 	use eigentrust::{
-		attestation::AttestationRaw,
-		storage::{str_to_20_byte_array, str_to_32_byte_array},
+		attestation::{AttestationRaw, SignedAttestationRaw},
+		circuit::attestations_hash,
+		storage::{
+			str_to_20_byte_array, str_to_32_byte_array, AttestationRecord, DedupPolicy, ScoreRecord,
+		},
+		Client,
 	};
+	use ethers::types::{Address, U256};
+	use std::time::{Duration, Instant};
 
 	#[test]
 	fn test_cli() {
@@ -738,6 +3296,11 @@ mod tests {
 			address: Some(address.clone()),
 			score: Some(score),
 			message: Some(message.clone()),
+			relay: false,
+			dry_run: false,
+			sign_only: false,
+			from_payload: None,
+			file: None,
 		};
 
 		let attestation = data.to_attestation_raw(&config).unwrap();
@@ -753,4 +3316,304 @@ mod tests {
 
 		assert_eq!(attestation, expected_attestation);
 	}
+
+	#[test]
+	fn test_signed_payload_verify() {
+		let config = CliConfig {
+			as_address: "0x5fbdb2315678afecb367f032d93f642f64180aa3".to_string(),
+			band_id: "38922764296632428858395574229367".to_string(),
+			band_th: "500".to_string(),
+			band_url: "http://localhost:3000".to_string(),
+			chain_id: "31337".to_string(),
+			domain: "0x0000000000000000000000000000000000000000".to_string(),
+			node_url: "http://localhost:8545".to_string(),
+		};
+		let client = Client::offline(
+			"test test test test test test test test test test test junk".to_string(),
+			config.chain_id().unwrap(),
+			config.as_address().unwrap(),
+			config.domain().unwrap(),
+		);
+		let attestations = vec![
+			AttestationRaw::new([1; 20], [0; 20], 5, [0; 32]),
+			AttestationRaw::new([2; 20], [0; 20], 10, [0; 32]),
+		];
+
+		let signed_payload = sign_offline(&client, &config, attestations.clone()).unwrap();
+		let signed_attestations = signed_payload.verify(&client, &config).unwrap();
+		let verified: Vec<AttestationRaw> =
+			signed_attestations.into_iter().map(|signed| signed.attestation).collect();
+		assert_eq!(verified, attestations);
+
+		// Payloads for another network are rejected
+		let other_chain = SignedPayload { chain_id: 1, ..signed_payload.clone() };
+		assert!(other_chain.verify(&client, &config).is_err());
+
+		// Call data must submit the signed attestations
+		let mut tampered = signed_payload.clone();
+		tampered.payloads.pop();
+		assert!(tampered.verify(&client, &config).is_err());
+
+		// Attesters must match the signatures
+		let mut forged = signed_payload;
+		forged.payloads[0].attester = format!("{:?}", Address::from([3; 20]));
+		assert!(forged.verify(&client, &config).is_err());
+	}
+
+	#[test]
+	fn test_attestations_data_to_query() {
+		let address = "0x5fbdb2315678afecb367f032d93f642f64180aa3".to_string();
+
+		let query = AttestationsData::default().to_query().unwrap();
+		assert!(!query.is_partial());
+
+		let data = AttestationsData {
+			from: None,
+			about: Some(address.clone()),
+			from_block: Some("10".to_string()),
+			to_block: Some("20".to_string()),
+		};
+		let query = data.to_query().unwrap();
+		assert_eq!(query.creator, None);
+		assert_eq!(query.about, Some(str_to_20_byte_array(&address).unwrap()));
+		assert_eq!((query.from_block, query.to_block), (10, Some(20)));
+
+		// Empty ranges and invalid blocks are rejected
+		let data = AttestationsData { to_block: Some("5".to_string()), ..data };
+		assert!(data.to_query().is_err());
+		let data = AttestationsData { to_block: Some("latest".to_string()), ..data };
+		assert!(data.to_query().is_err());
+	}
+
+	#[test]
+	fn test_output_format() {
+		assert_eq!("json".parse::<OutputFormat>().unwrap(), OutputFormat::Json);
+		assert_eq!("text".parse::<OutputFormat>().unwrap(), OutputFormat::Text);
+		assert!("table".parse::<OutputFormat>().is_err());
+
+		// Ranked scores are flat JSON objects
+		let record = ScoreRecord::new(
+			"0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+			"0x00".to_string(),
+			"1500".to_string(),
+			"1".to_string(),
+			"1500".to_string(),
+		);
+		let ranked = RankedScore { record, rank: 1, participants: 4 };
+		let json = serde_json::to_value(&ranked).unwrap();
+		assert_eq!(json["score"], "1500");
+		assert_eq!(json["rank"], 1);
+	}
+
+	#[test]
+	fn test_scores_due() {
+		let interval = Duration::from_secs(300);
+
+		// Nothing new to score
+		assert!(!scores_due(false, true, None, interval));
+
+		// First calculation, or new attestations scored right away
+		assert!(scores_due(true, false, None, interval));
+		assert!(scores_due(true, true, Some(Instant::now()), interval));
+
+		// New attestations wait for the interval
+		assert!(!scores_due(true, false, Some(Instant::now()), interval));
+		assert!(scores_due(
+			true,
+			false,
+			Some(Instant::now()),
+			Duration::ZERO
+		));
+	}
+
+	#[test]
+	fn test_validate_attestation_rows() {
+		let config = CliConfig {
+			as_address: "test".to_string(),
+			band_id: "38922764296632428858395574229367".to_string(),
+			band_th: "500".to_string(),
+			band_url: "http://localhost:3000".to_string(),
+			chain_id: "31337".to_string(),
+			domain: "0x0000000000000000000000000000000000000000".to_string(),
+			node_url: "http://localhost:8545".to_string(),
+		};
+
+		let row = |address: &str, score: &str| AttestationRow {
+			address: address.to_string(),
+			score: score.to_string(),
+			message: None,
+		};
+		let rows = vec![
+			row("0x5fbdb2315678afecb367f032d93f642f64180aa3", "5"),
+			row("0x5fbdb2315678afecb367f032d93f642f64180aa3", "7"),
+			row("0x70997970c51812dc3a010c7d01b50e0d17dc79c8", "256"),
+			row("invalid", "5"),
+			row("0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc", "9"),
+		];
+
+		let (attestations, failures) = validate_attestation_rows(rows, &config);
+		assert_eq!(attestations.len(), 2);
+		assert_eq!(attestations[1].value(), 9);
+
+		let lines: Vec<usize> = failures.iter().map(|(line, _)| *line).collect();
+		assert_eq!(lines, vec![3, 4, 5]);
+	}
+
+	#[test]
+	fn test_score_rank() {
+		let record = |address: &str, score: &str| {
+			ScoreRecord::new(
+				address.to_string(),
+				"0x00".to_string(),
+				score.to_string(),
+				"1".to_string(),
+				score.to_string(),
+			)
+		};
+		let records = vec![
+			record("0x5fbdb2315678afecb367f032d93f642f64180aa3", "500"),
+			record("0x70997970c51812dc3a010c7d01b50e0d17dc79c8", "2000"),
+			record("0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc", "500"),
+		];
+
+		let (rank, top) =
+			score_rank(&records, "0x70997970C51812dc3A010C7d01b50e0d17dc79C8").unwrap().unwrap();
+		assert_eq!((rank, top.score().as_str()), (1, "2000"));
+
+		// Tied participants share the same rank
+		let (rank, _) =
+			score_rank(&records, "0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc").unwrap().unwrap();
+		assert_eq!(rank, 2);
+
+		assert!(
+			score_rank(&records, "0x0000000000000000000000000000000000000001").unwrap().is_none()
+		);
+	}
+
+	#[test]
+	fn test_rank_scores() {
+		let record = |address: &str, score: &str| {
+			ScoreRecord::new(
+				address.to_string(),
+				"0x00".to_string(),
+				score.to_string(),
+				"1".to_string(),
+				score.to_string(),
+			)
+		};
+		let records = vec![
+			record("0x5fbdb2315678afecb367f032d93f642f64180aa3", "500"),
+			record("0x70997970c51812dc3a010c7d01b50e0d17dc79c8", "3000"),
+			record("0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc", "500"),
+		];
+
+		let ranking = rank_scores(&records).unwrap();
+		let rows: Vec<(usize, &str, &str)> = ranking
+			.iter()
+			.map(|row| (row.rank, row.peer_address.as_str(), row.share.as_str()))
+			.collect();
+		assert_eq!(
+			rows,
+			vec![
+				(1, "0x70997970c51812dc3a010c7d01b50e0d17dc79c8", "75.00"),
+				(2, "0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc", "12.50"),
+				(2, "0x5fbdb2315678afecb367f032d93f642f64180aa3", "12.50"),
+			]
+		);
+
+		assert!(rank_scores(&[record("0x01", "high")]).is_err());
+	}
+
+	#[test]
+	fn test_diff_scores() {
+		let record = |address: &str, score: &str| {
+			ScoreRecord::new(
+				address.to_string(),
+				"0x00".to_string(),
+				score.to_string(),
+				"1".to_string(),
+				score.to_string(),
+			)
+		};
+		let old = vec![record("0x0a", "3000"), record("0x0b", "500"), record("0x0c", "500")];
+		let new = vec![record("0x0A", "2900"), record("0x0b", "900"), record("0x0d", "100")];
+
+		// Participants joining or leaving are always reported
+		let changes = diff_scores(&old, &new, U256::from(200), 0).unwrap();
+		let rows: Vec<(&str, &str, Option<usize>, Option<usize>)> = changes
+			.iter()
+			.map(|change| {
+				(
+					change.peer_address.as_str(),
+					change.change.as_str(),
+					change.old_rank,
+					change.new_rank,
+				)
+			})
+			.collect();
+		assert_eq!(
+			rows,
+			vec![
+				("0x0c", "-500", Some(2), None),
+				("0x0b", "+400", Some(2), Some(2)),
+				("0x0d", "+100", None, Some(3)),
+			]
+		);
+
+		// Rank changes are reported regardless of the score change
+		let changes = diff_scores(&old, &[record("0x0c", "600")], U256::MAX, 0).unwrap();
+		assert_eq!(changes.len(), 3);
+		let changes = diff_scores(&old, &old, U256::zero(), 0).unwrap();
+		assert!(changes.is_empty());
+	}
+
+	#[test]
+	fn test_parse_indices() {
+		assert_eq!(parse_indices("0").unwrap(), vec![0]);
+		assert_eq!(parse_indices("3, 1,2").unwrap(), vec![3, 1, 2]);
+		assert!(parse_indices("1,-2").is_err());
+		assert!(parse_indices("").is_err());
+	}
+
+	#[test]
+	fn test_parse_seeds() {
+		let seeds = "0x5fbdb2315678afecb367f032d93f642f64180aa3, 0x0000000000000000000000000000000000000001";
+		let parsed = parse_seeds(seeds).unwrap();
+		assert_eq!(parsed.len(), 2);
+		assert_eq!(parsed[1][19], 1);
+		assert!(parse_seeds("").is_err());
+		assert!(parse_seeds("0x01,").is_err());
+	}
+
+	#[test]
+	fn test_scoring_records() {
+		let client = Client::offline(
+			"test test test test test test test test test test test junk".to_string(),
+			31337,
+			[0; 20],
+			[0; 20],
+		);
+		let record = |about: u8, value: u8| {
+			let attestation = AttestationRaw::new([about; 20], [0; 20], value, [0; 32]);
+			AttestationRecord::from(client.sign_attestation(attestation).unwrap())
+		};
+		let hash = |records: Vec<AttestationRecord>| {
+			let attestations: Vec<SignedAttestationRaw> =
+				records.into_iter().map(|record| record.try_into().unwrap()).collect();
+			attestations_hash(&attestations)
+		};
+		let indexed = vec![record(1, 5), record(2, 10), record(1, 7)];
+		let imported = vec![record(3, 4)];
+
+		// Proofs cover the scored attestations, imports included
+		let scored = scoring_records(indexed.clone(), imported.clone(), None, DedupPolicy::Latest);
+		assert_eq!(scored.len(), 3);
+		assert_eq!(
+			hash(scored),
+			hash(vec![record(2, 10), record(1, 7), record(3, 4)])
+		);
+
+		let all = scoring_records(indexed, imported, None, DedupPolicy::KeepAll);
+		assert_eq!(all.len(), 4);
+	}
 }
@@ -2,20 +2,34 @@
 //!
 //! This module provides functionalities for filesystem actions.
 
-use crate::CliConfig;
+use crate::{CliConfig, CONFIG_FIELDS};
 use dotenv::{dotenv, var};
 use eigentrust::{
-	circuit::Circuit,
+	attestation::AggregationPolicy,
+	circuit::{Circuit, ProofMetadata},
+	domain::DomainRegistry,
 	error::EigenError,
-	storage::{BinFileStorage, JSONFileStorage, Storage},
+	graph::GraphFormat,
+	storage::{
+		ArtifactKind, Compression, DedupPolicy, EncryptionKey, FramedBinFileStorage,
+		JSONFileStorage, ProofStorage, Storage,
+	},
 };
-use log::warn;
-use std::{env::current_dir, path::PathBuf};
+use serde_json::Value;
+use std::{
+	env::{current_dir, set_var},
+	path::{Path, PathBuf},
+};
+use tracing::warn;
 
 /// Default mnemonic seed phrase.
 const DEFAULT_MNEMONIC: &str = "test test test test test test test test test test test junk";
 /// Library configuration file name.
 pub const CONFIG_FILE: &str = "config";
+/// Prefix of the environment variables overriding the configuration fields.
+pub const CONFIG_ENV_PREFIX: &str = "EIGENTRUST_";
+/// Domain registry file name.
+pub const DOMAINS_FILE: &str = "domains";
 /// Proof file name.
 pub const PROOF_FILE: &str = "proof";
 /// Proving key file name.
@@ -24,6 +38,10 @@ pub const PROVING_KEY_FILE: &str = "proving-key";
 pub const PUB_INP_FILE: &str = "public-inputs";
 /// KZG parameters file name.
 pub const PARAMS_FILE: &str = "kzg-params";
+/// Artifact metadata file name suffix.
+pub const METADATA_FILE: &str = "metadata";
+/// Proof artifact file name suffix.
+pub const PROOF_ARTIFACT_FILE: &str = "proof-artifact";
 
 /// Enum representing the possible file extensions.
 pub enum FileType {
@@ -33,6 +51,10 @@ pub enum FileType {
 	Json,
 	/// Binary file.
 	Bin,
+	/// SVG image file.
+	Svg,
+	/// Graph file of the given format.
+	Graph(GraphFormat),
 }
 
 impl FileType {
@@ -42,6 +64,8 @@ impl FileType {
 			FileType::Csv => "csv",
 			FileType::Json => "json",
 			FileType::Bin => "bin",
+			FileType::Svg => "svg",
+			FileType::Graph(format) => format.extension(),
 		}
 	}
 }
@@ -55,16 +79,42 @@ pub enum EigenFile {
 }
 
 impl EigenFile {
-	/// Loads the contents of the file.
+	/// Loads the contents of the file, checking its integrity.
 	pub fn load(&self) -> Result<Vec<u8>, EigenError> {
 		let filepath = self.path()?;
-		BinFileStorage::new(filepath).load()
+		FramedBinFileStorage::new(filepath, self.kind()).load()
+	}
+
+	/// Returns whether the file was generated.
+	pub fn exists(&self) -> Result<bool, EigenError> {
+		Ok(self.path()?.exists())
 	}
 
-	/// Saves the data to the file.
+	/// Saves the data to the file, along with its integrity header.
 	pub fn save(&self, data: Vec<u8>) -> Result<(), EigenError> {
 		let filepath = self.path()?;
-		BinFileStorage::new(filepath).save(data)
+		FramedBinFileStorage::new(filepath, self.kind()).save(data)
+	}
+
+	/// Saves the metadata of the artifact, generated for the given circuit.
+	pub fn save_metadata(&self, circuit: &Circuit) -> Result<(), EigenError> {
+		let filepath = self.metadata_path()?;
+		JSONFileStorage::<ProofMetadata>::new(filepath).save(ProofMetadata::new(circuit))
+	}
+
+	/// Checks the metadata of the artifact against the given circuit and the current
+	/// hasher parameters. Artifacts without metadata are accepted with a warning.
+	pub fn validate_metadata(&self, circuit: &Circuit) -> Result<(), EigenError> {
+		let filepath = self.metadata_path()?;
+		if !filepath.exists() {
+			warn!(
+				"No metadata found for \"{}\", skipping compatibility checks.",
+				self.filename()
+			);
+			return Ok(());
+		}
+
+		JSONFileStorage::<ProofMetadata>::new(filepath).load()?.validate(circuit)
 	}
 
 	/// Returns the path of the file.
@@ -72,6 +122,24 @@ impl EigenFile {
 		get_file_path(&self.filename(), FileType::Bin)
 	}
 
+	/// Returns the path of the file metadata.
+	fn metadata_path(&self) -> Result<PathBuf, EigenError> {
+		get_file_path(
+			&format!("{}-{}", self.filename(), METADATA_FILE),
+			FileType::Json,
+		)
+	}
+
+	/// Returns the kind of artifact stored in the file.
+	fn kind(&self) -> ArtifactKind {
+		match self {
+			EigenFile::KzgParams(_) => ArtifactKind::KzgParams,
+			EigenFile::ProvingKey(_) => ArtifactKind::ProvingKey,
+			EigenFile::Proof(_) => ArtifactKind::Proof,
+			EigenFile::PublicInputs(_) => ArtifactKind::PublicInputs,
+		}
+	}
+
 	/// Returns the filename of the file.
 	fn filename(&self) -> String {
 		match self {
@@ -83,6 +151,16 @@ impl EigenFile {
 	}
 }
 
+/// Returns the storage of the proof artifact of the given circuit.
+pub fn proof_storage(circuit: &Circuit) -> Result<ProofStorage, EigenError> {
+	let filepath = get_file_path(
+		&format!("{}-{}", circuit.as_str(), PROOF_ARTIFACT_FILE),
+		FileType::Json,
+	)?;
+
+	Ok(ProofStorage::new(filepath))
+}
+
 /// Loads the mnemonic from the environment file.
 pub fn load_mnemonic() -> String {
 	dotenv().ok();
@@ -108,18 +186,176 @@ pub fn get_assets_path() -> Result<PathBuf, EigenError> {
 	})
 }
 
-/// Helper function to get the path of a file in the `assets` directory.
+/// Returns the compression of the CSV files selected by the `COMPRESSION` environment
+/// variable, none if not set.
+pub fn csv_compression() -> Result<Compression, EigenError> {
+	dotenv().ok();
+	var("COMPRESSION").map_or(Ok(Compression::None), |compression| compression.parse())
+}
+
+/// Returns the key encrypting the sensitive files, given in hex by the `ENCRYPTION_KEY`
+/// environment variable or derived from the keystore at `ENCRYPTION_KEYSTORE`, decrypted
+/// with `ENCRYPTION_KEYSTORE_PASSWORD`. Sensitive files are saved unencrypted if neither is set.
+pub fn encryption_key() -> Result<Option<EncryptionKey>, EigenError> {
+	dotenv().ok();
+	if let Ok(key) = var("ENCRYPTION_KEY") {
+		return EncryptionKey::from_hex(&key).map(Some);
+	}
+
+	match var("ENCRYPTION_KEYSTORE") {
+		Ok(path) => {
+			let password = var("ENCRYPTION_KEYSTORE_PASSWORD").unwrap_or_default();
+			EncryptionKey::from_keystore(Path::new(&path), &password).map(Some)
+		},
+		Err(_) => Ok(None),
+	}
+}
+
+/// Returns the deduplication policy of the loaded attestations selected by the
+/// `DEDUP_POLICY` environment variable, keeping the latest attestation of every key if not
+/// set.
+pub fn dedup_policy() -> Result<DedupPolicy, EigenError> {
+	dotenv().ok();
+	var("DEDUP_POLICY").map_or(Ok(DedupPolicy::default()), |policy| policy.parse())
+}
+
+/// Returns the aggregation policy of the attestations given by the same attester to the
+/// same peer selected by the `AGGREGATION_POLICY` environment variable, keeping the latest
+/// attestation if not set. Warns when the deduplication policy leaves nothing to aggregate.
+pub fn aggregation_policy() -> Result<AggregationPolicy, EigenError> {
+	dotenv().ok();
+	let policy = var("AGGREGATION_POLICY")
+		.map_or(Ok(AggregationPolicy::default()), |policy| policy.parse())?;
+	if policy != AggregationPolicy::LatestWins && dedup_policy()? != DedupPolicy::KeepAll {
+		warn!("Attestations are only aggregated with DEDUP_POLICY=keep-all.");
+	}
+
+	Ok(policy)
+}
+
+/// Returns the path of the saved scores of the epoch ending at the given block, the
+/// `scores-<block>.csv` file of the `assets` directory.
+pub fn epoch_scores_path(block: u64) -> Result<PathBuf, EigenError> {
+	get_file_path(&format!("scores-{}", block), FileType::Csv)
+}
+
+/// Helper function to get the path of a file in the `assets` directory. CSV files get the
+/// extension of the configured compression, so they are compressed transparently.
 pub fn get_file_path(file_name: &str, file_type: FileType) -> Result<PathBuf, EigenError> {
 	let assets_path = get_assets_path()?;
-	Ok(assets_path.join(format!("{}.{}", file_name, file_type.as_str())))
+	let mut file_name = format!("{}.{}", file_name, file_type.as_str());
+	if let FileType::Csv = file_type {
+		if let Some(extension) = csv_compression()?.extension() {
+			file_name = format!("{}.{}", file_name, extension);
+		}
+	}
+
+	Ok(assets_path.join(file_name))
+}
+
+/// Returns the configuration profile selected by the `PROFILE` environment variable, if any.
+pub fn active_profile() -> Option<String> {
+	dotenv().ok();
+	var("PROFILE").ok().filter(|profile| !profile.is_empty())
 }
 
-/// Loads the configuration file.
+/// Returns the configuration file name of the given profile. Profiles are stored in
+/// `config-<profile>.json`, the default configuration in `config.json`.
+pub fn profile_file_name(profile: Option<&str>) -> Result<String, EigenError> {
+	match profile {
+		None => Ok(CONFIG_FILE.to_string()),
+		Some(profile)
+			if !profile.is_empty()
+				&& profile.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+		{
+			Ok(format!("{}-{}", CONFIG_FILE, profile))
+		},
+		Some(profile) => Err(EigenError::ValidationError(format!(
+			"Invalid profile name: \"{}\"",
+			profile
+		))),
+	}
+}
+
+/// Loads the configuration file of the active profile, along with the overrides of the
+/// environment.
 pub fn load_config() -> Result<CliConfig, EigenError> {
-	let filepath = get_file_path(CONFIG_FILE, FileType::Json)?;
+	override_config(load_profile(active_profile().as_deref())?)
+}
+
+/// Returns the environment variable overriding the given configuration field.
+pub fn config_env_var(field: &str) -> String {
+	format!("{}{}", CONFIG_ENV_PREFIX, field.to_uppercase())
+}
+
+/// Overrides the configuration fields set in the environment, as `EIGENTRUST_<FIELD>`.
+pub fn override_config(config: CliConfig) -> Result<CliConfig, EigenError> {
+	dotenv().ok();
+	let mut fields = serde_json::to_value(config)?;
+	if let Value::Object(fields) = &mut fields {
+		for (field, value) in fields.iter_mut() {
+			if let Ok(env_value) = var(config_env_var(field)) {
+				*value = Value::String(env_value);
+			}
+		}
+	}
+
+	Ok(serde_json::from_value(fields)?)
+}
+
+/// Sets the `field=value` configuration overrides given on the command line, taking
+/// precedence over the environment ones.
+pub fn set_config_overrides(overrides: &[String]) -> Result<(), EigenError> {
+	for config_override in overrides {
+		let (field, value) = config_override.split_once('=').ok_or_else(|| {
+			EigenError::ParsingError(format!(
+				"Invalid configuration override \"{}\", expected field=value",
+				config_override
+			))
+		})?;
+		if !CONFIG_FIELDS.contains(&field) {
+			return Err(EigenError::ValidationError(format!(
+				"Unknown configuration field: {}",
+				field
+			)));
+		}
+
+		set_var(config_env_var(field), value);
+	}
+
+	Ok(())
+}
+
+/// Loads the configuration file of the given profile.
+pub fn load_profile(profile: Option<&str>) -> Result<CliConfig, EigenError> {
+	let filepath = get_file_path(&profile_file_name(profile)?, FileType::Json)?;
 	JSONFileStorage::<CliConfig>::new(filepath).load()
 }
 
+/// Saves the configuration file of the given profile, returning its path.
+pub fn save_profile(profile: Option<&str>, config: CliConfig) -> Result<PathBuf, EigenError> {
+	let filepath = get_file_path(&profile_file_name(profile)?, FileType::Json)?;
+	JSONFileStorage::<CliConfig>::new(filepath.clone()).save(config)?;
+
+	Ok(filepath)
+}
+
+/// Loads the domain registry. Returns an empty registry if there's no registry file.
+pub fn load_domain_registry() -> Result<DomainRegistry, EigenError> {
+	let filepath = get_file_path(DOMAINS_FILE, FileType::Json)?;
+	if !filepath.exists() {
+		return Ok(DomainRegistry::new());
+	}
+
+	JSONFileStorage::<DomainRegistry>::new(filepath).load()
+}
+
+/// Saves the domain registry.
+pub fn save_domain_registry(registry: DomainRegistry) -> Result<(), EigenError> {
+	let filepath = get_file_path(DOMAINS_FILE, FileType::Json)?;
+	JSONFileStorage::<DomainRegistry>::new(filepath).save(registry)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -137,6 +373,17 @@ mod tests {
 		fs::remove_file(eigen_file.path().unwrap()).unwrap();
 	}
 
+	#[test]
+	fn test_eigenfile_metadata() {
+		let eigen_file = EigenFile::KzgParams(998);
+
+		eigen_file.save_metadata(&Circuit::EigenTrust).unwrap();
+		assert!(eigen_file.validate_metadata(&Circuit::EigenTrust).is_ok());
+		assert!(eigen_file.validate_metadata(&Circuit::Threshold).is_err());
+
+		fs::remove_file(eigen_file.metadata_path().unwrap()).unwrap();
+	}
+
 	#[test]
 	fn test_eigenfile_path_and_filename() {
 		let eigen_file = EigenFile::KzgParams(999);
@@ -145,4 +392,48 @@ mod tests {
 
 		assert!(path.to_string_lossy().contains(&filename));
 	}
+
+	#[test]
+	fn test_profile_file_name() {
+		assert_eq!(profile_file_name(None).unwrap(), "config");
+		assert_eq!(
+			profile_file_name(Some("sepolia")).unwrap(),
+			"config-sepolia"
+		);
+		assert_eq!(
+			profile_file_name(Some("local_2")).unwrap(),
+			"config-local_2"
+		);
+		assert!(profile_file_name(Some("../config")).is_err());
+		assert!(profile_file_name(Some("")).is_err());
+	}
+
+	#[test]
+	fn test_config_overrides() {
+		let config = CliConfig {
+			as_address: "0x5fbdb2315678afecb367f032d93f642f64180aa3".to_string(),
+			band_id: "51629751621128677209874422363557".to_string(),
+			band_th: "500".to_string(),
+			band_url: "http://localhost:3000".to_string(),
+			chain_id: "31337".to_string(),
+			domain: "0x0000000000000000000000000000000000000000".to_string(),
+			node_url: "http://localhost:8545".to_string(),
+		};
+
+		// Every configuration field can be overridden
+		let fields = serde_json::to_value(&config).unwrap();
+		let mut keys: Vec<&str> = fields.as_object().unwrap().keys().map(String::as_str).collect();
+		keys.sort();
+		assert_eq!(keys, CONFIG_FIELDS);
+
+		assert_eq!(config_env_var("node_url"), "EIGENTRUST_NODE_URL");
+		set_config_overrides(&["band_th=750".to_string()]).unwrap();
+		let overridden = override_config(config.clone()).unwrap();
+		assert_eq!(overridden.band_th, "750");
+		assert_eq!(overridden.node_url, config.node_url);
+		std::env::remove_var(config_env_var("band_th"));
+
+		assert!(set_config_overrides(&["band_th".to_string()]).is_err());
+		assert!(set_config_overrides(&["mnemonic=test".to_string()]).is_err());
+	}
 }
@@ -27,36 +27,134 @@
 mod bandada;
 mod cli;
 mod fs;
+mod relayer;
+mod schedule;
+mod server;
+mod shell;
 
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches};
 use cli::*;
 use dotenv::dotenv;
 use eigentrust::error::EigenError;
-use env_logger::{init_from_env, Env};
-use fs::load_config;
-use log::info;
+use fs::{load_config, set_config_overrides};
+use shell::run_shell;
+use std::{fs::OpenOptions, io::stderr, process::ExitCode, sync::Mutex};
+use tracing::{error, info, info_span, Instrument};
+use tracing_subscriber::{
+	fmt::{self, format::FmtSpan},
+	prelude::*,
+	EnvFilter,
+};
 
 #[tokio::main]
-async fn main() -> Result<(), EigenError> {
+async fn main() -> ExitCode {
 	dotenv().ok();
-	init_from_env(Env::default().filter_or("LOG_LEVEL", "info"));
+	let matches = Cli::command().get_matches();
+	let cli = match Cli::from_arg_matches(&matches) {
+		Ok(cli) => cli,
+		Err(e) => e.exit(),
+	};
+
+	if let Err(e) = init_logging(&cli) {
+		eprintln!("Error: {}", e);
+		return ExitCode::FAILURE;
+	}
+
+	// Logs of the command are grouped under its span, timed in the log file
+	let span = info_span!(
+		"command",
+		name = matches.subcommand_name().unwrap_or_default()
+	);
+	match run(cli).instrument(span).await {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(e) => {
+			let mut chain = e.chain().into_iter();
+			error!(code = e.code(), "{}", chain.next().unwrap_or_default());
+			for cause in chain {
+				error!(code = e.code(), "Caused by: {}", cause);
+			}
+
+			ExitCode::FAILURE
+		},
+	}
+}
+
+/// Sets up the terminal logs, and the `--log-file` ones if requested.
+fn init_logging(cli: &Cli) -> Result<(), EigenError> {
+	let filter = match &cli.verbosity {
+		Some(verbosity) => EnvFilter::try_new(verbosity)
+			.map_err(|e| EigenError::ParsingError(format!("Error parsing verbosity: {}", e)))?,
+		None => EnvFilter::try_from_env("LOG_LEVEL").unwrap_or_else(|_| EnvFilter::new("info")),
+	};
+
+	// Logs don't mix with the JSON output
+	let terminal = match cli.output_format() {
+		Ok(OutputFormat::Json) => fmt::layer().with_writer(stderr).boxed(),
+		_ => fmt::layer().boxed(),
+	};
+	let file = match &cli.log_file {
+		Some(path) => {
+			let file = OpenOptions::new().create(true).append(true).open(path)?;
+			let layer = fmt::layer()
+				.with_ansi(false)
+				.with_span_events(FmtSpan::CLOSE)
+				.with_writer(Mutex::new(file));
+			Some(layer)
+		},
+		None => None,
+	};
+
+	tracing_subscriber::registry()
+		.with(filter)
+		.with(terminal)
+		.with(file)
+		.try_init()
+		.map_err(|e| EigenError::UnknownError(e.to_string()))
+}
+
+/// Runs the given command.
+async fn run(cli: Cli) -> Result<(), EigenError> {
+	let output = cli.output_format()?;
+	set_config_overrides(&cli.overrides)?;
 
-	match Cli::parse().mode {
-		Mode::Attest(attest_data) => handle_attest(attest_data).await?,
-		Mode::Attestations => handle_attestations().await?,
+	match cli.mode {
+		Mode::Attest(attest_data) => handle_attest(attest_data, output).await?,
+		Mode::Attestations(data) => handle_attestations(data, output).await?,
+		Mode::Badge(badge_data) => handle_badge(badge_data)?,
 		Mode::Bandada(bandada_data) => handle_bandada(bandada_data).await?,
-		Mode::Deploy => handle_deploy().await?,
+		Mode::Deploy(deploy_data) => handle_deploy(deploy_data).await?,
+		Mode::DiffScores(diff_scores_data) => handle_diff_scores(diff_scores_data, output).await?,
 		Mode::ETProof => handle_et_proof().await?,
 		Mode::ETProvingKey => handle_et_pk().await?,
 		Mode::ETVerify => handle_et_verify().await?,
+		Mode::GasReport(gas_report_data) => handle_gas_report(gas_report_data, output).await?,
+		Mode::Import(import_data) => handle_import(import_data).await?,
+		Mode::Key(key_data) => handle_key(key_data)?,
 		Mode::KZGParams(kzg_params_data) => handle_params(kzg_params_data)?,
-		Mode::LocalScores => handle_scores(AttestationsOrigin::Local).await?,
-		Mode::Scores => handle_scores(AttestationsOrigin::Fetch).await?,
-		Mode::Show => info!("Client config:\n{:#?}", load_config()?),
+		Mode::LocalScores(scores_data) => {
+			handle_scores(AttestationsOrigin::Local, scores_data, output).await?
+		},
+		Mode::Replay(replay_data) => handle_replay(replay_data).await?,
+		Mode::Scores(scores_data) => {
+			handle_scores(AttestationsOrigin::Fetch, scores_data, output).await?
+		},
+		Mode::Serve(serve_data) => handle_serve(serve_data).await?,
+		Mode::Shell => run_shell().await?,
+		Mode::Show => match output {
+			OutputFormat::Json => output.print(&load_config()?)?,
+			OutputFormat::Text => info!("Client config:\n{:#?}", load_config()?),
+		},
+		Mode::ShowScore(show_score_data) => handle_show_score(show_score_data, output).await?,
+		Mode::Simulate(simulate_data) => handle_simulate(simulate_data, output)?,
+		Mode::Status => handle_status(output).await?,
+		Mode::State(state_data) => handle_state(state_data).await?,
 		Mode::ThProof(th_proof_data) => handle_th_proof(th_proof_data).await?,
 		Mode::ThProvingKey => handle_th_pk().await?,
 		Mode::ThVerify => handle_th_verify().await?,
 		Mode::Update(update_data) => handle_update(update_data)?,
+		Mode::Validate(validate_data) => handle_validate(validate_data, output)?,
+		Mode::VerifyCertificates(verify_data) => handle_verify_certificates(verify_data, output)?,
+		Mode::Watch(watch_data) => handle_watch(watch_data).await?,
 	};
 
 	Ok(())
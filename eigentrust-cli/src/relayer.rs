@@ -0,0 +1,37 @@
+//! # Relayer API module.
+//!
+//! External relayer handling module. Relayers submit attestations signed off-chain
+//! on behalf of their attesters.
+
+use eigentrust::{attestation::RelayPayload, error::EigenError};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use reqwest::{Client, Response};
+
+/// Relayer API client.
+pub struct RelayerApi {
+	url: String,
+	token: Option<String>,
+	client: Client,
+}
+
+impl RelayerApi {
+	/// Creates a new `RelayerApi`, authenticating with the given bearer token, if any.
+	pub fn new(url: &str, token: Option<String>) -> Self {
+		Self { url: url.to_string(), token, client: Client::new() }
+	}
+
+	/// Submits the signed attestation payload to the relayer.
+	pub async fn submit(&self, payload: &RelayPayload) -> Result<Response, EigenError> {
+		let mut headers = HeaderMap::new();
+		headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+		let body = serde_json::to_string(payload)?;
+
+		let mut request = self.client.post(&self.url).headers(headers).body(body);
+		if let Some(token) = &self.token {
+			request = request.bearer_auth(token);
+		}
+
+		request.send().await.map_err(|e| EigenError::RequestError(e.to_string()))
+	}
+}
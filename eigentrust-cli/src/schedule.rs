@@ -0,0 +1,311 @@
+//! # Schedule module.
+//!
+//! Schedules of the `watch` command recalculations, either a fixed period (`10m`) or a
+//! five-field cron expression (`*/15 * * * *`) evaluated in UTC.
+
+use eigentrust::error::EigenError;
+use std::{
+	str::FromStr,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Seconds in a minute.
+const MINUTE: u64 = 60;
+/// Seconds in a day.
+const DAY: u64 = 86_400;
+/// Number of minutes searched for the next cron run, a bit more than 4 years so that
+/// expressions matching only on leap days are found.
+const CRON_SEARCH_MINUTES: u64 = 4 * 366 * 24 * 60;
+
+/// Parses a duration such as `90`, `30s`, `10m`, `2h` or `1d`. Plain numbers are seconds.
+pub fn parse_duration(s: &str) -> Result<Duration, EigenError> {
+	let s = s.trim();
+	let (value, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+		Some(index) => s.split_at(index),
+		None => (s, "s"),
+	};
+	let multiplier = match unit {
+		"s" => 1,
+		"m" => MINUTE,
+		"h" => 60 * MINUTE,
+		"d" => DAY,
+		_ => {
+			return Err(EigenError::ParsingError(format!(
+				"Invalid duration unit: {}",
+				s
+			)))
+		},
+	};
+	let value = value
+		.parse::<u64>()
+		.map_err(|e| EigenError::ParsingError(format!("Error parsing duration: {}", e)))?;
+
+	Ok(Duration::from_secs(value.saturating_mul(multiplier)))
+}
+
+/// Returns the current time, in seconds since the Unix epoch.
+pub fn unix_now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Returns a delay between 0 and the given maximum, drawn from the clock, so that
+/// watchers sharing a schedule don't all run at once.
+pub fn jitter(max: Duration) -> Duration {
+	let max_millis = u64::try_from(max.as_millis()).unwrap_or(u64::MAX);
+	if max_millis == 0 {
+		return Duration::ZERO;
+	}
+
+	let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().subsec_nanos();
+	Duration::from_millis(u64::from(nanos) % max_millis)
+}
+
+/// Recalculation schedule.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Schedule {
+	/// Runs at a fixed period.
+	Every(Duration),
+	/// Runs at the minutes matching a cron expression.
+	Cron(CronExpression),
+}
+
+impl Schedule {
+	/// Returns the time of the first run strictly after the given time, in seconds since
+	/// the Unix epoch.
+	pub fn next_after(&self, now: u64) -> Result<u64, EigenError> {
+		match self {
+			Schedule::Every(period) => Ok(now.saturating_add(period.as_secs().max(1))),
+			Schedule::Cron(expression) => expression.next_after(now).ok_or_else(|| {
+				EigenError::ValidationError("The cron expression never matches".to_string())
+			}),
+		}
+	}
+}
+
+/// Field of a cron expression, as the set of matching values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct CronField {
+	/// Matching values, one bit per value.
+	values: u64,
+	/// Whether the field is restricted, i.e. not `*`.
+	restricted: bool,
+}
+
+impl CronField {
+	/// Parses a field whose values are in the given inclusive range. Supports `*`, values,
+	/// ranges (`1-5`), steps (`*/15`, `0-30/10`) and comma-separated lists of them.
+	fn parse(field: &str, min: u64, max: u64) -> Result<Self, EigenError> {
+		let parse_err = || EigenError::ParsingError(format!("Invalid cron field: {}", field));
+		let parse_value = |value: &str| {
+			value
+				.parse::<u64>()
+				.ok()
+				.filter(|value| (min..=max).contains(value))
+				.ok_or_else(parse_err)
+		};
+
+		let mut values = 0;
+		for part in field.split(',') {
+			let (range, step) = match part.split_once('/') {
+				Some((range, step)) => (
+					range,
+					step.parse::<u64>().ok().filter(|step| *step > 0).ok_or_else(parse_err)?,
+				),
+				None => (part, 1),
+			};
+			let (start, end) = match range {
+				"*" => (min, max),
+				_ => match range.split_once('-') {
+					Some((start, end)) => (parse_value(start)?, parse_value(end)?),
+					// A single value with a step runs up to the maximum
+					None if step > 1 => (parse_value(range)?, max),
+					None => (parse_value(range)?, parse_value(range)?),
+				},
+			};
+			if start > end {
+				return Err(parse_err());
+			}
+
+			for value in (start..=end).step_by(usize::try_from(step).map_err(|_| parse_err())?) {
+				values |= 1 << value;
+			}
+		}
+
+		Ok(Self { values, restricted: field != "*" })
+	}
+
+	/// Returns whether the field matches the value.
+	fn matches(&self, value: u64) -> bool {
+		value < 64 && self.values & (1 << value) != 0
+	}
+}
+
+/// Five-field cron expression: minute, hour, day of the month, month and day of the week
+/// (0-7, 0 and 7 being Sunday). Like cron, a day matches either of the day fields when
+/// both are restricted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronExpression {
+	minute: CronField,
+	hour: CronField,
+	day_of_month: CronField,
+	month: CronField,
+	day_of_week: CronField,
+}
+
+impl CronExpression {
+	/// Returns the first matching minute strictly after the given time, in seconds since
+	/// the Unix epoch, if any.
+	pub fn next_after(&self, now: u64) -> Option<u64> {
+		let mut minute = now / MINUTE + 1;
+		let last = minute.saturating_add(CRON_SEARCH_MINUTES);
+
+		while minute < last {
+			let time = minute * MINUTE;
+			if !self.matches_day(time / DAY) {
+				// Skip to the next day
+				minute = (time / DAY + 1) * DAY / MINUTE;
+				continue;
+			}
+
+			let minute_of_day = (time % DAY) / MINUTE;
+			if self.hour.matches(minute_of_day / 60) && self.minute.matches(minute_of_day % 60) {
+				return Some(time);
+			}
+			minute += 1;
+		}
+
+		None
+	}
+
+	/// Returns whether the day, counted from the Unix epoch, matches the expression.
+	fn matches_day(&self, days: u64) -> bool {
+		let (month, day) = month_and_day(days);
+		// The epoch was a Thursday
+		let weekday = (days + 4) % 7;
+
+		let day_of_month = self.day_of_month.matches(day);
+		let day_of_week = self.day_of_week.matches(weekday);
+		let day_matches = match (self.day_of_month.restricted, self.day_of_week.restricted) {
+			(true, true) => day_of_month || day_of_week,
+			_ => day_of_month && day_of_week,
+		};
+
+		day_matches && self.month.matches(month)
+	}
+}
+
+impl FromStr for CronExpression {
+	type Err = EigenError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let fields: Vec<&str> = s.split_whitespace().collect();
+		let [minute, hour, day_of_month, month, day_of_week] = fields.as_slice() else {
+			return Err(EigenError::ParsingError(format!(
+				"Invalid cron expression, expected 5 fields: {}",
+				s
+			)));
+		};
+
+		let mut day_of_week = CronField::parse(day_of_week, 0, 7)?;
+		// 7 is also Sunday
+		if day_of_week.matches(7) {
+			day_of_week.values |= 1;
+		}
+
+		Ok(Self {
+			minute: CronField::parse(minute, 0, 59)?,
+			hour: CronField::parse(hour, 0, 23)?,
+			day_of_month: CronField::parse(day_of_month, 1, 31)?,
+			month: CronField::parse(month, 1, 12)?,
+			day_of_week,
+		})
+	}
+}
+
+impl FromStr for Schedule {
+	type Err = EigenError;
+
+	/// Parses a cron expression if the schedule has several fields, a period otherwise.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.split_whitespace().count() > 1 {
+			let expression: CronExpression = s.parse()?;
+			let schedule = Schedule::Cron(expression);
+			schedule.next_after(0)?;
+
+			return Ok(schedule);
+		}
+
+		let period = parse_duration(s)?;
+		if period.is_zero() {
+			return Err(EigenError::ValidationError(
+				"The schedule period must be positive".to_string(),
+			));
+		}
+
+		Ok(Schedule::Every(period))
+	}
+}
+
+/// Returns the month (1-12) and the day of the month (1-31) of the day counted from the
+/// Unix epoch, in the proleptic Gregorian calendar.
+fn month_and_day(days: u64) -> (u64, u64) {
+	// Days since 0000-03-01, so that leap days end the years
+	let days = days + 719_468;
+	let day_of_era = days % 146_097;
+	let year_of_era =
+		(day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let month_index = (5 * day_of_year + 2) / 153;
+
+	let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+	let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+
+	(month, day)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_schedule() {
+		assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+		assert_eq!(parse_duration("10m").unwrap(), Duration::from_secs(600));
+		assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7200));
+		assert!(parse_duration("10w").is_err());
+
+		assert_eq!(
+			"10m".parse::<Schedule>().unwrap(),
+			Schedule::Every(Duration::from_secs(600))
+		);
+		assert!("0s".parse::<Schedule>().is_err());
+		assert!("*/15 * * * *".parse::<Schedule>().is_ok());
+		assert!("60 * * * *".parse::<Schedule>().is_err());
+		assert!("* * *".parse::<Schedule>().is_err());
+		assert!("0 0 31 2 *".parse::<Schedule>().is_err());
+	}
+
+	#[test]
+	fn test_cron_next_after() {
+		// 2023-07-14 10:07:30 UTC, a Friday
+		let now = 1_689_329_250;
+
+		let every_quarter: CronExpression = "*/15 * * * *".parse().unwrap();
+		assert_eq!(every_quarter.next_after(now), Some(1_689_329_700));
+
+		// Next Monday at 09:30
+		let weekly: CronExpression = "30 9 * * 1".parse().unwrap();
+		assert_eq!(weekly.next_after(now), Some(1_689_586_200));
+
+		// Sundays match both 0 and 7
+		let sunday: CronExpression = "0 0 * * 7".parse().unwrap();
+		assert_eq!(sunday.next_after(now), Some(1_689_465_600));
+
+		// Leap days are found
+		let leap_day: CronExpression = "0 12 29 2 *".parse().unwrap();
+		assert_eq!(leap_day.next_after(now), Some(1_709_208_000));
+
+		assert_eq!(month_and_day(0), (1, 1));
+		assert_eq!(month_and_day(19_416), (2, 28));
+	}
+}
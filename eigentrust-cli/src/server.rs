@@ -0,0 +1,445 @@
+//! # Server module.
+//!
+//! Local HTTP API serving the stored attestations and scores, the reputation badges and
+//! the verification of the stored proofs, so web front-ends can query reputation without
+//! indexing the attestations themselves. The routes match the `eigentrust::sdk` client.
+
+use crate::{
+	cli::verify_proof,
+	fs::{epoch_scores_path, get_file_path, load_config, FileType},
+};
+use dotenv::{dotenv, var};
+use eigentrust::{
+	attestation::RelayPayload,
+	badge::{Badge, BadgeFormat},
+	circuit::Circuit,
+	error::EigenError,
+	graph::{TrustGraph, DEFAULT_SYBIL_SHARE},
+	storage::{
+		normalize_scores, parse_normalization, str_to_20_byte_array, AttestationIndex,
+		AttestationRecord, CSVFileStorage, ScoreRecord, Storage,
+	},
+	Client,
+};
+use ethers::types::Address;
+use hyper::{
+	body::HttpBody,
+	header::{AUTHORIZATION, CONTENT_TYPE},
+	service::{make_service_fn, service_fn},
+	Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use std::{convert::Infallible, io::ErrorKind, net::SocketAddr, sync::Arc};
+use tracing::{debug, info, warn};
+
+/// Default server address.
+pub const DEFAULT_SERVER_ADDRESS: &str = "127.0.0.1:3000";
+/// Maximum size of a request body, in bytes.
+const MAX_BODY_SIZE: usize = 16 * 1024;
+
+/// Error response body.
+#[derive(Serialize)]
+struct ErrorResponse {
+	code: &'static str,
+	error: String,
+}
+
+/// Status response body.
+#[derive(Serialize)]
+struct StatusResponse {
+	status: &'static str,
+}
+
+/// Relayer of the `POST /relay` route, submitting the attestations of the callers holding
+/// its bearer token from the `RELAYER_MNEMONIC` account.
+pub struct Relayer {
+	client: Client,
+	token: String,
+}
+
+impl Relayer {
+	/// Creates a relayer submitting from the given client's account, for the callers
+	/// holding the given token.
+	pub fn new(client: Client, token: String) -> Self {
+		Self { client, token }
+	}
+
+	/// Connects the `RELAYER_MNEMONIC` account, accepting the `RELAYER_TOKEN` bearer token.
+	pub async fn from_env() -> Result<Self, EigenError> {
+		dotenv().ok();
+		let (mnemonic, token) = match (var("RELAYER_MNEMONIC"), var("RELAYER_TOKEN")) {
+			(Ok(mnemonic), Ok(token)) if !token.is_empty() => (mnemonic, token),
+			_ => {
+				return Err(EigenError::ConfigurationError(
+					"Relaying requires RELAYER_MNEMONIC and RELAYER_TOKEN to be set.".to_string(),
+				))
+			},
+		};
+
+		let config = load_config()?;
+		let client = Client::connect(
+			mnemonic,
+			config.chain_id()?,
+			config.as_address()?,
+			config.domain()?,
+			&config.node_url,
+		)
+		.await?;
+
+		Ok(Self::new(client, token))
+	}
+
+	/// Returns whether the request carries the relayer's bearer token.
+	fn is_authorized(&self, req: &Request<Body>) -> bool {
+		let token = req
+			.headers()
+			.get(AUTHORIZATION)
+			.and_then(|value| value.to_str().ok())
+			.and_then(|value| value.strip_prefix("Bearer "))
+			.unwrap_or_default();
+
+		// Compares every byte, so the time taken doesn't leak the matching prefix
+		token.len() == self.token.len()
+			&& token.bytes().zip(self.token.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+	}
+}
+
+/// Serves the API on the given address until the process is stopped. The `POST /relay`
+/// route is only served with a relayer.
+pub async fn serve(addr: SocketAddr, relayer: Option<Relayer>) -> Result<(), EigenError> {
+	let relayer = Arc::new(relayer);
+	let make_service = make_service_fn(move |_| {
+		let relayer = relayer.clone();
+		async move { Ok::<_, Infallible>(service_fn(move |req| handle_request(req, relayer.clone()))) }
+	});
+
+	let server = Server::try_bind(&addr).map_err(|e| EigenError::ConnectionError(e.to_string()))?;
+	info!("Serving the EigenTrust API at http://{}", addr);
+
+	server.serve(make_service).await.map_err(|e| EigenError::NetworkError(e.to_string()))
+}
+
+/// Handles a request, turning errors into JSON error responses.
+async fn handle_request(
+	req: Request<Body>, relayer: Arc<Option<Relayer>>,
+) -> Result<Response<Body>, Infallible> {
+	let (method, path) = (req.method().clone(), req.uri().path().to_string());
+
+	let response = match route(req, relayer.as_ref().as_ref()).await {
+		Ok(response) => response,
+		Err(e) => {
+			warn!(%method, %path, code = e.code(), "{}", e);
+			error_response(&e)
+		},
+	};
+	debug!(%method, %path, status = %response.status(), "Handled request");
+
+	Ok(response)
+}
+
+/// Routes the request to its handler.
+async fn route(
+	req: Request<Body>, relayer: Option<&Relayer>,
+) -> Result<Response<Body>, EigenError> {
+	let method = req.method().clone();
+	let path = req.uri().path().trim_matches('/').to_string();
+	let query = req.uri().query().unwrap_or_default().to_string();
+	let segments: Vec<&str> = path.split('/').collect();
+
+	match (method, segments.as_slice()) {
+		(Method::GET, ["health"]) => {
+			json_response(StatusCode::OK, &StatusResponse { status: "ok" })
+		},
+		(Method::GET, ["attestations"]) => json_response(StatusCode::OK, &load_attestations()?),
+		(Method::GET, ["attestations", address]) => {
+			let filepath = get_file_path("attestations", FileType::Csv)?;
+			let records = CSVFileStorage::<AttestationRecord>::new(filepath)
+				.attestations_about(str_to_20_byte_array(address)?)?;
+			json_response(StatusCode::OK, &records)
+		},
+		(Method::GET, ["scores"]) => {
+			let records = match query_param(&query, "epoch") {
+				Some(epoch) => {
+					let epoch = epoch.parse::<u64>().map_err(|e| {
+						EigenError::ParsingError(format!("Error parsing epoch: {}", e))
+					})?;
+					let filepath = epoch_scores_path(epoch)?;
+					if !filepath.exists() {
+						return Err(EigenError::ResourceUnavailableError(format!(
+							"No saved scores for epoch {}",
+							epoch
+						)));
+					}
+
+					CSVFileStorage::<ScoreRecord>::new(filepath).load()?
+				},
+				None => load_scores()?,
+			};
+
+			match query_param(&query, "normalize") {
+				Some(normalization) => json_response(
+					StatusCode::OK,
+					&normalize_scores(&records, parse_normalization(normalization)?)?,
+				),
+				None => json_response(StatusCode::OK, &records),
+			}
+		},
+		(Method::GET, ["scores", address]) => {
+			let record = load_scores()?
+				.into_iter()
+				.find(|record| record.peer_address().eq_ignore_ascii_case(address))
+				.ok_or_else(|| {
+					EigenError::ResourceUnavailableError(format!("No score for {}", address))
+				})?;
+			json_response(StatusCode::OK, &record)
+		},
+		(Method::GET, ["sybil"]) => {
+			let min_internal_share = match query_param(&query, "share") {
+				Some(share) => parse_share(share)?,
+				None => DEFAULT_SYBIL_SHARE,
+			};
+
+			let graph = TrustGraph::new(load_attestations()?.iter().filter_map(attestation_edge));
+			json_response(StatusCode::OK, &graph.sybil_report(min_internal_share))
+		},
+		(Method::GET, ["badge", address]) => {
+			let format: BadgeFormat = match query_param(&query, "format") {
+				Some(format) => format.parse()?,
+				None => BadgeFormat::default(),
+			};
+			let content_type = match format {
+				BadgeFormat::Json => "application/json",
+				BadgeFormat::Svg => "image/svg+xml",
+			};
+
+			let badge = Badge::from_records(&load_scores()?, address)?;
+			response(StatusCode::OK, content_type, badge.render(format)?)
+		},
+		(Method::GET, ["verify", circuit]) => {
+			let circuit = match *circuit {
+				"et" => Circuit::EigenTrust,
+				"th" => Circuit::Threshold,
+				_ => {
+					return Err(EigenError::ValidationError(format!(
+						"Invalid circuit: {}",
+						circuit
+					)))
+				},
+			};
+
+			verify_proof(circuit).await?;
+			json_response(StatusCode::OK, &StatusResponse { status: "verified" })
+		},
+		(Method::POST, ["relay"]) if relayer.is_some() => {
+			let relayer = relayer.expect("Relayer is set");
+			if !relayer.is_authorized(&req) {
+				let body = ErrorResponse {
+					code: "E_UNAUTHORIZED",
+					error: "Missing or invalid relayer token".to_string(),
+				};
+				return json_response(StatusCode::UNAUTHORIZED, &body);
+			}
+
+			let body = read_body(req.into_body()).await?;
+			let payload: RelayPayload = serde_json::from_slice(&body)?;
+
+			relayer.client.relay(vec![payload.to_signed()?]).await?;
+			json_response(StatusCode::ACCEPTED, &StatusResponse { status: "relayed" })
+		},
+		_ => Err(EigenError::ResourceUnavailableError(format!(
+			"No route for /{}",
+			path
+		))),
+	}
+}
+
+/// Reads the request body, rejecting bodies larger than `MAX_BODY_SIZE`.
+async fn read_body(mut body: Body) -> Result<Vec<u8>, EigenError> {
+	if body.size_hint().lower() > MAX_BODY_SIZE as u64 {
+		return Err(EigenError::ValidationError(format!(
+			"Request body exceeds {} bytes",
+			MAX_BODY_SIZE
+		)));
+	}
+
+	let mut bytes = Vec::new();
+	while let Some(chunk) = body.data().await {
+		let chunk = chunk.map_err(|e| EigenError::RequestError(e.to_string()))?;
+		if bytes.len() + chunk.len() > MAX_BODY_SIZE {
+			return Err(EigenError::ValidationError(format!(
+				"Request body exceeds {} bytes",
+				MAX_BODY_SIZE
+			)));
+		}
+		bytes.extend_from_slice(&chunk);
+	}
+
+	Ok(bytes)
+}
+
+/// Loads the stored attestations.
+fn load_attestations() -> Result<Vec<AttestationRecord>, EigenError> {
+	let filepath = get_file_path("attestations", FileType::Csv)?;
+	CSVFileStorage::<AttestationRecord>::new(filepath).load()
+}
+
+/// Loads the stored scores.
+fn load_scores() -> Result<Vec<ScoreRecord>, EigenError> {
+	let filepath = get_file_path("scores", FileType::Csv)?;
+	CSVFileStorage::<ScoreRecord>::new(filepath).load()
+}
+
+/// Returns the (attester, about, value) triple of the record, or `None` if it is
+/// malformed.
+fn attestation_edge(record: &AttestationRecord) -> Option<(Address, Address, u8)> {
+	let attester = record.attester()?;
+	let about = str_to_20_byte_array(record.about()).ok()?;
+	let value = record.value().parse::<u8>().ok()?;
+
+	Some((Address::from(attester), Address::from(about), value))
+}
+
+/// Parses the internal trust share of the sybil report, between 0 and 1.
+fn parse_share(share: &str) -> Result<f64, EigenError> {
+	let share = share
+		.parse::<f64>()
+		.map_err(|e| EigenError::ParsingError(format!("Error parsing share: {}", e)))?;
+	if !(0.0..=1.0).contains(&share) {
+		return Err(EigenError::ValidationError(format!(
+			"Share must be between 0 and 1, got {}",
+			share
+		)));
+	}
+
+	Ok(share)
+}
+
+/// Returns the value of the given query parameter, if any.
+fn query_param<'a>(query: &'a str, key: &str) -> Option<&'a str> {
+	query.split('&').find_map(|pair| match pair.split_once('=') {
+		Some((k, v)) if k == key => Some(v),
+		_ => None,
+	})
+}
+
+/// Builds a JSON response.
+fn json_response<T: Serialize>(status: StatusCode, body: &T) -> Result<Response<Body>, EigenError> {
+	response(status, "application/json", serde_json::to_string(body)?)
+}
+
+/// Builds a response with the given content type.
+fn response(
+	status: StatusCode, content_type: &str, body: String,
+) -> Result<Response<Body>, EigenError> {
+	Response::builder()
+		.status(status)
+		.header(CONTENT_TYPE, content_type)
+		.body(Body::from(body))
+		.map_err(|e| EigenError::UnknownError(e.to_string()))
+}
+
+/// Builds the JSON response of an error, along with its stable code.
+fn error_response(error: &EigenError) -> Response<Body> {
+	let status = match error {
+		EigenError::ConversionError(_)
+		| EigenError::JSONError(_)
+		| EigenError::ParsingError(_)
+		| EigenError::ValidationError(_) => StatusCode::BAD_REQUEST,
+		EigenError::ResourceUnavailableError(_) => StatusCode::NOT_FOUND,
+		EigenError::IOError(e) if e.kind() == ErrorKind::NotFound => StatusCode::NOT_FOUND,
+		EigenError::VerificationError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+		EigenError::ConfigurationError(_) => StatusCode::SERVICE_UNAVAILABLE,
+		_ => StatusCode::INTERNAL_SERVER_ERROR,
+	};
+
+	let body = ErrorResponse { code: error.code(), error: error.to_string() };
+	let mut response = Response::new(Body::from(serde_json::to_string(&body).unwrap_or_default()));
+	*response.status_mut() = status;
+	response.headers_mut().insert(
+		CONTENT_TYPE,
+		hyper::header::HeaderValue::from_static("application/json"),
+	);
+
+	response
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_query_param() {
+		assert_eq!(query_param("format=svg", "format"), Some("svg"));
+		assert_eq!(query_param("a=1&format=json", "format"), Some("json"));
+		assert_eq!(query_param("", "format"), None);
+	}
+
+	#[test]
+	fn test_parse_share() {
+		assert_eq!(parse_share("0.5").unwrap(), 0.5);
+		assert!(parse_share("1.5").is_err());
+		assert!(parse_share("abc").is_err());
+	}
+
+	#[test]
+	fn test_error_response() {
+		let response = error_response(&EigenError::ValidationError("invalid".to_string()));
+		assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+		let not_found = std::io::Error::new(ErrorKind::NotFound, "missing");
+		let response = error_response(&EigenError::IOError(not_found));
+		assert_eq!(response.status(), StatusCode::NOT_FOUND);
+	}
+
+	#[tokio::test]
+	async fn test_unknown_route() {
+		let req = Request::builder().uri("/unknown").body(Body::empty()).unwrap();
+		let response = handle_request(req, Arc::new(None)).await.unwrap();
+		assert_eq!(response.status(), StatusCode::NOT_FOUND);
+	}
+
+	#[tokio::test]
+	async fn test_relay_route() {
+		let relay_request = |token: Option<&str>, body: Vec<u8>| {
+			let mut builder = Request::builder().method(Method::POST).uri("/relay");
+			if let Some(token) = token {
+				builder = builder.header(AUTHORIZATION, format!("Bearer {}", token));
+			}
+			builder.body(Body::from(body)).unwrap()
+		};
+
+		// Relaying is disabled without a relayer
+		let response = handle_request(relay_request(Some("secret"), Vec::new()), Arc::new(None))
+			.await
+			.unwrap();
+		assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+		let client = Client::offline(
+			"test test test test test test test test test test test junk".to_string(),
+			31337,
+			[0; 20],
+			[0; 20],
+		);
+		let relayer = Arc::new(Some(Relayer::new(client, "secret".to_string())));
+
+		// Callers without the token are rejected
+		for token in [None, Some("other"), Some("secre")] {
+			let response =
+				handle_request(relay_request(token, Vec::new()), relayer.clone()).await.unwrap();
+			assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+		}
+
+		// Bodies over the limit are rejected before being parsed
+		let response = handle_request(
+			relay_request(Some("secret"), vec![b' '; MAX_BODY_SIZE + 1]),
+			relayer.clone(),
+		)
+		.await
+		.unwrap();
+		assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+		let response =
+			handle_request(relay_request(Some("secret"), b"{}".to_vec()), relayer).await.unwrap();
+		assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+	}
+}
@@ -0,0 +1,302 @@
+//! # Shell module.
+//!
+//! Interactive mode keeping a connected client and the indexed attestations in memory
+//! between commands, so only new blocks are indexed and the scores are only recalculated
+//! when the attestations change.
+
+use crate::{
+	cli::{score_rank, sync_attestations, CliConfig},
+	fs::{
+		aggregation_policy, dedup_policy, get_file_path, load_config, load_domain_registry,
+		load_mnemonic, FileType,
+	},
+};
+use eigentrust::{
+	attestation::{AttestationRaw, SignedAttestationRaw},
+	error::EigenError,
+	storage::{
+		str_to_20_byte_array, str_to_32_byte_array, AttestationRecord, CSVFileStorage,
+		MemoryStorage, ScoreRecord, Storage,
+	},
+	Client,
+};
+use std::{
+	io::{stdin, stdout, Write},
+	str::FromStr,
+};
+use tracing::warn;
+
+/// Shell prompt.
+const PROMPT: &str = "eigentrust> ";
+
+/// Shell commands usage.
+const USAGE: &str = "Commands:
+  attest <address> <score> [message]  Submits an attestation.
+  attestations [address]              Lists the attestations, or the ones about an address.
+  sync                                Indexes the new attestations.
+  scores                              Displays the scores of all the participants.
+  score <address>                     Displays the score and rank of a participant.
+  help                                Displays this message.
+  exit                                Leaves the shell.";
+
+/// Shell command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShellCommand {
+	/// Submits an attestation.
+	Attest {
+		/// Attested address.
+		address: String,
+		/// Given score.
+		score: String,
+		/// Optional attestation message.
+		message: Option<String>,
+	},
+	/// Lists the attestations, optionally only the ones about an address.
+	Attestations(Option<String>),
+	/// Indexes the new attestations.
+	Sync,
+	/// Displays the scores of all the participants.
+	Scores,
+	/// Displays the score of a participant.
+	Score(String),
+	/// Displays the commands usage.
+	Help,
+	/// Leaves the shell.
+	Exit,
+}
+
+impl FromStr for ShellCommand {
+	type Err = EigenError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let args: Vec<&str> = s.split_whitespace().collect();
+
+		match args.as_slice() {
+			["attest", address, score] => Ok(Self::Attest {
+				address: address.to_string(),
+				score: score.to_string(),
+				message: None,
+			}),
+			["attest", address, score, message] => Ok(Self::Attest {
+				address: address.to_string(),
+				score: score.to_string(),
+				message: Some(message.to_string()),
+			}),
+			["attestations"] => Ok(Self::Attestations(None)),
+			["attestations", address] => Ok(Self::Attestations(Some(address.to_string()))),
+			["sync"] => Ok(Self::Sync),
+			["scores"] => Ok(Self::Scores),
+			["score", address] => Ok(Self::Score(address.to_string())),
+			["help"] => Ok(Self::Help),
+			["exit"] | ["quit"] => Ok(Self::Exit),
+			_ => Err(EigenError::ParsingError(format!(
+				"Invalid command: \"{}\", type \"help\" for the list of commands",
+				s.trim()
+			))),
+		}
+	}
+}
+
+/// Shell session state.
+struct Session {
+	config: CliConfig,
+	client: Client,
+	records: Vec<AttestationRecord>,
+	scores: MemoryStorage<Vec<ScoreRecord>>,
+}
+
+impl Session {
+	/// Connects the client and indexes the attestations.
+	async fn new() -> Result<Self, EigenError> {
+		let config = load_config()?;
+		let domain = config.domain()?;
+		let mut client = Client::connect(
+			load_mnemonic(),
+			config.chain_id()?,
+			config.as_address()?,
+			domain,
+			&config.node_url,
+		)
+		.await?;
+		client.set_domain_params(load_domain_registry()?.params(domain))?;
+		client.set_aggregation(aggregation_policy()?);
+
+		let mut session =
+			Self { config, client, records: Vec::new(), scores: MemoryStorage::new() };
+		session.sync().await?;
+
+		Ok(session)
+	}
+
+	/// Indexes the new attestations, dropping the cached scores if any changed.
+	async fn sync(&mut self) -> Result<(), EigenError> {
+		let (new_attestations, total) = sync_attestations(&self.client).await?;
+		if new_attestations == 0 && total == self.records.len() {
+			return Ok(());
+		}
+
+		let filepath = get_file_path("attestations", FileType::Csv)?;
+		self.records =
+			CSVFileStorage::<AttestationRecord>::new(filepath).load_deduplicated(dedup_policy()?)?;
+		self.scores.take();
+		println!("{} attestations indexed.", self.records.len());
+
+		Ok(())
+	}
+
+	/// Returns the scores, calculating them if the attestations changed since the last
+	/// calculation.
+	async fn scores(&mut self) -> Result<&[ScoreRecord], EigenError> {
+		if self.scores.data().is_none() {
+			if self.records.is_empty() {
+				return Err(EigenError::AttestationError(
+					"No attestations found.".to_string(),
+				));
+			}
+
+			let creators: Vec<Option<[u8; 20]>> =
+				self.records.iter().map(AttestationRecord::creator).collect::<Result<_, _>>()?;
+			let attestations: Vec<SignedAttestationRaw> = self
+				.records
+				.iter()
+				.cloned()
+				.map(SignedAttestationRaw::try_from)
+				.collect::<Result<_, _>>()?;
+
+			let contract_attesters =
+				self.client.contract_attesters(&attestations, &creators).await?;
+			let scores = self
+				.client
+				.calculate_scores_with_contract_attesters(attestations, &contract_attesters)?;
+			self.scores.save(scores.into_iter().map(ScoreRecord::from_score).collect())?;
+		}
+
+		Ok(self.scores.data().map(Vec::as_slice).unwrap_or_default())
+	}
+
+	/// Executes the command. Returns whether the shell must keep running.
+	async fn execute(&mut self, command: ShellCommand) -> Result<bool, EigenError> {
+		match command {
+			ShellCommand::Attest { address, score, message } => {
+				let about = str_to_20_byte_array(&address)?;
+				let value = score
+					.parse::<u8>()
+					.map_err(|e| EigenError::ParsingError(format!("Error parsing score: {}", e)))?;
+				let message =
+					message.map_or(Ok([0; 32]), |message| str_to_32_byte_array(&message))?;
+				let domain = self.config.domain()?;
+
+				self.client.attest(AttestationRaw::new(about, domain, value, message)).await?;
+				println!("Attestation submitted.");
+				self.sync().await?;
+			},
+			ShellCommand::Attestations(address) => {
+				let records = self.records.iter().filter(|record| {
+					address
+						.as_ref()
+						.map_or(true, |address| record.about().eq_ignore_ascii_case(address))
+				});
+
+				let mut count = 0;
+				for record in records {
+					let block = record.block()?.map(|(number, _)| number.to_string());
+					println!(
+						"{} {} (block {})",
+						record.about(),
+						record.value(),
+						block.as_deref().unwrap_or("unknown")
+					);
+					count += 1;
+				}
+				println!("{} attestations.", count);
+			},
+			ShellCommand::Sync => self.sync().await?,
+			ShellCommand::Scores => {
+				for record in self.scores().await? {
+					println!("{} {}", record.peer_address(), record.score());
+				}
+			},
+			ShellCommand::Score(address) => {
+				let scores = self.scores().await?;
+				let (rank, record) = score_rank(scores, &address)?.ok_or_else(|| {
+					EigenError::ResourceUnavailableError(format!("No score for {}", address))
+				})?;
+
+				println!(
+					"{} {} (rank {} of {}, {}/{})",
+					record.peer_address(),
+					record.score(),
+					rank,
+					scores.len(),
+					record.numerator(),
+					record.denominator()
+				);
+			},
+			ShellCommand::Help => println!("{}", USAGE),
+			ShellCommand::Exit => return Ok(false),
+		}
+
+		Ok(true)
+	}
+}
+
+/// Runs the interactive shell until the `exit` command or the end of the input.
+pub async fn run_shell() -> Result<(), EigenError> {
+	let mut session = Session::new().await?;
+	println!("{}", USAGE);
+
+	loop {
+		print!("{}", PROMPT);
+		stdout().flush()?;
+
+		let mut line = String::new();
+		if stdin().read_line(&mut line)? == 0 {
+			return Ok(());
+		}
+		if line.trim().is_empty() {
+			continue;
+		}
+
+		// Failed commands don't end the session
+		let result = match line.parse::<ShellCommand>() {
+			Ok(command) => session.execute(command).await,
+			Err(e) => Err(e),
+		};
+		match result {
+			Ok(true) => {},
+			Ok(false) => return Ok(()),
+			Err(e) => warn!(code = e.code(), "{}", e),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_shell_command() {
+		let address = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+
+		assert_eq!(
+			format!("attest {} 5", address).parse::<ShellCommand>().unwrap(),
+			ShellCommand::Attest {
+				address: address.to_string(),
+				score: "5".to_string(),
+				message: None
+			}
+		);
+		assert_eq!(
+			"  attestations  ".parse::<ShellCommand>().unwrap(),
+			ShellCommand::Attestations(None)
+		);
+		assert_eq!(
+			format!("score {}", address).parse::<ShellCommand>().unwrap(),
+			ShellCommand::Score(address.to_string())
+		);
+		assert_eq!("quit".parse::<ShellCommand>().unwrap(), ShellCommand::Exit);
+
+		assert!("score".parse::<ShellCommand>().is_err());
+		assert!("prove".parse::<ShellCommand>().is_err());
+	}
+}
@@ -1,5 +1,7 @@
 /// Native version of EigenTrustSet(ECDSA)
 pub mod native;
+/// Native EigenTrust over participants sharded into several sets
+pub mod sharded;
 
 use self::native::SignedAttestation;
 use super::opinion::{OpinionChipset, OpinionConfig};
@@ -846,16 +848,16 @@ mod test {
 			>::new(domain);
 
 			for i in 0..NUM_NEIGHBOURS {
-				et.add_member(set[i]);
+				et.add_member(set[i]).unwrap();
 			}
 
 			for i in 0..NUM_NEIGHBOURS {
 				let attestations_opt =
 					attestations[i].iter().map(|x| Some(x.clone())).collect_vec();
-				et.update_op(pub_keys[i].clone(), attestations_opt);
+				et.update_op(pub_keys[i].clone(), attestations_opt).unwrap();
 			}
 
-			et.converge()
+			et.converge().unwrap()
 		});
 		public_inputs.push(domain);
 		public_inputs.push(op_hash);
@@ -973,16 +975,16 @@ mod test {
 			>::new(domain);
 
 			for i in 0..NUM_NEIGHBOURS {
-				et.add_member(set[i]);
+				et.add_member(set[i]).unwrap();
 			}
 
 			for i in 0..NUM_NEIGHBOURS {
 				let attestations_opt =
 					attestations[i].iter().map(|x| Some(x.clone())).collect_vec();
-				et.update_op(pub_keys[i].clone(), attestations_opt);
+				et.update_op(pub_keys[i].clone(), attestations_opt).unwrap();
 			}
 
-			et.converge()
+			et.converge().unwrap()
 		});
 		public_inputs.push(domain);
 		public_inputs.push(op_hash);
@@ -1,16 +1,26 @@
 use crate::{
 	circuits::{opinion::native::Opinion, RationalScore, HASHER_WIDTH},
-	ecdsa::native::{PublicKey, Signature},
+	ecdsa::native::{EcdsaVerifier, PublicKey, Signature},
 	integer::native::Integer,
 	params::{ecc::EccParams, rns::RnsParams},
-	utils::fe_to_big,
+	utils::{big_to_fe, fe_to_big, field_to_string, modulus, rational_to_fe},
 	FieldExt, Hasher, SpongeHasher,
 };
 use halo2::halo2curves::CurveAffine;
-use num_bigint::{BigInt, ToBigInt};
+#[cfg(feature = "ndarray")]
+use ndarray::{Array1, Array2, Zip};
+use num_bigint::{BigInt, BigUint, ToBigInt};
 use num_rational::BigRational;
-use num_traits::{FromPrimitive, One, Zero};
-use std::{collections::HashMap, marker::PhantomData};
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::HashMap,
+	fmt::{self, Display, Formatter},
+	marker::PhantomData,
+	str::FromStr,
+	sync::Arc,
+};
 
 /// Attestation submission struct
 #[derive(Clone, Debug)]
@@ -96,12 +106,382 @@ impl<F: FieldExt> Attestation<F> {
 	}
 }
 
-/// Dynamic set for EigenTrust
-#[derive(Default)]
-pub struct EigenTrustSet<
-	const NUM_NEIGHBOURS: usize,
-	const NUM_ITERATIONS: usize,
-	const INITIAL_SCORE: u128,
+/// Errors of the native EigenTrust sets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetError {
+	/// The peer is already a set member
+	DuplicateMember,
+	/// The peer isn't a set member
+	MissingMember,
+	/// The peer address is zero, the address of the free slots
+	ZeroAddress,
+	/// The set has no free slot left
+	SetFull,
+	/// Fewer than 2 set members to compute the scores
+	InsufficientPeers,
+	/// The scores don't have one entry per set slot
+	ScoresMismatch,
+	/// An attestation isn't about the member of its slot
+	WrongSubject,
+	/// An attestation is of another domain than the set
+	WrongDomain,
+	/// The initial score of the member is zero
+	InvalidScore,
+	/// The set parameters are out of range, or can't change anymore
+	InvalidParams,
+	/// The serialized set state is malformed or of another version
+	InvalidState,
+}
+
+impl Display for SetError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let msg = match self {
+			SetError::DuplicateMember => "Peer is already a set member",
+			SetError::MissingMember => "Peer isn't a set member",
+			SetError::ZeroAddress => "Peer address can't be zero",
+			SetError::SetFull => "No free slot left in the set",
+			SetError::InsufficientPeers => "Insufficient peers for calculation",
+			SetError::ScoresMismatch => "Scores don't match the set slots",
+			SetError::WrongSubject => "Attestation isn't about the member of its slot",
+			SetError::WrongDomain => "Attestation is of another domain",
+			SetError::InvalidScore => "Initial score must be positive",
+			SetError::InvalidParams => "Invalid set parameters",
+			SetError::InvalidState => "Invalid set state",
+		};
+		write!(f, "{}", msg)
+	}
+}
+
+impl std::error::Error for SetError {}
+
+/// Scores of a single convergence iteration, see `RuntimeEigenTrustSet::converge_trajectory`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConvergenceStep<N> {
+	/// Iteration, 0 being the initial scores
+	pub iteration: usize,
+	/// Scores after the iteration
+	pub scores: Vec<N>,
+	/// Scores after the iteration, as BigRational numbers
+	pub rational_scores: Vec<RationalScore>,
+	/// Largest score change of the iteration, the one compared to the tolerance
+	pub residual: RationalScore,
+}
+
+/// Diagnostics of a convergence run, see `RuntimeEigenTrustSet::converge_with_report`, so
+/// that degenerate trust graphs can be detected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConvergenceReport<N> {
+	/// Iterations run, fewer than the configured ones if the tolerance was reached
+	pub iterations: usize,
+	/// Largest score change of the last iteration
+	pub residual: RationalScore,
+	/// Total final score minus the total initial score, non-zero when the opinion filter
+	/// drops the score of members without any valid opinion
+	pub sum_drift: RationalScore,
+	/// Opinion scores zeroed by the filtering, given to themselves, to free slots or to
+	/// distrusted peers
+	pub filtered_scores: usize,
+	/// Members without any valid opinion score, filled by the opinion filter policy
+	pub empty_opinions: Vec<N>,
+	/// Members no other member passes any score on to
+	pub no_inbound_trust: Vec<N>,
+}
+
+/// User-facing scale of the converged scores, see `ScoreNormalization::apply`, so that
+/// every consumer of the scores reports the same values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScoreNormalization {
+	/// The scores as they converged
+	#[default]
+	Raw,
+	/// Share of the total score, the scores summing to 1
+	Share,
+	/// Percentile from 0 to 100: the share of the other scores that are lower
+	Percentile,
+	/// Base 10 logarithm of 1 plus the score, compressing the long tail of high scores
+	Log,
+}
+
+impl ScoreNormalization {
+	/// Returns the name of the normalization.
+	pub fn name(&self) -> &'static str {
+		match self {
+			ScoreNormalization::Raw => "raw",
+			ScoreNormalization::Share => "share",
+			ScoreNormalization::Percentile => "percentile",
+			ScoreNormalization::Log => "log",
+		}
+	}
+
+	/// Converts the converged scores, such as the rational scores of `converge_rational`,
+	/// into user-facing values, in the same order. Equal scores get equal values.
+	pub fn apply(&self, scores: &[RationalScore]) -> Vec<f64> {
+		let to_f64 = |score: &RationalScore| score.to_f64().unwrap_or(0.);
+		match self {
+			ScoreNormalization::Raw => scores.iter().map(to_f64).collect(),
+			ScoreNormalization::Share => {
+				let total: BigRational = scores.iter().sum();
+				scores
+					.iter()
+					.map(|score| match total.is_zero() {
+						true => 0.,
+						false => to_f64(&(score / &total)),
+					})
+					.collect()
+			},
+			ScoreNormalization::Percentile => {
+				let mut sorted: Vec<&RationalScore> = scores.iter().collect();
+				sorted.sort();
+				let others = scores.len().saturating_sub(1);
+				scores
+					.iter()
+					.map(|score| {
+						let lower = sorted.partition_point(|x| *x < score);
+						match others {
+							0 => 100.,
+							others => lower as f64 * 100. / others as f64,
+						}
+					})
+					.collect()
+			},
+			ScoreNormalization::Log => {
+				scores.iter().map(|score| (1. + to_f64(score)).log10()).collect()
+			},
+		}
+	}
+}
+
+/// Score change of a member of two set states, see `RuntimeEigenTrustSet::diff`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScoreDelta<N> {
+	/// Member
+	pub member: N,
+	/// Converged score in the earlier state
+	pub old: RationalScore,
+	/// Converged score in the later state
+	pub new: RationalScore,
+	/// Later score minus the earlier one
+	pub delta: RationalScore,
+}
+
+/// Score and membership changes between two set states, see `RuntimeEigenTrustSet::diff`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SetDiff<N> {
+	/// Score changes of the members of both states, from the largest change to the smallest
+	pub changes: Vec<ScoreDelta<N>>,
+	/// Members only in the later state, with their converged score
+	pub joined: Vec<(N, RationalScore)>,
+	/// Members only in the earlier state, with their converged score
+	pub left: Vec<(N, RationalScore)>,
+}
+
+/// Version of the serialized set state.
+pub const SET_STATE_VERSION: u32 = 1;
+
+/// Serializable state of a native set, see `RuntimeEigenTrustSet::state`, so that it can
+/// be persisted and resumed, or shipped to another machine. Field elements are stored as
+/// decimal strings and rational scores as `numerator/denominator` strings. The opinion
+/// filter isn't part of the state.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetState {
+	/// State format version.
+	pub version: u32,
+	/// Epoch of the state, such as the last block of the attestations.
+	pub epoch: u64,
+	/// Attestation domain.
+	pub domain: String,
+	/// Number of convergence iterations.
+	pub num_iterations: usize,
+	/// Initial score of the set members.
+	pub initial_score: u128,
+	/// Member of every set slot, zero for the free slots.
+	pub members: Vec<String>,
+	/// Members' opinion scores, one per slot.
+	pub ops: Vec<(String, Vec<String>)>,
+	/// Members' distrust scores, one per slot.
+	pub distrust: Vec<(String, Vec<String>)>,
+	/// Blocks of the members' opinions.
+	pub op_blocks: Vec<(String, u64)>,
+	/// Members' initial score weights.
+	pub weights: Vec<(String, u128)>,
+	/// Members' stakes.
+	pub stakes: Vec<(String, u128)>,
+	/// Initial scores redistributed to the members by the removals.
+	pub redistributed: Vec<(String, String)>,
+	/// Pre-trusted peers.
+	pub pre_trusted: Vec<String>,
+	/// Damping factor (numerator, denominator), if any.
+	pub damping: Option<(u128, u128)>,
+	/// Convergence tolerance (numerator, denominator), if any.
+	pub tolerance: Option<(u128, u128)>,
+	/// Decay factor (numerator, denominator), period and current block, if any.
+	pub decay: Option<((u128, u128), u64, u64)>,
+	/// Removal policy.
+	pub removal: RemovalPolicy,
+	/// Converged scores of every set slot, the heartbeat ones if running.
+	pub scores: Vec<String>,
+	/// Number of heartbeats run, if any.
+	pub heartbeat: Option<usize>,
+}
+
+/// Normalized opinion matrix the convergence iterates over, restricted to the set members,
+/// see `RuntimeEigenTrustSet::trust_matrix`. Row `i` holds the share of its score member
+/// `i` passes on to every member.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrustMatrix<N> {
+	/// Members labelling the rows and the columns, in set order
+	pub members: Vec<N>,
+	/// Normalized opinion scores, as field elements
+	pub scores: Vec<Vec<N>>,
+	/// Normalized opinion scores, as BigRational numbers
+	pub rational_scores: Vec<Vec<RationalScore>>,
+}
+
+impl<N> TrustMatrix<N> {
+	/// Returns the normalized opinion scores as floating point numbers, for the analysis
+	/// tools and visualizers working on those.
+	pub fn to_f64(&self) -> Vec<Vec<f64>> {
+		self.rational_scores
+			.iter()
+			.map(|row| row.iter().map(|score| score.to_f64().unwrap_or(0.)).collect())
+			.collect()
+	}
+}
+
+/// Restart probability of the personalized convergence of sets without a damping factor,
+/// as a (numerator, denominator) fraction
+pub const PERSONALIZED_DAMPING: (u128, u128) = (15, 100);
+
+/// Number of decay periods after which an opinion has no weight left
+pub const MAX_DECAY_PERIODS: u64 = 64;
+
+/// Exponential decay of the opinion weights with their age
+#[derive(Clone, Copy, Debug, Default)]
+struct Decay {
+	/// Kept fraction of the weight every period, as a (numerator, denominator) pair
+	factor: (u128, u128),
+	/// Number of blocks of a period
+	period: u64,
+	/// Block the ages are measured at
+	current_block: u64,
+}
+
+/// Returns whether the (numerator, denominator) damping factor is in [0, 1).
+fn valid_damping((numerator, denominator): (u128, u128)) -> bool {
+	denominator > 0 && numerator < denominator
+}
+
+/// Returns whether the (numerator, denominator) tolerance has a denominator.
+fn valid_tolerance((_, denominator): (u128, u128)) -> bool {
+	denominator > 0
+}
+
+/// Returns whether the decay factor is in (0, 1] and the period isn't empty.
+fn valid_decay((numerator, denominator): (u128, u128), period: u64) -> bool {
+	numerator > 0 && numerator <= denominator && period > 0
+}
+
+/// Policy for the opinions left without any valid score once the scores given by members
+/// to themselves, to the free slots and to the peers they distrust are removed.
+/// Only `RedistributeEmpty`, the default, is covered by the circuit.
+pub trait OpinionFilter<N: FieldExt>: Send + Sync {
+	/// Returns the opinion replacing the empty opinion of the member at index `i` of the set,
+	/// given its distrust opinion, padded to the set size, and the indices of the pre-trusted
+	/// members.
+	fn fill_empty(
+		&self, set: &[(N, N)], i: usize, distrust_i: &[N], pre_trusted: &[usize],
+	) -> Vec<N>;
+}
+
+/// Spreads the empty opinions evenly over the other members, leaving out the distrusted
+/// ones unless every member is distrusted. Every member then passes its score on, so the
+/// total score is preserved.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RedistributeEmpty;
+
+impl<N: FieldExt> OpinionFilter<N> for RedistributeEmpty {
+	fn fill_empty(&self, set: &[(N, N)], i: usize, distrust_i: &[N], _: &[usize]) -> Vec<N> {
+		let (addr_i, _) = set[i];
+
+		// Peers distrusting every other member still distribute their score
+		let distrusts_all = set.iter().zip(distrust_i).all(|(&(addr_j, _), distrust)| {
+			addr_j == N::ZERO || addr_j == addr_i || *distrust != N::ZERO
+		});
+
+		set.iter()
+			.zip(distrust_i)
+			.map(|(&(addr_j, _), distrust)| {
+				// Conditions for distributing the score
+				// 1. addr_j != addr_i
+				// 2. addr_j != 0 (Default key)
+				// 3. addr_j isn't distrusted by addr_i
+				let is_diff_addr = addr_j != addr_i;
+				let is_not_default = addr_j != N::ZERO;
+				let is_not_distrusted = distrusts_all || *distrust == N::ZERO;
+
+				match is_diff_addr && is_not_default && is_not_distrusted {
+					true => N::ONE,
+					false => N::ZERO,
+				}
+			})
+			.collect()
+	}
+}
+
+/// Drops the members without any valid opinion score from the trust flow: they still
+/// receive trust, but don't pass their score on, so it leaks out of the set and the total
+/// score shrinks. Members can't gain trust by not giving any.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DropEmpty;
+
+impl<N: FieldExt> OpinionFilter<N> for DropEmpty {
+	fn fill_empty(&self, set: &[(N, N)], _: usize, _: &[N], _: &[usize]) -> Vec<N> {
+		vec![N::ZERO; set.len()]
+	}
+}
+
+/// Spreads the empty opinions evenly over the pre-trusted members, see
+/// `RuntimeEigenTrustSet::set_pre_trusted`, instead of every member, so that members
+/// without any opinion can't hand trust to peers nobody vouched for. The distrusted
+/// pre-trusted members are left out unless every one of them is distrusted, and members
+/// without any other pre-trusted member fall back to `RedistributeEmpty`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PreTrustedEmpty;
+
+impl<N: FieldExt> OpinionFilter<N> for PreTrustedEmpty {
+	fn fill_empty(
+		&self, set: &[(N, N)], i: usize, distrust_i: &[N], pre_trusted: &[usize],
+	) -> Vec<N> {
+		let others: Vec<usize> = pre_trusted.iter().copied().filter(|&j| j != i).collect();
+		if others.is_empty() {
+			return RedistributeEmpty.fill_empty(set, i, distrust_i, pre_trusted);
+		}
+
+		let trusted: Vec<usize> =
+			others.iter().copied().filter(|&j| distrust_i[j] == N::ZERO).collect();
+		let targets = if trusted.is_empty() { others } else { trusted };
+		(0..set.len()).map(|j| if targets.contains(&j) { N::ONE } else { N::ZERO }).collect()
+	}
+}
+
+/// Where the initial score of a removed member goes.
+/// Only `Drop`, the default, is covered by the circuit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RemovalPolicy {
+	/// The score leaves the set with the member, so the total score shrinks by its share
+	#[default]
+	Drop,
+	/// The score is spread over the remaining members, proportionally to their initial scores
+	Proportional,
+	/// The score is spread evenly over the remaining pre-trusted members, or leaves the set
+	/// without any
+	PreTrusted,
+}
+
+/// Dynamic set for EigenTrust, whose capacity is chosen at runtime. The set grows when
+/// members join a full set, and opinions shorter than the set are padded with zero scores.
+/// Only the sets sized by `EigenTrustSet` are covered by the circuit.
+pub struct RuntimeEigenTrustSet<
 	C: CurveAffine,
 	N: FieldExt,
 	const NUM_LIMBS: usize,
@@ -118,14 +498,25 @@ pub struct EigenTrustSet<
 {
 	set: Vec<(N, N)>,
 	ops: HashMap<N, Vec<N>>,
+	distrust: HashMap<N, Vec<N>>,
+	op_blocks: HashMap<N, u64>,
+	decay: Option<Decay>,
 	domain: N,
+	damping: Option<(u128, u128)>,
+	pre_trusted: Vec<N>,
+	tolerance: Option<(u128, u128)>,
+	num_iterations: usize,
+	initial_score: u128,
+	filter: Option<Arc<dyn OpinionFilter<N>>>,
+	weights: HashMap<N, u128>,
+	stakes: HashMap<N, u128>,
+	heartbeat: Option<(usize, HashMap<N, RationalScore>)>,
+	removal: RemovalPolicy,
+	redistributed: HashMap<N, RationalScore>,
 	_p: PhantomData<(C, P, EC, H, SH)>,
 }
 
 impl<
-		const NUM_NEIGHBOURS: usize,
-		const NUM_ITERATIONS: usize,
-		const INITIAL_SCORE: u128,
 		C: CurveAffine,
 		N: FieldExt,
 		const NUM_LIMBS: usize,
@@ -134,130 +525,570 @@ impl<
 		EC,
 		H: Hasher<N, HASHER_WIDTH>,
 		SH: SpongeHasher<N>,
-	>
-	EigenTrustSet<
-		NUM_NEIGHBOURS,
-		NUM_ITERATIONS,
-		INITIAL_SCORE,
-		C,
-		N,
-		NUM_LIMBS,
-		NUM_BITS,
-		P,
-		EC,
-		H,
-		SH,
-	> where
+	> RuntimeEigenTrustSet<C, N, NUM_LIMBS, NUM_BITS, P, EC, H, SH>
+where
 	P: RnsParams<C::ScalarExt, N, NUM_LIMBS, NUM_BITS> + RnsParams<C::Base, N, NUM_LIMBS, NUM_BITS>,
 	EC: EccParams<C>,
 	C::ScalarExt: FieldExt,
 	C::Base: FieldExt,
 {
-	/// Constructs new instance
-	pub fn new(domain: N) -> Self {
-		Self {
-			set: vec![(N::ZERO, N::ZERO); NUM_NEIGHBOURS],
+	/// Constructs new instance with room for `capacity` members, panicking on invalid
+	/// parameters, see `try_new`.
+	pub fn new(domain: N, capacity: usize, num_iterations: usize, initial_score: u128) -> Self {
+		Self::try_new(domain, capacity, num_iterations, initial_score)
+			.expect("Invalid set parameters")
+	}
+
+	/// Constructs new instance with room for `capacity` members
+	pub fn try_new(
+		domain: N, capacity: usize, num_iterations: usize, initial_score: u128,
+	) -> Result<Self, SetError> {
+		if num_iterations == 0 || initial_score == 0 {
+			return Err(SetError::InvalidParams);
+		}
+
+		Ok(Self {
+			set: vec![(N::ZERO, N::ZERO); capacity],
 			ops: HashMap::new(),
+			distrust: HashMap::new(),
+			op_blocks: HashMap::new(),
+			decay: None,
 			domain,
+			damping: None,
+			pre_trusted: Vec::new(),
+			tolerance: None,
+			num_iterations,
+			initial_score,
+			filter: None,
+			weights: HashMap::new(),
+			stakes: HashMap::new(),
+			heartbeat: None,
+			removal: RemovalPolicy::Drop,
+			redistributed: HashMap::new(),
 			_p: PhantomData,
+		})
+	}
+
+	/// Sets the policy for the opinions without any valid score, `RedistributeEmpty` by
+	/// default. Other policies aren't covered by the circuit.
+	pub fn with_opinion_filter(mut self, filter: impl OpinionFilter<N> + 'static) -> Self {
+		self.filter = Some(Arc::new(filter));
+		self
+	}
+
+	/// Returns the policy for the opinions without any valid score.
+	fn opinion_filter(&self) -> &dyn OpinionFilter<N> {
+		match &self.filter {
+			Some(filter) => filter.as_ref(),
+			None => &RedistributeEmpty,
 		}
 	}
 
-	/// Add new set member and initial score
-	pub fn add_member(&mut self, addr: N) {
-		let pos = self.set.iter().position(|&(x, _)| x == addr);
-		// Make sure not already in the set
-		assert!(pos.is_none());
+	/// Returns the number of member slots, free ones included.
+	pub fn capacity(&self) -> usize {
+		self.set.len()
+	}
 
-		let first_available = self.set.iter().position(|&(x, _)| x == N::ZERO);
-		let index = first_available.unwrap();
+	/// Set the damping factor, as a (numerator, denominator) fraction. Every iteration
+	/// then moves this fraction of the scores back to the pre-trusted scores, as in PageRank,
+	/// which guarantees convergence on periodic trust graphs. This is the `a` factor of the
+	/// EigenTrust paper, see `set_pre_trusted`.
+	/// Damped scores aren't covered by the circuit, which runs undamped iterations.
+	pub fn set_damping(&mut self, numerator: u128, denominator: u128) -> Result<(), SetError> {
+		// Make sure the factor is in [0, 1)
+		if !valid_damping((numerator, denominator)) {
+			return Err(SetError::InvalidParams);
+		}
 
-		// Give the initial score.
-		let initial_score = N::from_u128(INITIAL_SCORE);
-		self.set[index] = (addr, initial_score);
+		self.damping = if numerator == 0 { None } else { Some((numerator, denominator)) };
+
+		Ok(())
 	}
 
-	/// Remove the member and its opinion
-	pub fn remove_member(&mut self, addr: N) {
-		let pos = self.set.iter().position(|&(x, _)| x == addr);
+	/// Set the pre-trusted peers, the `p` distribution of the EigenTrust paper. Every
+	/// iteration computes `(1 - a) * C^T * t + a * p`, `a` being the damping factor: the
+	/// damped share of the scores goes to the pre-trusted peers, split evenly, instead of
+	/// every member, so that malicious collectives can't keep it among themselves.
+	/// Peers that aren't set members when converging are ignored, and without any
+	/// pre-trusted member the damped share is spread over every member.
+	pub fn set_pre_trusted(&mut self, peers: Vec<N>) {
+		self.pre_trusted = peers;
+	}
+
+	/// Set where the initial score of the removed members goes, see `RemovalPolicy`.
+	pub fn set_removal_policy(&mut self, policy: RemovalPolicy) {
+		self.removal = policy;
+	}
+
+	/// Returns the indices of the pre-trusted set members.
+	fn pre_trusted_indices(&self) -> Vec<usize> {
+		(0..self.set.len())
+			.filter(|&i| {
+				let (addr, _) = self.set[i];
+				addr != N::ZERO && self.pre_trusted.contains(&addr)
+			})
+			.collect()
+	}
+
+	/// Set the convergence tolerance, as a (numerator, denominator) fraction. The
+	/// convergence then stops at the first iteration changing no score by this much or
+	/// more, the number of iterations becoming a cap, see `iterations_needed`. The changes
+	/// are measured on f64 iterates, so that stopping early costs less than every iteration.
+	/// Scores stopped early aren't covered by the circuit, which runs every iteration.
+	pub fn set_tolerance(&mut self, numerator: u128, denominator: u128) -> Result<(), SetError> {
+		if !valid_tolerance((numerator, denominator)) {
+			return Err(SetError::InvalidParams);
+		}
+
+		self.tolerance = if numerator == 0 { None } else { Some((numerator, denominator)) };
+
+		Ok(())
+	}
+
+	/// Returns the number of iterations the convergence runs: all of them without a
+	/// tolerance, otherwise the ones until the largest score change falls under it, see
+	/// `tolerance_iterations`.
+	pub fn iterations_needed(&self) -> usize {
+		match self.tolerance {
+			Some(tolerance) => {
+				let (ops_norm, damping, _, pre_trust) = self.rational_params(None);
+				tolerance_iterations(
+					&ops_norm,
+					&self.initial_scores(),
+					&pre_trust,
+					&damping,
+					tolerance,
+					self.num_iterations,
+				)
+			},
+			None => self.num_iterations,
+		}
+	}
+
+	/// Set the decay of the opinions: every `period` blocks between the block of an opinion,
+	/// see `set_op_block`, and `current_block`, the opinion keeps the (numerator,
+	/// denominator) fraction of its weight, and loses all of it after `MAX_DECAY_PERIODS`.
+	/// The weight an opinion loses is spread evenly over the other members, as the score
+	/// of members without any opinion is, so that stale opinions fade into neutral ones.
+	/// Opinions without a block don't decay.
+	/// Decayed opinions aren't covered by the circuit.
+	pub fn set_decay(
+		&mut self, numerator: u128, denominator: u128, period: u64, current_block: u64,
+	) -> Result<(), SetError> {
+		// Make sure the factor is in (0, 1]
+		if !valid_decay((numerator, denominator), period) {
+			return Err(SetError::InvalidParams);
+		}
+
+		self.decay = if numerator == denominator {
+			None
+		} else {
+			Some(Decay { factor: (numerator, denominator), period, current_block })
+		};
+
+		Ok(())
+	}
+
+	/// Set the block the member's opinion was given at, such as the block of its latest
+	/// attestation.
+	pub fn set_op_block(&mut self, from: N, block: u64) -> Result<(), SetError> {
+		// Make sure already in the set
+		self.member_index(from)?;
+
+		self.op_blocks.insert(from, block);
+
+		Ok(())
+	}
+
+	/// Returns the number of decay periods elapsed since the member's opinion, if it decays.
+	fn decay_periods(&self, addr: N) -> Option<u64> {
+		let decay = self.decay?;
+		let block = self.op_blocks.get(&addr)?;
+		Some(decay.current_block.saturating_sub(*block) / decay.period)
+	}
+
+	/// Returns the weight of the member's opinion, if it decays.
+	fn decay_weight(&self, addr: N) -> Option<BigRational> {
+		let periods = self.decay_periods(addr)?;
+		if periods > MAX_DECAY_PERIODS {
+			return Some(BigRational::zero());
+		}
+
+		let (num, den) = self.decay.map(|decay| decay.factor)?;
+		let factor = BigRational::new(BigInt::from(num), BigInt::from(den));
+		// Capped by `MAX_DECAY_PERIODS`
+		Some(factor.pow(i32::try_from(periods).ok()?))
+	}
+
+	/// Returns the weight of the member's opinion as a field element, if it decays.
+	fn decay_weight_fe(&self, addr: N) -> Option<N> {
+		let periods = self.decay_periods(addr)?;
+		if periods > MAX_DECAY_PERIODS {
+			return Some(N::ZERO);
+		}
+
+		let (num, den) = self.decay.map(|decay| decay.factor)?;
+		let factor = N::from_u128(num) * N::from_u128(den).invert().unwrap();
+		Some(factor.pow_vartime([periods]))
+	}
+
+	/// Set the stake of the member, such as its on-chain stake or token balance. Once any
+	/// stake is set, the normalized opinion of every staked member is scaled by its stake
+	/// relative to the largest one, the rest of its score staying with it, so that the
+	/// opinions of small stakes pass on less trust. Opinions of members without a stake
+	/// aren't scaled.
+	/// Stake-weighted opinions aren't covered by the circuit.
+	pub fn set_stake(&mut self, from: N, stake: u128) -> Result<(), SetError> {
 		// Make sure already in the set
-		assert!(pos.is_some());
+		self.member_index(from)?;
+
+		self.stakes.insert(from, stake);
+
+		Ok(())
+	}
+
+	/// Returns the largest stake of the members, if any is non-zero.
+	fn max_stake(&self) -> Option<u128> {
+		self.stakes.values().copied().max().filter(|max| *max > 0)
+	}
+
+	/// Returns the weight of the member's opinion, if it has a stake.
+	fn stake_weight(&self, addr: N) -> Option<BigRational> {
+		let stake = self.stakes.get(&addr)?;
+		let max = self.max_stake().unwrap_or(1);
+		Some(BigRational::new(BigInt::from(*stake), BigInt::from(max)))
+	}
+
+	/// Returns the weight of the member's opinion as a field element, if it has a stake.
+	fn stake_weight_fe(&self, addr: N) -> Option<N> {
+		let stake = self.stakes.get(&addr)?;
+		let max = self.max_stake().unwrap_or(1);
+		Some(N::from_u128(*stake) * N::from_u128(max).invert().unwrap())
+	}
+
+	/// Returns the indices of the members sharing the score the opinion of `i` lost.
+	fn spread_indices(&self, i: usize) -> Vec<usize> {
+		(0..self.set.len()).filter(|&j| j != i && self.set[j].0 != N::ZERO).collect()
+	}
+
+	/// Set the number of iterations and the initial score of the set members, before any
+	/// member joins.
+	pub fn set_params(
+		&mut self, num_iterations: usize, initial_score: u128,
+	) -> Result<(), SetError> {
+		// Members already hold the initial score
+		let is_empty = self.set.iter().all(|&(addr, _)| addr == N::ZERO);
+		if !is_empty || num_iterations == 0 || initial_score == 0 {
+			return Err(SetError::InvalidParams);
+		}
+
+		self.num_iterations = num_iterations;
+		self.initial_score = initial_score;
+
+		Ok(())
+	}
+
+	/// Returns the number of convergence iterations.
+	pub fn num_iterations(&self) -> usize {
+		self.num_iterations
+	}
+
+	/// Returns the initial score of the set members.
+	pub fn initial_score(&self) -> u128 {
+		self.initial_score
+	}
+
+	/// Add new set member and initial score, growing the set when it's full
+	pub fn add_member(&mut self, addr: N) -> Result<(), SetError> {
+		self.add_member_with_score(addr, None)
+	}
+
+	/// Add new set member with the given initial score, e.g. proportional to its stake or
+	/// seniority, or the set's initial score if `None`. The initial scores are scaled to
+	/// keep their sum at the set's initial score per member, so the total trust doesn't
+	/// depend on the weights, see `initial_scores`.
+	/// Weighted initial scores aren't covered by the circuit, which starts every member at
+	/// the same score.
+	pub fn add_member_with_score(
+		&mut self, addr: N, initial_score: Option<u128>,
+	) -> Result<(), SetError> {
+		// The zero address marks the free slots
+		if addr == N::ZERO {
+			return Err(SetError::ZeroAddress);
+		}
+		// Make sure not already in the set
+		if self.set.iter().any(|&(x, _)| x == addr) {
+			return Err(SetError::DuplicateMember);
+		}
+		if initial_score == Some(0) {
+			return Err(SetError::InvalidScore);
+		}
+		if let Some(weight) = initial_score {
+			self.weights.insert(addr, weight);
+		}
+
+		// Give the initial score.
+		let member = (addr, N::from_u128(self.initial_score));
+		match self.set.iter().position(|&(x, _)| x == N::ZERO) {
+			Some(index) => self.set[index] = member,
+			None => self.set.push(member),
+		}
 
-		let index = pos.unwrap();
+		Ok(())
+	}
+
+	/// Remove the member and its opinion. Its initial score leaves the set, or is spread
+	/// over the remaining members, depending on the removal policy.
+	pub fn remove_member(&mut self, addr: N) -> Result<(), SetError> {
+		// Make sure already in the set
+		let index = self.member_index(addr)?;
+		let before = self.initial_scores();
 		self.set[index] = (N::ZERO, N::ZERO);
 
 		self.ops.remove(&addr);
+		self.distrust.remove(&addr);
+		self.op_blocks.remove(&addr);
+		self.weights.remove(&addr);
+		self.stakes.remove(&addr);
+		self.redistributed.remove(&addr);
+
+		let recipients = match self.removal {
+			RemovalPolicy::Drop => Vec::new(),
+			RemovalPolicy::Proportional => {
+				(0..self.set.len()).filter(|&i| self.set[i].0 != N::ZERO).collect()
+			},
+			RemovalPolicy::PreTrusted => self.pre_trusted_indices(),
+		};
+		if recipients.is_empty() {
+			return Ok(());
+		}
+
+		// Hand the released score over, so the total score is kept
+		let released = before.iter().sum::<BigRational>() - self.total_score();
+		let sum: BigRational = recipients.iter().map(|&i| &before[i]).sum();
+		let count = BigRational::from_integer(BigInt::from(recipients.len()));
+		for i in recipients {
+			let share = match self.removal {
+				RemovalPolicy::PreTrusted => BigRational::one() / &count,
+				_ => &before[i] / &sum,
+			};
+			let (addr_i, _) = self.set[i];
+			let redistributed = self.redistributed.entry(addr_i).or_insert_with(BigRational::zero);
+			*redistributed += released.clone() * share;
+		}
+
+		Ok(())
 	}
 
-	/// Update the opinion of the member
+	/// Returns the initial score of every slot: the members' initial scores, scaled so
+	/// that they sum to the set's initial score times the number of members. Free slots
+	/// start at zero. Without weighted members, every member starts at the set's initial
+	/// score.
+	pub fn initial_scores(&self) -> Vec<RationalScore> {
+		let weights: Vec<BigInt> = self
+			.set
+			.iter()
+			.map(|&(addr, _)| match addr == N::ZERO {
+				true => BigInt::zero(),
+				false => BigInt::from(*self.weights.get(&addr).unwrap_or(&self.initial_score)),
+			})
+			.collect();
+		let sum: BigInt = weights.iter().sum();
+		if sum.is_zero() {
+			return vec![BigRational::zero(); self.set.len()];
+		}
+
+		let members = self.set.iter().filter(|&&(addr, _)| addr != N::ZERO).count();
+		let total = BigInt::from(members) * self.initial_score;
+		self.set
+			.iter()
+			.zip(weights)
+			.map(|(&(addr, _), weight)| {
+				let score = BigRational::new(weight * &total, sum.clone());
+				match self.redistributed.get(&addr) {
+					Some(redistributed) => score + redistributed,
+					None => score,
+				}
+			})
+			.collect()
+	}
+
+	/// Returns the total score of the set, the sum of the initial scores, which the
+	/// convergence keeps.
+	pub fn total_score(&self) -> RationalScore {
+		self.initial_scores().iter().sum()
+	}
+
+	/// Returns the slot of the set member.
+	fn member_index(&self, addr: N) -> Result<usize, SetError> {
+		let pos = self.set.iter().position(|&(x, _)| x == addr);
+		pos.filter(|_| addr != N::ZERO).ok_or(SetError::MissingMember)
+	}
+
+	/// Update the opinion of the member, with an attestation slot for each set member
 	pub fn update_op(
 		&mut self, from: PublicKey<C, N, NUM_LIMBS, NUM_BITS, P, EC>,
 		op: Vec<Option<SignedAttestation<C, N, NUM_LIMBS, NUM_BITS, P>>>,
-	) -> N {
-		// Get participant set addresses
-		let set: Vec<N> = self.set.iter().map(|&(addr, _)| addr).collect();
-
-		// Build the opinion group by unwrapping attestations and filling the empty ones with default values.
-		// Enumerating to keep track of the participant this opinion is about.
-		let opinion_group = op
-			.into_iter()
-			.enumerate()
-			.map(|(index, attestation)| {
-				attestation.unwrap_or_else(|| {
-					SignedAttestation::<C, N, NUM_LIMBS, NUM_BITS, P>::empty_with_about(
-						set[index], self.domain,
-					)
-				})
-			})
-			.collect();
+	) -> Result<N, SetError> {
+		let addr = from.to_address();
+		self.member_index(addr)?;
+
+		let opinion_group = self.checked_op_group(op)?;
+
+		// Validate the opinion, as `Opinion::validate` does for sets sized at compile time
+		let is_default_pk = from == PublicKey::default();
+
+		let mut scores = Vec::new();
+		let mut hashes = Vec::new();
+		for (&(member, _), att) in self.set.iter().zip(opinion_group) {
+			let att_hash = H::new([
+				att.attestation.about,
+				att.attestation.domain,
+				att.attestation.value,
+				att.attestation.message,
+				N::ZERO,
+			])
+			.finalize()[0];
+
+			let msg_hash = Integer::<C::ScalarExt, N, NUM_LIMBS, NUM_BITS, P>::from_n(att_hash);
+			let ecdsa_verifier = EcdsaVerifier::new(att.signature, msg_hash, from.clone());
+			let is_valid = ecdsa_verifier.verify();
+
+			let invalid_condition = !is_valid || member == N::ZERO || is_default_pk;
+			if invalid_condition {
+				scores.push(N::ZERO);
+				hashes.push(N::ZERO);
+			} else {
+				scores.push(att.attestation.value);
+				hashes.push(att_hash);
+			}
+		}
 
-		// Build opinion from the opinion group and validate
-		let opinion = Opinion::<NUM_NEIGHBOURS, C, N, NUM_LIMBS, NUM_BITS, P, EC, H, SH>::new(
-			from, opinion_group, self.domain,
-		);
-		let (addr, scores, op_hash) = opinion.validate(set);
+		let mut sponge_hasher = SH::new();
+		sponge_hasher.update(&hashes);
+		let op_hash = sponge_hasher.squeeze();
 
 		self.ops.insert(addr, scores);
 
-		op_hash
+		Ok(op_hash)
+	}
+
+	/// Update the opinion of a member whose attestations were verified outside of the set,
+	/// such as contract wallets. These opinions aren't covered by the circuit.
+	pub fn update_verified_op(&mut self, from: N, scores: Vec<N>) -> Result<(), SetError> {
+		// Make sure already in the set
+		self.member_index(from)?;
+		if scores.len() > self.set.len() {
+			return Err(SetError::ScoresMismatch);
+		}
+
+		self.ops.insert(from, scores);
+
+		Ok(())
+	}
+
+	/// Update the distrust opinion of the member, holding how much it distrusts each set
+	/// member. Distrust is combined with trust per opinion, before normalization: the
+	/// local trust of `i` in `j` becomes `max(trust_ij - distrust_ij, 0)`, and peers
+	/// distrusted by `i` don't get a share when `i` trusts nobody. The scores stay
+	/// non-negative, so the convergence is unchanged, and distrusted peers lose the trust
+	/// that would have flowed to them. Distrust opinions aren't covered by the circuit.
+	pub fn update_distrust(&mut self, from: N, scores: Vec<N>) -> Result<(), SetError> {
+		// Make sure already in the set
+		self.member_index(from)?;
+		if scores.len() > self.set.len() {
+			return Err(SetError::ScoresMismatch);
+		}
+
+		self.distrust.insert(from, scores);
+
+		Ok(())
 	}
 
 	/// Unwraps a `Vec<Option<SignedAttestation>>`.
 	/// `None` values are replaced by empty signed attestations with the correct about field.
 	pub fn parse_op_group(
-		&mut self, op: Vec<Option<SignedAttestation<C, N, NUM_LIMBS, NUM_BITS, P>>>,
+		&self, op: Vec<Option<SignedAttestation<C, N, NUM_LIMBS, NUM_BITS, P>>>,
 	) -> Vec<SignedAttestation<C, N, NUM_LIMBS, NUM_BITS, P>> {
-		// Get participant set addresses
-		let set: Vec<N> = self.set.iter().map(|&(addr, _)| addr).collect();
-
 		op.into_iter()
 			.enumerate()
 			.map(|(index, attestation)| {
 				attestation.unwrap_or_else(|| {
 					SignedAttestation::<C, N, NUM_LIMBS, NUM_BITS, P>::empty_with_about(
-						set[index], self.domain,
+						self.set[index].0, self.domain,
 					)
 				})
 			})
 			.collect()
 	}
 
+	/// Unwraps the opinion like `parse_op_group`, making sure it has an attestation for each
+	/// set slot, about the member of the slot and of the set domain.
+	fn checked_op_group(
+		&self, op: Vec<Option<SignedAttestation<C, N, NUM_LIMBS, NUM_BITS, P>>>,
+	) -> Result<Vec<SignedAttestation<C, N, NUM_LIMBS, NUM_BITS, P>>, SetError> {
+		if op.len() != self.set.len() {
+			return Err(SetError::ScoresMismatch);
+		}
+
+		let opinion_group = self.parse_op_group(op);
+		for (&(member, _), att) in self.set.iter().zip(&opinion_group) {
+			if att.attestation.about != member {
+				return Err(SetError::WrongSubject);
+			}
+			if att.attestation.domain != self.domain {
+				return Err(SetError::WrongDomain);
+			}
+		}
+
+		Ok(opinion_group)
+	}
+
 	/// Method for filtering invalid opinions
 	fn filter_peers_ops(&self) -> HashMap<N, Vec<N>> {
+		self.filter_peers_ops_with_empty().0
+	}
+
+	/// Returns the members whose opinion is left without any valid score, once the scores
+	/// given to themselves, to the free slots and to the peers they distrust are removed,
+	/// so that the opinion filter policy decides where their score goes instead of them.
+	pub fn empty_opinions(&self) -> Vec<N> {
+		self.filter_peers_ops_with_empty().1
+	}
+
+	/// Filters the opinions like `filter_peers_ops`, also returning the members whose
+	/// opinion was filled by the opinion filter policy.
+	fn filter_peers_ops_with_empty(&self) -> (HashMap<N, Vec<N>>, Vec<N>) {
+		let size = self.set.len();
+		let pre_trusted = self.pre_trusted_indices();
 		let mut filtered_ops: HashMap<N, Vec<N>> = HashMap::new();
+		let mut empty = Vec::new();
 
 		// Distribute the scores to valid peers
-		for i in 0..NUM_NEIGHBOURS {
+		for i in 0..size {
 			let (addr_i, _) = self.set[i];
 			if addr_i == N::ZERO {
 				continue;
 			}
 
-			let default_ops = vec![N::default(); NUM_NEIGHBOURS];
-			let mut ops_i = self.ops.get(&addr_i).unwrap_or(&default_ops).clone();
+			// Opinions given before the set grew have no scores for the new members
+			let mut ops_i = self.ops.get(&addr_i).cloned().unwrap_or_default();
+			ops_i.resize(size, N::ZERO);
+			let mut distrust_i = self.distrust.get(&addr_i).cloned().unwrap_or_default();
+			distrust_i.resize(size, N::ZERO);
+
+			// Offset the trust by the distrust, field elements being compared as integers
+			for j in 0..size {
+				let (trust, distrust) = (fe_to_big(ops_i[j]), fe_to_big(distrust_i[j]));
+				if distrust >= trust {
+					ops_i[j] = N::ZERO;
+				} else {
+					ops_i[j] -= distrust_i[j];
+				}
+			}
 
 			// Update the opinion array - pairs of (key, score)
-			for j in 0..NUM_NEIGHBOURS {
+			for j in 0..size {
 				let (addr_j, _) = self.set[j];
 
 				// Conditions fro nullifying the score
@@ -271,92 +1102,675 @@ impl<
 				}
 			}
 
-			// Distribute the scores
+			// Fill the empty opinions according to the filter policy
 			let op_score_sum = ops_i.iter().fold(N::ZERO, |acc, &score| acc + score);
 			if op_score_sum == N::ZERO {
-				for j in 0..NUM_NEIGHBOURS {
-					let (addr_j, _) = self.set[j];
-
-					// Conditions for distributing the score
-					// 1. addr_j != addr_i
-					// 2. addr_j != 0 (Default key)
-					let is_diff_addr = addr_j != addr_i;
-					let is_not_default = addr_j != N::ZERO;
-
-					if is_diff_addr && is_not_default {
-						ops_i[j] = N::from(1);
-					}
-				}
+				ops_i = self.opinion_filter().fill_empty(&self.set, i, &distrust_i, &pre_trusted);
+				empty.push(addr_i);
 			}
 			filtered_ops.insert(addr_i, ops_i);
 		}
 
-		filtered_ops
+		(filtered_ops, empty)
 	}
 
 	/// Compute the EigenTrust score
-	pub fn converge(&self) -> Vec<N> {
-		// There should be at least 2 valid peers(valid opinions) for calculation
-		let valid_peers = self.set.iter().filter(|(addr, _)| *addr != N::ZERO).count();
-		assert!(valid_peers >= 2, "Insufficient peers for calculation!");
+	///
+	/// This is the computation the circuit constrains. The circuit divides with
+	/// modular inverses in the scalar field, so there is no fixed-point integer
+	/// variant that reproduces its scores bit for bit; use
+	/// [`Self::converge_rational`] when exact real-valued scores are needed.
+	pub fn converge(&self) -> Result<Vec<N>, SetError> {
+		self.converge_iterations(self.iterations_needed())
+	}
 
-		// Prepare the opinion scores
-		let mut ops = Vec::new();
-		let filtered_ops: HashMap<N, Vec<N>> = self.filter_peers_ops();
-		for i in 0..NUM_NEIGHBOURS {
-			let (addr, _) = self.set[i];
-			if addr == N::ZERO {
-				ops.push(vec![N::ZERO; NUM_NEIGHBOURS]);
-			} else {
-				let scores = filtered_ops.get(&addr).unwrap();
-				ops.push(scores.clone());
-			}
+	/// Compute the EigenTrust score both as field elements and as exact BigRational numbers,
+	/// keeping the rational scores of every iteration like `converge_rational_trace`. The
+	/// field scores run as many iterations as the rational ones, so that each field score
+	/// is its rational score in the field, see `rational_to_fe`.
+	pub fn converge_with_rational_trace(
+		&self,
+	) -> Result<(Vec<N>, Vec<Vec<RationalScore>>), SetError> {
+		let trace = self.converge_rational_trace();
+		let scores = self.converge_iterations(trace.len() - 1)?;
+
+		Ok((scores, trace))
+	}
+
+	/// Compute the EigenTrust score like `converge_with_rational_trace`, along with the
+	/// diagnostics of the run.
+	pub fn converge_with_report(&self) -> Result<(Vec<N>, ConvergenceReport<N>), SetError> {
+		let trace = self.converge_rational_trace();
+		let scores = self.converge_iterations(trace.len() - 1)?;
+
+		let (initial, last) = (&trace[0], &trace[trace.len() - 1]);
+		let residual = match trace.len() {
+			1 => BigRational::zero(),
+			len => max_change(&trace[len - 2], last),
+		};
+		let sum_drift = last.iter().sum::<BigRational>() - initial.iter().sum::<BigRational>();
+
+		// Non-zero scores of the raw opinions left out by the filtering
+		let (filtered_ops, empty_opinions) = self.filter_peers_ops_with_empty();
+		let mut filtered_scores = 0;
+		for (addr, filtered) in &filtered_ops {
+			let is_empty = empty_opinions.contains(addr);
+			let raw = self.ops.get(addr).cloned().unwrap_or_default();
+			filtered_scores += raw
+				.iter()
+				.enumerate()
+				.filter(|(j, score)| {
+					let zeroed = filtered.get(*j).map_or(true, |score| *score == N::ZERO);
+					**score != N::ZERO && (is_empty || zeroed)
+				})
+				.count();
 		}
 
-		// Normalize the opinion scores
-		let mut ops_norm = vec![vec![N::ZERO; NUM_NEIGHBOURS]; NUM_NEIGHBOURS];
-		for i in 0..NUM_NEIGHBOURS {
+		let matrix = self.trust_matrix();
+		let no_inbound_trust = (0..matrix.members.len())
+			.filter(|&j| {
+				(0..matrix.members.len()).all(|i| i == j || matrix.rational_scores[i][j].is_zero())
+			})
+			.map(|j| matrix.members[j])
+			.collect();
+
+		let report = ConvergenceReport {
+			iterations: trace.len() - 1,
+			residual,
+			sum_drift,
+			filtered_scores,
+			empty_opinions,
+			no_inbound_trust,
+		};
+
+		Ok((scores, report))
+	}
+
+	/// Compute the EigenTrust score, keeping the scores and the residual of every iteration
+	/// so the convergence can be plotted, e.g. to tune the number of iterations and the
+	/// initial score. The first step holds the initial scores, with a zero residual, and
+	/// the last one the final scores, as returned by `converge_with_rational_trace`.
+	pub fn converge_trajectory(&self) -> Result<Vec<ConvergenceStep<N>>, SetError> {
+		let trace = self.converge_rational_trace();
+		let field_trace = self.converge_iterations_trace(trace.len() - 1)?;
+
+		let mut steps: Vec<ConvergenceStep<N>> = Vec::with_capacity(trace.len());
+		for (iteration, (scores, rational_scores)) in field_trace.into_iter().zip(trace).enumerate()
+		{
+			let residual = match steps.last() {
+				Some(previous) => max_change(&previous.rational_scores, &rational_scores),
+				None => BigRational::zero(),
+			};
+			steps.push(ConvergenceStep { iteration, scores, rational_scores, residual });
+		}
+
+		Ok(steps)
+	}
+
+	/// Returns the filtered and normalized opinion matrix the convergence iterates over,
+	/// with the decay of the stale opinions, leaving out the free slots, whose rows and
+	/// columns are zero.
+	pub fn trust_matrix(&self) -> TrustMatrix<N> {
+		let indices: Vec<usize> =
+			(0..self.set.len()).filter(|&i| self.set[i].0 != N::ZERO).collect();
+		let members = indices.iter().map(|&i| self.set[i].0).collect();
+		let ops_norm = self.normalize_ops(&self.filtered_ops());
+		let scores =
+			indices.iter().map(|&i| indices.iter().map(|&j| ops_norm[i][j]).collect()).collect();
+		let ops_norm = self.normalize_ops_rational();
+		let rational_scores = indices
+			.iter()
+			.map(|&i| indices.iter().map(|&j| ops_norm[i][j].clone()).collect())
+			.collect();
+
+		TrustMatrix { members, scores, rational_scores }
+	}
+
+	/// Returns the filtered opinion scores of every set slot, zero for the free slots.
+	fn filtered_ops(&self) -> Vec<Vec<N>> {
+		let size = self.set.len();
+		let filtered_ops: HashMap<N, Vec<N>> = self.filter_peers_ops();
+		self.set
+			.iter()
+			.map(|(addr, _)| match filtered_ops.get(addr) {
+				Some(scores) if *addr != N::ZERO => scores.clone(),
+				_ => vec![N::ZERO; size],
+			})
+			.collect()
+	}
+
+	/// Normalizes the opinion scores and decays the stale ones, giving the matrix the
+	/// field convergence iterates over.
+	fn normalize_ops(&self, ops: &[Vec<N>]) -> Vec<Vec<N>> {
+		let size = self.set.len();
+
+		// Normalize the opinion scores
+		let mut ops_norm = vec![vec![N::ZERO; size]; size];
+		for i in 0..size {
 			let op_score_sum: N = ops[i].iter().sum();
 			let inverted_sum = op_score_sum.invert().unwrap_or(N::ZERO);
 
-			for j in 0..NUM_NEIGHBOURS {
+			for j in 0..size {
 				let ops_ij = ops[i][j];
 				ops_norm[i][j] = ops_ij * inverted_sum;
 			}
 		}
 
-		// Compute the EigenTrust scores using the filtered and normalized scores
-		let mut s: Vec<N> = self.set.iter().map(|(_, score)| *score).collect();
-		let mut new_s: Vec<N> = self.set.iter().map(|(_, score)| *score).collect();
-		for _ in 0..NUM_ITERATIONS {
-			for i in 0..NUM_NEIGHBOURS {
-				let mut score_i_sum = N::ZERO;
-				for j in 0..NUM_NEIGHBOURS {
-					let score = ops_norm[j][i] * s[j];
-					score_i_sum = score + score_i_sum;
+		// Decay the stale opinions towards an even spread over the other members
+		for i in 0..size {
+			let (addr, _) = self.set[i];
+			let spread = self.spread_indices(i);
+			let weight = match self.decay_weight_fe(addr) {
+				Some(weight) if addr != N::ZERO && !spread.is_empty() => weight,
+				_ => continue,
+			};
+
+			let share = (N::ONE - weight) * N::from(spread.len() as u64).invert().unwrap();
+			for j in 0..size {
+				ops_norm[i][j] *= weight;
+				if spread.contains(&j) {
+					ops_norm[i][j] += share;
 				}
-				new_s[i] = score_i_sum;
 			}
-			s = new_s.clone();
 		}
 
-		// Assert the score sum for checking the possible reputation leak
-		let sum_initial = self.set.iter().fold(N::ZERO, |acc, &(_, score)| acc + score);
+		// Scale the opinions by the stakes, the rest of the score staying with the member
+		for i in 0..size {
+			let (addr, _) = self.set[i];
+			let is_dropped = ops_norm[i].iter().all(|op| *op == N::ZERO);
+			let weight = match self.stake_weight_fe(addr) {
+				Some(weight) if addr != N::ZERO && !is_dropped => weight,
+				_ => continue,
+			};
+
+			for j in 0..size {
+				ops_norm[i][j] *= weight;
+			}
+			ops_norm[i][i] += N::ONE - weight;
+		}
+
+		ops_norm
+	}
+
+	/// Returns the damping factor and its complement as field elements.
+	fn damping_fe(&self) -> (N, N) {
+		match self.damping {
+			Some((num, den)) => {
+				let factor = N::from_u128(num) * N::from_u128(den).invert().unwrap();
+				(factor, N::ONE - factor)
+			},
+			None => (N::ZERO, N::ONE),
+		}
+	}
+
+	/// Returns the pre-trusted scores, sharing the total initial score, as field elements.
+	fn pre_trust_fe(&self, initial_s: &[N]) -> Vec<N> {
+		let pre_trusted = self.pre_trusted_indices();
+		if pre_trusted.is_empty() {
+			return initial_s.to_vec();
+		}
+
+		let total: N = initial_s.iter().sum();
+		let share = total * N::from(pre_trusted.len() as u64).invert().unwrap();
+		(0..self.set.len())
+			.map(|i| if pre_trusted.contains(&i) { share } else { N::ZERO })
+			.collect()
+	}
+
+	/// Asserts the score sum for checking the possible reputation leak, unless the filter
+	/// policy dropped members. The normalization keeps the sum whatever the opinions, so
+	/// this only checks the convergence itself, in debug builds.
+	fn assert_total(&self, ops: &[Vec<N>], initial_s: &[N], s: &[N]) {
+		let dropped = (0..self.set.len())
+			.any(|i| self.set[i].0 != N::ZERO && ops[i].iter().all(|op| *op == N::ZERO));
+		let sum_initial = initial_s.iter().fold(N::ZERO, |acc, &score| acc + score);
 		let sum_final = s.iter().fold(N::ZERO, |acc, &score| acc + score);
-		assert!(sum_initial == sum_final);
+		debug_assert!(dropped || sum_initial == sum_final);
+	}
+
+	/// Compute the EigenTrust score like `converge`, keeping the opinion matrix in a
+	/// contiguous `ndarray` array instead of a vector per member, so that the iterations
+	/// stream through memory on large sets. The scores are the same as `converge`.
+	#[cfg(feature = "ndarray")]
+	pub fn converge_ndarray(&self) -> Result<Vec<N>, SetError> {
+		let size = self.set.len();
 
-		s
+		// There should be at least 2 valid peers(valid opinions) for calculation
+		let valid_peers = self.set.iter().filter(|(addr, _)| *addr != N::ZERO).count();
+		if valid_peers < 2 {
+			return Err(SetError::InsufficientPeers);
+		}
+
+		let ops = self.filtered_ops();
+		let ops_norm = self.normalize_ops(&ops);
+		let (damping, undamped) = self.damping_fe();
+		let initial_s: Vec<N> = self.initial_scores().iter().map(rational_to_fe).collect();
+		let pre_trust = Array1::from(self.pre_trust_fe(&initial_s));
+
+		// Row `i` holds the opinion scores about member `i`
+		let ops_about = Array2::from_shape_fn((size, size), |(i, j)| ops_norm[j][i]);
+		let mut s = Array1::from(initial_s.clone());
+		for _ in 0..self.iterations_needed() {
+			s = Zip::from(ops_about.rows()).and(&pre_trust).par_map_collect(
+				|ops_i, &pre_trust_i| {
+					let score_i_sum = Zip::from(&ops_i)
+						.and(&s)
+						.fold(N::ZERO, |acc, &op, &score| acc + op * score);
+					undamped * score_i_sum + damping * pre_trust_i
+				},
+			);
+		}
+
+		let s = s.to_vec();
+		self.assert_total(&ops, &initial_s, &s);
+
+		Ok(s)
+	}
+
+	/// Compute the EigenTrust score over the given number of iterations
+	fn converge_iterations(&self, num_iterations: usize) -> Result<Vec<N>, SetError> {
+		let mut trace = self.converge_iterations_trace(num_iterations)?;
+		Ok(trace.pop().unwrap())
+	}
+
+	/// Compute the EigenTrust score over the given number of iterations, keeping the scores
+	/// of every iteration, starting with the initial scores
+	fn converge_iterations_trace(&self, num_iterations: usize) -> Result<Vec<Vec<N>>, SetError> {
+		// There should be at least 2 valid peers(valid opinions) for calculation
+		let valid_peers = self.set.iter().filter(|(addr, _)| *addr != N::ZERO).count();
+		if valid_peers < 2 {
+			return Err(SetError::InsufficientPeers);
+		}
+
+		let ops = self.filtered_ops();
+		let ops_norm = self.normalize_ops(&ops);
+		let (damping, undamped) = self.damping_fe();
+		let initial_s: Vec<N> = self.initial_scores().iter().map(rational_to_fe).collect();
+		let pre_trust = self.pre_trust_fe(&initial_s);
+
+		// Compute the EigenTrust scores using the filtered and normalized scores. Every score
+		// only depends on the previous ones, so they're computed in parallel, each from the
+		// opinion scores about its member.
+		let ops_about = transpose(&ops_norm);
+		let mut s: Vec<N> = initial_s.clone();
+		let mut trace = Vec::with_capacity(num_iterations + 1);
+		trace.push(s.clone());
+		// Field elements aren't ordered, so the stopping iteration is found on rationals
+		for _ in 0..num_iterations {
+			s = ops_about
+				.par_iter()
+				.zip(&pre_trust)
+				.map(|(ops_i, pre_trust_i)| {
+					let score_i_sum =
+						ops_i.iter().zip(&s).fold(N::ZERO, |acc, (&op, &score)| acc + op * score);
+					undamped * score_i_sum + damping * pre_trust_i
+				})
+				.collect();
+			trace.push(s.clone());
+		}
+
+		self.assert_total(&ops, &initial_s, &s);
+
+		Ok(trace)
+	}
+
+	/// Returns the set members with their converged BigRational scores, from the highest
+	/// score to the lowest. Members with the same score keep their set order.
+	pub fn ranking(&self) -> Vec<(N, RationalScore)> {
+		self.rank(self.converge_rational())
+	}
+
+	/// Returns the set members with their personalized scores for the viewer, from the
+	/// highest score to the lowest, see `converge_personalized`.
+	pub fn personalized_ranking(&self, viewer: N) -> Result<Vec<(N, RationalScore)>, SetError> {
+		Ok(self.rank(self.converge_personalized(viewer)?))
+	}
+
+	/// Pairs the members with their scores, sorted from the highest score to the lowest.
+	fn rank(&self, scores: Vec<RationalScore>) -> Vec<(N, RationalScore)> {
+		let mut ranking: Vec<(N, RationalScore)> = self
+			.set
+			.iter()
+			.zip(scores)
+			.filter(|((addr, _), _)| *addr != N::ZERO)
+			.map(|(&(addr, _), score)| (addr, score))
+			.collect();
+		ranking.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+		ranking
+	}
+
+	/// Returns the `k` members with the highest converged scores, see `ranking`.
+	pub fn top_k(&self, k: usize) -> Vec<(N, RationalScore)> {
+		let mut ranking = self.ranking();
+		ranking.truncate(k);
+
+		ranking
+	}
+
+	/// Returns the rank of the member after convergence, starting at 1 for the highest
+	/// score, see `ranking`.
+	pub fn rank_of(
+		&self, pk: &PublicKey<C, N, NUM_LIMBS, NUM_BITS, P, EC>,
+	) -> Result<usize, SetError> {
+		let addr = pk.to_address();
+		self.member_index(addr)?;
+
+		let ranking = self.ranking();
+		let pos = ranking.iter().position(|(member, _)| *member == addr);
+		pos.map(|pos| pos + 1).ok_or(SetError::MissingMember)
 	}
 
 	/// Compute the EigenTrust score using BigRational numbers
 	pub fn converge_rational(&self) -> Vec<RationalScore> {
+		let mut trace = self.converge_rational_trace();
+		trace.pop().unwrap()
+	}
+
+	/// Compute the EigenTrust score using BigRational numbers, keeping the scores of every
+	/// iteration. The first entry holds the initial scores and the last one the final scores.
+	/// With a tolerance, the trace ends at the first iteration changing no score by as much.
+	pub fn converge_rational_trace(&self) -> Vec<Vec<RationalScore>> {
+		self.converge_rational_trace_inner(None, None, self.num_iterations())
+	}
+
+	/// Compute the EigenTrust score using BigRational numbers, starting from the scores of a
+	/// previous convergence, one per set slot, instead of the initial scores. After a few
+	/// opinion updates the scores are already close to the new ones, so with a tolerance
+	/// the convergence stops after much fewer iterations.
+	/// The previous scores of the empty slots are ignored, and the others are scaled to
+	/// the total score of the current members, so members may have joined or left since.
+	pub fn converge_rational_from(
+		&self, previous: &[RationalScore],
+	) -> Result<Vec<RationalScore>, SetError> {
+		let mut trace = self.converge_rational_trace_from(previous)?;
+		Ok(trace.pop().unwrap())
+	}
+
+	/// Compute the EigenTrust score using BigRational numbers like `converge_rational_from`,
+	/// keeping the scores of every iteration like `converge_rational_trace`.
+	pub fn converge_rational_trace_from(
+		&self, previous: &[RationalScore],
+	) -> Result<Vec<Vec<RationalScore>>, SetError> {
+		if previous.len() != self.set.len() {
+			return Err(SetError::ScoresMismatch);
+		}
+
+		Ok(self.converge_rational_trace_inner(Some(previous), None, self.num_iterations()))
+	}
+
+	/// Run a single convergence iteration from the scores of the previous heartbeat, or from
+	/// the initial scores on the first one, keeping its scores for the next heartbeat.
+	/// Opinions and members can be updated between heartbeats, the convergence going on
+	/// from the current scores instead of restarting, as suits a long-running daemon.
+	/// Members that joined since the previous heartbeat start from their initial score, and
+	/// the scores are scaled to the total score of the current members, like
+	/// `converge_rational_from`. The residual of the step is the largest score change.
+	/// Heartbeat scores aren't covered by the circuit.
+	pub fn heartbeat(&mut self) -> ConvergenceStep<N> {
+		let (iteration, previous) = match self.heartbeat.take() {
+			Some((iteration, scores)) => {
+				let initial = self.initial_scores();
+				let previous: Vec<RationalScore> = self
+					.set
+					.iter()
+					.zip(initial)
+					.map(|((addr, _), initial)| scores.get(addr).cloned().unwrap_or(initial))
+					.collect();
+				(iteration + 1, Some(previous))
+			},
+			None => (1, None),
+		};
+
+		let mut trace = self.converge_rational_trace_inner(previous.as_deref(), None, 1);
+		let rational_scores = trace.pop().unwrap();
+		let residual = max_change(&trace[0], &rational_scores);
+
+		let scores = self
+			.set
+			.iter()
+			.zip(&rational_scores)
+			.filter(|((addr, _), _)| *addr != N::ZERO)
+			.map(|((addr, _), score)| (*addr, score.clone()))
+			.collect();
+		self.heartbeat = Some((iteration, scores));
+
+		let field_scores = rational_scores.iter().map(rational_to_fe).collect();
+		ConvergenceStep { iteration, scores: field_scores, rational_scores, residual }
+	}
+
+	/// Returns the score and membership changes from this set to the other one, a later
+	/// state of it, such as the set restored from a previous state, see `from_state`, and
+	/// the current one. The scores of both sets are converged.
+	pub fn diff(&self, other: &Self) -> SetDiff<N> {
+		let members = |set: &Self| -> Vec<(N, RationalScore)> {
+			set.set
+				.iter()
+				.zip(set.converge_rational())
+				.filter(|((addr, _), _)| *addr != N::ZERO)
+				.map(|((addr, _), score)| (*addr, score))
+				.collect()
+		};
+		let (old, new) = (members(self), members(other));
+		let old_scores: HashMap<N, RationalScore> = old.iter().cloned().collect();
+		let new_scores: HashMap<N, RationalScore> = new.iter().cloned().collect();
+
+		let mut changes: Vec<ScoreDelta<N>> = new
+			.iter()
+			.filter_map(|(member, new)| {
+				let old = old_scores.get(member)?.clone();
+				let delta = new - &old;
+				Some(ScoreDelta { member: *member, old, new: new.clone(), delta })
+			})
+			.collect();
+		changes.sort_by(|a, b| b.delta.abs().cmp(&a.delta.abs()));
+
+		let joined =
+			new.into_iter().filter(|(member, _)| !old_scores.contains_key(member)).collect();
+		let left = old.into_iter().filter(|(member, _)| !new_scores.contains_key(member)).collect();
+
+		SetDiff { changes, joined, left }
+	}
+
+	/// Returns the state of the set at the given epoch, along with its converged scores,
+	/// the heartbeat ones if running, see `heartbeat`.
+	pub fn state(&self, epoch: u64) -> SetState {
+		let members: Vec<N> = self.set.iter().map(|&(addr, _)| addr).collect();
+		let to_string = |x: &N| field_to_string(x);
+		let of_members = |map: &HashMap<N, Vec<N>>| -> Vec<(String, Vec<String>)> {
+			members
+				.iter()
+				.filter_map(|addr| map.get(addr).map(|scores| (addr, scores)))
+				.map(|(addr, scores)| (to_string(addr), scores.iter().map(to_string).collect()))
+				.collect()
+		};
+		let values_of_members = |map: &HashMap<N, u128>| -> Vec<(String, u128)> {
+			members
+				.iter()
+				.filter_map(|addr| map.get(addr).map(|value| (to_string(addr), *value)))
+				.collect()
+		};
+
+		let scores = match &self.heartbeat {
+			Some((_, scores)) => members
+				.iter()
+				.map(|addr| scores.get(addr).cloned().unwrap_or_else(BigRational::zero))
+				.collect(),
+			None => self.converge_rational(),
+		};
+
+		SetState {
+			version: SET_STATE_VERSION,
+			epoch,
+			domain: to_string(&self.domain),
+			num_iterations: self.num_iterations,
+			initial_score: self.initial_score,
+			members: members.iter().map(to_string).collect(),
+			ops: of_members(&self.ops),
+			distrust: of_members(&self.distrust),
+			op_blocks: members
+				.iter()
+				.filter_map(|addr| self.op_blocks.get(addr).map(|block| (to_string(addr), *block)))
+				.collect(),
+			weights: values_of_members(&self.weights),
+			stakes: values_of_members(&self.stakes),
+			redistributed: members
+				.iter()
+				.filter_map(|addr| {
+					self.redistributed.get(addr).map(|score| (to_string(addr), score.to_string()))
+				})
+				.collect(),
+			pre_trusted: self.pre_trusted.iter().map(to_string).collect(),
+			damping: self.damping,
+			tolerance: self.tolerance,
+			decay: self.decay.map(|decay| (decay.factor, decay.period, decay.current_block)),
+			removal: self.removal,
+			scores: scores.iter().map(|score| score.to_string()).collect(),
+			heartbeat: self.heartbeat.as_ref().map(|(iteration, _)| *iteration),
+		}
+	}
+
+	/// Restores the set from its state, resuming the heartbeats if they were running.
+	pub fn from_state(state: &SetState) -> Result<Self, SetError> {
+		if state.version != SET_STATE_VERSION {
+			return Err(SetError::InvalidState);
+		}
+
+		let members: Vec<N> =
+			state.members.iter().map(|x| parse_fe(x)).collect::<Result<_, _>>()?;
+		let mut set = Self::try_new(
+			parse_fe(&state.domain)?,
+			members.len(),
+			state.num_iterations,
+			state.initial_score,
+		)
+		.map_err(|_| SetError::InvalidState)?;
+		for (slot, addr) in members.iter().enumerate() {
+			if *addr != N::ZERO {
+				set.set[slot] = (*addr, N::from_u128(state.initial_score));
+			}
+		}
+
+		let parse_scores = |scores: &[String]| -> Result<Vec<N>, SetError> {
+			if scores.len() > members.len() {
+				return Err(SetError::InvalidState);
+			}
+			scores.iter().map(|x| parse_fe(x)).collect()
+		};
+		for (addr, scores) in &state.ops {
+			set.ops.insert(set.state_member(addr)?, parse_scores(scores)?);
+		}
+		for (addr, scores) in &state.distrust {
+			set.distrust.insert(set.state_member(addr)?, parse_scores(scores)?);
+		}
+		for (addr, block) in &state.op_blocks {
+			set.op_blocks.insert(set.state_member(addr)?, *block);
+		}
+		for (addr, weight) in &state.weights {
+			if *weight == 0 {
+				return Err(SetError::InvalidState);
+			}
+			set.weights.insert(set.state_member(addr)?, *weight);
+		}
+		for (addr, stake) in &state.stakes {
+			set.stakes.insert(set.state_member(addr)?, *stake);
+		}
+		for (addr, score) in &state.redistributed {
+			set.redistributed.insert(set.state_member(addr)?, parse_rational(score)?);
+		}
+		set.pre_trusted =
+			state.pre_trusted.iter().map(|x| parse_fe(x)).collect::<Result<_, _>>()?;
+
+		// Check the parameters like their setters, as the convergence relies on them
+		let valid_params = state.damping.map_or(true, valid_damping)
+			&& state.tolerance.map_or(true, valid_tolerance)
+			&& state.decay.map_or(true, |(factor, period, _)| valid_decay(factor, period));
+		if !valid_params {
+			return Err(SetError::InvalidState);
+		}
+		set.damping = state.damping;
+		set.tolerance = state.tolerance;
+		set.decay = state.decay.map(|(factor, period, current_block)| Decay {
+			factor,
+			period,
+			current_block,
+		});
+		set.removal = state.removal;
+
+		if let Some(iteration) = state.heartbeat {
+			if state.scores.len() != members.len() {
+				return Err(SetError::InvalidState);
+			}
+			let scores = members
+				.iter()
+				.zip(&state.scores)
+				.filter(|(addr, _)| **addr != N::ZERO)
+				.map(|(addr, score)| Ok((*addr, parse_rational(score)?)))
+				.collect::<Result<_, SetError>>()?;
+			set.heartbeat = Some((iteration, scores));
+		}
+
+		Ok(set)
+	}
+
+	/// Parses the member of a set state, which must hold a slot of the set.
+	fn state_member(&self, addr: &str) -> Result<N, SetError> {
+		let addr = parse_fe(addr)?;
+		self.member_index(addr).map_err(|_| SetError::InvalidState)?;
+		Ok(addr)
+	}
+
+	/// Compute the EigenTrust scores personalized for the viewer, using BigRational numbers.
+	/// The damped share of the scores restarts from the viewer's local trust, its normalized
+	/// opinion, instead of the pre-trusted peers: every iteration computes
+	/// `(1 - a) * C^T * t + a * c_v`, as in personalized PageRank, so the scores rank the
+	/// members by how much the viewer trusts them, directly or through its peers.
+	/// Sets without a damping factor use `PERSONALIZED_DAMPING`, since undamped scores
+	/// don't depend on the viewer. Personalized scores aren't covered by the circuit.
+	pub fn converge_personalized(&self, viewer: N) -> Result<Vec<RationalScore>, SetError> {
+		let index = self.member_index(viewer)?;
+		let valid_peers = self.set.iter().filter(|(addr, _)| *addr != N::ZERO).count();
+		if valid_peers < 2 {
+			return Err(SetError::InsufficientPeers);
+		}
+
+		let mut trace =
+			self.converge_rational_trace_inner(None, Some(index), self.num_iterations());
+		Ok(trace.pop().unwrap())
+	}
+
+	/// Returns the scores a convergence starting from the previous scores starts with.
+	fn warm_start(&self, previous: &[RationalScore]) -> Option<Vec<RationalScore>> {
+		let start: Vec<RationalScore> = self
+			.set
+			.iter()
+			.zip(previous)
+			.map(|(&(addr, _), score)| match addr == N::ZERO {
+				true => BigRational::zero(),
+				false => score.clone(),
+			})
+			.collect();
+
+		let sum: BigRational = start.iter().sum();
+		if !sum.is_positive() {
+			return None;
+		}
+
+		let total = self.total_score();
+		Some(start.into_iter().map(|score| score * &total / &sum).collect())
+	}
+
+	/// Normalizes the filtered opinion scores and decays the stale ones, giving the matrix
+	/// the rational convergence iterates over.
+	fn normalize_ops_rational(&self) -> Vec<Vec<RationalScore>> {
+		let size = self.set.len();
 		let mut filtered_ops: HashMap<N, Vec<N>> = self.filter_peers_ops();
 
 		let mut ops = Vec::new();
-		for i in 0..NUM_NEIGHBOURS {
+		for i in 0..size {
 			let (addr, _) = self.set[i];
 			if addr == N::ZERO {
-				ops.push(vec![BigInt::zero(); NUM_NEIGHBOURS]);
+				ops.push(vec![BigInt::zero(); size]);
 			} else {
 				let ops_i = filtered_ops.get_mut(&addr).unwrap();
 				let scores =
@@ -366,41 +1780,598 @@ impl<
 		}
 
 		// Sanity check
-		assert!(ops.len() == NUM_NEIGHBOURS);
+		assert!(ops.len() == size);
 		for op in &ops {
-			assert!(op.len() == NUM_NEIGHBOURS);
+			assert!(op.len() == size);
 		}
 
-		let init_score_bn = BigInt::from_u128(INITIAL_SCORE).unwrap();
-		let mut s: Vec<BigRational> =
-			vec![BigRational::from_integer(init_score_bn); NUM_NEIGHBOURS];
-
-		let mut ops_norm = vec![vec![BigRational::zero(); NUM_NEIGHBOURS]; NUM_NEIGHBOURS];
-		for i in 0..NUM_NEIGHBOURS {
+		let mut ops_norm = vec![vec![BigRational::zero(); size]; size];
+		for i in 0..size {
 			let mut op_score_sum = ops[i].iter().fold(BigInt::zero(), |acc, score| acc + score);
 			if op_score_sum.is_zero() {
 				op_score_sum = BigInt::one();
 			}
 
-			for j in 0..NUM_NEIGHBOURS {
+			for j in 0..size {
 				let score = ops[i][j].clone();
 				ops_norm[i][j] = BigRational::new(score, op_score_sum.clone());
 			}
 		}
 
-		let mut new_s = s.clone();
-		for _ in 0..NUM_ITERATIONS {
-			for i in 0..NUM_NEIGHBOURS {
-				let mut score_i_sum = BigRational::zero();
-				for j in 0..NUM_NEIGHBOURS {
-					let score = ops_norm[j][i].clone() * s[j].clone();
-					score_i_sum = score + score_i_sum;
+		// Decay the stale opinions towards an even spread over the other members
+		for i in 0..size {
+			let (addr, _) = self.set[i];
+			let spread = self.spread_indices(i);
+			let weight = match self.decay_weight(addr) {
+				Some(weight) if addr != N::ZERO && !spread.is_empty() => weight,
+				_ => continue,
+			};
+
+			let share = (BigRational::one() - weight.clone())
+				/ BigRational::from_integer(BigInt::from(spread.len()));
+			for j in 0..size {
+				ops_norm[i][j] = ops_norm[i][j].clone() * weight.clone();
+				if spread.contains(&j) {
+					ops_norm[i][j] = ops_norm[i][j].clone() + share.clone();
 				}
-				new_s[i] = score_i_sum;
 			}
-			s = new_s.clone();
 		}
-		s
+
+		// Scale the opinions by the stakes, the rest of the score staying with the member
+		for i in 0..size {
+			let (addr, _) = self.set[i];
+			let is_dropped = ops_norm[i].iter().all(|op| op.is_zero());
+			let weight = match self.stake_weight(addr) {
+				Some(weight) if addr != N::ZERO && !is_dropped => weight,
+				_ => continue,
+			};
+
+			for j in 0..size {
+				ops_norm[i][j] = ops_norm[i][j].clone() * weight.clone();
+			}
+			ops_norm[i][i] = ops_norm[i][i].clone() + BigRational::one() - weight;
+		}
+
+		ops_norm
+	}
+
+	/// Compute the rational trace over the given number of iterations, from the previous
+	/// scores if any, else the initial ones. The damped scores restart from the local trust of the viewer at the given index,
+	/// if any, else from the pre-trusted scores.
+	fn converge_rational_trace_inner(
+		&self, previous: Option<&[RationalScore]>, viewer: Option<usize>, iterations: usize,
+	) -> Vec<Vec<RationalScore>> {
+		let (ops_norm, damping, undamped, pre_trust) = self.rational_params(viewer);
+		let mut s: Vec<BigRational> = match previous.and_then(|previous| self.warm_start(previous))
+		{
+			Some(start) => start,
+			None => self.initial_scores(),
+		};
+
+		// The stopping iteration is found beforehand, without the costly exact iterates
+		let iterations = match self.tolerance {
+			Some(tolerance) => {
+				tolerance_iterations(&ops_norm, &s, &pre_trust, &damping, tolerance, iterations)
+			},
+			None => iterations,
+		};
+
+		let mut trace = Vec::with_capacity(iterations + 1);
+		trace.push(s.clone());
+
+		let ops_about = transpose(&ops_norm);
+		for _ in 0..iterations {
+			s = ops_about
+				.par_iter()
+				.zip(&pre_trust)
+				.map(|(ops_i, pre_trust_i)| {
+					let score_i_sum = ops_i
+						.iter()
+						.zip(&s)
+						.fold(BigRational::zero(), |acc, (op, score)| acc + op * score);
+					&undamped * score_i_sum + &damping * pre_trust_i
+				})
+				.collect();
+			trace.push(s.clone());
+		}
+		trace
+	}
+
+	/// Returns the normalized opinion matrix, the damping factor and its complement, and
+	/// the scores the damped share goes to, of the rational convergence.
+	#[allow(clippy::type_complexity)]
+	fn rational_params(
+		&self, viewer: Option<usize>,
+	) -> (
+		Vec<Vec<RationalScore>>,
+		RationalScore,
+		RationalScore,
+		Vec<RationalScore>,
+	) {
+		let size = self.set.len();
+		let ops_norm = self.normalize_ops_rational();
+
+		// Damping factor and its complement. The damped scores are pulled back to the
+		// viewer's local trust, the pre-trusted scores, or the initial scores of the set
+		// members without any.
+		let damping = match viewer {
+			Some(_) => self.damping.or(Some(PERSONALIZED_DAMPING)),
+			None => self.damping,
+		};
+		let (damping, undamped) = match damping {
+			Some((num, den)) => {
+				let factor = BigRational::new(BigInt::from(num), BigInt::from(den));
+				(factor.clone(), BigRational::one() - factor)
+			},
+			None => (BigRational::zero(), BigRational::one()),
+		};
+		let pre_trusted = self.pre_trusted_indices();
+		let pre_trust: Vec<BigRational> = if let Some(viewer) = viewer {
+			let total = self.total_score();
+			ops_norm[viewer].iter().map(|op| op * &total).collect()
+		} else if pre_trusted.is_empty() {
+			self.initial_scores()
+		} else {
+			let share = self.total_score() / BigInt::from(pre_trusted.len());
+			(0..size)
+				.map(|i| match pre_trusted.contains(&i) {
+					true => share.clone(),
+					false => BigRational::zero(),
+				})
+				.collect()
+		};
+
+		(ops_norm, damping, undamped, pre_trust)
+	}
+}
+
+/// Returns the number of iterations until the largest score change falls under the
+/// (numerator, denominator) tolerance, capped by `iterations`. The changes are measured on
+/// f64 iterates of the same convergence, so that the field and rational convergences stop
+/// at the same iteration without computing the exact iterates twice.
+fn tolerance_iterations(
+	ops_norm: &[Vec<RationalScore>], start: &[RationalScore], pre_trust: &[RationalScore],
+	damping: &RationalScore, (numerator, denominator): (u128, u128), iterations: usize,
+) -> usize {
+	let to_f64 = |x: &RationalScore| x.to_f64().unwrap_or(0.0);
+	let tolerance = to_f64(&BigRational::new(
+		BigInt::from(numerator),
+		BigInt::from(denominator),
+	));
+	let damping = to_f64(damping);
+	let ops_about: Vec<Vec<f64>> =
+		transpose(ops_norm).iter().map(|ops_i| ops_i.iter().map(to_f64).collect()).collect();
+	let pre_trust: Vec<f64> = pre_trust.iter().map(to_f64).collect();
+
+	let mut s: Vec<f64> = start.iter().map(to_f64).collect();
+	for iteration in 1..=iterations {
+		let new_s: Vec<f64> = ops_about
+			.iter()
+			.zip(&pre_trust)
+			.map(|(ops_i, pre_trust_i)| {
+				let score_i_sum: f64 = ops_i.iter().zip(&s).map(|(op, score)| op * score).sum();
+				(1.0 - damping) * score_i_sum + damping * pre_trust_i
+			})
+			.collect();
+		let max_change =
+			s.iter().zip(&new_s).map(|(old, new)| (new - old).abs()).fold(0.0, f64::max);
+		s = new_s;
+
+		if max_change < tolerance {
+			return iteration;
+		}
+	}
+	iterations
+}
+
+/// Parses a field element of a set state.
+fn parse_fe<N: FieldExt>(x: &str) -> Result<N, SetError> {
+	let valid = !x.is_empty() && x.bytes().all(|b| b.is_ascii_digit());
+	let big = BigUint::from_str(x).ok().filter(|_| valid).ok_or(SetError::InvalidState)?;
+	if big >= modulus::<N>() {
+		return Err(SetError::InvalidState);
+	}
+	Ok(big_to_fe(big))
+}
+
+/// Parses a non-negative rational score of a set state.
+fn parse_rational(x: &str) -> Result<RationalScore, SetError> {
+	BigRational::from_str(x).ok().filter(|x| !x.is_negative()).ok_or(SetError::InvalidState)
+}
+
+/// Returns the largest change between the two score vectors.
+fn max_change(old: &[RationalScore], new: &[RationalScore]) -> RationalScore {
+	old.iter()
+		.zip(new)
+		.map(|(old, new)| (new - old).abs())
+		.max()
+		.unwrap_or_else(BigRational::zero)
+}
+
+/// Transposes the square matrix, so that its columns can be iterated over as rows.
+fn transpose<T: Clone + Send + Sync>(matrix: &[Vec<T>]) -> Vec<Vec<T>> {
+	(0..matrix.len())
+		.into_par_iter()
+		.map(|i| matrix.iter().map(|row| row[i].clone()).collect())
+		.collect()
+}
+
+/// Dynamic set for EigenTrust
+pub struct EigenTrustSet<
+	const NUM_NEIGHBOURS: usize,
+	const NUM_ITERATIONS: usize,
+	const INITIAL_SCORE: u128,
+	C: CurveAffine,
+	N: FieldExt,
+	const NUM_LIMBS: usize,
+	const NUM_BITS: usize,
+	P,
+	EC,
+	H: Hasher<N, HASHER_WIDTH>,
+	SH: SpongeHasher<N>,
+> where
+	P: RnsParams<C::ScalarExt, N, NUM_LIMBS, NUM_BITS> + RnsParams<C::Base, N, NUM_LIMBS, NUM_BITS>,
+	EC: EccParams<C>,
+	C::ScalarExt: FieldExt,
+	C::Base: FieldExt,
+{
+	inner: RuntimeEigenTrustSet<C, N, NUM_LIMBS, NUM_BITS, P, EC, H, SH>,
+	num_iterations: Option<usize>,
+	initial_score: Option<u128>,
+}
+
+impl<
+		const NUM_NEIGHBOURS: usize,
+		const NUM_ITERATIONS: usize,
+		const INITIAL_SCORE: u128,
+		C: CurveAffine,
+		N: FieldExt,
+		const NUM_LIMBS: usize,
+		const NUM_BITS: usize,
+		P,
+		EC,
+		H: Hasher<N, HASHER_WIDTH>,
+		SH: SpongeHasher<N>,
+	>
+	EigenTrustSet<
+		NUM_NEIGHBOURS,
+		NUM_ITERATIONS,
+		INITIAL_SCORE,
+		C,
+		N,
+		NUM_LIMBS,
+		NUM_BITS,
+		P,
+		EC,
+		H,
+		SH,
+	> where
+	P: RnsParams<C::ScalarExt, N, NUM_LIMBS, NUM_BITS> + RnsParams<C::Base, N, NUM_LIMBS, NUM_BITS>,
+	EC: EccParams<C>,
+	C::ScalarExt: FieldExt,
+	C::Base: FieldExt,
+{
+	/// Constructs new instance
+	pub fn new(domain: N) -> Self {
+		Self {
+			inner: RuntimeEigenTrustSet::new(domain, NUM_NEIGHBOURS, NUM_ITERATIONS, INITIAL_SCORE),
+			num_iterations: None,
+			initial_score: None,
+		}
+	}
+
+	/// Sets the policy for the opinions without any valid score, see
+	/// `RuntimeEigenTrustSet::with_opinion_filter`.
+	pub fn with_opinion_filter(mut self, filter: impl OpinionFilter<N> + 'static) -> Self {
+		self.inner = self.inner.with_opinion_filter(filter);
+		self
+	}
+
+	/// Returns the members whose opinion has no valid score, see
+	/// `RuntimeEigenTrustSet::empty_opinions`.
+	pub fn empty_opinions(&self) -> Vec<N> {
+		self.inner.empty_opinions()
+	}
+
+	/// Set the damping factor, see `RuntimeEigenTrustSet::set_damping`.
+	pub fn set_damping(&mut self, numerator: u128, denominator: u128) -> Result<(), SetError> {
+		self.inner.set_damping(numerator, denominator)
+	}
+
+	/// Set the pre-trusted peers, see `RuntimeEigenTrustSet::set_pre_trusted`.
+	pub fn set_pre_trusted(&mut self, peers: Vec<N>) {
+		self.inner.set_pre_trusted(peers);
+	}
+
+	/// Set where the initial score of the removed members goes, see `RemovalPolicy`.
+	/// Policies other than `RemovalPolicy::Drop` aren't covered by the circuit.
+	pub fn set_removal_policy(&mut self, policy: RemovalPolicy) {
+		self.inner.set_removal_policy(policy);
+	}
+
+	/// Set the convergence tolerance, see `RuntimeEigenTrustSet::set_tolerance`.
+	pub fn set_tolerance(&mut self, numerator: u128, denominator: u128) -> Result<(), SetError> {
+		self.inner.set_tolerance(numerator, denominator)
+	}
+
+	/// Returns the number of iterations the convergence runs: all of them without a
+	/// tolerance, otherwise the ones until the largest score change falls under it.
+	pub fn iterations_needed(&self) -> usize {
+		self.inner.iterations_needed()
+	}
+
+	/// Set the decay of the opinions: every `period` blocks between the block of an opinion,
+	/// see `set_op_block`, and `current_block`, the opinion keeps the (numerator,
+	/// denominator) fraction of its weight, and loses all of it after `MAX_DECAY_PERIODS`.
+	/// Decayed opinions aren't covered by the circuit.
+	pub fn set_decay(
+		&mut self, numerator: u128, denominator: u128, period: u64, current_block: u64,
+	) -> Result<(), SetError> {
+		self.inner.set_decay(numerator, denominator, period, current_block)
+	}
+
+	/// Set the block of the member's opinion, see `RuntimeEigenTrustSet::set_op_block`.
+	pub fn set_op_block(&mut self, from: N, block: u64) -> Result<(), SetError> {
+		self.inner.set_op_block(from, block)
+	}
+
+	/// Set the stake of the member, see `RuntimeEigenTrustSet::set_stake`.
+	pub fn set_stake(&mut self, from: N, stake: u128) -> Result<(), SetError> {
+		self.inner.set_stake(from, stake)
+	}
+
+	/// Override the number of iterations and the initial score of the set members.
+	/// Overridden scores aren't covered by the circuit, which uses the set parameters.
+	pub fn set_params(
+		&mut self, num_iterations: usize, initial_score: u128,
+	) -> Result<(), SetError> {
+		self.inner.set_params(num_iterations, initial_score)?;
+
+		self.num_iterations = Some(num_iterations).filter(|&n| n != NUM_ITERATIONS);
+		self.initial_score = Some(initial_score).filter(|&s| s != INITIAL_SCORE);
+
+		Ok(())
+	}
+
+	/// Returns the number of convergence iterations.
+	pub fn num_iterations(&self) -> usize {
+		self.num_iterations.unwrap_or(NUM_ITERATIONS)
+	}
+
+	/// Returns the initial score of the set members.
+	pub fn initial_score(&self) -> u128 {
+		self.initial_score.unwrap_or(INITIAL_SCORE)
+	}
+
+	/// Add new set member and initial score
+	pub fn add_member(&mut self, addr: N) -> Result<(), SetError> {
+		self.add_member_with_score(addr, None)
+	}
+
+	/// Add new set member with the given initial score, see
+	/// `RuntimeEigenTrustSet::add_member_with_score`.
+	pub fn add_member_with_score(
+		&mut self, addr: N, initial_score: Option<u128>,
+	) -> Result<(), SetError> {
+		// Make sure there's a free slot, the circuit can't grow the set
+		let is_full = self.inner.set.iter().all(|&(x, _)| x != N::ZERO);
+		let is_member = self.inner.set.iter().any(|&(x, _)| x == addr);
+		if is_full && !is_member {
+			return Err(SetError::SetFull);
+		}
+
+		self.inner.add_member_with_score(addr, initial_score)
+	}
+
+	/// Remove the member and its opinion
+	pub fn remove_member(&mut self, addr: N) -> Result<(), SetError> {
+		self.inner.remove_member(addr)
+	}
+
+	/// Update the opinion of the member
+	pub fn update_op(
+		&mut self, from: PublicKey<C, N, NUM_LIMBS, NUM_BITS, P, EC>,
+		op: Vec<Option<SignedAttestation<C, N, NUM_LIMBS, NUM_BITS, P>>>,
+	) -> Result<N, SetError> {
+		// Make sure already in the set
+		self.inner.member_index(from.to_address())?;
+
+		// Get participant set addresses
+		let set: Vec<N> = self.inner.set.iter().map(|&(addr, _)| addr).collect();
+
+		// Build opinion from the opinion group and validate
+		let opinion_group = self.inner.checked_op_group(op)?;
+		let opinion = Opinion::<NUM_NEIGHBOURS, C, N, NUM_LIMBS, NUM_BITS, P, EC, H, SH>::new(
+			from, opinion_group, self.inner.domain,
+		);
+		let (addr, scores, op_hash) = opinion.validate(set);
+
+		self.inner.ops.insert(addr, scores);
+
+		Ok(op_hash)
+	}
+
+	/// Update the opinion of a member whose attestations were verified outside of the set,
+	/// such as contract wallets. These opinions aren't covered by the circuit.
+	pub fn update_verified_op(&mut self, from: N, scores: Vec<N>) -> Result<(), SetError> {
+		if scores.len() != NUM_NEIGHBOURS {
+			return Err(SetError::ScoresMismatch);
+		}
+
+		self.inner.update_verified_op(from, scores)
+	}
+
+	/// Update the distrust opinion of the member, see `RuntimeEigenTrustSet::update_distrust`.
+	pub fn update_distrust(&mut self, from: N, scores: Vec<N>) -> Result<(), SetError> {
+		if scores.len() != NUM_NEIGHBOURS {
+			return Err(SetError::ScoresMismatch);
+		}
+
+		self.inner.update_distrust(from, scores)
+	}
+
+	/// Unwraps a `Vec<Option<SignedAttestation>>`.
+	/// `None` values are replaced by empty signed attestations with the correct about field.
+	pub fn parse_op_group(
+		&mut self, op: Vec<Option<SignedAttestation<C, N, NUM_LIMBS, NUM_BITS, P>>>,
+	) -> Vec<SignedAttestation<C, N, NUM_LIMBS, NUM_BITS, P>> {
+		self.inner.parse_op_group(op)
+	}
+
+	/// Compute the EigenTrust score
+	pub fn converge(&self) -> Result<Vec<N>, SetError> {
+		self.inner.converge()
+	}
+
+	/// Compute the EigenTrust score over a contiguous opinion matrix, see
+	/// `RuntimeEigenTrustSet::converge_ndarray`.
+	#[cfg(feature = "ndarray")]
+	pub fn converge_ndarray(&self) -> Result<Vec<N>, SetError> {
+		self.inner.converge_ndarray()
+	}
+
+	/// Compute the EigenTrust score both as field elements and as exact BigRational numbers,
+	/// see `RuntimeEigenTrustSet::converge_with_rational_trace`.
+	pub fn converge_with_rational_trace(
+		&self,
+	) -> Result<(Vec<N>, Vec<Vec<RationalScore>>), SetError> {
+		self.inner.converge_with_rational_trace()
+	}
+
+	/// Compute the EigenTrust score, keeping the scores and the residual of every iteration,
+	/// see `RuntimeEigenTrustSet::converge_trajectory`.
+	pub fn converge_trajectory(&self) -> Result<Vec<ConvergenceStep<N>>, SetError> {
+		self.inner.converge_trajectory()
+	}
+
+	/// Compute the EigenTrust score along with the diagnostics of the run, see
+	/// `RuntimeEigenTrustSet::converge_with_report`.
+	pub fn converge_with_report(&self) -> Result<(Vec<N>, ConvergenceReport<N>), SetError> {
+		self.inner.converge_with_report()
+	}
+
+	/// Returns the normalized opinion matrix the convergence iterates over, see
+	/// `RuntimeEigenTrustSet::trust_matrix`.
+	pub fn trust_matrix(&self) -> TrustMatrix<N> {
+		self.inner.trust_matrix()
+	}
+
+	/// Returns the set members from the highest converged score to the lowest, see
+	/// `RuntimeEigenTrustSet::ranking`.
+	pub fn ranking(&self) -> Vec<(N, RationalScore)> {
+		self.inner.ranking()
+	}
+
+	/// Returns the `k` members with the highest converged scores.
+	pub fn top_k(&self, k: usize) -> Vec<(N, RationalScore)> {
+		self.inner.top_k(k)
+	}
+
+	/// Compute the EigenTrust scores personalized for the viewer, see
+	/// `RuntimeEigenTrustSet::converge_personalized`.
+	pub fn converge_personalized(&self, viewer: N) -> Result<Vec<RationalScore>, SetError> {
+		self.inner.converge_personalized(viewer)
+	}
+
+	/// Returns the set members from the highest personalized score for the viewer to the
+	/// lowest.
+	pub fn personalized_ranking(&self, viewer: N) -> Result<Vec<(N, RationalScore)>, SetError> {
+		self.inner.personalized_ranking(viewer)
+	}
+
+	/// Returns the rank of the member after convergence, starting at 1 for the highest score.
+	pub fn rank_of(
+		&self, pk: &PublicKey<C, N, NUM_LIMBS, NUM_BITS, P, EC>,
+	) -> Result<usize, SetError> {
+		self.inner.rank_of(pk)
+	}
+
+	/// Compute the EigenTrust score using BigRational numbers
+	pub fn converge_rational(&self) -> Vec<RationalScore> {
+		self.inner.converge_rational()
+	}
+
+	/// Compute the EigenTrust score using BigRational numbers, keeping the scores of every
+	/// iteration. The first entry holds the initial scores and the last one the final scores.
+	pub fn converge_rational_trace(&self) -> Vec<Vec<RationalScore>> {
+		self.inner.converge_rational_trace()
+	}
+
+	/// Returns the score and membership changes from this set to the other one, see
+	/// `RuntimeEigenTrustSet::diff`.
+	pub fn diff(&self, other: &Self) -> SetDiff<N> {
+		self.inner.diff(&other.inner)
+	}
+
+	/// Returns the state of the set at the given epoch, see `RuntimeEigenTrustSet::state`.
+	pub fn state(&self, epoch: u64) -> SetState {
+		self.inner.state(epoch)
+	}
+
+	/// Restores the set from its state, which must have as many slots as the circuit, see
+	/// `RuntimeEigenTrustSet::from_state`.
+	pub fn from_state(state: &SetState) -> Result<Self, SetError> {
+		if state.members.len() != NUM_NEIGHBOURS {
+			return Err(SetError::InvalidState);
+		}
+
+		Ok(Self {
+			inner: RuntimeEigenTrustSet::from_state(state)?,
+			num_iterations: Some(state.num_iterations).filter(|&n| n != NUM_ITERATIONS),
+			initial_score: Some(state.initial_score).filter(|&s| s != INITIAL_SCORE),
+		})
+	}
+
+	/// Run a single convergence iteration from the scores of the previous heartbeat, see
+	/// `RuntimeEigenTrustSet::heartbeat`.
+	pub fn heartbeat(&mut self) -> ConvergenceStep<N> {
+		self.inner.heartbeat()
+	}
+
+	/// Compute the EigenTrust score using BigRational numbers, starting from the scores of a
+	/// previous convergence, see `RuntimeEigenTrustSet::converge_rational_from`.
+	/// Warm-started scores aren't covered by the circuit, which starts from the initial scores.
+	pub fn converge_rational_from(
+		&self, previous: &[RationalScore],
+	) -> Result<Vec<RationalScore>, SetError> {
+		self.inner.converge_rational_from(previous)
+	}
+}
+
+impl<
+		const NUM_NEIGHBOURS: usize,
+		const NUM_ITERATIONS: usize,
+		const INITIAL_SCORE: u128,
+		C: CurveAffine,
+		N: FieldExt,
+		const NUM_LIMBS: usize,
+		const NUM_BITS: usize,
+		P,
+		EC,
+		H: Hasher<N, HASHER_WIDTH>,
+		SH: SpongeHasher<N>,
+	> Default
+	for EigenTrustSet<
+		NUM_NEIGHBOURS,
+		NUM_ITERATIONS,
+		INITIAL_SCORE,
+		C,
+		N,
+		NUM_LIMBS,
+		NUM_BITS,
+		P,
+		EC,
+		H,
+		SH,
+	> where
+	P: RnsParams<C::ScalarExt, N, NUM_LIMBS, NUM_BITS> + RnsParams<C::Base, N, NUM_LIMBS, NUM_BITS>,
+	EC: EccParams<C>,
+	C::ScalarExt: FieldExt,
+	C::Base: FieldExt,
+{
+	fn default() -> Self {
+		// A set of the default domain, with a slot per neighbour
+		Self::new(N::default())
 	}
 }
 
@@ -410,13 +2381,13 @@ mod test {
 		circuits::{PoseidonNativeHasher, PoseidonNativeSponge},
 		ecdsa::native::EcdsaKeypair,
 		params::{ecc::secp256k1::Secp256k1Params, rns::secp256k1::Secp256k1_4_68},
-		utils::big_to_fe,
+		utils::{big_to_fe, rational_to_fe},
 	};
 
 	use super::*;
 	use halo2::halo2curves::{bn256::Fr, ff::PrimeField, secp256k1::Secp256k1Affine};
 	use num_rational::BigRational;
-	use rand::thread_rng;
+	use rand::{thread_rng, Rng};
 	use std::time::Instant;
 
 	const DOMAIN: u128 = 42;
@@ -433,6 +2404,21 @@ mod test {
 	type H = PoseidonNativeHasher;
 	type SH = PoseidonNativeSponge;
 
+	type Set = EigenTrustSet<
+		NUM_NEIGHBOURS,
+		NUM_ITERATIONS,
+		INITIAL_SCORE,
+		C,
+		N,
+		NUM_LIMBS,
+		NUM_BITS,
+		P,
+		EC,
+		H,
+		SH,
+	>;
+	type RuntimeSet = RuntimeEigenTrustSet<C, N, NUM_LIMBS, NUM_BITS, P, EC, H, SH>;
+
 	fn sign_opinion<
 		const NUM_NEIGHBOURS: usize,
 		const NUM_ITERATIONS: usize,
@@ -465,7 +2451,6 @@ mod test {
 	}
 
 	#[test]
-	#[should_panic]
 	fn test_add_member_in_initial_set() {
 		let domain = N::from_u128(DOMAIN);
 		let mut set = EigenTrustSet::<
@@ -487,14 +2472,62 @@ mod test {
 		let keypair = EcdsaKeypair::<C, N, NUM_LIMBS, NUM_BITS, P, EC>::generate_keypair(rng);
 		let addr = keypair.public_key.to_address();
 
-		set.add_member(addr);
+		set.add_member(addr).unwrap();
+
+		// Re-adding the member should fail
+		assert_eq!(set.add_member(addr), Err(SetError::DuplicateMember));
+		assert_eq!(set.add_member(N::ZERO), Err(SetError::ZeroAddress));
+		assert_eq!(set.remove_member(N::from(7)), Err(SetError::MissingMember));
+	}
+
+	#[test]
+	fn test_update_invalid_opinion() {
+		let domain = N::from_u128(DOMAIN);
+		let mut set = Set::new(domain);
+
+		let rng = &mut thread_rng();
+
+		let keypair1 = EcdsaKeypair::<C, N, NUM_LIMBS, NUM_BITS, P, EC>::generate_keypair(rng);
+		let keypair2 = EcdsaKeypair::<C, N, NUM_LIMBS, NUM_BITS, P, EC>::generate_keypair(rng);
+
+		let addr1 = keypair1.public_key.to_address();
+		let addr2 = keypair2.public_key.to_address();
+
+		set.add_member(addr1).unwrap();
+		set.add_member(addr2).unwrap();
+
+		// Attestations about the members of other slots
+		let mut addrs = [N::zero(); NUM_NEIGHBOURS];
+		addrs[0] = addr2;
+		addrs[1] = addr1;
+
+		let mut scores = [N::zero(); NUM_NEIGHBOURS];
+		scores[0] = N::from_u128(INITIAL_SCORE);
+
+		let op = sign_opinion::<NUM_NEIGHBOURS, NUM_ITERATIONS, INITIAL_SCORE>(
+			&keypair1, &addrs, &scores,
+		);
+		assert_eq!(
+			set.update_op(keypair1.public_key.clone(), op[..1].to_vec()),
+			Err(SetError::ScoresMismatch)
+		);
+		assert_eq!(
+			set.update_op(keypair1.public_key, op),
+			Err(SetError::WrongSubject)
+		);
 
-		// Re-adding the member should panic
-		set.add_member(addr);
+		let scores = vec![N::ZERO; NUM_NEIGHBOURS + 1];
+		assert_eq!(
+			set.update_verified_op(addr2, scores.clone()),
+			Err(SetError::ScoresMismatch)
+		);
+		assert_eq!(
+			set.update_distrust(addr2, scores),
+			Err(SetError::ScoresMismatch)
+		);
 	}
 
 	#[test]
-	#[should_panic]
 	fn test_one_member_converge() {
 		let domain = N::from_u128(DOMAIN);
 		let mut set = EigenTrustSet::<
@@ -516,9 +2549,9 @@ mod test {
 		let keypair = EcdsaKeypair::<C, N, NUM_LIMBS, NUM_BITS, P, EC>::generate_keypair(rng);
 		let addr = keypair.public_key.to_address();
 
-		set.add_member(addr);
+		set.add_member(addr).unwrap();
 
-		set.converge();
+		assert_eq!(set.converge(), Err(SetError::InsufficientPeers));
 	}
 
 	#[test]
@@ -546,10 +2579,10 @@ mod test {
 		let addr1 = keypair1.public_key.to_address();
 		let addr2 = keypair2.public_key.to_address();
 
-		set.add_member(addr1);
-		set.add_member(addr2);
+		set.add_member(addr1).unwrap();
+		set.add_member(addr2).unwrap();
 
-		set.converge();
+		set.converge().unwrap();
 	}
 
 	#[test]
@@ -577,8 +2610,8 @@ mod test {
 		let addr1 = keypair1.public_key.to_address();
 		let addr2 = keypair2.public_key.to_address();
 
-		set.add_member(addr1);
-		set.add_member(addr2);
+		set.add_member(addr1).unwrap();
+		set.add_member(addr2).unwrap();
 
 		// Peer1(addr1) signs the opinion
 		let mut addrs = [N::zero(); NUM_NEIGHBOURS];
@@ -592,9 +2625,9 @@ mod test {
 			&keypair1, &addrs, &scores,
 		);
 
-		set.update_op(keypair1.public_key, op1);
+		set.update_op(keypair1.public_key, op1).unwrap();
 
-		set.converge();
+		set.converge().unwrap();
 	}
 
 	#[test]
@@ -622,8 +2655,8 @@ mod test {
 		let addr1 = keypair1.public_key.to_address();
 		let addr2 = keypair2.public_key.to_address();
 
-		set.add_member(addr1);
-		set.add_member(addr2);
+		set.add_member(addr1).unwrap();
+		set.add_member(addr2).unwrap();
 
 		// Peer1(addr1) signs the opinion
 		let mut addrs = [N::zero(); NUM_NEIGHBOURS];
@@ -637,7 +2670,7 @@ mod test {
 			&keypair1, &addrs, &scores,
 		);
 
-		set.update_op(keypair1.public_key, op1);
+		set.update_op(keypair1.public_key, op1).unwrap();
 
 		// Peer2(addr2) signs the opinion
 		let mut addrs = [N::zero(); NUM_NEIGHBOURS];
@@ -651,9 +2684,14 @@ mod test {
 			&keypair2, &addrs, &scores,
 		);
 
-		set.update_op(keypair2.public_key, op2);
+		set.update_op(keypair2.public_key, op2).unwrap();
+
+		set.converge().unwrap();
 
-		set.converge();
+		// The trace ends with the converged scores
+		let trace = set.converge_rational_trace();
+		assert_eq!(trace.len(), NUM_ITERATIONS + 1);
+		assert_eq!(trace.last().unwrap(), &set.converge_rational());
 	}
 
 	#[test]
@@ -682,9 +2720,9 @@ mod test {
 		let addr2 = keypair2.public_key.to_address();
 		let addr3 = keypair3.public_key.to_address();
 
-		set.add_member(addr1);
-		set.add_member(addr2);
-		set.add_member(addr3);
+		set.add_member(addr1).unwrap();
+		set.add_member(addr2).unwrap();
+		set.add_member(addr3).unwrap();
 
 		// Peer1(addr1) signs the opinion
 		let mut addrs = [N::zero(); NUM_NEIGHBOURS];
@@ -700,7 +2738,7 @@ mod test {
 			&keypair1, &addrs, &scores,
 		);
 
-		set.update_op(keypair1.public_key, op1);
+		set.update_op(keypair1.public_key, op1).unwrap();
 
 		// Peer2(addr2) signs the opinion
 		let mut addrs = [N::zero(); NUM_NEIGHBOURS];
@@ -716,7 +2754,7 @@ mod test {
 			&keypair2, &addrs, &scores,
 		);
 
-		set.update_op(keypair2.public_key, op2);
+		set.update_op(keypair2.public_key, op2).unwrap();
 
 		// Peer3(addr3) signs the opinion
 		let mut addrs = [N::zero(); NUM_NEIGHBOURS];
@@ -732,9 +2770,9 @@ mod test {
 			&keypair3, &addrs, &scores,
 		);
 
-		set.update_op(keypair3.public_key, op3);
+		set.update_op(keypair3.public_key, op3).unwrap();
 
-		set.converge();
+		set.converge().unwrap();
 	}
 
 	#[test]
@@ -764,9 +2802,9 @@ mod test {
 		let addr2 = keypair2.public_key.to_address();
 		let addr3 = keypair3.public_key.to_address();
 
-		set.add_member(addr1);
-		set.add_member(addr2);
-		set.add_member(addr3);
+		set.add_member(addr1).unwrap();
+		set.add_member(addr2).unwrap();
+		set.add_member(addr3).unwrap();
 
 		// Peer1(addr1) signs the opinion
 		let mut addrs = [N::zero(); NUM_NEIGHBOURS];
@@ -782,7 +2820,7 @@ mod test {
 			&keypair1, &addrs, &scores,
 		);
 
-		set.update_op(keypair1.public_key, op1);
+		set.update_op(keypair1.public_key, op1).unwrap();
 
 		// Peer2(addr2) signs the opinion
 		let mut addrs = [N::zero(); NUM_NEIGHBOURS];
@@ -798,9 +2836,9 @@ mod test {
 			&keypair2, &addrs, &scores,
 		);
 
-		set.update_op(keypair2.public_key, op2);
+		set.update_op(keypair2.public_key, op2).unwrap();
 
-		set.converge();
+		set.converge().unwrap();
 	}
 
 	#[test]
@@ -830,9 +2868,9 @@ mod test {
 		let addr2 = keypair2.public_key.to_address();
 		let addr3 = keypair3.public_key.to_address();
 
-		set.add_member(addr1);
-		set.add_member(addr2);
-		set.add_member(addr3);
+		set.add_member(addr1).unwrap();
+		set.add_member(addr2).unwrap();
+		set.add_member(addr3).unwrap();
 
 		// Peer1(addr1) signs the opinion
 		let mut addrs = [N::zero(); NUM_NEIGHBOURS];
@@ -848,7 +2886,7 @@ mod test {
 			&keypair1, &addrs, &scores,
 		);
 
-		set.update_op(keypair1.public_key, op1);
+		set.update_op(keypair1.public_key, op1).unwrap();
 
 		// Peer2(addr2) signs the opinion
 		let mut addrs = [N::zero(); NUM_NEIGHBOURS];
@@ -864,7 +2902,7 @@ mod test {
 			&keypair2, &addrs, &scores,
 		);
 
-		set.update_op(keypair2.public_key, op2);
+		set.update_op(keypair2.public_key, op2).unwrap();
 
 		// Peer3(addr3) signs the opinion
 		let mut addrs = [N::zero(); NUM_NEIGHBOURS];
@@ -880,14 +2918,14 @@ mod test {
 			&keypair3, &addrs, &scores,
 		);
 
-		set.update_op(keypair3.public_key, op3);
+		set.update_op(keypair3.public_key, op3).unwrap();
 
-		set.converge();
+		set.converge().unwrap();
 
 		// Peer2 quits
-		set.remove_member(addr2);
+		set.remove_member(addr2).unwrap();
 
-		set.converge();
+		set.converge().unwrap();
 	}
 
 	#[test]
@@ -917,9 +2955,9 @@ mod test {
 		let addr2 = keypair2.public_key.to_address();
 		let addr3 = keypair3.public_key.to_address();
 
-		set.add_member(addr1);
-		set.add_member(addr2);
-		set.add_member(addr3);
+		set.add_member(addr1).unwrap();
+		set.add_member(addr2).unwrap();
+		set.add_member(addr3).unwrap();
 
 		// Peer1(addr1) signs the opinion
 		let mut addrs = [N::zero(); NUM_NEIGHBOURS];
@@ -935,7 +2973,7 @@ mod test {
 			&keypair1, &addrs, &scores,
 		);
 
-		set.update_op(keypair1.public_key, op1);
+		set.update_op(keypair1.public_key, op1).unwrap();
 
 		let mut addrs = [N::zero(); NUM_NEIGHBOURS];
 		addrs[0] = addr1;
@@ -951,9 +2989,984 @@ mod test {
 		);
 
 		// Peer2(addr2) signs the opinion
-		set.update_op(keypair2.public_key, op2);
+		set.update_op(keypair2.public_key, op2).unwrap();
+
+		set.converge().unwrap();
+	}
+
+	#[test]
+	fn test_damping_converges_on_periodic_graph() {
+		// Bipartite graph: peers 1 and 2 only trust peer 3, which splits its trust
+		// between them. Undamped iterations oscillate with period 2.
+		let (addr1, addr2, addr3) = (N::from(1), N::from(2), N::from(3));
+		let build_set = || {
+			let mut set = Set::new(N::from_u128(DOMAIN));
+			set.add_member(addr1).unwrap();
+			set.add_member(addr2).unwrap();
+			set.add_member(addr3).unwrap();
+
+			let mut scores = vec![N::ZERO; NUM_NEIGHBOURS];
+			scores[2] = N::from(1);
+			set.update_verified_op(addr1, scores.clone()).unwrap();
+			set.update_verified_op(addr2, scores).unwrap();
+
+			let mut scores = vec![N::ZERO; NUM_NEIGHBOURS];
+			scores[0] = N::from(1);
+			scores[1] = N::from(1);
+			set.update_verified_op(addr3, scores).unwrap();
+
+			set
+		};
+
+		let last_change = |trace: &[Vec<RationalScore>]| {
+			let (prev, last) = (&trace[trace.len() - 2], &trace[trace.len() - 1]);
+			(0..3).map(|i| (last[i].clone() - prev[i].clone()).abs()).max().unwrap()
+		};
+
+		let undamped = build_set();
+		let undamped_trace = undamped.converge_rational_trace();
+		assert_eq!(
+			last_change(&undamped_trace),
+			BigRational::from_integer(BigInt::from(1000))
+		);
+
+		let mut damped = build_set();
+		damped.set_damping(1, 2).unwrap();
+		let damped_trace = damped.converge_rational_trace();
+		assert!(last_change(&damped_trace) < BigRational::one());
+
+		// Damping keeps the total score
+		let total: BigRational = damped_trace.last().unwrap().iter().sum();
+		assert_eq!(total, BigRational::from_integer(BigInt::from(3000)));
+
+		// The field scores match the rational ones
+		let scores = damped.converge().unwrap();
+		let rational_scores = damped.converge_rational();
+		for i in 0..3 {
+			let score = &rational_scores[i];
+			let expected = rational_to_fe::<N>(score);
+			assert_eq!(scores[i], expected);
+		}
+	}
+
+	#[test]
+	fn test_pre_trusted_peers() {
+		// Peers 1 and 2 trust each other, and so do the colluding peers 3 and 4
+		let addrs = [N::from(1), N::from(2), N::from(3), N::from(4)];
+		let build_set = |pre_trusted: Vec<N>| {
+			let mut set = Set::new(N::from_u128(DOMAIN));
+			for addr in addrs {
+				set.add_member(addr).unwrap();
+			}
+			for (i, addr) in addrs.iter().enumerate() {
+				let mut scores = vec![N::ZERO; NUM_NEIGHBOURS];
+				scores[i ^ 1] = N::from(1);
+				set.update_verified_op(*addr, scores).unwrap();
+			}
+			set.set_damping(1, 2).unwrap();
+			set.set_pre_trusted(pre_trusted);
+
+			set
+		};
+
+		// Without pre-trusted members, the collective keeps its score
+		let scores = build_set(vec![N::from(5)]).converge_rational();
+		assert_eq!(
+			scores[2],
+			BigRational::from_integer(BigInt::from(INITIAL_SCORE))
+		);
+
+		// The damped share only goes to the pre-trusted peers
+		let set = build_set(vec![addrs[0], addrs[1]]);
+		let scores = set.converge_rational();
+		let collective = scores[2].clone() + scores[3].clone();
+		let expected = BigRational::new(BigInt::from(2 * INITIAL_SCORE), BigInt::from(1024));
+		assert_eq!(collective, expected);
+
+		let total: BigRational = scores.iter().sum();
+		assert_eq!(
+			total,
+			BigRational::from_integer(BigInt::from(4 * INITIAL_SCORE))
+		);
+
+		// The field scores match the rational ones
+		let field_scores = set.converge().unwrap();
+		for i in 0..4 {
+			let score = &scores[i];
+			let expected = rational_to_fe::<N>(score);
+			assert_eq!(field_scores[i], expected);
+		}
+	}
+
+	#[test]
+	fn test_personalized_convergence() {
+		// Peers 1 and 2 trust each other, and so do peers 3 and 4
+		let addrs = [N::from(1), N::from(2), N::from(3), N::from(4)];
+		let mut set = Set::new(N::from_u128(DOMAIN));
+		for addr in addrs {
+			set.add_member(addr).unwrap();
+		}
+		for (i, addr) in addrs.iter().enumerate() {
+			let mut scores = vec![N::ZERO; NUM_NEIGHBOURS];
+			scores[i ^ 1] = N::from(1);
+			set.update_verified_op(*addr, scores).unwrap();
+		}
+
+		// Globally, every peer has the same score
+		let global = set.converge_rational();
+		assert!(global[..4].iter().all(|score| *score == global[0]));
+
+		// The viewer's peers rank first, without leaking any score
+		let scores = set.converge_personalized(addrs[0]).unwrap();
+		assert!(scores[2] < global[2] && scores[3] < global[3]);
+		let total: BigRational = scores.iter().sum();
+		assert_eq!(
+			total,
+			BigRational::from_integer(BigInt::from(4 * INITIAL_SCORE))
+		);
+
+		let ranking = set.personalized_ranking(addrs[3]).unwrap();
+		let top: Vec<N> = ranking[..2].iter().map(|(addr, _)| *addr).collect();
+		assert!(top.contains(&addrs[2]) && top.contains(&addrs[3]));
+
+		assert_eq!(
+			set.converge_personalized(N::from(5)),
+			Err(SetError::MissingMember)
+		);
+	}
+
+	#[test]
+	fn test_distrust_penalizes_peers() {
+		// Every peer trusts the two others
+		let addrs = [N::from(1), N::from(2), N::from(3)];
+		let mut set = Set::new(N::from_u128(DOMAIN));
+		for addr in addrs {
+			set.add_member(addr).unwrap();
+		}
+		for (i, addr) in addrs.iter().enumerate() {
+			let mut scores = vec![N::ZERO; NUM_NEIGHBOURS];
+			scores[..3].fill(N::from(1));
+			scores[i] = N::ZERO;
+			set.update_verified_op(*addr, scores).unwrap();
+		}
+
+		// Peers 1 and 2 distrust peer 3, cancelling and outweighing their trust in it
+		let mut distrust = vec![N::ZERO; NUM_NEIGHBOURS];
+		distrust[2] = N::from(1);
+		set.update_distrust(addrs[0], distrust.clone()).unwrap();
+		distrust[2] = N::from(5);
+		set.update_distrust(addrs[1], distrust).unwrap();
+		assert_eq!(
+			set.update_distrust(N::from(4), vec![N::ZERO; NUM_NEIGHBOURS]),
+			Err(SetError::MissingMember)
+		);
+
+		let scores = set.converge_rational();
+		assert_eq!(scores[2], BigRational::zero());
+
+		let total: BigRational = scores.iter().sum();
+		assert_eq!(
+			total,
+			BigRational::from_integer(BigInt::from(3 * INITIAL_SCORE))
+		);
+
+		// The field scores match the rational ones
+		let field_scores = set.converge().unwrap();
+		for i in 0..3 {
+			let score = &scores[i];
+			let expected = rational_to_fe::<N>(score);
+			assert_eq!(field_scores[i], expected);
+		}
+	}
+
+	#[test]
+	fn test_decayed_opinions() {
+		// Peer 1 only trusts peer 2, which only trusts peer 3
+		let addrs = [N::from(1), N::from(2), N::from(3)];
+		let build_set = |with_op: bool, op_block: u64| {
+			let mut set = Set::new(N::from_u128(DOMAIN));
+			for addr in addrs {
+				set.add_member(addr).unwrap();
+			}
+			for i in 0..2 {
+				if i == 0 && !with_op {
+					continue;
+				}
+				let mut scores = vec![N::ZERO; NUM_NEIGHBOURS];
+				scores[i + 1] = N::from(1);
+				set.update_verified_op(addrs[i], scores).unwrap();
+			}
+			set.set_op_block(addrs[0], op_block).unwrap();
+			set.set_decay(1, 2, 10, 1000).unwrap();
+
+			set
+		};
+
+		// Recent opinions keep their weight
+		let fresh = build_set(true, 995).converge_rational();
+		let mut undecayed = build_set(true, 995);
+		undecayed.set_decay(1, 1, 10, 1000).unwrap();
+		assert_eq!(fresh, undecayed.converge_rational());
+
+		// Opinions older than the decay periods weigh as much as no opinion at all
+		let stale = build_set(true, 0).converge_rational();
+		let without_op = build_set(false, 0).converge_rational();
+		assert_eq!(stale, without_op);
+		assert_ne!(stale, fresh);
+
+		// Opinions in between keep part of their weight, in both computations
+		let set = build_set(true, 980);
+		let scores = set.converge_rational();
+		assert!(scores[1] < fresh[1] && scores[1] > stale[1]);
+
+		let field_scores = set.converge().unwrap();
+		for i in 0..3 {
+			let score = &scores[i];
+			let expected = rational_to_fe::<N>(score);
+			assert_eq!(field_scores[i], expected);
+		}
+	}
+
+	#[test]
+	fn test_warm_started_convergence() {
+		let domain = N::from_u128(DOMAIN);
+		let mut set = RuntimeSet::new(domain, 4, 100, INITIAL_SCORE);
+		set.set_damping(1, 2).unwrap();
+		set.set_tolerance(1, 1000).unwrap();
+
+		let addrs = [N::from(1), N::from(2), N::from(3), N::from(4)];
+		let ops = [[0u64, 1, 2, 0], [0, 0, 1, 0], [0, 0, 0, 1], [1, 0, 0, 0]];
+		for (addr, op) in addrs.iter().zip(ops) {
+			set.add_member(*addr).unwrap();
+			set.update_verified_op(*addr, op.map(N::from).to_vec()).unwrap();
+		}
+		let previous = set.converge_rational();
+
+		// Converged scores only need a single iteration to stop
+		let trace = set.converge_rational_trace_from(&previous).unwrap();
+		assert_eq!(trace.len(), 2);
+
+		// After an opinion update, the warm start needs fewer iterations for the same scores
+		set.update_verified_op(addrs[0], vec![N::ZERO, N::from(1), N::from(2), N::from(3)])
+			.unwrap();
+		let cold = set.converge_rational_trace();
+		let warm = set.converge_rational_trace_from(&previous).unwrap();
+		assert!(warm.len() < cold.len());
+
+		let max_diff = BigRational::new(BigInt::from(1), BigInt::from(100));
+		for (cold_score, warm_score) in cold.last().unwrap().iter().zip(warm.last().unwrap()) {
+			assert!((cold_score - warm_score).abs() < max_diff);
+		}
+
+		assert_eq!(
+			set.converge_rational_from(&previous[..3]),
+			Err(SetError::ScoresMismatch)
+		);
+	}
+
+	#[test]
+	fn test_converge_with_rational_trace() {
+		let domain = N::from_u128(DOMAIN);
+		let mut set = RuntimeSet::new(domain, 4, NUM_ITERATIONS, INITIAL_SCORE);
+		set.set_tolerance(1, 10).unwrap();
+
+		let addrs = [N::from(1), N::from(2), N::from(3)];
+		let ops = [[0u64, 3, 1], [2, 0, 5], [1, 1, 0]];
+		for (addr, op) in addrs.iter().zip(ops) {
+			set.add_member(*addr).unwrap();
+			set.update_verified_op(*addr, op.map(N::from).to_vec()).unwrap();
+		}
+
+		let (scores, trace) = set.converge_with_rational_trace().unwrap();
+		assert_eq!(trace, set.converge_rational_trace());
+		assert_eq!(scores, set.converge().unwrap());
+
+		// Every field score is its exact rational score
+		let rational_scores = trace.last().unwrap();
+		assert_eq!(scores.len(), rational_scores.len());
+		for (score, rational_score) in scores.iter().zip(rational_scores) {
+			assert_eq!(*score, rational_to_fe::<N>(rational_score));
+		}
+	}
+
+	#[test]
+	fn test_opinion_filters() {
+		let addrs = [N::from(1), N::from(2), N::from(3)];
+		let build_set = |mut set: RuntimeSet| {
+			for addr in addrs {
+				set.add_member(addr).unwrap();
+			}
+			// Peer 3 gives no opinion
+			set.update_verified_op(addrs[0], vec![N::ZERO, N::from(1), N::from(1)]).unwrap();
+			set.update_verified_op(addrs[1], vec![N::from(1), N::ZERO, N::from(3)]).unwrap();
+
+			set
+		};
+		let new_set = || RuntimeSet::new(N::from_u128(DOMAIN), 3, NUM_ITERATIONS, INITIAL_SCORE);
+		let total = BigRational::from_integer(BigInt::from(3 * INITIAL_SCORE));
+
+		// The empty opinion is spread over the other members by default
+		let redistributed = build_set(new_set()).converge_rational();
+		assert_eq!(redistributed.iter().sum::<BigRational>(), total);
+		let set = build_set(new_set().with_opinion_filter(RedistributeEmpty));
+		assert_eq!(set.converge_rational(), redistributed);
+
+		// Dropped members don't pass their score on, so it drains out of the set
+		let set = build_set(new_set().with_opinion_filter(DropEmpty));
+		let dropped = set.converge_rational();
+		assert!(dropped.iter().sum::<BigRational>() < total);
+		assert!(dropped[2] < redistributed[2]);
+
+		// The field scores match the rational ones, even though the total shrinks
+		let scores = set.converge().unwrap();
+		for (score, rational_score) in scores.iter().zip(&dropped) {
+			assert_eq!(*score, rational_to_fe::<N>(rational_score));
+		}
+
+		// The empty opinion can go to the pre-trusted members only, keeping the total
+		let mut set = build_set(new_set().with_opinion_filter(PreTrustedEmpty));
+		set.set_pre_trusted(vec![addrs[0]]);
+		assert_eq!(set.empty_opinions(), vec![addrs[2]]);
+		let pre_trusted = set.converge_rational();
+		assert_eq!(pre_trusted.iter().sum::<BigRational>(), total);
+		assert!(pre_trusted[0] > redistributed[0]);
+		assert!(pre_trusted[1] < redistributed[1]);
+	}
+
+	#[cfg(feature = "ndarray")]
+	#[test]
+	fn test_converge_ndarray() {
+		let rng = &mut thread_rng();
+		let domain = N::from_u128(DOMAIN);
+		let mut set = RuntimeSet::new(domain, 16, NUM_ITERATIONS, INITIAL_SCORE);
+		set.set_damping(1, 5).unwrap();
+
+		let addrs: Vec<N> = (1..=12u64).map(N::from).collect();
+		set.set_pre_trusted(addrs[..2].to_vec());
+		for addr in &addrs {
+			set.add_member(*addr).unwrap();
+		}
+		for addr in &addrs {
+			let op: Vec<N> = (0..16).map(|_| N::from(rng.gen_range(0..100u64))).collect();
+			set.update_verified_op(*addr, op).unwrap();
+		}
+
+		assert_eq!(set.converge_ndarray().unwrap(), set.converge().unwrap());
+	}
+
+	#[test]
+	fn test_converge_trajectory() {
+		let domain = N::from_u128(DOMAIN);
+		let mut set = RuntimeSet::new(domain, 4, NUM_ITERATIONS, INITIAL_SCORE);
+		set.set_tolerance(1, 10).unwrap();
+
+		let addrs = [N::from(1), N::from(2), N::from(3)];
+		let ops = [[0u64, 3, 1], [2, 0, 5], [1, 1, 0]];
+		for (addr, op) in addrs.iter().zip(ops) {
+			set.add_member(*addr).unwrap();
+			set.update_verified_op(*addr, op.map(N::from).to_vec()).unwrap();
+		}
+
+		let steps = set.converge_trajectory().unwrap();
+		assert_eq!(steps.len(), set.converge_rational_trace().len());
+		assert_eq!(steps[0].residual, BigRational::zero());
+		assert_eq!(steps.last().unwrap().scores, set.converge().unwrap());
+		for step in &steps {
+			for (score, rational_score) in step.scores.iter().zip(&step.rational_scores) {
+				assert_eq!(*score, rational_to_fe::<N>(rational_score));
+			}
+		}
+
+		// The convergence stops at the first residual under the tolerance
+		let tolerance = BigRational::new(BigInt::from(1), BigInt::from(10));
+		let (last, iterations) = steps[1..].split_last().unwrap();
+		assert!(last.residual < tolerance);
+		assert!(iterations.iter().all(|step| step.residual >= tolerance));
+	}
+
+	#[test]
+	fn test_weighted_initial_scores() {
+		let domain = N::from_u128(DOMAIN);
+		let mut set = RuntimeSet::new(domain, 4, NUM_ITERATIONS, INITIAL_SCORE);
+
+		// Peer 1 stakes three times as much as the others
+		let addrs = [N::from(1), N::from(2), N::from(3)];
+		set.add_member_with_score(addrs[0], Some(3 * INITIAL_SCORE)).unwrap();
+		set.add_member(addrs[1]).unwrap();
+		set.add_member_with_score(addrs[2], Some(INITIAL_SCORE)).unwrap();
+		assert_eq!(
+			set.add_member_with_score(N::from(4), Some(0)),
+			Err(SetError::InvalidScore)
+		);
+
+		// The initial scores keep the total of the unweighted set
+		let initial = set.initial_scores();
+		let total = BigRational::from_integer(BigInt::from(3 * INITIAL_SCORE));
+		assert_eq!(initial.iter().sum::<BigRational>(), total);
+		assert_eq!(
+			initial[0],
+			initial[1].clone() * BigRational::from_integer(BigInt::from(3))
+		);
+		assert_eq!(initial[1], initial[2]);
+		assert_eq!(initial[3], BigRational::zero());
+
+		let ops = [[0u64, 3, 1], [2, 0, 5], [1, 1, 0]];
+		for (addr, op) in addrs.iter().zip(ops) {
+			set.update_verified_op(*addr, op.map(N::from).to_vec()).unwrap();
+		}
+
+		let trace = set.converge_rational_trace();
+		assert_eq!(trace[0], initial);
+		let rational_scores = trace.last().unwrap();
+		assert_eq!(rational_scores.iter().sum::<BigRational>(), total);
+		let scores = set.converge().unwrap();
+		for (score, rational_score) in scores.iter().zip(rational_scores) {
+			assert_eq!(*score, rational_to_fe::<N>(rational_score));
+		}
+
+		// Removing the weighted member restores the even start
+		set.remove_member(addrs[0]).unwrap();
+		let initial = set.initial_scores();
+		assert_eq!(
+			initial[1],
+			BigRational::from_integer(BigInt::from(INITIAL_SCORE))
+		);
+	}
+
+	#[test]
+	fn test_trust_matrix() {
+		let domain = N::from_u128(DOMAIN);
+		let mut set = RuntimeSet::new(domain, 4, 1, INITIAL_SCORE);
+
+		// The second slot is left free
+		let addrs = [N::from(1), N::from(2), N::from(3)];
+		let ops = [[0u64, 0, 3, 1], [0, 0, 0, 0], [2, 0, 0, 5], [1, 0, 1, 0]];
+		for addr in [addrs[0], N::from(9), addrs[1], addrs[2]] {
+			set.add_member(addr).unwrap();
+		}
+		set.remove_member(N::from(9)).unwrap();
+		for (addr, op) in addrs.iter().zip([ops[0], ops[2], ops[3]]) {
+			set.update_verified_op(*addr, op.map(N::from).to_vec()).unwrap();
+		}
+
+		let matrix = set.trust_matrix();
+		assert_eq!(matrix.members, addrs.to_vec());
+		let ratio = |num: u64, den: u64| BigRational::new(BigInt::from(num), BigInt::from(den));
+		assert_eq!(
+			matrix.rational_scores[0],
+			vec![ratio(0, 1), ratio(3, 4), ratio(1, 4)]
+		);
+		for (row, rational_row) in matrix.scores.iter().zip(&matrix.rational_scores) {
+			assert_eq!(rational_row.iter().sum::<BigRational>(), BigRational::one());
+			for (score, rational_score) in row.iter().zip(rational_row) {
+				assert_eq!(*score, rational_to_fe::<N>(rational_score));
+			}
+		}
+		for row in matrix.to_f64() {
+			assert!((row.iter().sum::<f64>() - 1.).abs() < 1e-9);
+		}
+
+		// A single iteration multiplies the initial scores by the matrix
+		let scores = set.converge_rational();
+		let initial = BigRational::from_integer(BigInt::from(INITIAL_SCORE));
+		for (j, member_j) in [0, 2, 3].into_iter().enumerate() {
+			let expected: BigRational =
+				matrix.rational_scores.iter().map(|row| &row[j] * &initial).sum();
+			assert_eq!(scores[member_j], expected);
+		}
+	}
+
+	#[test]
+	fn test_convergence_report() {
+		let mut set = RuntimeSet::new(N::from_u128(DOMAIN), 5, NUM_ITERATIONS, INITIAL_SCORE)
+			.with_opinion_filter(DropEmpty);
+
+		// The last slot is left free, the second member only trusts itself and nobody
+		// trusts the fourth member
+		let addrs = [N::from(1), N::from(2), N::from(3), N::from(4)];
+		let ops = [[5u64, 3, 1, 0, 2], [0, 7, 0, 0, 0], [0, 4, 0, 0, 0], [2, 0, 0, 0, 0]];
+		for addr in addrs {
+			set.add_member(addr).unwrap();
+		}
+		for (addr, op) in addrs.iter().zip(ops) {
+			set.update_verified_op(*addr, op.map(N::from).to_vec()).unwrap();
+		}
 
-		set.converge();
+		let (scores, report) = set.converge_with_report().unwrap();
+		assert_eq!(scores, set.converge().unwrap());
+		assert_eq!(report.iterations, NUM_ITERATIONS);
+		assert_eq!(report.filtered_scores, 3);
+		assert_eq!(report.empty_opinions, vec![addrs[1]]);
+		assert_eq!(report.no_inbound_trust, vec![addrs[3]]);
+
+		// The score of the dropped opinion leaks out of the set
+		assert!(report.sum_drift < BigRational::zero());
+		let trace = set.converge_rational_trace();
+		assert_eq!(
+			report.residual,
+			max_change(&trace[trace.len() - 2], &trace[trace.len() - 1])
+		);
+	}
+
+	#[test]
+	fn test_stake_weighted_opinions() {
+		let mut set = RuntimeSet::new(N::from_u128(DOMAIN), 3, NUM_ITERATIONS, INITIAL_SCORE);
+
+		let addrs = [N::from(1), N::from(2), N::from(3)];
+		let ops = [[0u64, 1, 1], [1, 0, 3], [3, 1, 0]];
+		for addr in addrs {
+			set.add_member(addr).unwrap();
+		}
+		for (addr, op) in addrs.iter().zip(ops) {
+			set.update_verified_op(*addr, op.map(N::from).to_vec()).unwrap();
+		}
+		let unweighted = set.converge_rational();
+
+		// The third member holds a quarter of the largest stake, the first one has none
+		set.set_stake(addrs[1], 400).unwrap();
+		set.set_stake(addrs[2], 100).unwrap();
+		assert_eq!(set.set_stake(N::from(9), 100), Err(SetError::MissingMember));
+
+		let ratio = |num: u64, den: u64| BigRational::new(BigInt::from(num), BigInt::from(den));
+		let matrix = set.trust_matrix();
+		assert_eq!(
+			matrix.rational_scores[0],
+			vec![ratio(0, 1), ratio(1, 2), ratio(1, 2)]
+		);
+		assert_eq!(
+			matrix.rational_scores[1],
+			vec![ratio(1, 4), ratio(0, 1), ratio(3, 4)]
+		);
+		assert_eq!(
+			matrix.rational_scores[2],
+			vec![ratio(3, 16), ratio(1, 16), ratio(3, 4)]
+		);
+
+		// The field scores follow the rational ones and the total score is kept
+		let scores = set.converge_rational();
+		assert_ne!(scores, unweighted);
+		let total = BigRational::from_integer(BigInt::from(3 * INITIAL_SCORE));
+		assert_eq!(scores.iter().sum::<BigRational>(), total);
+		let expected: Vec<N> = scores.iter().map(rational_to_fe).collect();
+		assert_eq!(set.converge().unwrap(), expected);
+
+		// Removed members lose their stake
+		set.remove_member(addrs[1]).unwrap();
+		assert_eq!(set.stake_weight(addrs[2]), Some(BigRational::one()));
+	}
+
+	#[test]
+	fn test_heartbeat() {
+		let mut set = RuntimeSet::new(N::from_u128(DOMAIN), 3, NUM_ITERATIONS, INITIAL_SCORE);
+
+		let addrs = [N::from(1), N::from(2), N::from(3)];
+		let ops = [[0u64, 1, 1], [1, 0, 3], [3, 1, 0]];
+		for addr in addrs {
+			set.add_member(addr).unwrap();
+		}
+		for (addr, op) in addrs.iter().zip(ops) {
+			set.update_verified_op(*addr, op.map(N::from).to_vec()).unwrap();
+		}
+
+		// As many heartbeats as iterations give the batch scores
+		let mut step = set.heartbeat();
+		for _ in 1..NUM_ITERATIONS {
+			step = set.heartbeat();
+		}
+		assert_eq!(step.iteration, NUM_ITERATIONS);
+		assert_eq!(step.rational_scores, set.converge_rational());
+		let expected: Vec<N> = step.rational_scores.iter().map(rational_to_fe).collect();
+		assert_eq!(step.scores, expected);
+
+		// The convergence goes on from the current scores after an update, a new member
+		// starting from its initial score
+		let previous = step.rational_scores;
+		set.add_member(N::from(4)).unwrap();
+		set.update_verified_op(
+			addrs[0],
+			vec![0, 1, 1, 2].into_iter().map(N::from).collect(),
+		)
+		.unwrap();
+		let step = set.heartbeat();
+		assert_eq!(step.iteration, NUM_ITERATIONS + 1);
+		assert_eq!(step.rational_scores.len(), 4);
+		assert!(step.residual > BigRational::zero());
+		assert!(step.rational_scores[3] > BigRational::zero());
+		assert_eq!(
+			step.rational_scores.iter().sum::<BigRational>(),
+			set.total_score()
+		);
+		assert_ne!(step.rational_scores[..3], previous[..]);
+	}
+
+	#[test]
+	fn test_set_state() {
+		let mut set = RuntimeSet::new(N::from_u128(DOMAIN), 4, NUM_ITERATIONS, INITIAL_SCORE);
+		let addrs = [N::from(1), N::from(2), N::from(3)];
+		let ops = [[0u64, 1, 1, 0], [1, 0, 3, 0], [3, 1, 0, 0]];
+		for (addr, weight) in addrs.iter().zip([1, 2, 3]) {
+			set.add_member_with_score(*addr, Some(weight)).unwrap();
+		}
+		for (addr, op) in addrs.iter().zip(ops) {
+			set.update_verified_op(*addr, op.map(N::from).to_vec()).unwrap();
+		}
+		set.update_distrust(addrs[0], vec![N::ZERO, N::from(1), N::ZERO, N::ZERO]).unwrap();
+		set.set_stake(addrs[2], 10).unwrap();
+		set.set_pre_trusted(vec![addrs[0]]);
+		set.set_damping(1, 10).unwrap();
+		set.set_removal_policy(RemovalPolicy::Proportional);
+
+		// The state survives a JSON round trip and restores the same scores
+		let state = set.state(7);
+		assert_eq!(state.epoch, 7);
+		assert_eq!(state.scores.len(), 4);
+		let json = serde_json::to_string(&state).unwrap();
+		let restored = RuntimeSet::from_state(&serde_json::from_str(&json).unwrap()).unwrap();
+		assert_eq!(restored.state(7), state);
+		assert_eq!(restored.converge().unwrap(), set.converge().unwrap());
+
+		// Heartbeats resume where they stopped
+		set.heartbeat();
+		let mut restored = RuntimeSet::from_state(&set.state(8)).unwrap();
+		assert_eq!(restored.heartbeat(), set.heartbeat());
+
+		// Malformed states are rejected
+		let mut invalid = state.clone();
+		invalid.ops[0].0 = "9".to_string();
+		assert_eq!(
+			RuntimeSet::from_state(&invalid).err(),
+			Some(SetError::InvalidState)
+		);
+		let mut invalid = state.clone();
+		invalid.members[0] = "0x01".to_string();
+		assert_eq!(
+			RuntimeSet::from_state(&invalid).err(),
+			Some(SetError::InvalidState)
+		);
+
+		// Tampered parameters the setters would reject are too
+		let tampered = [
+			SetState { damping: Some((1, 0)), ..state.clone() },
+			SetState { damping: Some((10, 10)), ..state.clone() },
+			SetState { tolerance: Some((1, 0)), ..state.clone() },
+			SetState { decay: Some(((1, 2), 0, 100)), ..state.clone() },
+			SetState { decay: Some(((3, 2), 10, 100)), ..state.clone() },
+			SetState { weights: vec![("1".to_string(), 0)], ..state.clone() },
+			SetState { ops: vec![("1".to_string(), vec!["1".to_string(); 5])], ..state },
+		];
+		for invalid in &tampered {
+			assert_eq!(
+				RuntimeSet::from_state(invalid).err(),
+				Some(SetError::InvalidState)
+			);
+		}
+	}
+
+	#[test]
+	fn test_score_normalization() {
+		let scores: Vec<BigRational> = [0u64, 999, 3000, 999]
+			.iter()
+			.map(|x| BigRational::from_integer(BigInt::from(*x)))
+			.collect();
+
+		assert_eq!(
+			ScoreNormalization::default().apply(&scores),
+			vec![0., 999., 3000., 999.]
+		);
+
+		let shares = ScoreNormalization::Share.apply(&scores);
+		assert!((shares.iter().sum::<f64>() - 1.).abs() < 1e-9);
+		assert!((shares[2] - 0.6).abs() < 1e-9);
+		assert_eq!(
+			ScoreNormalization::Share.apply(&[BigRational::zero()]),
+			vec![0.]
+		);
+
+		let percentiles = ScoreNormalization::Percentile.apply(&scores);
+		assert_eq!(percentiles, vec![0., 100. / 3., 100., 100. / 3.]);
+		assert_eq!(
+			ScoreNormalization::Percentile.apply(&scores[..1]),
+			vec![100.]
+		);
+
+		let logs = ScoreNormalization::Log.apply(&scores);
+		assert_eq!(logs[0], 0.);
+		assert!((logs[1] - 3.).abs() < 1e-9);
+		assert!(logs[2] > logs[1]);
+	}
+
+	#[test]
+	fn test_set_diff() {
+		let mut set = RuntimeSet::new(N::from_u128(DOMAIN), 3, NUM_ITERATIONS, INITIAL_SCORE);
+		let addrs = [N::from(1), N::from(2), N::from(3), N::from(4)];
+		let ops = [[0u64, 1, 1], [1, 0, 3], [3, 1, 0]];
+		for addr in &addrs[..3] {
+			set.add_member(*addr).unwrap();
+		}
+		for (addr, op) in addrs.iter().zip(ops) {
+			set.update_verified_op(*addr, op.map(N::from).to_vec()).unwrap();
+		}
+
+		// An unchanged set has no membership change and zero deltas
+		let diff = set.diff(&set);
+		assert!(diff.joined.is_empty() && diff.left.is_empty());
+		assert!(diff.changes.iter().all(|change| change.delta.is_zero()));
+
+		// The third member leaves and a fourth one joins
+		let mut later = RuntimeSet::from_state(&set.state(0)).unwrap();
+		later.remove_member(addrs[2]).unwrap();
+		later.add_member(addrs[3]).unwrap();
+		later.update_verified_op(addrs[3], vec![N::from(1), N::ZERO, N::ZERO]).unwrap();
+
+		let diff = set.diff(&later);
+		let old = set.converge_rational();
+		let new = later.converge_rational();
+		assert_eq!(diff.left, vec![(addrs[2], old[2].clone())]);
+		assert_eq!(diff.joined, vec![(addrs[3], new[2].clone())]);
+		assert_eq!(diff.changes.len(), 2);
+		for change in &diff.changes {
+			assert_eq!(change.delta, &change.new - &change.old);
+		}
+		assert!(diff.changes[0].delta.abs() >= diff.changes[1].delta.abs());
+	}
+
+	#[test]
+	fn test_removal_policies() {
+		let addrs = [N::from(1), N::from(2), N::from(3), N::from(4)];
+		let build_set = |policy: RemovalPolicy| {
+			let mut set = RuntimeSet::new(N::from_u128(DOMAIN), 4, NUM_ITERATIONS, INITIAL_SCORE);
+			set.set_removal_policy(policy);
+			set.set_pre_trusted(vec![addrs[0]]);
+			for (i, addr) in addrs.iter().enumerate() {
+				let weight = if i == 1 { Some(2 * INITIAL_SCORE) } else { None };
+				set.add_member_with_score(*addr, weight).unwrap();
+			}
+			let ops = [[0u64, 3, 1, 2], [2, 0, 5, 1], [1, 1, 0, 4], [3, 2, 1, 0]];
+			for (addr, op) in addrs.iter().zip(ops) {
+				set.update_verified_op(*addr, op.map(N::from).to_vec()).unwrap();
+			}
+			set.remove_member(addrs[3]).unwrap();
+
+			set
+		};
+		let unit = |score: u128| BigRational::from_integer(BigInt::from(score));
+
+		// The removed score leaves the set by default
+		let set = build_set(RemovalPolicy::Drop);
+		let dropped = set.initial_scores();
+		assert_eq!(set.total_score(), unit(3 * INITIAL_SCORE));
+
+		// Otherwise the total score is kept
+		let set = build_set(RemovalPolicy::Proportional);
+		let initial = set.initial_scores();
+		assert_eq!(set.total_score(), unit(4 * INITIAL_SCORE));
+		for i in 0..3 {
+			assert_eq!(&initial[i] * unit(3), &dropped[i] * unit(4));
+		}
+		assert_eq!(initial[3], BigRational::zero());
+
+		let set = build_set(RemovalPolicy::PreTrusted);
+		let initial = set.initial_scores();
+		assert_eq!(set.total_score(), unit(4 * INITIAL_SCORE));
+		assert_eq!(&initial[0] - &dropped[0], unit(INITIAL_SCORE));
+		assert_eq!(initial[1..], dropped[1..]);
+
+		// The convergence keeps the redistributed total, and the field scores match
+		let rational_scores = set.converge_rational();
+		assert_eq!(
+			rational_scores.iter().sum::<BigRational>(),
+			set.total_score()
+		);
+		let scores = set.converge().unwrap();
+		for (score, rational_score) in scores.iter().zip(&rational_scores) {
+			assert_eq!(*score, rational_to_fe::<N>(rational_score));
+		}
+	}
+
+	#[test]
+	fn test_ranking_queries() {
+		let domain = N::from_u128(DOMAIN);
+		let mut set = Set::new(domain);
+
+		let rng = &mut thread_rng();
+		let keypairs: Vec<_> = (0..3)
+			.map(|_| EcdsaKeypair::<C, N, NUM_LIMBS, NUM_BITS, P, EC>::generate_keypair(rng))
+			.collect();
+		let addrs: Vec<N> = keypairs.iter().map(|kp| kp.public_key.to_address()).collect();
+		for addr in &addrs {
+			set.add_member(*addr).unwrap();
+		}
+
+		// Peers 1 and 3 trust peer 2 the most, which only trusts peer 1
+		let ops = [[0u64, 4, 1], [1, 0, 0], [1, 3, 0]];
+		for (addr, op) in addrs.iter().zip(ops) {
+			let mut scores = vec![N::ZERO; NUM_NEIGHBOURS];
+			for (score, value) in scores.iter_mut().zip(op) {
+				*score = N::from(value);
+			}
+			set.update_verified_op(*addr, scores).unwrap();
+		}
+
+		let scores = set.converge_rational();
+		let ranking = set.ranking();
+		let ranked: Vec<N> = ranking.iter().map(|(addr, _)| *addr).collect();
+		assert_eq!(ranked, vec![addrs[0], addrs[1], addrs[2]]);
+		assert_eq!(ranking[0].1, scores[0]);
+		assert!(ranking.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+		assert_eq!(set.top_k(2), ranking[..2].to_vec());
+		assert_eq!(set.top_k(10).len(), 3);
+
+		assert_eq!(set.rank_of(&keypairs[0].public_key), Ok(1));
+		assert_eq!(set.rank_of(&keypairs[2].public_key), Ok(3));
+		let outsider = EcdsaKeypair::<C, N, NUM_LIMBS, NUM_BITS, P, EC>::generate_keypair(rng);
+		assert_eq!(
+			set.rank_of(&outsider.public_key),
+			Err(SetError::MissingMember)
+		);
+	}
+
+	#[test]
+	fn test_tolerance_stops_convergence() {
+		// Peers 1 and 2 trust peer 3, which trusts them both
+		let addrs = [N::from(1), N::from(2), N::from(3)];
+		let mut set = Set::new(N::from_u128(DOMAIN));
+		for addr in addrs {
+			set.add_member(addr).unwrap();
+		}
+		let mut scores = vec![N::ZERO; NUM_NEIGHBOURS];
+		scores[2] = N::from(1);
+		set.update_verified_op(addrs[0], scores.clone()).unwrap();
+		set.update_verified_op(addrs[1], scores).unwrap();
+		let mut scores = vec![N::ZERO; NUM_NEIGHBOURS];
+		scores[0] = N::from(1);
+		scores[1] = N::from(1);
+		set.update_verified_op(addrs[2], scores).unwrap();
+		set.set_damping(1, 2).unwrap();
+		assert_eq!(set.iterations_needed(), NUM_ITERATIONS);
+
+		// The score changes halve every iteration, the ninth one being under 2
+		set.set_tolerance(2, 1).unwrap();
+		assert_eq!(set.iterations_needed(), 9);
+		let trace = set.converge_rational_trace();
+		assert_eq!(trace.len(), 10);
+
+		// The field scores stop at the same iteration
+		let scores = set.converge().unwrap();
+		let rational_scores = trace.last().unwrap();
+		for i in 0..3 {
+			let score = &rational_scores[i];
+			let expected = rational_to_fe::<N>(score);
+			assert_eq!(scores[i], expected);
+		}
+
+		// The field convergence stops early instead of running every iteration
+		let field_trace = set.inner.converge_iterations_trace(NUM_ITERATIONS).unwrap();
+		assert_eq!(scores, field_trace[9]);
+
+		// Unreachable tolerances run every iteration
+		set.set_tolerance(1, 1_000_000).unwrap();
+		assert_eq!(set.iterations_needed(), NUM_ITERATIONS);
+		assert_eq!(&set.converge().unwrap(), field_trace.last().unwrap());
+	}
+
+	#[test]
+	fn test_param_overrides() {
+		let mut set = Set::new(N::from_u128(DOMAIN));
+		set.set_params(5, 500).unwrap();
+		assert_eq!((set.num_iterations(), set.initial_score()), (5, 500));
+
+		let addrs = [N::from(1), N::from(2), N::from(3)];
+		for (i, addr) in addrs.iter().enumerate() {
+			set.add_member(*addr).unwrap();
+
+			let mut scores = vec![N::ONE; NUM_NEIGHBOURS];
+			scores[i] = N::ZERO;
+			set.update_verified_op(*addr, scores).unwrap();
+		}
+
+		// The trace holds the initial scores and every overridden iteration
+		let trace = set.converge_rational_trace();
+		assert_eq!(trace.len(), 6);
+
+		let total: BigRational = trace.last().unwrap().iter().sum();
+		assert_eq!(total, BigRational::from_integer(BigInt::from(1500)));
+
+		let scores = set.converge().unwrap();
+		assert_eq!(scores.iter().sum::<N>(), N::from_u128(1500));
+
+		// Circuit parameters aren't overrides
+		let mut set = Set::new(N::from_u128(DOMAIN));
+		set.set_params(NUM_ITERATIONS, INITIAL_SCORE).unwrap();
+		assert_eq!((set.num_iterations, set.initial_score), (None, None));
+
+		// Invalid parameters are errors, as are overrides once members joined
+		assert_eq!(
+			set.set_params(0, INITIAL_SCORE),
+			Err(SetError::InvalidParams)
+		);
+		set.add_member(addrs[0]).unwrap();
+		assert_eq!(set.set_params(5, 500), Err(SetError::InvalidParams));
+		assert_eq!(set.set_damping(2, 2), Err(SetError::InvalidParams));
+		assert_eq!(set.set_tolerance(1, 0), Err(SetError::InvalidParams));
+		assert_eq!(set.set_decay(1, 2, 0, 1000), Err(SetError::InvalidParams));
+		assert!(matches!(
+			RuntimeSet::try_new(N::from_u128(DOMAIN), 3, 0, INITIAL_SCORE),
+			Err(SetError::InvalidParams)
+		));
+	}
+
+	#[test]
+	fn test_runtime_set_grows() {
+		let domain = N::from_u128(DOMAIN);
+		let mut set = RuntimeSet::new(domain, 2, NUM_ITERATIONS, INITIAL_SCORE);
+
+		let rng = &mut thread_rng();
+		let keypairs: Vec<_> = (0..3)
+			.map(|_| EcdsaKeypair::<C, N, NUM_LIMBS, NUM_BITS, P, EC>::generate_keypair(rng))
+			.collect();
+		let addrs: Vec<N> = keypairs.iter().map(|kp| kp.public_key.to_address()).collect();
+
+		// Peer1 gives its opinion before the set grows
+		set.add_member(addrs[0]).unwrap();
+		set.add_member(addrs[1]).unwrap();
+		let op1 = sign_opinion::<2, NUM_ITERATIONS, INITIAL_SCORE>(
+			&keypairs[0],
+			&addrs[..2],
+			&[N::ZERO, N::from(5)],
+		);
+		set.update_op(keypairs[0].public_key.clone(), op1).unwrap();
+
+		set.add_member(addrs[2]).unwrap();
+		assert_eq!(set.capacity(), 3);
+
+		let op2 = sign_opinion::<3, NUM_ITERATIONS, INITIAL_SCORE>(
+			&keypairs[1],
+			&addrs,
+			&[N::from(3), N::ZERO, N::from(7)],
+		);
+		set.update_op(keypairs[1].public_key.clone(), op2).unwrap();
+		set.update_verified_op(addrs[2], vec![N::from(2)]).unwrap();
+
+		let scores = set.converge_rational();
+		let total: BigRational = scores.iter().sum();
+		assert_eq!(
+			total,
+			BigRational::from_integer(BigInt::from(3 * INITIAL_SCORE))
+		);
+
+		// The field scores match the rational ones
+		let field_scores = set.converge().unwrap();
+		for i in 0..3 {
+			let score = &scores[i];
+			let expected = rational_to_fe::<N>(score);
+			assert_eq!(field_scores[i], expected);
+		}
+
+		// Freed slots are reused before growing
+		set.remove_member(addrs[1]).unwrap();
+		set.add_member(N::from(4)).unwrap();
+		assert_eq!(set.capacity(), 3);
 	}
 
 	#[test]
@@ -1033,18 +4046,18 @@ mod test {
 			SH,
 		>::new(domain);
 
-		eigen_trust_set.add_member(addr1);
-		eigen_trust_set.add_member(addr2);
-		eigen_trust_set.add_member(addr3);
+		eigen_trust_set.add_member(addr1).unwrap();
+		eigen_trust_set.add_member(addr2).unwrap();
+		eigen_trust_set.add_member(addr3).unwrap();
 
-		eigen_trust_set.update_op(keypair1.public_key, op1);
-		eigen_trust_set.update_op(keypair2.public_key, op2);
-		eigen_trust_set.update_op(keypair3.public_key, op3);
+		eigen_trust_set.update_op(keypair1.public_key, op1).unwrap();
+		eigen_trust_set.update_op(keypair2.public_key, op2).unwrap();
+		eigen_trust_set.update_op(keypair3.public_key, op3).unwrap();
 
-		let filtered_ops = eigen_trust_set.filter_peers_ops();
+		let filtered_ops = eigen_trust_set.inner.filter_peers_ops();
 
 		let final_peers_count =
-			eigen_trust_set.set.iter().filter(|&&(addr, _)| addr != N::zero()).count();
+			eigen_trust_set.inner.set.iter().filter(|&&(addr, _)| addr != N::zero()).count();
 		let final_ops_count = filtered_ops.keys().count();
 		assert!(final_peers_count == final_ops_count);
 	}
@@ -1084,7 +4097,7 @@ mod test {
 		let addrs: Vec<N> = keys.iter().map(|key| key.public_key.to_address()).collect();
 
 		// Add the publicKey to the set
-		addrs.iter().for_each(|f| set.add_member(f.clone()));
+		addrs.iter().for_each(|f| set.add_member(f.clone()).unwrap());
 
 		// Update the opinions
 		for i in 0..NUM_NEIGHBOURS {
@@ -1095,15 +4108,85 @@ mod test {
 			);
 
 			let pk_i = keys[i].public_key.clone();
-			set.update_op(pk_i, op_i);
+			set.update_op(pk_i, op_i).unwrap();
 		}
 
-		let s = set.converge();
+		let s = set.converge().unwrap();
 		let s_ratios = set.converge_rational();
 
 		(s, s_ratios)
 	}
 
+	#[test]
+	#[ignore = "benchmark, run with `cargo test --release -- --ignored bench_parallel_converge`"]
+	fn bench_parallel_converge() {
+		const NUM_MEMBERS: usize = 1024;
+
+		let rng = &mut thread_rng();
+		let domain = N::from_u128(DOMAIN);
+		let mut set = RuntimeSet::new(domain, NUM_MEMBERS, NUM_ITERATIONS, INITIAL_SCORE);
+		for i in 1..=NUM_MEMBERS {
+			set.add_member(N::from(i as u64)).unwrap();
+		}
+		for i in 1..=NUM_MEMBERS {
+			let scores = (0..NUM_MEMBERS).map(|_| N::from(rng.gen_range(0..100u64))).collect();
+			set.update_verified_op(N::from(i as u64), scores).unwrap();
+		}
+
+		let sequential = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+		let start = Instant::now();
+		let sequential_scores = sequential.install(|| set.converge().unwrap());
+		let sequential_time = start.elapsed();
+
+		let start = Instant::now();
+		let parallel_scores = set.converge().unwrap();
+		let parallel_time = start.elapsed();
+
+		assert_eq!(sequential_scores, parallel_scores);
+		println!(
+			"Convergence of {} members: {:?} on 1 thread, {:?} on {} threads",
+			NUM_MEMBERS,
+			sequential_time,
+			parallel_time,
+			rayon::current_num_threads()
+		);
+	}
+
+	#[test]
+	#[ignore = "benchmark, run with `cargo test --release -- --ignored bench_tolerance_converge`"]
+	fn bench_tolerance_converge() {
+		const NUM_MEMBERS: usize = 256;
+
+		let rng = &mut thread_rng();
+		let domain = N::from_u128(DOMAIN);
+		let mut set = RuntimeSet::new(domain, NUM_MEMBERS, NUM_ITERATIONS, INITIAL_SCORE);
+		for i in 1..=NUM_MEMBERS {
+			set.add_member(N::from(i as u64)).unwrap();
+		}
+		for i in 1..=NUM_MEMBERS {
+			let scores = (0..NUM_MEMBERS).map(|_| N::from(rng.gen_range(0..100u64))).collect();
+			set.update_verified_op(N::from(i as u64), scores).unwrap();
+		}
+
+		let start = Instant::now();
+		let fixed_scores = set.converge_iterations_trace(NUM_ITERATIONS).unwrap();
+		let fixed_time = start.elapsed();
+
+		// The tolerance path runs a prefix of the same iterations
+		set.set_tolerance(1, 1).unwrap();
+		let start = Instant::now();
+		let iterations = set.iterations_needed();
+		let tolerance_scores = set.converge().unwrap();
+		let tolerance_time = start.elapsed();
+
+		assert!(iterations <= NUM_ITERATIONS);
+		assert_eq!(tolerance_scores, fixed_scores[iterations]);
+		println!(
+			"Convergence of {} members: {:?} over {} iterations, {:?} over {} with a tolerance",
+			NUM_MEMBERS, fixed_time, NUM_ITERATIONS, tolerance_time, iterations
+		);
+	}
+
 	#[test]
 	fn test_scaling_1() {
 		const NUM_NEIGHBOURS: usize = 10;
@@ -0,0 +1,415 @@
+use super::native::{RuntimeEigenTrustSet, SetError};
+use crate::{
+	circuits::{RationalScore, HASHER_WIDTH},
+	params::{ecc::EccParams, rns::RnsParams},
+	utils::fe_to_big,
+	FieldExt, Hasher, SpongeHasher,
+};
+use halo2::halo2curves::CurveAffine;
+use num_bigint::{BigInt, ToBigInt};
+use num_rational::BigRational;
+use num_traits::Zero;
+use std::{collections::HashMap, marker::PhantomData};
+
+/// EigenTrust over more participants than a set holds. The members are split into shards
+/// of at most `shard_size` members, each converged like a `RuntimeEigenTrustSet` over the
+/// opinions within it. The opinions across shards then weigh the shards against each
+/// other, as in BlockRank: the trust a shard gives to another is the local score of its
+/// members times the share of their opinions about the other shard's members, and the
+/// shard scores converge over this trust like the member scores do over theirs. The final
+/// score of a member is its share of the local scores of its shard times the shard score.
+/// The total score is kept, but the scores approximate the ones of a single set, since
+/// the trust across shards doesn't flow back into the local scores. Sharded scores
+/// aren't covered by the circuit.
+pub struct ShardedEigenTrustSet<
+	C: CurveAffine,
+	N: FieldExt,
+	const NUM_LIMBS: usize,
+	const NUM_BITS: usize,
+	P,
+	EC,
+	H: Hasher<N, HASHER_WIDTH>,
+	SH: SpongeHasher<N>,
+> where
+	P: RnsParams<C::ScalarExt, N, NUM_LIMBS, NUM_BITS> + RnsParams<C::Base, N, NUM_LIMBS, NUM_BITS>,
+	EC: EccParams<C>,
+	C::ScalarExt: FieldExt,
+	C::Base: FieldExt,
+{
+	members: Vec<N>,
+	index: HashMap<N, usize>,
+	ops: HashMap<N, Vec<(N, N)>>,
+	domain: N,
+	shard_size: usize,
+	num_iterations: usize,
+	initial_score: u128,
+	_p: PhantomData<(C, P, EC, H, SH)>,
+}
+
+impl<
+		C: CurveAffine,
+		N: FieldExt,
+		const NUM_LIMBS: usize,
+		const NUM_BITS: usize,
+		P,
+		EC,
+		H: Hasher<N, HASHER_WIDTH>,
+		SH: SpongeHasher<N>,
+	> ShardedEigenTrustSet<C, N, NUM_LIMBS, NUM_BITS, P, EC, H, SH>
+where
+	P: RnsParams<C::ScalarExt, N, NUM_LIMBS, NUM_BITS> + RnsParams<C::Base, N, NUM_LIMBS, NUM_BITS>,
+	EC: EccParams<C>,
+	C::ScalarExt: FieldExt,
+	C::Base: FieldExt,
+{
+	/// Constructs new instance splitting the members into shards of at most `shard_size`
+	pub fn new(
+		domain: N, shard_size: usize, num_iterations: usize, initial_score: u128,
+	) -> Result<Self, SetError> {
+		if shard_size < 2 || num_iterations == 0 || initial_score == 0 {
+			return Err(SetError::InvalidParams);
+		}
+
+		Ok(Self {
+			members: Vec::new(),
+			index: HashMap::new(),
+			ops: HashMap::new(),
+			domain,
+			shard_size,
+			num_iterations,
+			initial_score,
+			_p: PhantomData,
+		})
+	}
+
+	/// Add new member
+	pub fn add_member(&mut self, addr: N) -> Result<(), SetError> {
+		if addr == N::ZERO {
+			return Err(SetError::ZeroAddress);
+		}
+		if self.index.contains_key(&addr) {
+			return Err(SetError::DuplicateMember);
+		}
+
+		self.index.insert(addr, self.members.len());
+		self.members.push(addr);
+
+		Ok(())
+	}
+
+	/// Returns the members, in the order they joined.
+	pub fn members(&self) -> &[N] {
+		&self.members
+	}
+
+	/// Update the opinion of the member, as (peer, score) pairs. The scores given to
+	/// itself and to peers that aren't members are ignored.
+	pub fn update_op(&mut self, from: N, op: Vec<(N, N)>) -> Result<(), SetError> {
+		if !self.index.contains_key(&from) {
+			return Err(SetError::MissingMember);
+		}
+
+		self.ops.insert(from, op);
+
+		Ok(())
+	}
+
+	/// Returns the shards, as the indices of their members. The members are spread evenly
+	/// over as few shards as possible, in the order they joined, so the shard sizes differ
+	/// by at most one. With a `shard_size` of 2 and an odd number of members, the last
+	/// shard holds a single member, which keeps the initial score as its local score.
+	pub fn shards(&self) -> Vec<Vec<usize>> {
+		let len = self.members.len();
+		let count = ((len + self.shard_size - 1) / self.shard_size).max(1);
+		let (base, extra) = (len / count, len % count);
+
+		let mut shards = Vec::with_capacity(count);
+		let mut start = 0;
+		for shard in 0..count {
+			let size = if shard < extra { base + 1 } else { base };
+			shards.push((start..start + size).collect());
+			start += size;
+		}
+		shards
+	}
+
+	/// Compute the EigenTrust scores of the members, in the order they joined, using
+	/// BigRational numbers.
+	pub fn converge_rational(&self) -> Result<Vec<RationalScore>, SetError> {
+		if self.members.len() < 2 {
+			return Err(SetError::InsufficientPeers);
+		}
+
+		let shards = self.shards();
+		let mut shard_of = vec![0; self.members.len()];
+		for (shard, indices) in shards.iter().enumerate() {
+			for &i in indices {
+				shard_of[i] = shard;
+			}
+		}
+
+		// Local scores of every shard, over the opinions within it
+		let mut local = vec![BigRational::zero(); self.members.len()];
+		for indices in &shards {
+			for (&i, score) in indices.iter().zip(self.local_scores(indices)) {
+				local[i] = score;
+			}
+		}
+		let local_totals: Vec<BigRational> =
+			shards.iter().map(|indices| indices.iter().map(|&i| &local[i]).sum()).collect();
+
+		// Trust between the shards, each row summing to one
+		let mut shard_ops = vec![vec![BigRational::zero(); shards.len()]; shards.len()];
+		for (i, score) in local.iter().enumerate() {
+			let shard = shard_of[i];
+			if local_totals[shard].is_zero() {
+				continue;
+			}
+
+			let weight = score / &local_totals[shard];
+			for (to, share) in self.shard_shares(i, &shard_of, &shards) {
+				shard_ops[shard][to] += &weight * share;
+			}
+		}
+
+		// Converge the shard scores, starting from the total initial score of their members
+		let initial = BigInt::from(self.initial_score);
+		let mut shard_scores: Vec<BigRational> = shards
+			.iter()
+			.map(|indices| BigRational::from_integer(BigInt::from(indices.len()) * &initial))
+			.collect();
+		for _ in 0..self.num_iterations {
+			shard_scores = (0..shards.len())
+				.map(|to| {
+					shard_ops
+						.iter()
+						.zip(&shard_scores)
+						.fold(BigRational::zero(), |acc, (ops, score)| {
+							acc + &ops[to] * score
+						})
+				})
+				.collect();
+		}
+
+		// Share the shard scores out according to the local scores
+		let scores = (0..self.members.len())
+			.map(|i| {
+				let shard = shard_of[i];
+				match local_totals[shard].is_zero() {
+					true => BigRational::zero(),
+					false => &shard_scores[shard] * &local[i] / &local_totals[shard],
+				}
+			})
+			.collect();
+
+		Ok(scores)
+	}
+
+	/// Returns the local scores of the members of the shard, converged over the opinions
+	/// about the other members of the shard.
+	fn local_scores(&self, indices: &[usize]) -> Vec<RationalScore> {
+		let initial = BigRational::from_integer(BigInt::from(self.initial_score));
+		if indices.len() < 2 {
+			return vec![initial; indices.len()];
+		}
+
+		let mut set = RuntimeEigenTrustSet::<C, N, NUM_LIMBS, NUM_BITS, P, EC, H, SH>::new(
+			self.domain,
+			indices.len(),
+			self.num_iterations,
+			self.initial_score,
+		);
+		let slots: HashMap<N, usize> =
+			indices.iter().enumerate().map(|(slot, &i)| (self.members[i], slot)).collect();
+		for &i in indices {
+			set.add_member(self.members[i]).unwrap();
+		}
+
+		for &i in indices {
+			let mut scores = vec![N::ZERO; indices.len()];
+			for (peer, score) in self.ops.get(&self.members[i]).into_iter().flatten() {
+				if let Some(&slot) = slots.get(peer) {
+					scores[slot] = *score;
+				}
+			}
+			set.update_verified_op(self.members[i], scores).unwrap();
+		}
+
+		set.converge_rational()
+	}
+
+	/// Returns the shares of the opinion of the member at index `i` going to every shard,
+	/// as (shard, share) pairs. Members without any valid opinion score spread theirs
+	/// evenly over the other members.
+	fn shard_shares(
+		&self, i: usize, shard_of: &[usize], shards: &[Vec<usize>],
+	) -> Vec<(usize, BigRational)> {
+		let mut given: HashMap<usize, BigInt> = HashMap::new();
+		for (peer, score) in self.ops.get(&self.members[i]).into_iter().flatten() {
+			match self.index.get(peer) {
+				Some(&j) if j != i => {
+					let score = fe_to_big(*score).to_bigint().unwrap();
+					*given.entry(shard_of[j]).or_insert_with(BigInt::zero) += score;
+				},
+				_ => {},
+			}
+		}
+
+		let sum: BigInt = given.values().sum();
+		if sum.is_zero() {
+			let others = BigInt::from(self.members.len() - 1);
+			return shards
+				.iter()
+				.enumerate()
+				.map(|(shard, indices)| {
+					let count = indices.len() - usize::from(shard == shard_of[i]);
+					(shard, BigRational::new(BigInt::from(count), others.clone()))
+				})
+				.collect();
+		}
+
+		given
+			.into_iter()
+			.map(|(shard, score)| (shard, BigRational::new(score, sum.clone())))
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		circuits::{PoseidonNativeHasher, PoseidonNativeSponge},
+		params::{ecc::secp256k1::Secp256k1Params, rns::secp256k1::Secp256k1_4_68},
+	};
+	use halo2::halo2curves::{bn256::Fr, ff::PrimeField, secp256k1::Secp256k1Affine};
+
+	const DOMAIN: u128 = 42;
+	const NUM_ITERATIONS: usize = 10;
+	const INITIAL_SCORE: u128 = 1000;
+
+	type C = Secp256k1Affine;
+	type N = Fr;
+	const NUM_LIMBS: usize = 4;
+	const NUM_BITS: usize = 68;
+	type P = Secp256k1_4_68;
+	type EC = Secp256k1Params;
+	type H = PoseidonNativeHasher;
+	type SH = PoseidonNativeSponge;
+
+	type Sharded = ShardedEigenTrustSet<C, N, NUM_LIMBS, NUM_BITS, P, EC, H, SH>;
+
+	fn build_set(shard_size: usize, ops: &[Vec<u64>]) -> Sharded {
+		let mut set = Sharded::new(
+			N::from_u128(DOMAIN),
+			shard_size,
+			NUM_ITERATIONS,
+			INITIAL_SCORE,
+		)
+		.unwrap();
+		let addrs: Vec<N> = (1..=ops.len() as u64).map(N::from).collect();
+		for addr in &addrs {
+			set.add_member(*addr).unwrap();
+		}
+		for (addr, op) in addrs.iter().zip(ops) {
+			let op = addrs.iter().zip(op).map(|(&peer, &score)| (peer, N::from(score))).collect();
+			set.update_op(*addr, op).unwrap();
+		}
+		set
+	}
+
+	#[test]
+	fn test_single_shard_matches_set() {
+		let ops = vec![vec![0, 3, 1, 2], vec![2, 0, 5, 1], vec![1, 1, 0, 4], vec![3, 2, 1, 0]];
+		let sharded = build_set(4, &ops);
+		assert_eq!(sharded.shards(), vec![vec![0, 1, 2, 3]]);
+
+		let mut set = RuntimeEigenTrustSet::<C, N, NUM_LIMBS, NUM_BITS, P, EC, H, SH>::new(
+			N::from_u128(DOMAIN),
+			4,
+			NUM_ITERATIONS,
+			INITIAL_SCORE,
+		);
+		for (addr, op) in sharded.members().iter().zip(&ops) {
+			set.add_member(*addr).unwrap();
+			set.update_verified_op(*addr, op.iter().map(|&score| N::from(score)).collect())
+				.unwrap();
+		}
+
+		assert_eq!(
+			sharded.converge_rational().unwrap(),
+			set.converge_rational()
+		);
+	}
+
+	#[test]
+	fn test_sharded_scores() {
+		// Everyone trusts the first member the most
+		let size = 10;
+		let ops: Vec<Vec<u64>> = (0..size)
+			.map(|i| {
+				(0..size)
+					.map(|j| match (i == j, j == 0) {
+						(true, _) => 0,
+						(false, true) => 10,
+						(false, false) => 1,
+					})
+					.collect()
+			})
+			.collect();
+		let set = build_set(4, &ops);
+
+		// Shards are as even as possible
+		let sizes: Vec<usize> = set.shards().iter().map(Vec::len).collect();
+		assert_eq!(sizes, vec![4, 3, 3]);
+
+		// The total score is kept, and the most trusted member gets the highest score
+		let scores = set.converge_rational().unwrap();
+		let total = BigRational::from_integer(BigInt::from(size as u128 * INITIAL_SCORE));
+		assert_eq!(scores.iter().sum::<BigRational>(), total);
+		assert!(scores[1..].iter().all(|score| *score < scores[0]));
+
+		assert_eq!(set.members().len(), size);
+		assert!(matches!(
+			build_set(4, &ops[..1]).converge_rational(),
+			Err(SetError::InsufficientPeers)
+		));
+	}
+
+	#[test]
+	fn test_single_member_shard() {
+		let ops: Vec<Vec<u64>> =
+			(0..5).map(|i| (0..5).map(|j| if i == j { 0 } else { j + 1 }).collect()).collect();
+		let set = build_set(2, &ops);
+		let sizes: Vec<usize> = set.shards().iter().map(Vec::len).collect();
+		assert_eq!(sizes, vec![2, 2, 1]);
+
+		let scores = set.converge_rational().unwrap();
+		let total = BigRational::from_integer(BigInt::from(5 * INITIAL_SCORE));
+		assert_eq!(scores.iter().sum::<BigRational>(), total);
+	}
+
+	#[test]
+	fn test_invalid_params() {
+		let domain = N::from_u128(DOMAIN);
+		assert!(matches!(
+			Sharded::new(domain, 1, NUM_ITERATIONS, INITIAL_SCORE),
+			Err(SetError::InvalidParams)
+		));
+		assert!(matches!(
+			Sharded::new(domain, 4, 0, INITIAL_SCORE),
+			Err(SetError::InvalidParams)
+		));
+		assert!(matches!(
+			Sharded::new(domain, 4, NUM_ITERATIONS, 0),
+			Err(SetError::InvalidParams)
+		));
+	}
+
+	#[test]
+	fn test_add_invalid_member() {
+		let mut set = build_set(4, &[vec![0, 1], vec![1, 0]]);
+		assert_eq!(set.add_member(N::ZERO), Err(SetError::ZeroAddress));
+		assert_eq!(set.add_member(N::from(1)), Err(SetError::DuplicateMember));
+	}
+}
@@ -1,6 +1,7 @@
 use self::{
 	dynamic_sets::{
 		native::{EigenTrustSet as NativeEigenTrustSet, SignedAttestation},
+		sharded::ShardedEigenTrustSet,
 		EigenTrustSet,
 	},
 	opinion::native::Opinion,
@@ -12,7 +13,7 @@ use crate::{
 	edwards::params::BabyJubJub,
 	params::{
 		ecc::{bn254::Bn254Params, secp256k1::Secp256k1Params},
-		hasher::poseidon_bn254_5x5::Params,
+		hasher::{poseidon_bn254_5x5::Params, ParamSet, RoundParams},
 		rns::{bn256::Bn256_4_68, secp256k1::Secp256k1_4_68},
 	},
 	poseidon::{
@@ -61,6 +62,11 @@ pub const ET_PARAMS_K: u32 = 20;
 /// Default polynomial degree for KZG parameters for Threshold circuit.
 pub const TH_PARAMS_K: u32 = 21;
 
+/// Returns the parameter set of the hasher used by the circuits.
+pub fn hasher_param_set() -> ParamSet {
+	<Params as RoundParams<Scalar, HASHER_WIDTH>>::param_set()
+}
+
 /// KZG Commitment Scheme
 pub type KZGParams = KZGCommitmentScheme<Bn256>;
 /// Rational score
@@ -125,6 +131,17 @@ pub type NativeEigenTrust4 = NativeEigenTrustSet<
 	PoseidonNativeHasher,
 	PoseidonNativeSponge,
 >;
+/// Native EigenTrust set sharded over more participants than a set holds
+pub type NativeShardedEigenTrust = ShardedEigenTrustSet<
+	Secp256k1Affine,
+	Scalar,
+	NUM_LIMBS,
+	NUM_BITS,
+	Secp256k1_4_68,
+	Secp256k1Params,
+	PoseidonNativeHasher,
+	PoseidonNativeSponge,
+>;
 /// EigenTrust set with 4 participants
 pub type EigenTrust4 = EigenTrustSet<
 	NUM_NEIGHBOURS,
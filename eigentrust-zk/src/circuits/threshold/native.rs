@@ -288,7 +288,7 @@ mod tests {
 		let pks = keypairs.iter().map(|kp| kp.public_key.clone()).collect_vec();
 		let addresses = pks.iter().map(|pk| pk.to_address()).collect_vec();
 		// Add the publicKey to the set
-		addresses.iter().for_each(|&addr| set.add_member(addr));
+		addresses.iter().for_each(|&addr| set.add_member(addr).unwrap());
 		// Update the opinions
 		for i in 0..NUM_NEIGHBOURS {
 			let scores = ops[i].to_vec();
@@ -297,10 +297,10 @@ mod tests {
 				&keypairs[i], &addresses, &scores,
 			);
 
-			set.update_op(pks[i].clone(), op_i);
+			set.update_op(pks[i].clone(), op_i).unwrap();
 		}
 
-		let s = set.converge();
+		let s = set.converge().unwrap();
 		let s_ratios = set.converge_rational();
 
 		(s, s_ratios)
@@ -8,10 +8,20 @@ pub mod rescue_prime_bn254_5x5;
 use std::fmt::Debug;
 
 use crate::FieldExt;
-use halo2::{circuit::Value, plonk::Expression};
+use halo2::{circuit::Value, halo2curves::bn256::Fr, plonk::Expression};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
 
 /// Trait definition of Round parameters of Poseidon
 pub trait RoundParams<F: FieldExt, const WIDTH: usize>: Sbox + Clone + Debug {
+	/// Returns the name of the parameter set.
+	fn name() -> &'static str;
+
+	/// Returns the descriptor of the parameter set.
+	fn param_set() -> ParamSet {
+		ParamSet::from_params::<F, WIDTH, Self>()
+	}
+
 	/// Returns a number of full rounds.
 	fn full_rounds() -> usize;
 	/// Returns a number of partial rounds.
@@ -141,6 +151,61 @@ pub trait Sbox {
 	fn sbox_inv_f<F: FieldExt>(f: F) -> F;
 }
 
+/// Named hasher parameter set, identified by a fingerprint of all its parameters.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParamSet {
+	/// Name of the parameter set.
+	pub name: String,
+	/// Width of the hasher state.
+	pub width: usize,
+	/// Number of full rounds.
+	pub full_rounds: usize,
+	/// Number of partial rounds.
+	pub partial_rounds: usize,
+	/// Keccak256 hash of the parameters, as a hex string.
+	pub fingerprint: String,
+}
+
+impl ParamSet {
+	/// Builds the descriptor of the given round parameters.
+	pub fn from_params<F: FieldExt, const WIDTH: usize, P: RoundParams<F, WIDTH>>() -> Self {
+		let name = P::name().to_string();
+		let full_rounds = P::full_rounds();
+		let partial_rounds = P::partial_rounds();
+
+		let mut hasher = Keccak256::new();
+		hasher.update(name.as_bytes());
+		hasher.update((WIDTH as u64).to_be_bytes());
+		hasher.update((full_rounds as u64).to_be_bytes());
+		hasher.update((partial_rounds as u64).to_be_bytes());
+		for constant in P::round_constants_raw() {
+			hasher.update(constant.as_bytes());
+		}
+		for row in P::mds_raw() {
+			for item in row {
+				hasher.update(item.as_bytes());
+			}
+		}
+		let fingerprint = hex::encode(hasher.finalize());
+
+		Self { name, width: WIDTH, full_rounds, partial_rounds, fingerprint }
+	}
+}
+
+/// Returns the registry of the supported parameter sets.
+pub fn param_set_registry() -> Vec<ParamSet> {
+	vec![
+		<poseidon_bn254_5x5::Params as RoundParams<Fr, 5>>::param_set(),
+		<poseidon_bn254_10x5::Params as RoundParams<Fr, 10>>::param_set(),
+		<rescue_prime_bn254_5x5::Params as RoundParams<Fr, 5>>::param_set(),
+	]
+}
+
+/// Finds a registered parameter set by name.
+pub fn find_param_set(name: &str) -> Option<ParamSet> {
+	param_set_registry().into_iter().find(|param_set| param_set.name == name)
+}
+
 /// Returns congruent field element for the given hex string.
 pub fn hex_to_field<F: FieldExt>(s: &str) -> F {
 	let s = &s[2..];
@@ -150,3 +215,27 @@ pub fn hex_to_field<F: FieldExt>(s: &str) -> F {
 	bytes_wide[..bytes.len()].copy_from_slice(&bytes[..]);
 	F::from_uniform_bytes(&bytes_wide)
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_param_set_registry() {
+		let registry = param_set_registry();
+
+		// Names and fingerprints identify a single parameter set
+		for (i, param_set) in registry.iter().enumerate() {
+			for other in &registry[i + 1..] {
+				assert_ne!(param_set.name, other.name);
+				assert_ne!(param_set.fingerprint, other.fingerprint);
+			}
+		}
+
+		let poseidon = find_param_set("poseidon_bn254_5x5").unwrap();
+		assert_eq!(poseidon.width, 5);
+		assert_eq!(poseidon.full_rounds, 8);
+		assert_eq!(poseidon.partial_rounds, 60);
+		assert!(find_param_set("unknown").is_none());
+	}
+}
@@ -27,6 +27,10 @@ impl Sbox for Params {
 }
 
 impl RoundParams<Fr, 10> for Params {
+	fn name() -> &'static str {
+		"poseidon_bn254_10x5"
+	}
+
 	fn partial_rounds() -> usize {
 		60
 	}
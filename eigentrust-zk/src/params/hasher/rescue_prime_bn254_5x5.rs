@@ -27,6 +27,10 @@ impl Sbox for Params {
 }
 
 impl RoundParams<Fr, 5> for Params {
+	fn name() -> &'static str {
+		"rescue_prime_bn254_5x5"
+	}
+
 	fn partial_rounds() -> usize {
 		0
 	}
@@ -323,6 +323,14 @@ pub fn big_to_fe<F: FieldExt>(e: BigUint) -> F {
 	F::from_str_vartime(&e.to_str_radix(10)[..]).unwrap()
 }
 
+/// Returns [`FieldExt`] for the given non-negative [`BigRational`], dividing its numerator by
+/// its denominator in the field.
+pub fn rational_to_fe<F: FieldExt>(ratio: &BigRational) -> F {
+	let num = ratio.numer().to_biguint().unwrap_or_default();
+	let den = ratio.denom().to_biguint().unwrap_or_default();
+	big_to_fe::<F>(num) * big_to_fe::<F>(den).invert().unwrap()
+}
+
 /// Returns [`BigUint`] representation for the given [`FieldExt`].
 pub fn fe_to_big<F: FieldExt>(fe: F) -> BigUint {
 	BigUint::from_bytes_le(fe.to_repr().as_ref())
@@ -429,7 +429,7 @@ mod test {
 		let pks_fr: Vec<Scalar> = keypairs.iter().map(|kp| kp.public_key.to_address()).collect();
 
 		// Add the "address"(pk_fr) to the set
-		pks_fr.iter().for_each(|pk| set.add_member(*pk));
+		pks_fr.iter().for_each(|pk| set.add_member(*pk).unwrap());
 
 		// Update the opinions
 		for i in 0..NUM_NEIGHBOURS {
@@ -437,10 +437,10 @@ mod test {
 			let op_i = sign_opinion::<NUM_NEIGHBOURS, NUM_ITERATIONS, INITIAL_SCORE, DOMAIN>(
 				&keypairs[i], &pks_fr, &scores,
 			);
-			set.update_op(pks[i].clone(), op_i);
+			set.update_op(pks[i].clone(), op_i).unwrap();
 		}
 
-		let s = set.converge();
+		let s = set.converge().unwrap();
 
 		// Prepare the EigenTrustSet Circuit inputs
 		let (attestations, set, op_hash) = {
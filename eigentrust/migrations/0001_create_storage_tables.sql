@@ -0,0 +1,28 @@
+-- Attestation records, with the queried fields extracted from the record.
+CREATE TABLE attestations (
+	id BIGSERIAL PRIMARY KEY,
+	about TEXT NOT NULL,
+	attester TEXT,
+	block_number BIGINT,
+	record JSONB NOT NULL
+);
+
+CREATE INDEX attestations_about_idx ON attestations (about);
+CREATE INDEX attestations_attester_idx ON attestations (attester);
+CREATE INDEX attestations_block_number_idx ON attestations (block_number);
+
+-- Score records of the last computation.
+CREATE TABLE scores (
+	id BIGSERIAL PRIMARY KEY,
+	peer_address TEXT NOT NULL,
+	epoch BIGINT,
+	record JSONB NOT NULL
+);
+
+CREATE INDEX scores_peer_address_idx ON scores (peer_address);
+
+-- Attestation indexing state, a single row.
+CREATE TABLE sync_state (
+	id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+	state JSONB NOT NULL
+);
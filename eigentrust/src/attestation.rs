@@ -19,12 +19,19 @@ use eigentrust_zk::{
 	integer::native::Integer,
 	params::rns::secp256k1::Secp256k1_4_68,
 };
-use ethers::types::{Address, Bytes, Uint8, H160, H256};
+use ethers::{
+	types::{Address, Bytes, Uint8, H160, H256},
+	utils::hex,
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 /// Domain prefix.
 pub const DOMAIN_PREFIX: [u8; DOMAIN_PREFIX_LEN] = *b"eigen_trust_";
 /// Domain prefix length.
 pub const DOMAIN_PREFIX_LEN: usize = 12;
+/// EIP-1271 `isValidSignature(bytes32,bytes)` selector, returned by wallets on valid signatures.
+pub const EIP1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
 /// Attestation represented with field.
 pub type AttestationScalar = Attestation<Scalar>;
 /// Signed Attestation represented with field elements.
@@ -237,6 +244,15 @@ impl SignedAttestationEth {
 		Ok(public_key)
 	}
 
+	/// Returns the big-endian attestation hash, the message checked by EIP-1271 wallets.
+	pub fn eip1271_hash(&self) -> Result<H256, EigenError> {
+		let attestation = self.attestation.to_attestation_fr()?;
+		let mut att_hash = attestation.hash::<HASHER_WIDTH, PoseidonNativeHasher>().to_bytes();
+		att_hash.reverse();
+
+		Ok(H256::from(att_hash))
+	}
+
 	/// Convert to payload bytes
 	pub fn to_payload(&self) -> Bytes {
 		let sig_raw: SignatureRaw = self.signature.clone().into();
@@ -311,6 +327,16 @@ impl AttestationRaw {
 		Self { about, domain, value, message }
 	}
 
+	/// Returns the address of the peer being rated.
+	pub fn about(&self) -> [u8; 20] {
+		self.about
+	}
+
+	/// Returns the given rating.
+	pub fn value(&self) -> u8 {
+		self.value
+	}
+
 	/// Converts a vector of bytes into the struct.
 	pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, EigenError> {
 		if bytes.len() != 73 {
@@ -503,6 +529,12 @@ impl SignedAttestationRaw {
 		Self { attestation, signature }
 	}
 
+	/// Returns whether the attestation carries no signature, like the attestations
+	/// imported from EAS, which are attested by their creator.
+	pub fn is_unsigned(&self) -> bool {
+		self.signature == SignatureRaw::default()
+	}
+
 	/// Converts a vector of bytes into the struct.
 	pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, EigenError> {
 		let attestation = AttestationRaw::from_bytes(bytes[..73].to_vec())?;
@@ -532,6 +564,250 @@ impl From<SignedAttestationEth> for SignedAttestationRaw {
 	}
 }
 
+/// Signed attestation along with the block that included it.
+#[derive(Clone, Debug, Default)]
+pub struct IndexedAttestation {
+	/// Signed attestation
+	pub(crate) signed_attestation: SignedAttestationRaw,
+	/// Account that submitted the attestation
+	pub(crate) creator: [u8; 20],
+	/// Number of the block including the attestation
+	pub(crate) block_number: u64,
+	/// Hash of the block including the attestation
+	pub(crate) block_hash: [u8; 32],
+	/// Hash of the transaction and index of the log emitting the attestation, if known
+	pub(crate) transaction: Option<([u8; 32], u64)>,
+}
+
+impl IndexedAttestation {
+	/// Constructor for indexed attestations
+	pub fn new(
+		signed_attestation: SignedAttestationRaw, creator: [u8; 20], block_number: u64,
+		block_hash: [u8; 32],
+	) -> Self {
+		Self { signed_attestation, creator, block_number, block_hash, transaction: None }
+	}
+
+	/// Sets the hash of the transaction and the index of the log emitting the attestation.
+	pub fn with_transaction(mut self, transaction_hash: [u8; 32], log_index: u64) -> Self {
+		self.transaction = Some((transaction_hash, log_index));
+		self
+	}
+
+	/// Returns the signed attestation.
+	pub fn signed_attestation(&self) -> &SignedAttestationRaw {
+		&self.signed_attestation
+	}
+
+	/// Returns the account that submitted the attestation.
+	pub fn creator(&self) -> [u8; 20] {
+		self.creator
+	}
+
+	/// Returns the number of the block including the attestation.
+	pub fn block_number(&self) -> u64 {
+		self.block_number
+	}
+
+	/// Returns the hash of the block including the attestation.
+	pub fn block_hash(&self) -> [u8; 32] {
+		self.block_hash
+	}
+
+	/// Returns the hash of the transaction and the index of the log emitting the
+	/// attestation, if known.
+	pub fn transaction(&self) -> Option<([u8; 32], u64)> {
+		self.transaction
+	}
+}
+
+impl From<IndexedAttestation> for SignedAttestationRaw {
+	fn from(indexed: IndexedAttestation) -> Self {
+		indexed.signed_attestation
+	}
+}
+
+/// Signed attestation payload, submitted to the AttestationStation by a relayer
+/// on behalf of the attester.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayPayload {
+	/// Attester's address, recovered from the signature
+	pub attester: String,
+	/// Attested peer's address
+	pub about: String,
+	/// Attestation key
+	pub key: String,
+	/// Attestation value: signature, score and optional message
+	pub val: String,
+}
+
+impl RelayPayload {
+	/// Builds the relay payload of the signed attestation.
+	pub fn from_signed(signed_attestation: &SignedAttestationRaw) -> Result<Self, EigenError> {
+		let signed_attestation_eth = SignedAttestationEth::from(signed_attestation.clone());
+		let (attester, about, key, val) = signed_attestation_eth.to_tx_data()?;
+
+		Ok(Self {
+			attester: format!("{:?}", attester),
+			about: format!("{:?}", about),
+			key: format!("{:?}", key),
+			val: format!("0x{}", hex::encode(val)),
+		})
+	}
+
+	/// Parses the payload into a signed attestation, checking the recovered attester.
+	pub fn to_signed(&self) -> Result<SignedAttestationRaw, EigenError> {
+		let parse_err =
+			|e: String| EigenError::ParsingError(format!("Invalid relay payload: {}", e));
+		let attester = Address::from_str(&self.attester).map_err(|e| parse_err(e.to_string()))?;
+		let log = AttestationCreatedFilter {
+			creator: attester,
+			about: Address::from_str(&self.about).map_err(|e| parse_err(e.to_string()))?,
+			key: H256::from_str(&self.key).map_err(|e| parse_err(e.to_string()))?.to_fixed_bytes(),
+			val: Bytes::from_str(&self.val).map_err(|e| parse_err(e.to_string()))?,
+		};
+
+		let attestation: AttestationRaw = log.clone().try_into()?;
+		let signature: SignatureRaw = log.try_into()?;
+		let signed_attestation = SignedAttestationRaw::new(attestation, signature);
+
+		let public_key =
+			SignedAttestationEth::from(signed_attestation.clone()).recover_public_key()?;
+		if address_from_ecdsa_key(&public_key) != attester {
+			return Err(EigenError::ValidationError(
+				"Relay payload signature doesn't match the attester".to_string(),
+			));
+		}
+
+		Ok(signed_attestation)
+	}
+}
+
+/// Attestation about a peer, as part of its attestation history.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttestationHistoryEntry {
+	/// Ethereum address of the attester
+	pub(crate) attester: [u8; 20],
+	/// Attestation
+	pub(crate) attestation: AttestationRaw,
+	/// Number of the block including the attestation
+	pub(crate) block_number: u64,
+}
+
+impl AttestationHistoryEntry {
+	/// Constructor for attestation history entries
+	pub fn new(attester: [u8; 20], attestation: AttestationRaw, block_number: u64) -> Self {
+		Self { attester, attestation, block_number }
+	}
+
+	/// Returns the address of the attester.
+	pub fn attester(&self) -> [u8; 20] {
+		self.attester
+	}
+
+	/// Returns the attestation.
+	pub fn attestation(&self) -> &AttestationRaw {
+		&self.attestation
+	}
+
+	/// Returns the number of the block including the attestation.
+	pub fn block_number(&self) -> u64 {
+		self.block_number
+	}
+}
+
+/// Selection of attestations, pushed down to the log filter so only the matching
+/// events are fetched.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AttestationQuery {
+	/// Account that submitted the attestations. Attestations submitted by a relayer are
+	/// created by the relayer account, not by the attester.
+	pub creator: Option<[u8; 20]>,
+	/// Attested peer.
+	pub about: Option<[u8; 20]>,
+	/// First block, inclusive.
+	pub from_block: u64,
+	/// Last block, inclusive. Defaults to the latest block.
+	pub to_block: Option<u64>,
+}
+
+impl AttestationQuery {
+	/// Returns whether the query selects only part of the attestations history.
+	pub fn is_partial(&self) -> bool {
+		*self != Self::default()
+	}
+
+	/// Checks that the block range isn't empty.
+	pub fn validate(&self) -> Result<(), EigenError> {
+		match self.to_block {
+			Some(to_block) if to_block < self.from_block => {
+				Err(EigenError::ValidationError(format!(
+					"Invalid block range: {} is before {}",
+					to_block, self.from_block
+				)))
+			},
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Aggregation policy of the attestations given by the same attester to the same peer,
+/// applied when building the attesters' opinions. Only the latest attestation is covered
+/// by the EigenTrust circuit, since it verifies the signature of every opinion score.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AggregationPolicy {
+	/// Scores the peer with the latest attestation.
+	#[default]
+	LatestWins,
+	/// Scores the peer with the average of the attestations.
+	Average,
+	/// Scores the peer with the average of the attestations weighted by their recency:
+	/// the `n`th attestation weighs `n`.
+	RecencyWeighted,
+}
+
+impl AggregationPolicy {
+	/// Aggregates the values of the attestations, given in attestation order. Averages
+	/// are rounded to the nearest value.
+	pub fn aggregate(&self, values: &[u8]) -> u8 {
+		let (sum, total_weight) = match self {
+			AggregationPolicy::LatestWins => return values.last().copied().unwrap_or_default(),
+			AggregationPolicy::Average => (
+				values.iter().map(|value| u64::from(*value)).sum(),
+				values.len() as u64,
+			),
+			AggregationPolicy::RecencyWeighted => {
+				values.iter().zip(1u64..).fold((0, 0), |(sum, total_weight), (value, weight)| {
+					(sum + u64::from(*value) * weight, total_weight + weight)
+				})
+			},
+		};
+		if total_weight == 0 {
+			return 0;
+		}
+
+		// The rounded average of u8 values fits in a u8
+		u8::try_from((sum + total_weight / 2) / total_weight).unwrap_or(u8::MAX)
+	}
+}
+
+impl FromStr for AggregationPolicy {
+	type Err = EigenError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"latest" => Ok(AggregationPolicy::LatestWins),
+			"average" => Ok(AggregationPolicy::Average),
+			"recency-weighted" => Ok(AggregationPolicy::RecencyWeighted),
+			_ => Err(EigenError::ParsingError(format!(
+				"Invalid aggregation policy: {}",
+				s
+			))),
+		}
+	}
+}
+
 /// Builds the attestation default key for the given domain.
 pub fn build_att_key(domain: H160) -> H256 {
 	let mut key = [0; 32];
@@ -634,6 +910,37 @@ mod tests {
 		assert_eq!(attestation_payload.to_vec(), payload_bytes);
 	}
 
+	#[test]
+	fn test_relay_payload() {
+		let rng = &mut rand::thread_rng();
+		let keypair = ECDSAKeypair::generate_keypair(rng);
+
+		let attestation_eth = AttestationEth::new(
+			Address::from([1; 20]),
+			H160::from([2; 20]),
+			Uint8::from(10),
+			None,
+		);
+		let attestation_fr = attestation_eth.to_attestation_fr().unwrap();
+
+		let message = attestation_fr.hash::<HASHER_WIDTH, PoseidonNativeHasher>().to_bytes();
+		let message_fq = SecpScalar::from_bytes(&message).unwrap();
+
+		let signature_raw = SignatureRaw::from(keypair.sign(message_fq, rng));
+		let signed_attestation =
+			SignedAttestationRaw::new(attestation_eth.into(), signature_raw.clone());
+
+		let payload = RelayPayload::from_signed(&signed_attestation).unwrap();
+		let parsed = payload.to_signed().unwrap();
+
+		assert_eq!(parsed.attestation, signed_attestation.attestation);
+		assert_eq!(parsed.signature, signature_raw);
+
+		// Payloads claiming another attester are rejected
+		let forged = RelayPayload { attester: format!("{:?}", Address::from([3; 20])), ..payload };
+		assert!(forged.to_signed().is_err());
+	}
+
 	#[test]
 	fn test_address_from_signed_att() {
 		let rng = &mut rand::thread_rng();
@@ -716,4 +1023,37 @@ mod tests {
 		let expected_payload: Bytes = signed_attestation.to_payload();
 		assert_eq!(contract_att_data.val, expected_payload);
 	}
+
+	#[test]
+	fn test_attestation_query() {
+		let query = AttestationQuery::default();
+		assert!(!query.is_partial());
+		assert!(query.validate().is_ok());
+
+		let query = AttestationQuery { from_block: 10, to_block: Some(10), ..Default::default() };
+		assert!(query.is_partial());
+		assert!(query.validate().is_ok());
+
+		let query = AttestationQuery { from_block: 10, to_block: Some(9), ..Default::default() };
+		assert!(query.validate().is_err());
+	}
+
+	#[test]
+	fn test_aggregation_policy() {
+		let values = [10, 20, 30];
+		assert_eq!(AggregationPolicy::LatestWins.aggregate(&values), 30);
+		assert_eq!(AggregationPolicy::Average.aggregate(&values), 20);
+		assert_eq!(AggregationPolicy::RecencyWeighted.aggregate(&values), 23);
+
+		// Averages are rounded to the nearest value
+		assert_eq!(AggregationPolicy::Average.aggregate(&[1, 2]), 2);
+		assert_eq!(AggregationPolicy::Average.aggregate(&[255, 255]), 255);
+		assert_eq!(AggregationPolicy::RecencyWeighted.aggregate(&[]), 0);
+
+		assert_eq!(
+			"recency-weighted".parse::<AggregationPolicy>().unwrap(),
+			AggregationPolicy::RecencyWeighted
+		);
+		assert!("median".parse::<AggregationPolicy>().is_err());
+	}
 }
@@ -0,0 +1,249 @@
+//! # Badge Module.
+//!
+//! This module renders per-address reputation badges from the global scores, either as
+//! a shields.io endpoint JSON or as a standalone SVG, so they can be embedded in READMEs
+//! and profiles.
+
+use crate::{error::EigenError, storage::ScoreRecord};
+use eigentrust_zk::circuits::INITIAL_SCORE;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Default badge label.
+pub const BADGE_LABEL: &str = "eigentrust";
+/// Approximate width of a badge character, in pixels.
+const CHAR_WIDTH: usize = 7;
+/// Horizontal padding of each badge half, in pixels.
+const PADDING: usize = 10;
+
+/// Score tier, relative to the initial score of every participant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ScoreTier {
+	/// Not part of the scored set, or without any trust.
+	Unrated,
+	/// Below half the initial score.
+	Low,
+	/// Below the initial score.
+	Medium,
+	/// Below twice the initial score.
+	High,
+	/// Twice the initial score or above.
+	Top,
+}
+
+impl ScoreTier {
+	/// Returns the tier of the given score.
+	pub fn from_score(score: u128) -> Self {
+		match score {
+			0 => Self::Unrated,
+			s if s < INITIAL_SCORE / 2 => Self::Low,
+			s if s < INITIAL_SCORE => Self::Medium,
+			s if s < INITIAL_SCORE * 2 => Self::High,
+			_ => Self::Top,
+		}
+	}
+
+	/// Returns the tier name.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Unrated => "unrated",
+			Self::Low => "low",
+			Self::Medium => "medium",
+			Self::High => "high",
+			Self::Top => "top",
+		}
+	}
+
+	/// Returns the badge color of the tier, as a hex string.
+	pub fn color(&self) -> &'static str {
+		match self {
+			Self::Unrated => "#9f9f9f",
+			Self::Low => "#e05d44",
+			Self::Medium => "#dfb317",
+			Self::High => "#97ca00",
+			Self::Top => "#4c1",
+		}
+	}
+}
+
+/// Badge output format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BadgeFormat {
+	/// shields.io endpoint JSON.
+	#[default]
+	Json,
+	/// Standalone SVG image.
+	Svg,
+}
+
+impl FromStr for BadgeFormat {
+	type Err = EigenError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"json" => Ok(Self::Json),
+			"svg" => Ok(Self::Svg),
+			_ => Err(EigenError::ParsingError(format!(
+				"Invalid badge format: {}",
+				s
+			))),
+		}
+	}
+}
+
+/// shields.io endpoint badge schema.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShieldsEndpoint {
+	/// Schema version, always 1.
+	pub schema_version: u8,
+	/// Left-hand side text.
+	pub label: String,
+	/// Right-hand side text.
+	pub message: String,
+	/// Right-hand side color.
+	pub color: String,
+}
+
+/// Reputation badge of a participant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Badge {
+	address: String,
+	score: u128,
+	tier: ScoreTier,
+}
+
+impl Badge {
+	/// Creates the badge of the given address and score.
+	pub fn new(address: String, score: u128) -> Self {
+		Self { address, score, tier: ScoreTier::from_score(score) }
+	}
+
+	/// Creates the badge of an address from the score records.
+	/// Addresses without a record get an unrated badge.
+	pub fn from_records(records: &[ScoreRecord], address: &str) -> Result<Self, EigenError> {
+		let record =
+			records.iter().find(|record| record.peer_address().eq_ignore_ascii_case(address));
+
+		let score = match record {
+			Some(record) => record
+				.score()
+				.parse()
+				.map_err(|_| EigenError::ParsingError("Failed to parse score.".to_string()))?,
+			None => 0,
+		};
+
+		Ok(Self::new(address.to_lowercase(), score))
+	}
+
+	/// Returns the participant's address.
+	pub fn address(&self) -> &String {
+		&self.address
+	}
+
+	/// Returns the score.
+	pub fn score(&self) -> u128 {
+		self.score
+	}
+
+	/// Returns the score tier.
+	pub fn tier(&self) -> ScoreTier {
+		self.tier
+	}
+
+	/// Returns the badge message.
+	pub fn message(&self) -> String {
+		match self.tier {
+			ScoreTier::Unrated => self.tier.as_str().to_string(),
+			tier => format!("{} ({})", self.score, tier.as_str()),
+		}
+	}
+
+	/// Returns the shields.io endpoint representation of the badge.
+	pub fn to_shields(&self) -> ShieldsEndpoint {
+		ShieldsEndpoint {
+			schema_version: 1,
+			label: BADGE_LABEL.to_string(),
+			message: self.message(),
+			color: self.tier.color().trim_start_matches('#').to_string(),
+		}
+	}
+
+	/// Renders the badge in the given format.
+	pub fn render(&self, format: BadgeFormat) -> Result<String, EigenError> {
+		match format {
+			BadgeFormat::Json => Ok(serde_json::to_string(&self.to_shields())?),
+			BadgeFormat::Svg => Ok(self.to_svg()),
+		}
+	}
+
+	/// Renders the badge as a flat SVG image.
+	pub fn to_svg(&self) -> String {
+		let message = self.message();
+		let label_width = BADGE_LABEL.len() * CHAR_WIDTH + PADDING * 2;
+		let message_width = message.len() * CHAR_WIDTH + PADDING * 2;
+		let width = label_width + message_width;
+
+		format!(
+			concat!(
+				r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" "##,
+				r##"role="img" aria-label="{label}: {message}">"##,
+				r##"<title>{label}: {message}</title>"##,
+				r##"<rect width="{label_width}" height="20" fill="#555"/>"##,
+				r##"<rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/>"##,
+				r##"<g fill="#fff" text-anchor="middle" "##,
+				r##"font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">"##,
+				r##"<text x="{label_x}" y="14">{label}</text>"##,
+				r##"<text x="{message_x}" y="14">{message}</text>"##,
+				r##"</g></svg>"##,
+			),
+			width = width,
+			label = BADGE_LABEL,
+			message = message,
+			label_width = label_width,
+			message_width = message_width,
+			color = self.tier.color(),
+			label_x = label_width / 2,
+			message_x = label_width + message_width / 2,
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::badge::*;
+
+	#[test]
+	fn test_badge_from_records() {
+		let address = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+		let records = vec![ScoreRecord::new(
+			address.to_string(),
+			"0x00".to_string(),
+			"1500".to_string(),
+			"1".to_string(),
+			"1500".to_string(),
+		)];
+
+		let badge = Badge::from_records(&records, &address.to_uppercase()).unwrap();
+		assert_eq!(badge.tier(), ScoreTier::High);
+		assert_eq!(
+			badge.to_shields(),
+			ShieldsEndpoint {
+				schema_version: 1,
+				label: BADGE_LABEL.to_string(),
+				message: "1500 (high)".to_string(),
+				color: "97ca00".to_string(),
+			}
+		);
+
+		let json = badge.render(BadgeFormat::Json).unwrap();
+		assert!(json.contains("\"schemaVersion\":1"));
+
+		let svg = badge.render(BadgeFormat::Svg).unwrap();
+		assert!(svg.starts_with("<svg") && svg.contains("1500 (high)"));
+
+		// Unknown addresses are unrated
+		let unknown = Badge::from_records(&records, "0x0000000000000000000000000000000000000001");
+		assert_eq!(unknown.unwrap().tier(), ScoreTier::Unrated);
+	}
+}
@@ -0,0 +1,458 @@
+//! # BLS Module.
+//!
+//! This module provides BLS signatures over the BN254 curve. Independent scorer
+//! operators sign the same scoring manifest hash, and their signatures are aggregated
+//! into a single multi-operator certificate that can be checked with one pairing equation.
+//!
+//! Aggregating signatures over the same message is only safe against rogue-key attacks
+//! when every operator key has been registered along with a valid proof of possession,
+//! so quorums are checked against an `OperatorRegistry` only admitting such keys.
+
+use crate::error::EigenError;
+use eigentrust_zk::halo2::{
+	arithmetic::Field,
+	halo2curves::{
+		bn256::{Bn256, Fq, Fr, G1Affine, G2Affine, G1, G2},
+		ff::FromUniformBytes,
+		group::{prime::PrimeCurveAffine, Curve, Group, GroupEncoding},
+		pairing::Engine,
+		CurveAffine,
+	},
+};
+use ethers::utils::{hex, keccak256};
+use rand::RngCore;
+
+/// Domain separation tag for signed messages.
+const SIGNATURE_DST: &[u8] = b"EIGENTRUST_BLS_BN254_SIG";
+/// Domain separation tag for proofs of possession.
+const POP_DST: &[u8] = b"EIGENTRUST_BLS_BN254_POP";
+/// Compressed G1 point length in bytes.
+pub const SIGNATURE_LEN: usize = 32;
+/// Compressed G2 point length in bytes.
+pub const PUBLIC_KEY_LEN: usize = 64;
+
+/// BLS public key, a point on G2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlsPublicKey(G2Affine);
+
+impl BlsPublicKey {
+	/// Verifies a signature over the given message.
+	pub fn verify(&self, message: &[u8], signature: &BlsSignature) -> bool {
+		pairing_check(hash_to_g1(SIGNATURE_DST, message), self.0, signature.0)
+	}
+
+	/// Verifies the proof of possession of the secret key.
+	pub fn verify_possession(&self, proof: &BlsSignature) -> bool {
+		pairing_check(hash_to_g1(POP_DST, &self.to_bytes()), self.0, proof.0)
+	}
+
+	/// Returns the compressed public key bytes.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		self.0.to_bytes().as_ref().to_vec()
+	}
+
+	/// Parses a compressed public key.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, EigenError> {
+		let mut repr = <G2Affine as GroupEncoding>::Repr::default();
+		if bytes.len() != repr.as_ref().len() {
+			return Err(EigenError::ParsingError(
+				"Invalid BLS public key length".to_string(),
+			));
+		}
+		repr.as_mut().copy_from_slice(bytes);
+
+		let point = G2Affine::from_bytes(&repr);
+		match point.is_some().into() {
+			true => Ok(Self(point.unwrap())),
+			false => Err(EigenError::ParsingError(
+				"Invalid BLS public key".to_string(),
+			)),
+		}
+	}
+}
+
+/// BLS signature, a point on G1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlsSignature(G1Affine);
+
+impl BlsSignature {
+	/// Aggregates the given signatures.
+	pub fn aggregate(signatures: &[BlsSignature]) -> Self {
+		let sum = signatures.iter().fold(G1::identity(), |acc, signature| acc + signature.0);
+		Self(sum.to_affine())
+	}
+
+	/// Returns the compressed signature bytes.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		self.0.to_bytes().as_ref().to_vec()
+	}
+
+	/// Parses a compressed signature.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, EigenError> {
+		let mut repr = <G1Affine as GroupEncoding>::Repr::default();
+		if bytes.len() != repr.as_ref().len() {
+			return Err(EigenError::ParsingError(
+				"Invalid BLS signature length".to_string(),
+			));
+		}
+		repr.as_mut().copy_from_slice(bytes);
+
+		let point = G1Affine::from_bytes(&repr);
+		match point.is_some().into() {
+			true => Ok(Self(point.unwrap())),
+			false => Err(EigenError::ParsingError(
+				"Invalid BLS signature".to_string(),
+			)),
+		}
+	}
+}
+
+/// BLS keypair.
+#[derive(Clone, Debug)]
+pub struct BlsKeypair {
+	secret_key: Fr,
+	public_key: BlsPublicKey,
+}
+
+impl BlsKeypair {
+	/// Generates a random keypair.
+	pub fn generate<R: RngCore>(rng: &mut R) -> Self {
+		Self::from_secret_key(Fr::random(rng))
+	}
+
+	/// Constructs the keypair from the given secret key.
+	pub fn from_secret_key(secret_key: Fr) -> Self {
+		let public_key = BlsPublicKey((G2Affine::generator() * secret_key).to_affine());
+		Self { secret_key, public_key }
+	}
+
+	/// Returns the public key.
+	pub fn public_key(&self) -> BlsPublicKey {
+		self.public_key
+	}
+
+	/// Signs the given message.
+	pub fn sign(&self, message: &[u8]) -> BlsSignature {
+		BlsSignature((hash_to_g1(SIGNATURE_DST, message) * self.secret_key).to_affine())
+	}
+
+	/// Produces a proof of possession of the secret key, to be checked when
+	/// registering the operator.
+	pub fn prove_possession(&self) -> BlsSignature {
+		let message = self.public_key.to_bytes();
+		BlsSignature((hash_to_g1(POP_DST, &message) * self.secret_key).to_affine())
+	}
+}
+
+/// Registered operators, each admitted along with a valid proof of possession of its
+/// secret key.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OperatorRegistry {
+	operators: Vec<BlsPublicKey>,
+}
+
+impl OperatorRegistry {
+	/// Creates an empty registry.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Builds the registry from the operators' public keys and proofs of possession.
+	pub fn from_proofs(operators: &[(BlsPublicKey, BlsSignature)]) -> Result<Self, EigenError> {
+		let mut registry = Self::new();
+		for (public_key, proof) in operators {
+			registry.register(*public_key, proof)?;
+		}
+
+		Ok(registry)
+	}
+
+	/// Registers the operator, rejecting keys without a valid proof of possession.
+	pub fn register(
+		&mut self, public_key: BlsPublicKey, proof: &BlsSignature,
+	) -> Result<(), EigenError> {
+		if !public_key.verify_possession(proof) {
+			return Err(EigenError::VerificationError(format!(
+				"Invalid proof of possession from operator 0x{}",
+				hex::encode(public_key.to_bytes())
+			)));
+		}
+
+		if !self.operators.contains(&public_key) {
+			self.operators.push(public_key);
+		}
+
+		Ok(())
+	}
+
+	/// Returns whether the operator is registered.
+	pub fn contains(&self, public_key: &BlsPublicKey) -> bool {
+		self.operators.contains(public_key)
+	}
+}
+
+/// Certificate of multiple operators signing the same scoring manifest hash.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiOperatorCertificate {
+	/// Signed scoring manifest hash.
+	pub manifest_hash: [u8; 32],
+	/// Public keys of the signing operators.
+	pub operators: Vec<BlsPublicKey>,
+	/// Aggregated signature.
+	pub signature: BlsSignature,
+}
+
+impl MultiOperatorCertificate {
+	/// Builds a certificate from the operators' individual signatures.
+	/// Every signature is verified before being aggregated.
+	pub fn aggregate(
+		manifest_hash: [u8; 32], signatures: &[(BlsPublicKey, BlsSignature)],
+	) -> Result<Self, EigenError> {
+		for (operator, signature) in signatures {
+			if !operator.verify(&manifest_hash, signature) {
+				return Err(EigenError::VerificationError(format!(
+					"Invalid signature from operator 0x{}",
+					hex::encode(operator.to_bytes())
+				)));
+			}
+		}
+
+		let operators: Vec<BlsPublicKey> = signatures.iter().map(|(pk, _)| *pk).collect();
+		let sigs: Vec<BlsSignature> = signatures.iter().map(|(_, sig)| *sig).collect();
+		let certificate =
+			Self { manifest_hash, operators, signature: BlsSignature::aggregate(&sigs) };
+		certificate.verify()?;
+
+		Ok(certificate)
+	}
+
+	/// Verifies the aggregated signature against the listed operators.
+	pub fn verify(&self) -> Result<(), EigenError> {
+		if self.operators.is_empty() {
+			return Err(EigenError::VerificationError(
+				"Certificate has no operators".to_string(),
+			));
+		}
+
+		for (i, operator) in self.operators.iter().enumerate() {
+			if self.operators[..i].contains(operator) {
+				return Err(EigenError::VerificationError(
+					"Certificate has duplicate operators".to_string(),
+				));
+			}
+		}
+
+		let aggregated_key =
+			self.operators.iter().fold(G2::identity(), |acc, operator| acc + operator.0);
+		let message_point = hash_to_g1(SIGNATURE_DST, &self.manifest_hash);
+
+		match pairing_check(message_point, aggregated_key.to_affine(), self.signature.0) {
+			true => Ok(()),
+			false => Err(EigenError::VerificationError(
+				"Invalid aggregated signature".to_string(),
+			)),
+		}
+	}
+
+	/// Verifies the certificate and checks that at least `threshold` of its operators
+	/// belong to the given registry.
+	pub fn verify_quorum(
+		&self, registry: &OperatorRegistry, threshold: usize,
+	) -> Result<(), EigenError> {
+		if let Some(unknown) = self.operators.iter().find(|pk| !registry.contains(pk)) {
+			return Err(EigenError::VerificationError(format!(
+				"Unregistered operator 0x{}",
+				hex::encode(unknown.to_bytes())
+			)));
+		}
+
+		if self.operators.len() < threshold {
+			return Err(EigenError::VerificationError(format!(
+				"Not enough operators: {} < {}",
+				self.operators.len(),
+				threshold
+			)));
+		}
+
+		self.verify()
+	}
+
+	/// Converts the certificate into bytes:
+	/// manifest hash | operators count (u32 BE) | operators' public keys | signature.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		bytes.extend(self.manifest_hash);
+		bytes.extend((self.operators.len() as u32).to_be_bytes());
+		for operator in &self.operators {
+			bytes.extend(operator.to_bytes());
+		}
+		bytes.extend(self.signature.to_bytes());
+
+		bytes
+	}
+
+	/// Parses a certificate from bytes.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, EigenError> {
+		if bytes.len() < 36 {
+			return Err(EigenError::ParsingError(
+				"Invalid certificate length".to_string(),
+			));
+		}
+
+		let mut manifest_hash = [0; 32];
+		manifest_hash.copy_from_slice(&bytes[..32]);
+
+		let mut count_bytes = [0; 4];
+		count_bytes.copy_from_slice(&bytes[32..36]);
+		let count = u32::from_be_bytes(count_bytes) as usize;
+
+		if bytes.len() != 36 + count * PUBLIC_KEY_LEN + SIGNATURE_LEN {
+			return Err(EigenError::ParsingError(
+				"Invalid certificate length".to_string(),
+			));
+		}
+
+		let operators = bytes[36..36 + count * PUBLIC_KEY_LEN]
+			.chunks(PUBLIC_KEY_LEN)
+			.map(BlsPublicKey::from_bytes)
+			.collect::<Result<Vec<_>, _>>()?;
+		let signature = BlsSignature::from_bytes(&bytes[36 + count * PUBLIC_KEY_LEN..])?;
+
+		Ok(Self { manifest_hash, operators, signature })
+	}
+}
+
+/// Checks e(signature, g2) == e(message_point, public_key).
+fn pairing_check(message_point: G1Affine, public_key: G2Affine, signature: G1Affine) -> bool {
+	Bn256::pairing(&signature, &G2Affine::generator())
+		== Bn256::pairing(&message_point, &public_key)
+}
+
+/// Hashes the message to a G1 point using try-and-increment.
+fn hash_to_g1(dst: &[u8], message: &[u8]) -> G1Affine {
+	// BN254 G1 curve equation: y^2 = x^3 + 3
+	let b = Fq::from(3);
+
+	let mut counter: u32 = 0;
+	loop {
+		let mut preimage = dst.to_vec();
+		preimage.extend(message);
+		preimage.extend(counter.to_be_bytes());
+
+		let mut low_preimage = preimage.clone();
+		low_preimage.push(0);
+		let mut high_preimage = preimage;
+		high_preimage.push(1);
+
+		let mut uniform_bytes = [0u8; 64];
+		uniform_bytes[..32].copy_from_slice(&keccak256(low_preimage));
+		uniform_bytes[32..].copy_from_slice(&keccak256(high_preimage));
+
+		let x = Fq::from_uniform_bytes(&uniform_bytes);
+		let y = (x.square() * x + b).sqrt();
+
+		if bool::from(y.is_some()) {
+			let point = G1Affine::from_xy(x, y.unwrap());
+			if bool::from(point.is_some()) {
+				return point.unwrap();
+			}
+		}
+
+		counter += 1;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::bls::*;
+
+	#[test]
+	fn test_sign_and_verify() {
+		let rng = &mut rand::thread_rng();
+		let keypair = BlsKeypair::generate(rng);
+
+		let signature = keypair.sign(b"manifest");
+
+		assert!(keypair.public_key().verify(b"manifest", &signature));
+		assert!(!keypair.public_key().verify(b"tampered", &signature));
+	}
+
+	#[test]
+	fn test_proof_of_possession() {
+		let rng = &mut rand::thread_rng();
+		let keypair = BlsKeypair::generate(rng);
+		let other = BlsKeypair::generate(rng);
+
+		assert!(keypair.public_key().verify_possession(&keypair.prove_possession()));
+		assert!(!keypair.public_key().verify_possession(&other.prove_possession()));
+	}
+
+	#[test]
+	fn test_multi_operator_certificate() {
+		let rng = &mut rand::thread_rng();
+		let operators: Vec<BlsKeypair> = (0..3).map(|_| BlsKeypair::generate(rng)).collect();
+		let manifest_hash = [7; 32];
+
+		let signatures: Vec<(BlsPublicKey, BlsSignature)> = operators
+			.iter()
+			.map(|keypair| (keypair.public_key(), keypair.sign(&manifest_hash)))
+			.collect();
+		let proofs: Vec<(BlsPublicKey, BlsSignature)> =
+			operators.iter().map(|kp| (kp.public_key(), kp.prove_possession())).collect();
+		let registry = OperatorRegistry::from_proofs(&proofs).unwrap();
+		let partial_registry = OperatorRegistry::from_proofs(&proofs[..2]).unwrap();
+
+		let certificate = MultiOperatorCertificate::aggregate(manifest_hash, &signatures).unwrap();
+		assert!(certificate.verify_quorum(&registry, 3).is_ok());
+		assert!(certificate.verify_quorum(&registry, 4).is_err());
+		assert!(certificate.verify_quorum(&partial_registry, 2).is_err());
+
+		// Bytes round trip
+		let parsed = MultiOperatorCertificate::from_bytes(&certificate.to_bytes()).unwrap();
+		assert_eq!(parsed, certificate);
+
+		// Dropping an operator invalidates the aggregated signature
+		let mut tampered = certificate;
+		tampered.operators.pop();
+		assert!(tampered.verify().is_err());
+	}
+
+	#[test]
+	fn test_rogue_key_quorum() {
+		let rng = &mut rand::thread_rng();
+		let honest = BlsKeypair::generate(rng);
+		let attacker = BlsKeypair::generate(rng);
+		let manifest_hash = [7; 32];
+
+		// The rogue key cancels the honest key out of the aggregated key
+		let rogue_key = BlsPublicKey(
+			(G2::from(attacker.public_key().0) - G2::from(honest.public_key().0)).to_affine(),
+		);
+		let forged = MultiOperatorCertificate {
+			manifest_hash,
+			operators: vec![honest.public_key(), rogue_key],
+			signature: attacker.sign(&manifest_hash),
+		};
+		assert!(forged.verify().is_ok());
+
+		// Without the secret key of the rogue key, its possession can't be proven
+		let mut registry = OperatorRegistry::new();
+		registry.register(honest.public_key(), &honest.prove_possession()).unwrap();
+		assert!(registry.register(rogue_key, &attacker.prove_possession()).is_err());
+		assert!(
+			OperatorRegistry::from_proofs(&[(rogue_key, attacker.prove_possession())]).is_err()
+		);
+		assert!(forged.verify_quorum(&registry, 2).is_err());
+	}
+
+	#[test]
+	fn test_aggregate_rejects_invalid_signature() {
+		let rng = &mut rand::thread_rng();
+		let keypair = BlsKeypair::generate(rng);
+		let other = BlsKeypair::generate(rng);
+
+		let signatures = vec![(keypair.public_key(), other.sign(&[1; 32]))];
+
+		assert!(MultiOperatorCertificate::aggregate([1; 32], &signatures).is_err());
+	}
+}
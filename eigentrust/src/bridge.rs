@@ -0,0 +1,479 @@
+//! # Bridge Module.
+//!
+//! This module mirrors the decoded attestation events and the score updates to a message
+//! queue. Events are delivered at least once: the indexed block offset is only stored once
+//! all the attestations of a block have been acknowledged by the queue, so a restarted
+//! bridge may publish the last block again, but never skips one.
+//!
+//! The NATS JetStream and Kafka publishers are available behind the `nats` and `kafka`
+//! features respectively.
+
+use crate::{
+	attestation::{IndexedAttestation, SignedAttestationEth},
+	circuit::Score,
+	error::EigenError,
+	eth::address_from_ecdsa_key,
+	storage::Storage,
+	Client,
+};
+use async_trait::async_trait;
+use ethers::{
+	types::{Address, U256},
+	utils::hex,
+};
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use tracing::{debug, info, warn};
+
+/// Default subject prefix.
+pub const DEFAULT_SUBJECT_PREFIX: &str = "eigentrust";
+
+/// Event mirrored to the message queue.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BridgeEvent {
+	/// Attestation created on-chain.
+	Attestation {
+		/// Attester's address.
+		attester: String,
+		/// Attested peer's address.
+		about: String,
+		/// Attestation domain.
+		domain: String,
+		/// Given score.
+		value: u8,
+		/// Attestation message.
+		message: String,
+		/// Number of the block including the attestation.
+		block_number: u64,
+		/// Hash of the block including the attestation.
+		block_hash: String,
+	},
+	/// Global score of a participant.
+	ScoreUpdate {
+		/// Participant's address.
+		address: String,
+		/// Score numerator.
+		numerator: String,
+		/// Score denominator.
+		denominator: String,
+		/// Score.
+		score: String,
+	},
+}
+
+impl BridgeEvent {
+	/// Builds the event of an indexed attestation, attested by the given contract wallet, if
+	/// any, as returned by `Client::contract_attesters`, or else by its recovered signer.
+	pub fn from_attestation(
+		indexed: &IndexedAttestation, contract_attester: Option<[u8; 20]>,
+	) -> Result<Self, EigenError> {
+		let attester = match contract_attester {
+			Some(contract) => Address::from(contract),
+			None => {
+				let signed_att = SignedAttestationEth::from(indexed.signed_attestation().clone());
+				address_from_ecdsa_key(&signed_att.recover_public_key()?)
+			},
+		};
+		let attestation = &indexed.signed_attestation().attestation;
+
+		Ok(Self::Attestation {
+			attester: format!("{:?}", attester),
+			about: format!("0x{}", hex::encode(attestation.about)),
+			domain: format!("0x{}", hex::encode(attestation.domain)),
+			value: attestation.value,
+			message: format!("0x{}", hex::encode(attestation.message)),
+			block_number: indexed.block_number(),
+			block_hash: format!("0x{}", hex::encode(indexed.block_hash())),
+		})
+	}
+
+	/// Builds the event of a participant's score.
+	pub fn from_score(score: &Score) -> Self {
+		Self::ScoreUpdate {
+			address: format!("0x{}", hex::encode(score.address)),
+			numerator: U256::from_big_endian(&score.score_rat.0).to_string(),
+			denominator: U256::from_big_endian(&score.score_rat.1).to_string(),
+			score: U256::from_big_endian(&score.score_hex).to_string(),
+		}
+	}
+
+	/// Returns the subject of the event, under the given prefix.
+	pub fn subject(&self, prefix: &str) -> String {
+		match self {
+			Self::Attestation { .. } => format!("{}.attestations", prefix),
+			Self::ScoreUpdate { .. } => format!("{}.scores", prefix),
+		}
+	}
+
+	/// Returns the event as JSON bytes.
+	pub fn to_bytes(&self) -> Result<Vec<u8>, EigenError> {
+		Ok(serde_json::to_vec(self)?)
+	}
+}
+
+/// Publishes messages to a queue.
+#[async_trait]
+pub trait EventPublisher {
+	/// Publishes the payload under the given subject, returning once the queue
+	/// acknowledged it.
+	async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), EigenError>;
+}
+
+/// Offset of the bridged attestation events.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeOffset {
+	/// Next block to bridge.
+	pub next_block: u64,
+}
+
+/// Bridge mirroring the attestation events and score updates to a message queue.
+pub struct EventBridge<P: EventPublisher, S: Storage<BridgeOffset, Err = EigenError>> {
+	publisher: P,
+	offset_storage: S,
+	subject_prefix: String,
+}
+
+impl<P: EventPublisher, S: Storage<BridgeOffset, Err = EigenError>> EventBridge<P, S> {
+	/// Creates a new bridge, tracking its offset in the given storage.
+	pub fn new(publisher: P, offset_storage: S, subject_prefix: String) -> Self {
+		Self { publisher, offset_storage, subject_prefix }
+	}
+
+	/// Returns the stored offset, starting from the first block if there's none.
+	pub fn offset(&self) -> Result<BridgeOffset, EigenError> {
+		match self.offset_storage.load() {
+			Err(EigenError::IOError(e)) if e.kind() == ErrorKind::NotFound => {
+				Ok(BridgeOffset::default())
+			},
+			result => result,
+		}
+	}
+
+	/// Publishes the attestations created since the stored offset.
+	/// Returns the number of published events.
+	pub async fn publish_attestations(&mut self, client: &Client) -> Result<usize, EigenError> {
+		let offset = self.offset()?;
+		let attestations = client.get_indexed_attestations(offset.next_block).await?;
+
+		// Attestations submitted by contract wallets are verified through EIP-1271
+		let signed: Vec<_> =
+			attestations.iter().map(|indexed| indexed.signed_attestation().clone()).collect();
+		let creators: Vec<_> = attestations.iter().map(|indexed| Some(indexed.creator())).collect();
+		let contract_attesters = client.contract_attesters(&signed, &creators).await?;
+
+		self.publish_indexed(attestations.into_iter().zip(contract_attesters).collect()).await
+	}
+
+	/// Publishes the attestations along with their contract wallet attester, if any.
+	/// Attestations whose attester can't be recovered are skipped.
+	/// Returns the number of published events.
+	async fn publish_indexed(
+		&mut self, mut attestations: Vec<(IndexedAttestation, Option<[u8; 20]>)>,
+	) -> Result<usize, EigenError> {
+		attestations.sort_by_key(|(indexed, _)| indexed.block_number());
+
+		let mut published = 0;
+		for (i, (indexed, contract_attester)) in attestations.iter().enumerate() {
+			match BridgeEvent::from_attestation(indexed, *contract_attester) {
+				Ok(event) => {
+					self.publish(&event).await?;
+					published += 1;
+				},
+				// Unattributable attestations can't be mirrored, but shouldn't stall the bridge
+				Err(e) => warn!(
+					block_number = indexed.block_number(),
+					error = ?e,
+					"Skipping undecodable attestation"
+				),
+			}
+
+			// Move the offset once the whole block has been acknowledged
+			let block_number = indexed.block_number();
+			let is_block_end = attestations
+				.get(i + 1)
+				.map_or(true, |(next, _)| next.block_number() != block_number);
+			if is_block_end {
+				self.offset_storage.save(BridgeOffset { next_block: block_number + 1 })?;
+				debug!("Bridged attestations up to block {}", block_number);
+			}
+		}
+
+		info!("Published {} attestation events.", published);
+
+		Ok(published)
+	}
+
+	/// Publishes the given scores.
+	pub async fn publish_scores(&self, scores: &[Score]) -> Result<(), EigenError> {
+		for score in scores {
+			self.publish(&BridgeEvent::from_score(score)).await?;
+		}
+
+		info!("Published {} score events.", scores.len());
+
+		Ok(())
+	}
+
+	/// Publishes a single event.
+	async fn publish(&self, event: &BridgeEvent) -> Result<(), EigenError> {
+		let subject = event.subject(&self.subject_prefix);
+		self.publisher.publish(&subject, event.to_bytes()?).await
+	}
+}
+
+/// NATS JetStream publisher.
+#[cfg(feature = "nats")]
+pub struct NatsPublisher {
+	jetstream: async_nats::jetstream::Context,
+}
+
+#[cfg(feature = "nats")]
+impl NatsPublisher {
+	/// Connects to the given NATS server.
+	pub async fn connect(url: &str) -> Result<Self, EigenError> {
+		let client = async_nats::connect(url)
+			.await
+			.map_err(|e| EigenError::ConnectionError(e.to_string()))?;
+
+		Ok(Self { jetstream: async_nats::jetstream::new(client) })
+	}
+}
+
+#[cfg(feature = "nats")]
+#[async_trait]
+impl EventPublisher for NatsPublisher {
+	async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), EigenError> {
+		// Wait for the stream acknowledgement
+		self.jetstream
+			.publish(subject.to_string(), payload.into())
+			.await
+			.map_err(|e| EigenError::NetworkError(e.to_string()))?
+			.await
+			.map_err(|e| EigenError::NetworkError(e.to_string()))?;
+
+		Ok(())
+	}
+}
+
+/// Kafka publisher. Subjects are used as topics.
+#[cfg(feature = "kafka")]
+pub struct KafkaPublisher {
+	producer: rdkafka::producer::FutureProducer,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaPublisher {
+	/// Creates a producer for the given bootstrap servers.
+	pub fn new(bootstrap_servers: &str) -> Result<Self, EigenError> {
+		let producer = rdkafka::ClientConfig::new()
+			.set("bootstrap.servers", bootstrap_servers)
+			.set("acks", "all")
+			.set("enable.idempotence", "true")
+			.create()
+			.map_err(|e| EigenError::ConnectionError(e.to_string()))?;
+
+		Ok(Self { producer })
+	}
+}
+
+#[cfg(feature = "kafka")]
+#[async_trait]
+impl EventPublisher for KafkaPublisher {
+	async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), EigenError> {
+		let record = rdkafka::producer::FutureRecord::<(), _>::to(subject).payload(&payload);
+
+		self.producer
+			.send(record, std::time::Duration::from_secs(30))
+			.await
+			.map_err(|(e, _)| EigenError::NetworkError(e.to_string()))?;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		attestation::{AttestationRaw, SignedAttestationRaw},
+		bridge::*,
+	};
+	use std::sync::Mutex;
+
+	#[derive(Default)]
+	struct RecordingPublisher {
+		messages: Mutex<Vec<(String, Vec<u8>)>>,
+		/// Number of messages acknowledged before the queue goes down, if any.
+		capacity: Option<usize>,
+	}
+
+	#[async_trait]
+	impl EventPublisher for RecordingPublisher {
+		async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), EigenError> {
+			let mut messages = self.messages.lock().unwrap();
+			if self.capacity.map_or(false, |capacity| messages.len() >= capacity) {
+				return Err(EigenError::NetworkError("Queue unavailable".to_string()));
+			}
+
+			messages.push((subject.to_string(), payload));
+			Ok(())
+		}
+	}
+
+	#[derive(Default)]
+	struct MemoryOffset(Option<BridgeOffset>);
+
+	impl Storage<BridgeOffset> for MemoryOffset {
+		type Err = EigenError;
+
+		fn load(&self) -> Result<BridgeOffset, EigenError> {
+			self.0.clone().ok_or_else(|| {
+				EigenError::IOError(std::io::Error::new(ErrorKind::NotFound, "No offset"))
+			})
+		}
+
+		fn save(&mut self, data: BridgeOffset) -> Result<(), EigenError> {
+			self.0 = Some(data);
+			Ok(())
+		}
+	}
+
+	#[tokio::test]
+	async fn test_publish_scores() {
+		let bridge = EventBridge::new(
+			RecordingPublisher::default(),
+			MemoryOffset::default(),
+			DEFAULT_SUBJECT_PREFIX.to_string(),
+		);
+
+		let mut denominator = [0; 32];
+		denominator[31] = 1;
+		let mut score_hex = [0; 32];
+		score_hex[31] = 10;
+		let score = Score {
+			address: [1; 20],
+			score_fr: [0; 32],
+			score_rat: (score_hex, denominator),
+			score_hex,
+		};
+
+		bridge.publish_scores(&[score]).await.unwrap();
+
+		let messages = bridge.publisher.messages.lock().unwrap();
+		assert_eq!(messages.len(), 1);
+		assert_eq!(messages[0].0, "eigentrust.scores");
+
+		let event: BridgeEvent = serde_json::from_slice(&messages[0].1).unwrap();
+		assert_eq!(
+			event,
+			BridgeEvent::ScoreUpdate {
+				address: format!("0x{}", hex::encode([1; 20])),
+				numerator: "10".to_string(),
+				denominator: "1".to_string(),
+				score: "10".to_string(),
+			}
+		);
+
+		// Scores don't move the attestations offset
+		assert_eq!(bridge.offset().unwrap(), BridgeOffset::default());
+	}
+
+	#[tokio::test]
+	async fn test_publish_attestations_resume() {
+		let client = Client::offline(
+			"test test test test test test test test test test test junk".to_string(),
+			31337,
+			[0; 20],
+			[0; 20],
+		);
+		let indexed = |about: u8, block_number: u64| {
+			let attestation = AttestationRaw::new([about; 20], [0; 20], 5, [0; 32]);
+			let signed = client.sign_attestation(attestation).unwrap();
+			(
+				IndexedAttestation::new(signed, [0; 20], block_number, [0; 32]),
+				None,
+			)
+		};
+
+		// The second attestation of block 2 has no recoverable signer
+		let unsigned = SignedAttestationRaw {
+			attestation: AttestationRaw::new([9; 20], [0; 20], 5, [0; 32]),
+			..SignedAttestationRaw::default()
+		};
+		let attestations = vec![
+			indexed(1, 1),
+			indexed(2, 1),
+			indexed(3, 2),
+			(IndexedAttestation::new(unsigned, [0; 20], 2, [0; 32]), None),
+			indexed(4, 2),
+			indexed(5, 3),
+		];
+		let abouts = |bridge: &EventBridge<RecordingPublisher, MemoryOffset>| {
+			let messages = bridge.publisher.messages.lock().unwrap();
+			messages
+				.iter()
+				.filter_map(
+					|(_, payload)| match serde_json::from_slice(payload).unwrap() {
+						BridgeEvent::Attestation { about, .. } => Some(about),
+						BridgeEvent::ScoreUpdate { .. } => None,
+					},
+				)
+				.collect::<Vec<_>>()
+		};
+
+		// The queue goes down in the middle of block 2
+		let mut bridge = EventBridge::new(
+			RecordingPublisher { capacity: Some(3), ..Default::default() },
+			MemoryOffset::default(),
+			DEFAULT_SUBJECT_PREFIX.to_string(),
+		);
+		assert!(bridge.publish_indexed(attestations.clone()).await.is_err());
+		assert_eq!(bridge.offset().unwrap(), BridgeOffset { next_block: 2 });
+
+		// The next run publishes block 2 again, skipping the undecodable attestation
+		let mut bridge = EventBridge::new(
+			RecordingPublisher::default(),
+			bridge.offset_storage,
+			DEFAULT_SUBJECT_PREFIX.to_string(),
+		);
+		let next_block = bridge.offset().unwrap().next_block;
+		let remaining = attestations
+			.into_iter()
+			.filter(|(indexed, _)| indexed.block_number() >= next_block)
+			.collect();
+		assert_eq!(bridge.publish_indexed(remaining).await.unwrap(), 3);
+		assert_eq!(bridge.offset().unwrap(), BridgeOffset { next_block: 4 });
+		assert_eq!(
+			abouts(&bridge),
+			[3, 4, 5].map(|about: u8| format!("0x{}", hex::encode([about; 20])))
+		);
+	}
+
+	#[test]
+	fn test_offset_errors() {
+		struct BrokenOffset;
+
+		impl Storage<BridgeOffset> for BrokenOffset {
+			type Err = EigenError;
+
+			fn load(&self) -> Result<BridgeOffset, EigenError> {
+				Err(EigenError::ReadWriteError("Corrupted offset".to_string()))
+			}
+
+			fn save(&mut self, _: BridgeOffset) -> Result<(), EigenError> {
+				Ok(())
+			}
+		}
+
+		// Only a missing offset starts from the first block
+		let bridge = EventBridge::new(
+			RecordingPublisher::default(),
+			BrokenOffset,
+			DEFAULT_SUBJECT_PREFIX.to_string(),
+		);
+		assert!(matches!(
+			bridge.offset(),
+			Err(EigenError::ReadWriteError(_))
+		));
+	}
+}
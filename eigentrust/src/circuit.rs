@@ -2,12 +2,20 @@
 //!
 //! This module provides types and utilities for the circuits.
 
-use crate::{attestation::SignedAttestationScalar, error::EigenError};
+use crate::{
+	attestation::{SignedAttestationRaw, SignedAttestationScalar},
+	error::EigenError,
+};
 use eigentrust_zk::{
-	circuits::{ECDSAPublicKey, EigenTrust4, RationalScore, Threshold4},
+	circuits::{hasher_param_set, ECDSAPublicKey, EigenTrust4, RationalScore, Threshold4},
 	halo2::halo2curves::bn256::Fr as Scalar,
+	params::hasher::ParamSet,
+};
+use ethers::{
+	types::Address,
+	utils::{hex, keccak256},
 };
-use ethers::types::Address;
+use serde::{Deserialize, Serialize};
 
 // Re export eigentrust and threshold KZG params constants.
 pub use eigentrust_zk::circuits::{ET_PARAMS_K, TH_PARAMS_K};
@@ -18,6 +26,7 @@ pub const SCALAR_LEN: usize = 32;
 pub type OpinionVector = Vec<Option<SignedAttestationScalar>>;
 
 /// Circuits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Circuit {
 	/// Eigentrust circuit
 	EigenTrust,
@@ -35,6 +44,135 @@ impl Circuit {
 	}
 }
 
+/// Metadata recorded along with the circuit artifacts.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofMetadata {
+	/// Circuit the artifact belongs to.
+	pub circuit: String,
+	/// Hasher parameter set used to generate the artifact.
+	pub hasher: ParamSet,
+}
+
+impl ProofMetadata {
+	/// Creates the metadata of an artifact generated with the current hasher parameters.
+	pub fn new(circuit: &Circuit) -> Self {
+		Self { circuit: circuit.as_str().to_string(), hasher: hasher_param_set() }
+	}
+
+	/// Checks that the artifact is compatible with the given circuit and the current
+	/// hasher parameters.
+	pub fn validate(&self, circuit: &Circuit) -> Result<(), EigenError> {
+		if self.circuit != circuit.as_str() {
+			return Err(EigenError::VerificationError(format!(
+				"Artifact belongs to the '{}' circuit, expected '{}'",
+				self.circuit,
+				circuit.as_str()
+			)));
+		}
+
+		let current = hasher_param_set();
+		if self.hasher != current {
+			return Err(EigenError::VerificationError(format!(
+				"Artifact generated with hasher parameters '{}' ({}), current are '{}' ({})",
+				self.hasher.name, self.hasher.fingerprint, current.name, current.fingerprint
+			)));
+		}
+
+		Ok(())
+	}
+}
+
+/// Generated proof, along with the inputs it was generated from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofArtifact {
+	/// Circuit the proof belongs to.
+	pub circuit: String,
+	/// Proof.
+	pub proof: String,
+	/// Public inputs.
+	pub public_inputs: String,
+	/// Hash of the KZG parameters and the proving key, see `params_hash`.
+	pub params_hash: String,
+	/// Hash of the attestations the proof was generated from, see `attestations_hash`.
+	pub attestations_hash: String,
+}
+
+impl ProofArtifact {
+	/// Creates the artifact of a proof of the given circuit.
+	pub fn new(
+		circuit: &Circuit, proof: &[u8], public_inputs: &[u8], params_hash: [u8; 32],
+		attestations_hash: [u8; 32],
+	) -> Self {
+		Self {
+			circuit: circuit.as_str().to_string(),
+			proof: format!("0x{}", hex::encode(proof)),
+			public_inputs: format!("0x{}", hex::encode(public_inputs)),
+			params_hash: format!("0x{}", hex::encode(params_hash)),
+			attestations_hash: format!("0x{}", hex::encode(attestations_hash)),
+		}
+	}
+
+	/// Returns the proof.
+	pub fn proof(&self) -> Result<Vec<u8>, EigenError> {
+		decode_hex("proof", &self.proof)
+	}
+
+	/// Returns the public inputs.
+	pub fn public_inputs(&self) -> Result<Vec<u8>, EigenError> {
+		decode_hex("public inputs", &self.public_inputs)
+	}
+
+	/// Checks that the proof belongs to the given circuit and was generated with the given
+	/// parameters and, if given, from the given attestations.
+	pub fn validate(
+		&self, circuit: &Circuit, params_hash: [u8; 32], attestations_hash: Option<[u8; 32]>,
+	) -> Result<(), EigenError> {
+		if self.circuit != circuit.as_str() {
+			return Err(EigenError::VerificationError(format!(
+				"Proof belongs to the '{}' circuit, expected '{}'",
+				self.circuit,
+				circuit.as_str()
+			)));
+		}
+		if decode_hex("parameters hash", &self.params_hash)? != params_hash {
+			return Err(EigenError::VerificationError(
+				"Proof generated with other KZG parameters or proving key".to_string(),
+			));
+		}
+		match attestations_hash {
+			Some(hash) if decode_hex("attestations hash", &self.attestations_hash)? != hash => {
+				Err(EigenError::VerificationError(
+					"Proof generated from other attestations".to_string(),
+				))
+			},
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Returns the hash identifying the KZG parameters and the proving key of a proof.
+pub fn params_hash(kzg_params: &[u8], proving_key: &[u8]) -> [u8; 32] {
+	let mut hashes = keccak256(kzg_params).to_vec();
+	hashes.extend(keccak256(proving_key));
+
+	keccak256(hashes)
+}
+
+/// Returns the hash identifying a set of attestations, regardless of their order.
+pub fn attestations_hash(attestations: &[SignedAttestationRaw]) -> [u8; 32] {
+	let mut encoded: Vec<Vec<u8>> =
+		attestations.iter().map(SignedAttestationRaw::to_bytes).collect();
+	encoded.sort();
+
+	keccak256(encoded.concat())
+}
+
+/// Decodes the given hex field.
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>, EigenError> {
+	hex::decode(value.trim_start_matches("0x"))
+		.map_err(|e| EigenError::ParsingError(format!("Invalid {}: {}", field, e)))
+}
+
 /// EigenTrust report struct.
 pub struct ETReport {
 	/// Verifier public inputs
@@ -69,6 +207,8 @@ pub struct ETSetup {
 	pub pub_inputs: ETPublicInputs,
 	/// Rational scores.
 	pub rational_scores: Vec<RationalScore>,
+	/// Rational scores of every convergence iteration, starting with the initial scores.
+	pub score_trace: Vec<Vec<RationalScore>>,
 }
 
 impl ETSetup {
@@ -76,9 +216,54 @@ impl ETSetup {
 	pub fn new(
 		address_set: Vec<Address>, attestation_matrix: Vec<Vec<Option<SignedAttestationScalar>>>,
 		circuit: EigenTrust4, ecdsa_set: Vec<Option<ECDSAPublicKey>>, pub_inputs: ETPublicInputs,
-		rational_scores: Vec<RationalScore>,
+		rational_scores: Vec<RationalScore>, score_trace: Vec<Vec<RationalScore>>,
+	) -> Self {
+		Self {
+			address_set,
+			attestation_matrix,
+			circuit,
+			ecdsa_set,
+			pub_inputs,
+			rational_scores,
+			score_trace,
+		}
+	}
+}
+
+/// Participants' scores at every convergence iteration of a replayed scoring run.
+pub struct ScoreTrace {
+	/// Ethereum addresses set.
+	pub address_set: Vec<Address>,
+	/// Rational scores of every iteration, starting with the initial scores.
+	pub iterations: Vec<Vec<RationalScore>>,
+	/// Whether the replayed scores match the manifest.
+	pub matches_manifest: bool,
+}
+
+impl ScoreTrace {
+	/// Constructs a new ScoreTrace instance.
+	pub fn new(
+		address_set: Vec<Address>, iterations: Vec<Vec<RationalScore>>, matches_manifest: bool,
 	) -> Self {
-		Self { address_set, attestation_matrix, circuit, ecdsa_set, pub_inputs, rational_scores }
+		Self { address_set, iterations, matches_manifest }
+	}
+
+	/// Returns the residual of every iteration: its largest score change, zero for the
+	/// initial scores.
+	pub fn residuals(&self) -> Vec<RationalScore> {
+		let zero = RationalScore::from_integer(0.into());
+		let mut residuals = vec![zero.clone(); self.iterations.len().min(1)];
+		for window in self.iterations.windows(2) {
+			let residual = window[0]
+				.iter()
+				.zip(&window[1])
+				.map(|(old, new)| if new > old { new - old } else { old - new })
+				.max()
+				.unwrap_or_else(|| zero.clone());
+			residuals.push(residual);
+		}
+
+		residuals
 	}
 }
 
@@ -195,6 +380,11 @@ impl ThPublicInputs {
 		Self { address, threshold, th_check, instances }
 	}
 
+	/// Returns whether the participant's score is above the threshold.
+	pub fn is_above_threshold(&self) -> bool {
+		self.th_check == Scalar::from(1)
+	}
+
 	/// Returns the struct as a concatenated Vec<Scalar>.
 	pub fn to_vec(&self) -> Vec<Scalar> {
 		let mut result = Vec::new();
@@ -264,6 +454,46 @@ mod tests {
 	use super::*;
 	use eigentrust_zk::halo2::arithmetic::Field;
 
+	#[test]
+	fn test_proof_artifact_validate() {
+		let attestations = vec![SignedAttestationRaw::default(); 2];
+		let params = params_hash(&[1, 2], &[3]);
+		let artifact = ProofArtifact::new(
+			&Circuit::EigenTrust,
+			&[4, 5],
+			&[6],
+			params,
+			attestations_hash(&attestations),
+		);
+		assert_eq!(artifact.proof().unwrap(), vec![4, 5]);
+		assert_eq!(artifact.public_inputs().unwrap(), vec![6]);
+
+		assert!(artifact.validate(&Circuit::EigenTrust, params, None).is_ok());
+		assert!(artifact
+			.validate(
+				&Circuit::EigenTrust,
+				params,
+				Some(attestations_hash(&attestations))
+			)
+			.is_ok());
+		assert!(artifact.validate(&Circuit::Threshold, params, None).is_err());
+		assert!(artifact.validate(&Circuit::EigenTrust, params_hash(&[1], &[2, 3]), None).is_err());
+		assert!(artifact
+			.validate(&Circuit::EigenTrust, params, Some(attestations_hash(&[])))
+			.is_err());
+	}
+
+	#[test]
+	fn test_proof_metadata_validate() {
+		let metadata = ProofMetadata::new(&Circuit::EigenTrust);
+		assert!(metadata.validate(&Circuit::EigenTrust).is_ok());
+		assert!(metadata.validate(&Circuit::Threshold).is_err());
+
+		let mut outdated = metadata;
+		outdated.hasher.fingerprint = "00".to_string();
+		assert!(outdated.validate(&Circuit::EigenTrust).is_err());
+	}
+
 	#[test]
 	fn test_et_public_inputs_new() {
 		let scalar = Scalar::random(&mut rand::thread_rng());
@@ -0,0 +1,126 @@
+//! # Domain Module.
+//!
+//! This module provides the domain registry, holding the scoring parameters of every
+//! attestation domain. Domains without an entry use the circuit parameters, which are the
+//! only ones that can be proven.
+
+use crate::error::EigenError;
+use eigentrust_zk::circuits::{INITIAL_SCORE, NUM_ITERATIONS};
+use ethers::utils::hex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Scoring parameters of a domain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DomainParams {
+	/// Number of convergence iterations.
+	pub num_iterations: u64,
+	/// Initial score of each participant.
+	pub initial_score: u128,
+}
+
+impl Default for DomainParams {
+	fn default() -> Self {
+		Self::circuit()
+	}
+}
+
+impl DomainParams {
+	/// Creates new domain parameters.
+	pub fn new(num_iterations: u64, initial_score: u128) -> Self {
+		Self { num_iterations, initial_score }
+	}
+
+	/// Returns the parameters of the EigenTrust circuit.
+	pub fn circuit() -> Self {
+		Self::new(NUM_ITERATIONS as u64, INITIAL_SCORE)
+	}
+
+	/// Returns whether the parameters match the EigenTrust circuit ones.
+	pub fn is_circuit(&self) -> bool {
+		*self == Self::circuit()
+	}
+
+	/// Checks that the parameters can be used for scoring.
+	pub fn validate(&self) -> Result<(), EigenError> {
+		if self.num_iterations == 0 {
+			return Err(EigenError::ValidationError(
+				"The number of iterations must be positive".to_string(),
+			));
+		}
+		if self.initial_score == 0 {
+			return Err(EigenError::ValidationError(
+				"The initial score must be positive".to_string(),
+			));
+		}
+
+		Ok(())
+	}
+}
+
+/// Registry of the domains' scoring parameters.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DomainRegistry {
+	domains: BTreeMap<String, DomainParams>,
+}
+
+impl DomainRegistry {
+	/// Creates an empty registry.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the parameters of the given domain.
+	pub fn params(&self, domain: [u8; 20]) -> DomainParams {
+		self.domains.get(&Self::key(domain)).copied().unwrap_or_default()
+	}
+
+	/// Overrides the parameters of the given domain. Overrides matching the circuit
+	/// parameters are removed.
+	pub fn set_params(&mut self, domain: [u8; 20], params: DomainParams) -> Result<(), EigenError> {
+		params.validate()?;
+
+		if params.is_circuit() {
+			self.domains.remove(&Self::key(domain));
+		} else {
+			self.domains.insert(Self::key(domain), params);
+		}
+
+		Ok(())
+	}
+
+	/// Returns the registry key of the given domain.
+	fn key(domain: [u8; 20]) -> String {
+		format!("0x{}", hex::encode(domain))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::domain::*;
+
+	#[test]
+	fn test_domain_registry() {
+		let mut registry = DomainRegistry::new();
+		assert!(registry.params([1; 20]).is_circuit());
+
+		let params = DomainParams::new(5, 500);
+		registry.set_params([1; 20], params).unwrap();
+		assert_eq!(registry.params([1; 20]), params);
+		assert!(registry.params([2; 20]).is_circuit());
+
+		// Invalid parameters are rejected
+		assert!(registry.set_params([1; 20], DomainParams::new(0, 500)).is_err());
+
+		// Circuit parameters clear the override
+		registry.set_params([1; 20], DomainParams::circuit()).unwrap();
+		assert_eq!(registry, DomainRegistry::new());
+
+		let json = serde_json::to_string(&registry).unwrap();
+		assert_eq!(
+			serde_json::from_str::<DomainRegistry>(&json).unwrap(),
+			registry
+		);
+	}
+}
@@ -0,0 +1,204 @@
+//! # EAS Module.
+//!
+//! This module imports attestations from the Ethereum Attestation Service registry.
+//! EAS attestations aren't signed over the EigenTrust attestation hash, so they are
+//! stored unsigned, with their EAS attester as creator. They are scored natively like the
+//! attestations of contract wallets, but can't be proven.
+//!
+//! The data of the imported schema must start with a `uint8` score, optionally followed
+//! by a `bytes32` message, e.g. `uint8 score, bytes32 message`.
+
+use crate::{
+	attestation::{AttestationRaw, IndexedAttestation, SignatureRaw, SignedAttestationRaw},
+	error::EigenError,
+};
+use ethers::{
+	abi::{decode, Address, ParamType, Token},
+	contract::EthEvent,
+	utils::id,
+};
+
+/// Signature of the EAS `getAttestation` function.
+pub const GET_ATTESTATION_SIGNATURE: &str = "getAttestation(bytes32)";
+
+/// `Attested` event of the EAS contract.
+#[derive(Clone, Debug, Default, PartialEq, Eq, EthEvent)]
+#[ethevent(name = "Attested", abi = "Attested(address,address,bytes32,bytes32)")]
+pub struct AttestedFilter {
+	/// Recipient of the attestation.
+	#[ethevent(indexed)]
+	pub recipient: Address,
+	/// Attester.
+	#[ethevent(indexed)]
+	pub attester: Address,
+	/// Attestation unique identifier.
+	pub uid: [u8; 32],
+	/// Schema unique identifier.
+	#[ethevent(indexed, name = "schemaUID")]
+	pub schema_uid: [u8; 32],
+}
+
+/// Attestation stored in the EAS registry.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EasAttestation {
+	/// Attestation unique identifier.
+	pub uid: [u8; 32],
+	/// Schema unique identifier.
+	pub schema: [u8; 32],
+	/// Creation time.
+	pub time: u64,
+	/// Expiration time, 0 if the attestation doesn't expire.
+	pub expiration_time: u64,
+	/// Revocation time, 0 if the attestation wasn't revoked.
+	pub revocation_time: u64,
+	/// Recipient of the attestation.
+	pub recipient: [u8; 20],
+	/// Attester.
+	pub attester: [u8; 20],
+	/// ABI-encoded schema data.
+	pub data: Vec<u8>,
+}
+
+impl EasAttestation {
+	/// Returns the call data of `getAttestation` for the given attestation.
+	pub fn call_data(uid: [u8; 32]) -> Vec<u8> {
+		let mut data = id(GET_ATTESTATION_SIGNATURE).to_vec();
+		data.extend(ethers::abi::encode(&[Token::FixedBytes(uid.to_vec())]));
+
+		data
+	}
+
+	/// Decodes the output of `getAttestation`.
+	pub fn decode(output: &[u8]) -> Result<Self, EigenError> {
+		let attestation_type = ParamType::Tuple(vec![
+			ParamType::FixedBytes(32),
+			ParamType::FixedBytes(32),
+			ParamType::Uint(64),
+			ParamType::Uint(64),
+			ParamType::Uint(64),
+			ParamType::FixedBytes(32),
+			ParamType::Address,
+			ParamType::Address,
+			ParamType::Bool,
+			ParamType::Bytes,
+		]);
+		let parse_err = || EigenError::ParsingError("Invalid EAS attestation".to_string());
+
+		let tokens = decode(&[attestation_type], output)
+			.map_err(|e| EigenError::ParsingError(format!("Invalid EAS attestation: {}", e)))?;
+		let fields = match tokens.into_iter().next() {
+			Some(Token::Tuple(fields)) if fields.len() == 10 => fields,
+			_ => return Err(parse_err()),
+		};
+
+		let bytes32 = |token: &Token| -> Result<[u8; 32], EigenError> {
+			token
+				.clone()
+				.into_fixed_bytes()
+				.and_then(|bytes| bytes.try_into().ok())
+				.ok_or_else(parse_err)
+		};
+		let uint = |token: &Token| {
+			token.clone().into_uint().map(|value| value.as_u64()).ok_or_else(parse_err)
+		};
+		let address = |token: &Token| {
+			token
+				.clone()
+				.into_address()
+				.map(|address| address.to_fixed_bytes())
+				.ok_or_else(parse_err)
+		};
+
+		Ok(Self {
+			uid: bytes32(&fields[0])?,
+			schema: bytes32(&fields[1])?,
+			time: uint(&fields[2])?,
+			expiration_time: uint(&fields[3])?,
+			revocation_time: uint(&fields[4])?,
+			recipient: address(&fields[6])?,
+			attester: address(&fields[7])?,
+			data: fields[9].clone().into_bytes().ok_or_else(parse_err)?,
+		})
+	}
+
+	/// Returns whether the attestation is neither revoked nor expired at the given time.
+	pub fn is_active(&self, now: u64) -> bool {
+		self.revocation_time == 0 && (self.expiration_time == 0 || self.expiration_time > now)
+	}
+
+	/// Decodes the score and the message of the schema data.
+	pub fn score_data(&self) -> Result<(u8, [u8; 32]), EigenError> {
+		let tokens = decode(&[ParamType::Uint(8), ParamType::FixedBytes(32)], &self.data)
+			.or_else(|_| decode(&[ParamType::Uint(8)], &self.data))
+			.map_err(|e| EigenError::ParsingError(format!("Invalid EAS schema data: {}", e)))?;
+
+		let value = match tokens.first() {
+			Some(Token::Uint(value)) => u8::try_from(*value).map_err(|_| {
+				EigenError::ValidationError("EAS score out of the 0-255 range".to_string())
+			})?,
+			_ => return Err(EigenError::ParsingError("Missing EAS score".to_string())),
+		};
+		let mut message = [0; 32];
+		if let Some(Token::FixedBytes(bytes)) = tokens.get(1) {
+			message.copy_from_slice(bytes);
+		}
+
+		Ok((value, message))
+	}
+
+	/// Converts the attestation into an unsigned attestation of the given domain, created
+	/// by the EAS attester.
+	pub fn to_indexed_attestation(
+		&self, domain: [u8; 20], block_number: u64, block_hash: [u8; 32],
+	) -> Result<IndexedAttestation, EigenError> {
+		let (value, message) = self.score_data()?;
+		let attestation = AttestationRaw::new(self.recipient, domain, value, message);
+		let signed_attestation = SignedAttestationRaw::new(attestation, SignatureRaw::default());
+
+		Ok(IndexedAttestation::new(
+			signed_attestation, self.attester, block_number, block_hash,
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::eas::*;
+	use ethers::abi::encode;
+
+	#[test]
+	fn test_eas_attestation_decode() {
+		let data = encode(&[Token::Uint(200.into()), Token::FixedBytes(vec![7; 32])]);
+		let output = encode(&[Token::Tuple(vec![
+			Token::FixedBytes(vec![1; 32]),
+			Token::FixedBytes(vec![2; 32]),
+			Token::Uint(1000.into()),
+			Token::Uint(2000.into()),
+			Token::Uint(0.into()),
+			Token::FixedBytes(vec![0; 32]),
+			Token::Address(Address::from([3; 20])),
+			Token::Address(Address::from([4; 20])),
+			Token::Bool(true),
+			Token::Bytes(data),
+		])]);
+
+		let attestation = EasAttestation::decode(&output).unwrap();
+		assert_eq!(attestation.uid, [1; 32]);
+		assert_eq!(attestation.recipient, [3; 20]);
+		assert_eq!(attestation.attester, [4; 20]);
+		assert!(attestation.is_active(1500));
+		assert!(!attestation.is_active(2000));
+
+		let indexed = attestation.to_indexed_attestation([5; 20], 10, [6; 32]).unwrap();
+		assert_eq!(indexed.creator(), [4; 20]);
+		assert_eq!(indexed.signed_attestation().attestation.value(), 200);
+		assert_eq!(indexed.signed_attestation().attestation.message, [7; 32]);
+		assert!(indexed.signed_attestation().is_unsigned());
+
+		// Scores must fit the attestation value
+		let out_of_range =
+			EasAttestation { data: encode(&[Token::Uint(256.into())]), ..attestation };
+		assert!(out_of_range.score_data().is_err());
+		assert!(EasAttestation::decode(&[0; 4]).is_err());
+	}
+}
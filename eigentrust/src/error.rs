@@ -1,7 +1,10 @@
 //! # Error Module.
 //!
 //! This module features the `EigenError` enum for error handling throughout the project.
+//! Errors wrapping a lower level error keep it as their source, and every variant has a
+//! stable code, shared by the CLI and any other interface built on the library.
 
+use eigentrust_zk::circuits::dynamic_sets::native::SetError;
 use thiserror::Error;
 
 /// The crate-wide error variants.
@@ -33,7 +36,19 @@ pub enum EigenError {
 
 	/// Input/output error
 	#[error("IOError: {0}")]
-	IOError(std::io::Error),
+	IOError(#[from] std::io::Error),
+
+	/// CSV serialization error
+	#[error("CSVError: {0}")]
+	CSVError(#[from] csv::Error),
+
+	/// JSON serialization error
+	#[error("JSONError: {0}")]
+	JSONError(#[from] serde_json::Error),
+
+	/// Ethereum node provider error
+	#[error("ProviderError: {0}")]
+	ProviderError(#[from] ethers::providers::ProviderError),
 
 	/// Keys Error
 	#[error("KeysError: {0}")]
@@ -87,3 +102,75 @@ pub enum EigenError {
 	#[error("KeygenError: {0}")]
 	KeygenError(String),
 }
+
+impl EigenError {
+	/// Returns the stable code of the error.
+	pub fn code(&self) -> &'static str {
+		match self {
+			EigenError::AttestationError(_) => "E_ATTESTATION",
+			EigenError::ConfigurationError(_) => "E_CONFIGURATION",
+			EigenError::ConnectionError(_) => "E_CONNECTION",
+			EigenError::ContractError(_) => "E_CONTRACT",
+			EigenError::ConversionError(_) => "E_CONVERSION",
+			EigenError::FileIOError(_) => "E_FILE_IO",
+			EigenError::IOError(_) => "E_IO",
+			EigenError::CSVError(_) => "E_CSV",
+			EigenError::JSONError(_) => "E_JSON",
+			EigenError::ProviderError(_) => "E_PROVIDER",
+			EigenError::KeysError(_) => "E_KEYS",
+			EigenError::NetworkError(_) => "E_NETWORK",
+			EigenError::ParsingError(_) => "E_PARSING",
+			EigenError::ProvingError(_) => "E_PROVING",
+			EigenError::ReadWriteError(_) => "E_READ_WRITE",
+			EigenError::RecoveryError(_) => "E_RECOVERY",
+			EigenError::RequestError(_) => "E_REQUEST",
+			EigenError::ResourceUnavailableError(_) => "E_RESOURCE_UNAVAILABLE",
+			EigenError::TransactionError(_) => "E_TRANSACTION",
+			EigenError::UnknownError(_) => "E_UNKNOWN",
+			EigenError::ValidationError(_) => "E_VALIDATION",
+			EigenError::VerificationError(_) => "E_VERIFICATION",
+			EigenError::KeygenError(_) => "E_KEYGEN",
+		}
+	}
+
+	/// Returns the error messages, from this error down to its root cause.
+	pub fn chain(&self) -> Vec<String> {
+		let mut messages = vec![self.to_string()];
+		let mut source = std::error::Error::source(self);
+		while let Some(error) = source {
+			messages.push(error.to_string());
+			source = error.source();
+		}
+
+		messages
+	}
+}
+
+impl From<SetError> for EigenError {
+	fn from(e: SetError) -> Self {
+		EigenError::ValidationError(e.to_string())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::error::*;
+	use std::error::Error;
+
+	#[test]
+	fn test_error_source_and_code() {
+		let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "missing file");
+		let error = EigenError::from(io_error);
+
+		assert_eq!(error.code(), "E_IO");
+		assert!(error.source().is_some());
+		assert_eq!(error.chain().len(), 2);
+
+		let error = EigenError::ValidationError("invalid".to_string());
+		assert_eq!(error.code(), "E_VALIDATION");
+		assert!(error.source().is_none());
+
+		let error = EigenError::from(SetError::InsufficientPeers);
+		assert_eq!(error.code(), "E_VALIDATION");
+	}
+}
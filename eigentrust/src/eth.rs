@@ -10,24 +10,83 @@ use eigentrust_zk::{
 use ethers::{
 	abi::Address,
 	prelude::k256::ecdsa::SigningKey,
-	signers::coins_bip39::{English, Mnemonic},
+	providers::Middleware,
+	signers::{
+		coins_bip39::{English, Mnemonic},
+		Signer,
+	},
+	types::U256,
+	utils::hex,
 };
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+/// Base gas of a transaction.
+pub const TX_BASE_GAS: u64 = 21_000;
+/// Gas of a zero call data byte.
+pub const ZERO_BYTE_GAS: u64 = 4;
+/// Gas of a non-zero call data byte (EIP-2028).
+pub const NON_ZERO_BYTE_GAS: u64 = 16;
+/// Gas of the two-pair pairing check closing a KZG proof verification (EIP-1108).
+pub const KZG_PAIRING_GAS: u64 = 45_000 + 2 * 34_000;
+
 /// Deploys the AttestationStation contract.
 pub async fn deploy_as(signer: Arc<ClientSigner>) -> Result<Address, EigenError> {
+	deploy_as_confirmed(signer, 1).await
+}
+
+/// Deploys the AttestationStation contract, waiting for the given number of confirmations.
+/// Fails if the node isn't on the signer's network.
+pub async fn deploy_as_confirmed(
+	signer: Arc<ClientSigner>, confirmations: usize,
+) -> Result<Address, EigenError> {
+	let node_chain_id =
+		signer.get_chainid().await.map_err(|e| EigenError::ConnectionError(e.to_string()))?;
+	let chain_id = signer.signer().chain_id();
+	if node_chain_id != U256::from(chain_id) {
+		return Err(EigenError::ConfigurationError(format!(
+			"The node is on chain {}, expected {}",
+			node_chain_id, chain_id
+		)));
+	}
+
+	// The contract has no constructor arguments
 	let res = AttestationStation::deploy(signer, ())
-		.map_err(|e| EigenError::ContractError(e.to_string()))?;
+		.map_err(|e| EigenError::ContractError(e.to_string()))?
+		.confirmations(confirmations);
 
 	let transaction = res.send().await.map_err(|e| EigenError::TransactionError(e.to_string()))?;
 
 	Ok(transaction.address())
 }
 
+/// Public identity of an attester, derived from a mnemonic phrase.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttesterIdentity {
+	/// Derivation index.
+	pub index: u32,
+	/// Ethereum address.
+	pub address: String,
+	/// Uncompressed public key, as a hex string.
+	pub public_key: String,
+}
+
+/// Generates a new random 12 words mnemonic phrase.
+pub fn generate_mnemonic() -> String {
+	let mut rng = rand::thread_rng();
+	Mnemonic::<English>::new(&mut rng).to_phrase()
+}
+
 /// Returns a vector of ECDSA key pairs derived from the given mnemonic phrase.
 pub fn ecdsa_keypairs_from_mnemonic(
 	mnemonic: &str, count: u32,
 ) -> Result<Vec<ECDSAKeypair>, EigenError> {
+	let indices: Vec<u32> = (0..count).collect();
+	ecdsa_keypairs_at(mnemonic, &indices)
+}
+
+/// Returns the ECDSA key pairs derived from the given mnemonic phrase at the given indices.
+pub fn ecdsa_keypairs_at(mnemonic: &str, indices: &[u32]) -> Result<Vec<ECDSAKeypair>, EigenError> {
 	let mnemonic = Mnemonic::<English>::new_from_phrase(mnemonic)
 		.map_err(|e| EigenError::ParsingError(e.to_string()))?;
 	let mut keys = Vec::new();
@@ -35,7 +94,7 @@ pub fn ecdsa_keypairs_from_mnemonic(
 	// The hardened derivation flag.
 	const BIP32_HARDEN: u32 = 0x8000_0000;
 
-	for i in 0..count {
+	for i in indices.iter().copied() {
 		// Set standard derivation path 44'/60'/0'/0/i
 		let derivation_path: Vec<u32> =
 			vec![44 + BIP32_HARDEN, 60 + BIP32_HARDEN, BIP32_HARDEN, 0, i];
@@ -66,6 +125,33 @@ pub fn ecdsa_keypairs_from_mnemonic(
 	Ok(keys)
 }
 
+/// Returns the public identities of the attesters derived from the given mnemonic phrase
+/// at the given indices.
+pub fn attester_identities(
+	mnemonic: &str, indices: &[u32],
+) -> Result<Vec<AttesterIdentity>, EigenError> {
+	let keypairs = ecdsa_keypairs_at(mnemonic, indices)?;
+
+	Ok(indices
+		.iter()
+		.zip(keypairs)
+		.map(|(index, keypair)| {
+			// Coordinates are little-endian
+			let raw_pub_key = keypair.public_key.to_bytes();
+			let (x, y) = raw_pub_key.split_at(32);
+			let mut public_key = vec![0x04];
+			public_key.extend(x.iter().rev());
+			public_key.extend(y.iter().rev());
+
+			AttesterIdentity {
+				index: *index,
+				address: format!("{:?}", address_from_ecdsa_key(&keypair.public_key)),
+				public_key: format!("0x{}", hex::encode(public_key)),
+			}
+		})
+		.collect())
+}
+
 /// Constructs an Ethereum address for the given ECDSA public key.
 pub fn address_from_ecdsa_key(pub_key: &ECDSAPublicKey) -> Address {
 	let mut address_bytes = pub_key.to_address().to_bytes();
@@ -73,6 +159,18 @@ pub fn address_from_ecdsa_key(pub_key: &ECDSAPublicKey) -> Address {
 	Address::from_slice(&address_bytes[0..20])
 }
 
+/// Returns the gas of the given transaction call data.
+pub fn call_data_gas(data: &[u8]) -> u64 {
+	data.iter().map(|byte| if *byte == 0 { ZERO_BYTE_GAS } else { NON_ZERO_BYTE_GAS }).sum()
+}
+
+/// Returns a lower bound of the gas of verifying a proof on-chain: the base gas and call data
+/// of the transaction carrying the proof and its public inputs, and the final pairing check.
+/// The elliptic curve operations of the verifier itself aren't counted.
+pub fn min_verification_gas(proof: &[u8], public_inputs: &[u8]) -> u64 {
+	TX_BASE_GAS + call_data_gas(proof) + call_data_gas(public_inputs) + KZG_PAIRING_GAS
+}
+
 /// Constructs a Scalar from the given Ethereum address.
 pub fn scalar_from_address(address: &Address) -> Result<Scalar, EigenError> {
 	let mut address_fixed = address.to_fixed_bytes();
@@ -97,10 +195,7 @@ pub fn scalar_from_address(address: &Address) -> Result<Scalar, EigenError> {
 #[cfg(test)]
 mod tests {
 	use crate::{eth::*, Client, SecpScalar};
-	use ethers::{
-		types::H160,
-		utils::{hex, Anvil},
-	};
+	use ethers::{types::H160, utils::Anvil};
 	use std::str::FromStr;
 
 	const TEST_MNEMONIC: &'static str =
@@ -124,9 +219,29 @@ mod tests {
 		let res = deploy_as(client.signer).await;
 		assert!(res.is_ok());
 
+		// Deploying to another network is refused
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			1,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			anvil.endpoint(),
+		);
+		let res = deploy_as_confirmed(client.signer, 1).await;
+		assert!(matches!(res, Err(EigenError::ConfigurationError(_))));
+
 		drop(anvil);
 	}
 
+	#[test]
+	fn test_min_verification_gas() {
+		assert_eq!(call_data_gas(&[0, 1, 0, 255]), 40);
+		assert_eq!(
+			min_verification_gas(&[1; 10], &[0; 32]),
+			21_000 + 160 + 128 + 113_000
+		);
+	}
+
 	#[test]
 	fn test_ecdsa_keypairs_from_mnemonic() {
 		// Expected address
@@ -171,4 +286,25 @@ mod tests {
 
 		assert_eq!(recovered_address.to_fixed_bytes(), expected_address_bytes);
 	}
+
+	#[test]
+	fn test_attester_identities() {
+		let identities = attester_identities(TEST_MNEMONIC, &[1, 0]).unwrap();
+		assert_eq!(identities[0].index, 1);
+		assert_eq!(
+			identities[0].address,
+			"0x70997970c51812dc3a010c7d01b50e0d17dc79c8"
+		);
+		assert_eq!(
+			identities[1].address,
+			"0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+		);
+		assert!(identities[1].public_key.starts_with("0x04"));
+		assert_eq!(identities[1].public_key.len(), 2 + 65 * 2);
+
+		// Generated mnemonics derive keys
+		let mnemonic = generate_mnemonic();
+		assert_eq!(mnemonic.split_whitespace().count(), 12);
+		assert!(ecdsa_keypairs_from_mnemonic(&mnemonic, 1).is_ok());
+	}
 }
@@ -0,0 +1,192 @@
+//! # Events Module.
+//!
+//! This module decodes the logs of the AttestationStation contract into typed events.
+//! Logs of events unknown to the client, or that don't match the expected shape, are
+//! kept as raw logs instead of failing the whole fetch.
+
+use crate::{
+	att_station::AttestationCreatedFilter,
+	attestation::{AttestationRaw, IndexedAttestation, SignatureRaw, SignedAttestationRaw},
+	error::EigenError,
+};
+use ethers::{
+	abi::RawLog,
+	contract::EthEvent,
+	types::{Log, H256},
+};
+
+/// Event emitted by the AttestationStation contract.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StationEvent {
+	/// `AttestationCreated` event.
+	AttestationCreated(AttestationCreatedFilter),
+	/// Log of an unknown event, or of a known event that couldn't be decoded.
+	Unknown(RawLog),
+}
+
+impl StationEvent {
+	/// Decodes the given log.
+	pub fn decode(log: &Log) -> Self {
+		let raw_log = RawLog::from((log.topics.clone(), log.data.to_vec()));
+
+		match raw_log.topics.first() {
+			Some(topic) if *topic == AttestationCreatedFilter::signature() => {
+				match AttestationCreatedFilter::decode_log(&raw_log) {
+					Ok(event) => Self::AttestationCreated(event),
+					Err(_) => Self::Unknown(raw_log),
+				}
+			},
+			_ => Self::Unknown(raw_log),
+		}
+	}
+
+	/// Returns the event signature, the first topic of the log.
+	pub fn signature(&self) -> Option<H256> {
+		match self {
+			Self::AttestationCreated(_) => Some(AttestationCreatedFilter::signature()),
+			Self::Unknown(raw_log) => raw_log.topics.first().cloned(),
+		}
+	}
+}
+
+/// Decoded event, along with the block that included it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StationLog {
+	/// Decoded event
+	event: StationEvent,
+	/// Number of the block including the event
+	block_number: u64,
+	/// Hash of the block including the event
+	block_hash: [u8; 32],
+	/// Hash of the transaction and index of the log, if known
+	transaction: Option<([u8; 32], u64)>,
+}
+
+impl StationLog {
+	/// Decodes the given log.
+	pub fn decode(log: &Log) -> Self {
+		Self {
+			event: StationEvent::decode(log),
+			block_number: log.block_number.unwrap_or_default().as_u64(),
+			block_hash: log.block_hash.unwrap_or_default().to_fixed_bytes(),
+			transaction: log
+				.transaction_hash
+				.zip(log.log_index)
+				.map(|(hash, index)| (hash.to_fixed_bytes(), index.low_u64())),
+		}
+	}
+
+	/// Returns the decoded event.
+	pub fn event(&self) -> &StationEvent {
+		&self.event
+	}
+
+	/// Returns the number of the block including the event.
+	pub fn block_number(&self) -> u64 {
+		self.block_number
+	}
+
+	/// Returns the hash of the block including the event.
+	pub fn block_hash(&self) -> [u8; 32] {
+		self.block_hash
+	}
+
+	/// Returns the hash of the transaction and the index of the log, if known.
+	pub fn transaction(&self) -> Option<([u8; 32], u64)> {
+		self.transaction
+	}
+
+	/// Converts an `AttestationCreated` event into an indexed attestation.
+	/// Returns `None` for other events.
+	pub fn to_indexed_attestation(&self) -> Option<Result<IndexedAttestation, EigenError>> {
+		match &self.event {
+			StationEvent::AttestationCreated(att_log) => Some(
+				Self::indexed_attestation(att_log, self.block_number, self.block_hash).map(
+					|indexed| match self.transaction {
+						Some((hash, index)) => indexed.with_transaction(hash, index),
+						None => indexed,
+					},
+				),
+			),
+			StationEvent::Unknown(_) => None,
+		}
+	}
+
+	/// Builds the indexed attestation of an `AttestationCreated` event.
+	fn indexed_attestation(
+		att_log: &AttestationCreatedFilter, block_number: u64, block_hash: [u8; 32],
+	) -> Result<IndexedAttestation, EigenError> {
+		let creator = att_log.creator.to_fixed_bytes();
+		let att_raw: AttestationRaw = att_log.clone().try_into()?;
+		let sig_raw: SignatureRaw = att_log.clone().try_into()?;
+		let signed_attestation = SignedAttestationRaw::new(att_raw, sig_raw);
+
+		Ok(IndexedAttestation::new(
+			signed_attestation, creator, block_number, block_hash,
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::events::*;
+	use ethers::{
+		abi::{encode, Token},
+		types::{Address, Bytes, U256, U64},
+	};
+
+	fn build_log(topics: Vec<H256>, data: Vec<u8>) -> Log {
+		Log {
+			topics,
+			data: Bytes::from(data),
+			block_number: Some(U64::from(7)),
+			block_hash: Some(H256::from([8; 32])),
+			transaction_hash: Some(H256::from([5; 32])),
+			log_index: Some(U256::from(2)),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn test_decode_station_events() {
+		let (creator, about, key) = (Address::from([1; 20]), Address::from([2; 20]), [3; 32]);
+		let val = vec![4; 66];
+
+		let log = build_log(
+			vec![
+				AttestationCreatedFilter::signature(),
+				H256::from(creator),
+				H256::from(about),
+				H256::from(key),
+			],
+			encode(&[Token::Bytes(val.clone())]),
+		);
+
+		let station_log = StationLog::decode(&log);
+		assert_eq!(station_log.block_number(), 7);
+		assert_eq!(
+			station_log.event(),
+			&StationEvent::AttestationCreated(AttestationCreatedFilter {
+				creator,
+				about,
+				key,
+				val: Bytes::from(val),
+			})
+		);
+		let indexed = station_log.to_indexed_attestation().unwrap().unwrap();
+		assert_eq!(indexed.transaction(), Some(([5; 32], 2)));
+
+		// Unknown events are kept raw
+		let unknown_topic = H256::from([9; 32]);
+		let unknown = StationLog::decode(&build_log(vec![unknown_topic], vec![]));
+		assert_eq!(unknown.event().signature(), Some(unknown_topic));
+		assert!(unknown.to_indexed_attestation().is_none());
+
+		// Known events with an unexpected shape too
+		let malformed = StationLog::decode(&build_log(
+			vec![AttestationCreatedFilter::signature()],
+			vec![],
+		));
+		assert!(matches!(malformed.event(), StationEvent::Unknown(_)));
+	}
+}
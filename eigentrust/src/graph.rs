@@ -0,0 +1,524 @@
+//! # Graph Module.
+//!
+//! This module provides the trust graph built from the attestations, used to find the
+//! participants that are disconnected from a seed set before scoring and the clusters
+//! keeping their trust among themselves, and its export for visualization tools such as
+//! Graphviz and Gephi.
+
+use crate::{error::EigenError, storage::ScoreRecord};
+use ethers::{types::Address, utils::hex};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::{BTreeMap, BTreeSet, VecDeque},
+	str::FromStr,
+};
+
+/// Policy for handling the participants disconnected from the seed set.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DisconnectedPolicy {
+	/// Excludes the disconnected participants and their attestations from the run.
+	#[default]
+	Exclude,
+	/// Excludes the disconnected participants from the run and gives them a zero score.
+	ScoreZero,
+	/// Scores the disconnected participants in a separate run.
+	SeparateRun,
+}
+
+impl FromStr for DisconnectedPolicy {
+	type Err = EigenError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"exclude" => Ok(Self::Exclude),
+			"score-zero" => Ok(Self::ScoreZero),
+			"separate-run" => Ok(Self::SeparateRun),
+			_ => Err(EigenError::ParsingError(format!(
+				"Invalid disconnected policy: {}",
+				s
+			))),
+		}
+	}
+}
+
+/// Default share of the trust given by a cluster's members that has to stay within the
+/// cluster for it to be flagged as a potential sybil ring.
+pub const DEFAULT_SYBIL_SHARE: f64 = 0.9;
+
+/// Directed trust graph, with an edge from every attester to the peers it gave
+/// a positive score to, weighted by the score.
+#[derive(Clone, Debug, Default)]
+pub struct TrustGraph {
+	participants: BTreeSet<Address>,
+	edges: BTreeMap<Address, BTreeMap<Address, u8>>,
+}
+
+impl TrustGraph {
+	/// Builds the graph from (attester, about, value) triples, in attestation order.
+	/// Later positive scores replace earlier ones between the same peers.
+	pub fn new(attestations: impl IntoIterator<Item = (Address, Address, u8)>) -> Self {
+		let mut graph = Self::default();
+		for (attester, about, value) in attestations {
+			graph.participants.insert(attester);
+			graph.participants.insert(about);
+
+			if value > 0 && attester != about {
+				graph.edges.entry(attester).or_default().insert(about, value);
+			}
+		}
+
+		graph
+	}
+
+	/// Returns all the participants of the graph.
+	pub fn participants(&self) -> &BTreeSet<Address> {
+		&self.participants
+	}
+
+	/// Returns the participants reachable from the given seeds, seeds included.
+	/// Seeds that aren't participants are ignored.
+	pub fn reachable(&self, seeds: &[Address]) -> BTreeSet<Address> {
+		let mut visited: BTreeSet<Address> =
+			seeds.iter().filter(|seed| self.participants.contains(seed)).cloned().collect();
+		let mut queue: VecDeque<Address> = visited.iter().cloned().collect();
+
+		while let Some(peer) = queue.pop_front() {
+			for next in self.edges.get(&peer).into_iter().flat_map(BTreeMap::keys) {
+				if visited.insert(*next) {
+					queue.push_back(*next);
+				}
+			}
+		}
+
+		visited
+	}
+
+	/// Returns the participants that can't be reached from the given seeds.
+	pub fn disconnected(&self, seeds: &[Address]) -> BTreeSet<Address> {
+		let reachable = self.reachable(seeds);
+		self.participants.difference(&reachable).cloned().collect()
+	}
+
+	/// Returns the strongly connected components of the graph with more than one
+	/// participant: the groups whose members can all reach each other.
+	pub fn strongly_connected(&self) -> Vec<BTreeSet<Address>> {
+		// Kosaraju's algorithm, with explicit stacks to handle large graphs
+		let mut order = Vec::with_capacity(self.participants.len());
+		let mut visited = BTreeSet::new();
+		for start in &self.participants {
+			if !visited.insert(*start) {
+				continue;
+			}
+			let mut stack = vec![(*start, self.successors(start))];
+			while let Some((peer, next)) = stack.last_mut() {
+				match next.pop() {
+					Some(next) if visited.insert(next) => {
+						let successors = self.successors(&next);
+						stack.push((next, successors));
+					},
+					Some(_) => {},
+					None => {
+						order.push(*peer);
+						stack.pop();
+					},
+				}
+			}
+		}
+
+		let mut reversed: BTreeMap<Address, Vec<Address>> = BTreeMap::new();
+		for (from, edges) in &self.edges {
+			for to in edges.keys() {
+				reversed.entry(*to).or_default().push(*from);
+			}
+		}
+
+		let mut components = Vec::new();
+		let mut assigned = BTreeSet::new();
+		for start in order.into_iter().rev() {
+			if !assigned.insert(start) {
+				continue;
+			}
+			let mut component = BTreeSet::from([start]);
+			let mut stack = vec![start];
+			while let Some(peer) = stack.pop() {
+				for prev in reversed.get(&peer).into_iter().flatten() {
+					if assigned.insert(*prev) {
+						component.insert(*prev);
+						stack.push(*prev);
+					}
+				}
+			}
+
+			if component.len() > 1 {
+				components.push(component);
+			}
+		}
+
+		components
+	}
+
+	/// Returns the report of the potential sybil rings: the strongly connected clusters
+	/// keeping at least `min_internal_share` of the trust their members give, such as
+	/// `DEFAULT_SYBIL_SHARE`. Clusters are sorted from the most to the least internal.
+	pub fn sybil_report(&self, min_internal_share: f64) -> SybilReport {
+		let mut clusters: Vec<SybilCluster> = self
+			.strongly_connected()
+			.into_iter()
+			.map(|members| self.cluster(&members))
+			.filter(|cluster| cluster.internal_share() >= min_internal_share)
+			.collect();
+		clusters.sort_by(|a, b| b.internal_share().total_cmp(&a.internal_share()));
+
+		SybilReport { min_internal_share, participants: self.participants.len(), clusters }
+	}
+
+	/// Returns the trust flows of the cluster.
+	fn cluster(&self, members: &BTreeSet<Address>) -> SybilCluster {
+		let (mut internal_weight, mut outgoing_weight, mut incoming_weight) = (0, 0, 0);
+		for (from, edges) in &self.edges {
+			for (to, weight) in edges {
+				let weight = u64::from(*weight);
+				match (members.contains(from), members.contains(to)) {
+					(true, true) => internal_weight += weight,
+					(true, false) => outgoing_weight += weight,
+					(false, true) => incoming_weight += weight,
+					(false, false) => {},
+				}
+			}
+		}
+
+		SybilCluster {
+			members: members.iter().map(GraphExport::address).collect(),
+			internal_weight,
+			outgoing_weight,
+			incoming_weight,
+		}
+	}
+
+	/// Returns the peers the participant trusts, in reverse order.
+	fn successors(&self, peer: &Address) -> Vec<Address> {
+		self.edges.get(peer).into_iter().flat_map(BTreeMap::keys).rev().cloned().collect()
+	}
+}
+
+/// Cluster of participants whose trust mostly flows among themselves.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SybilCluster {
+	/// Member addresses, sorted.
+	pub members: Vec<String>,
+	/// Sum of the scores the members give each other.
+	pub internal_weight: u64,
+	/// Sum of the scores the members give outside of the cluster.
+	pub outgoing_weight: u64,
+	/// Sum of the scores the cluster receives from outside.
+	pub incoming_weight: u64,
+}
+
+impl SybilCluster {
+	/// Returns the share of the trust given by the members that stays within the cluster.
+	pub fn internal_share(&self) -> f64 {
+		let total = self.internal_weight + self.outgoing_weight;
+		if total == 0 {
+			return 0.0;
+		}
+
+		self.internal_weight as f64 / total as f64
+	}
+}
+
+/// Report of the potential sybil rings of the trust graph.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SybilReport {
+	/// Share of the members' trust kept within a cluster for it to be flagged.
+	pub min_internal_share: f64,
+	/// Number of participants of the graph.
+	pub participants: usize,
+	/// Flagged clusters, from the most to the least internal.
+	pub clusters: Vec<SybilCluster>,
+}
+
+/// Graph export format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphFormat {
+	/// Graphviz DOT.
+	Dot,
+	/// GraphML, as read by Gephi.
+	GraphMl,
+	/// JSON nodes and edges lists.
+	Json,
+}
+
+impl GraphFormat {
+	/// Returns the file extension of the format.
+	pub fn extension(&self) -> &'static str {
+		match self {
+			Self::Dot => "dot",
+			Self::GraphMl => "graphml",
+			Self::Json => "json",
+		}
+	}
+}
+
+impl FromStr for GraphFormat {
+	type Err = EigenError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"dot" => Ok(Self::Dot),
+			"graphml" => Ok(Self::GraphMl),
+			"json" => Ok(Self::Json),
+			_ => Err(EigenError::ParsingError(format!(
+				"Invalid graph format: {}",
+				s
+			))),
+		}
+	}
+}
+
+/// Node of the exported graph.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphNode {
+	/// Participant address.
+	pub address: String,
+	/// Global score, if the participant was scored.
+	pub score: Option<String>,
+}
+
+/// Edge of the exported graph.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+	/// Attester address.
+	pub from: String,
+	/// Attested address.
+	pub to: String,
+	/// Attested value.
+	pub weight: u8,
+}
+
+/// Attestation graph export, with the participants as nodes and the attestations as
+/// weighted edges.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphExport {
+	/// Participants, sorted by address.
+	pub nodes: Vec<GraphNode>,
+	/// Attestations, sorted by attester and attested address.
+	pub edges: Vec<GraphEdge>,
+}
+
+impl GraphExport {
+	/// Builds the export from (attester, about, value) triples, in attestation order, and
+	/// the score records of the participants. Later attestations replace earlier ones
+	/// between the same peers, and self-attestations are left out.
+	pub fn new(
+		attestations: impl IntoIterator<Item = (Address, Address, u8)>, scores: &[ScoreRecord],
+	) -> Self {
+		let mut participants: BTreeSet<Address> = BTreeSet::new();
+		let mut weights: BTreeMap<(Address, Address), u8> = BTreeMap::new();
+		for (attester, about, value) in attestations {
+			participants.insert(attester);
+			participants.insert(about);
+
+			if attester != about {
+				weights.insert((attester, about), value);
+			}
+		}
+
+		let nodes = participants
+			.iter()
+			.map(|participant| {
+				let address = Self::address(participant);
+				let score = scores
+					.iter()
+					.find(|record| record.peer_address().eq_ignore_ascii_case(&address))
+					.map(|record| record.score().clone());
+
+				GraphNode { address, score }
+			})
+			.collect();
+		let edges = weights
+			.into_iter()
+			.map(|((from, to), weight)| GraphEdge {
+				from: Self::address(&from),
+				to: Self::address(&to),
+				weight,
+			})
+			.collect();
+
+		Self { nodes, edges }
+	}
+
+	/// Renders the graph in the given format.
+	pub fn render(&self, format: GraphFormat) -> Result<String, EigenError> {
+		match format {
+			GraphFormat::Dot => Ok(self.to_dot()),
+			GraphFormat::GraphMl => Ok(self.to_graphml()),
+			GraphFormat::Json => Ok(serde_json::to_string(self)?),
+		}
+	}
+
+	/// Renders the graph as a Graphviz DOT digraph.
+	pub fn to_dot(&self) -> String {
+		let mut dot = String::from("digraph eigentrust {\n");
+		for node in &self.nodes {
+			let score = node.score.as_deref().unwrap_or("unscored");
+			dot.push_str(&format!(
+				"  \"{}\" [label=\"{}\\n{}\", score=\"{}\"];\n",
+				node.address, node.address, score, score
+			));
+		}
+		for edge in &self.edges {
+			dot.push_str(&format!(
+				"  \"{}\" -> \"{}\" [weight={}, label=\"{}\"];\n",
+				edge.from, edge.to, edge.weight, edge.weight
+			));
+		}
+		dot.push_str("}\n");
+
+		dot
+	}
+
+	/// Renders the graph as a directed GraphML document.
+	pub fn to_graphml(&self) -> String {
+		let mut graphml = String::from(concat!(
+			"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+			"<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+			"  <key id=\"score\" for=\"node\" attr.name=\"score\" attr.type=\"string\"/>\n",
+			"  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"int\"/>\n",
+			"  <graph id=\"eigentrust\" edgedefault=\"directed\">\n",
+		));
+		for node in &self.nodes {
+			match &node.score {
+				Some(score) => graphml.push_str(&format!(
+					"    <node id=\"{}\"><data key=\"score\">{}</data></node>\n",
+					node.address, score
+				)),
+				None => graphml.push_str(&format!("    <node id=\"{}\"/>\n", node.address)),
+			}
+		}
+		for edge in &self.edges {
+			graphml.push_str(&format!(
+				"    <edge source=\"{}\" target=\"{}\"><data key=\"weight\">{}</data></edge>\n",
+				edge.from, edge.to, edge.weight
+			));
+		}
+		graphml.push_str("  </graph>\n</graphml>\n");
+
+		graphml
+	}
+
+	/// Returns the hex representation of the address.
+	pub(crate) fn address(address: &Address) -> String {
+		format!("0x{}", hex::encode(address))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::graph::*;
+
+	#[test]
+	fn test_disconnected_participants() {
+		let (seed, a, b, c, d) = (
+			Address::from([1; 20]),
+			Address::from([2; 20]),
+			Address::from([3; 20]),
+			Address::from([4; 20]),
+			Address::from([5; 20]),
+		);
+
+		// seed -> a -> b, while c and d only trust each other.
+		// b gives a zero score to c, which doesn't connect it.
+		let graph = TrustGraph::new(vec![
+			(seed, a, 5),
+			(a, b, 3),
+			(b, c, 0),
+			(c, d, 7),
+			(d, c, 7),
+		]);
+
+		assert_eq!(graph.participants().len(), 5);
+		assert_eq!(graph.reachable(&[seed]), BTreeSet::from([seed, a, b]));
+		assert_eq!(graph.disconnected(&[seed]), BTreeSet::from([c, d]));
+
+		// Unknown seeds reach nothing
+		let unknown = Address::from([9; 20]);
+		assert!(graph.reachable(&[unknown]).is_empty());
+	}
+
+	#[test]
+	fn test_sybil_report() {
+		let addr = |i: u8| Address::from([i; 20]);
+
+		// 1 <-> 2 <-> 3 trust each other, and 3 slightly trusts 4.
+		// 4 <-> 5 form a ring, with most of their trust going to 1.
+		let graph = TrustGraph::new(vec![
+			(addr(1), addr(2), 5),
+			(addr(2), addr(1), 5),
+			(addr(2), addr(3), 5),
+			(addr(3), addr(2), 9),
+			(addr(3), addr(4), 1),
+			(addr(4), addr(5), 2),
+			(addr(5), addr(4), 2),
+			(addr(4), addr(1), 8),
+		]);
+
+		let components = graph.strongly_connected();
+		assert_eq!(components.len(), 2);
+		assert!(components.contains(&BTreeSet::from([addr(1), addr(2), addr(3)])));
+		assert!(components.contains(&BTreeSet::from([addr(4), addr(5)])));
+
+		let report = graph.sybil_report(DEFAULT_SYBIL_SHARE);
+		assert_eq!(report.participants, 5);
+		assert_eq!(report.clusters.len(), 1);
+
+		let cluster = &report.clusters[0];
+		assert_eq!(cluster.members.len(), 3);
+		assert_eq!(
+			(cluster.internal_weight, cluster.outgoing_weight, cluster.incoming_weight),
+			(24, 1, 8)
+		);
+		assert!(cluster.internal_share() > 0.95);
+
+		// Lowering the share also flags the second ring
+		assert_eq!(graph.sybil_report(0.2).clusters.len(), 2);
+	}
+
+	#[test]
+	fn test_graph_export() {
+		let (a, b, c) = (
+			Address::from([1; 20]),
+			Address::from([2; 20]),
+			Address::from([3; 20]),
+		);
+		let scores = vec![ScoreRecord::new(
+			format!("0x{}", hex::encode(a)),
+			"0x00".to_string(),
+			"1500".to_string(),
+			"1".to_string(),
+			"1500".to_string(),
+		)];
+
+		// The second attestation from a to b replaces the first one
+		let export = GraphExport::new(vec![(a, b, 3), (b, c, 7), (a, b, 5), (c, c, 9)], &scores);
+		assert_eq!(export.nodes.len(), 3);
+		assert_eq!(export.nodes[0].score, Some("1500".to_string()));
+		assert_eq!(export.nodes[1].score, None);
+		assert_eq!(export.edges.len(), 2);
+		assert_eq!(export.edges[0].weight, 5);
+
+		let dot = export.render(GraphFormat::Dot).unwrap();
+		assert!(dot.starts_with("digraph eigentrust {"));
+		assert!(dot.contains(&format!(
+			"\"{}\" -> \"{}\" [weight=7",
+			export.edges[1].from, export.edges[1].to
+		)));
+
+		let graphml = export.render(GraphFormat::GraphMl).unwrap();
+		assert_eq!(graphml.matches("<edge ").count(), 2);
+
+		let json = export.render(GraphFormat::Json).unwrap();
+		assert_eq!(serde_json::from_str::<GraphExport>(&json).unwrap(), export);
+
+		assert!("gexf".parse::<GraphFormat>().is_err());
+	}
+}
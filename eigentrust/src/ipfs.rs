@@ -0,0 +1,198 @@
+//! # IPFS Module.
+//!
+//! This module publishes score snapshots to IPFS through the RPC API of a node, such as
+//! Kubo, pinning them so they stay available, and loads published snapshots by CID.
+//!
+//! Available behind the `ipfs` feature.
+
+use crate::{error::EigenError, snapshot::ScoreSnapshot, storage::AsyncStorage};
+use async_trait::async_trait;
+use reqwest::{
+	multipart::{Form, Part},
+	Client, Response,
+};
+use serde::Deserialize;
+
+/// Path of the RPC endpoint adding and pinning a file.
+pub const ADD_PATH: &str = "/api/v0/add";
+/// Path of the RPC endpoint returning the content of a CID.
+pub const CAT_PATH: &str = "/api/v0/cat";
+/// File name of the published snapshots.
+const SNAPSHOT_FILE_NAME: &str = "scores.json";
+
+/// Response of the add endpoint.
+#[derive(Clone, Debug, Deserialize)]
+struct AddResponse {
+	/// CID of the added file.
+	#[serde(rename = "Hash")]
+	hash: String,
+}
+
+/// Storage of score snapshots on IPFS. Saving pins the snapshot and keeps its CID, which
+/// loading then fetches.
+#[derive(Clone, Debug)]
+pub struct IpfsStorage {
+	api_url: String,
+	cid: Option<String>,
+	client: Client,
+}
+
+impl IpfsStorage {
+	/// Creates a storage using the RPC API of the node at the given URL.
+	pub fn new(api_url: &str) -> Self {
+		Self {
+			api_url: api_url.trim_end_matches('/').to_string(),
+			cid: None,
+			client: Client::new(),
+		}
+	}
+
+	/// Sets the CID of the snapshot to load.
+	pub fn with_cid(mut self, cid: String) -> Self {
+		self.cid = Some(cid);
+		self
+	}
+
+	/// Returns the CID of the last pinned or selected snapshot, if any.
+	pub fn cid(&self) -> Option<&str> {
+		self.cid.as_deref()
+	}
+
+	/// Pins the snapshot, returning its CID.
+	pub async fn pin(&self, snapshot: &ScoreSnapshot) -> Result<String, EigenError> {
+		let body = serde_json::to_vec(snapshot)?;
+		let form = Form::new().part("file", Part::bytes(body).file_name(SNAPSHOT_FILE_NAME));
+
+		let response = self
+			.client
+			.post(self.url(ADD_PATH))
+			.query(&[("pin", "true"), ("cid-version", "1")])
+			.multipart(form)
+			.send()
+			.await
+			.map_err(|e| EigenError::RequestError(e.to_string()))?;
+		let body = Self::check_status(response).await?;
+		let added: AddResponse = serde_json::from_str(&body)?;
+
+		Ok(added.hash)
+	}
+
+	/// Loads the snapshot of the given CID.
+	pub async fn load_cid(&self, cid: &str) -> Result<ScoreSnapshot, EigenError> {
+		let response = self
+			.client
+			.post(self.url(CAT_PATH))
+			.query(&[("arg", cid)])
+			.send()
+			.await
+			.map_err(|e| EigenError::RequestError(e.to_string()))?;
+		let body = Self::check_status(response).await?;
+
+		let snapshot: ScoreSnapshot = serde_json::from_str(&body)?;
+		snapshot.check_version()?;
+
+		Ok(snapshot)
+	}
+
+	/// Returns the response body, or an error if the request failed.
+	async fn check_status(response: Response) -> Result<String, EigenError> {
+		let status = response.status();
+		let body = response.text().await.map_err(|e| EigenError::RequestError(e.to_string()))?;
+
+		if !status.is_success() {
+			return Err(EigenError::RequestError(format!(
+				"IPFS API returned {}: {}",
+				status, body
+			)));
+		}
+
+		Ok(body)
+	}
+
+	/// Returns the URL of the given path.
+	fn url(&self, path: &str) -> String {
+		format!("{}{}", self.api_url, path)
+	}
+}
+
+#[async_trait]
+impl AsyncStorage<ScoreSnapshot> for IpfsStorage {
+	type Err = EigenError;
+
+	async fn load_async(&self) -> Result<ScoreSnapshot, Self::Err> {
+		let cid = self.cid.as_deref().ok_or_else(|| {
+			EigenError::ResourceUnavailableError("No snapshot CID to load".to_string())
+		})?;
+
+		self.load_cid(cid).await
+	}
+
+	async fn save_async(&mut self, data: ScoreSnapshot) -> Result<(), Self::Err> {
+		self.cid = Some(self.pin(&data).await?);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{domain::DomainParams, ipfs::*};
+	use std::{
+		io::{Read, Write},
+		net::TcpListener,
+		thread,
+	};
+
+	/// Serves a single HTTP response, returning the server URL.
+	fn serve_once(status: &'static str, body: String) -> String {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let url = format!("http://{}", listener.local_addr().unwrap());
+
+		thread::spawn(move || {
+			let (mut stream, _) = listener.accept().unwrap();
+			let mut request = [0; 4096];
+			let _ = stream.read(&mut request).unwrap();
+
+			let response = format!(
+				"HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+				status,
+				body.len(),
+				body
+			);
+			stream.write_all(response.as_bytes()).unwrap();
+		});
+
+		url
+	}
+
+	#[tokio::test]
+	async fn test_ipfs_storage() {
+		let cid = "bafkreigh2akiscaildcqabsyg3dfr6chu3fgpregiymsck7e7aqa4s52zy";
+		let snapshot = ScoreSnapshot::new(
+			31337,
+			[1; 20],
+			[2; 20],
+			42,
+			DomainParams::new(20, 1000),
+			&[],
+		);
+
+		// Saving keeps the CID of the pinned snapshot
+		let body = format!(
+			"{{\"Name\":\"scores.json\",\"Hash\":\"{}\",\"Size\":\"120\"}}",
+			cid
+		);
+		let mut storage = IpfsStorage::new(&serve_once("200 OK", body));
+		storage.save_async(snapshot.clone()).await.unwrap();
+		assert_eq!(storage.cid(), Some(cid));
+
+		let body = serde_json::to_string(&snapshot).unwrap();
+		let storage = IpfsStorage::new(&serve_once("200 OK", body)).with_cid(cid.to_string());
+		assert_eq!(storage.load_async().await.unwrap(), snapshot);
+
+		// Loading requires a CID
+		assert!(IpfsStorage::new("http://127.0.0.1:1").load_async().await.is_err());
+
+		let storage = IpfsStorage::new(&serve_once("500 Internal Server Error", String::new()));
+		assert!(storage.load_cid(cid).await.is_err());
+	}
+}
@@ -31,25 +31,47 @@
 
 pub mod att_station;
 pub mod attestation;
+pub mod badge;
+pub mod bls;
+pub mod bridge;
 pub mod circuit;
+pub mod domain;
+pub mod eas;
 pub mod error;
 pub mod eth;
+pub mod events;
+pub mod graph;
+#[cfg(feature = "ipfs")]
+pub mod ipfs;
+pub mod manifest;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "sdk")]
+pub mod sdk;
+pub mod simulation;
+pub mod snapshot;
 pub mod storage;
+pub mod transport;
+pub mod waitlist;
 
 use crate::{
 	attestation::{SignatureEth, SignatureRaw, SignedAttestationEth, SignedAttestationScalar},
 	circuit::{ETPublicInputs, OpinionVector, Score},
 };
-use att_station::{
-	AttestationCreatedFilter, AttestationData as ContractAttestationData, AttestationStation,
+use att_station::{AttestationData as ContractAttestationData, AttestationStation};
+use attestation::{
+	build_att_key, AggregationPolicy, AttestationEth, AttestationHistoryEntry, AttestationQuery,
+	AttestationRaw, IndexedAttestation, SignedAttestationRaw, EIP1271_MAGIC_VALUE,
 };
-use attestation::{build_att_key, AttestationEth, AttestationRaw, SignedAttestationRaw};
-use circuit::{Circuit, ETReport, ETSetup, ThPublicInputs, ThReport, ThSetup};
+use circuit::{Circuit, ETReport, ETSetup, ScoreTrace, ThPublicInputs, ThReport, ThSetup};
+use domain::DomainParams;
+use eas::{AttestedFilter, EasAttestation};
 use eigentrust_zk::{
 	circuits::{
 		threshold::native::Threshold, ECDSAPublicKey, EigenTrust4, KZGParams, NativeAggregator4,
-		NativeEigenTrust4, NativeThreshold4, Opinion4, PoseidonNativeHasher, PoseidonNativeSponge,
-		Threshold4, HASHER_WIDTH, MIN_PEER_COUNT, NUM_DECIMAL_LIMBS, NUM_NEIGHBOURS, POWER_OF_TEN,
+		NativeEigenTrust4, NativeShardedEigenTrust, NativeThreshold4, Opinion4,
+		PoseidonNativeHasher, PoseidonNativeSponge, Threshold4, HASHER_WIDTH, MIN_PEER_COUNT,
+		NUM_DECIMAL_LIMBS, NUM_NEIGHBOURS, POWER_OF_TEN,
 	},
 	ecdsa::native::PublicKey,
 	halo2::{
@@ -62,30 +84,41 @@ use eigentrust_zk::{
 		poly::commitment::{CommitmentScheme, Params},
 		SerdeFormat,
 	},
-	utils::{big_to_fe, big_to_fe_rat, fe_to_big, keygen, prove, verify},
+	utils::{big_to_fe, big_to_fe_rat, fe_to_big, keygen, prove, rational_to_fe, verify},
 	verifier::aggregator::native::Snark,
 };
 use error::EigenError;
 use eth::{address_from_ecdsa_key, ecdsa_keypairs_from_mnemonic, scalar_from_address};
 use ethers::{
-	abi::{Address, RawLog},
+	abi::{encode, Address, RawLog, Token},
 	contract::EthEvent,
 	middleware::SignerMiddleware,
-	providers::{Http, Middleware, Provider},
+	providers::{Middleware, Provider},
 	signers::{coins_bip39::English, LocalWallet, MnemonicBuilder, Signer},
-	types::{Log, H160, H256},
+	types::{
+		transaction::eip2718::TypedTransaction, BlockNumber, Filter, Log, TransactionRequest, H160,
+		H256, U256,
+	},
 };
-use log::{debug, info, warn};
+use events::StationLog;
+use graph::{DisconnectedPolicy, GraphExport, SybilReport, TrustGraph};
+use manifest::{ScoreCertificate, ScoringManifest};
 use num_rational::BigRational;
 use rand::thread_rng;
+use rayon::prelude::*;
+use snapshot::StateSnapshot;
 use std::{
-	collections::{BTreeSet, HashMap},
+	collections::{BTreeMap, BTreeSet, HashMap},
 	sync::Arc,
-	time::Instant,
+	time::{Instant, SystemTime, UNIX_EPOCH},
 };
+use storage::{str_to_20_byte_array, ScoreRecord, SyncState};
+use tracing::{debug, info, info_span, instrument, warn};
+use transport::ClientTransport;
+use waitlist::{Admission, Waitlist};
 
 /// Client Signer.
-pub type ClientSigner = SignerMiddleware<Provider<Http>, LocalWallet>;
+pub type ClientSigner = SignerMiddleware<Provider<ClientTransport>, LocalWallet>;
 
 /// Client struct.
 pub struct Client {
@@ -93,6 +126,10 @@ pub struct Client {
 	domain: H160,
 	mnemonic: String,
 	signer: Arc<ClientSigner>,
+	damping: Option<(u128, u128)>,
+	aggregation: AggregationPolicy,
+	params: DomainParams,
+	stakes: BTreeMap<Address, u128>,
 }
 
 impl Client {
@@ -101,9 +138,53 @@ impl Client {
 		mnemonic: String, chain_id: u32, as_address: [u8; 20], domain: [u8; 20], node_url: String,
 	) -> Self {
 		// Setup provider
-		let provider = Provider::<Http>::try_from(&node_url)
+		let transport = ClientTransport::http(&node_url)
 			.expect("Failed to create provider from config node url");
 
+		Self::with_provider(
+			mnemonic,
+			chain_id,
+			as_address,
+			domain,
+			Provider::new(transport),
+		)
+	}
+
+	/// Connects a new Client instance to the given node endpoint, which can either
+	/// be an HTTP(S) URL or an IPC path.
+	pub async fn connect(
+		mnemonic: String, chain_id: u32, as_address: [u8; 20], domain: [u8; 20], endpoint: &str,
+	) -> Result<Self, EigenError> {
+		let transport = ClientTransport::connect(endpoint).await?;
+
+		Ok(Self::with_provider(
+			mnemonic,
+			chain_id,
+			as_address,
+			domain,
+			Provider::new(transport),
+		))
+	}
+
+	/// Creates a Client instance without node connection, which can only sign attestations
+	/// and build their call data, e.g. on an air-gapped machine.
+	pub fn offline(
+		mnemonic: String, chain_id: u32, as_address: [u8; 20], domain: [u8; 20],
+	) -> Self {
+		Self::with_provider(
+			mnemonic,
+			chain_id,
+			as_address,
+			domain,
+			Provider::new(ClientTransport::Offline),
+		)
+	}
+
+	/// Creates a new Client instance using a pre-built provider.
+	pub fn with_provider(
+		mnemonic: String, chain_id: u32, as_address: [u8; 20], domain: [u8; 20],
+		provider: Provider<ClientTransport>,
+	) -> Self {
 		// Setup wallet
 		let wallet = MnemonicBuilder::<English>::default()
 			.phrase(mnemonic.as_str())
@@ -121,16 +202,95 @@ impl Client {
 			mnemonic,
 			as_address: Address::from(as_address),
 			domain: H160::from(domain),
+			damping: None,
+			aggregation: AggregationPolicy::default(),
+			params: DomainParams::circuit(),
+			stakes: BTreeMap::new(),
 		}
 	}
 
+	/// Sets the convergence damping factor, as a (numerator, denominator) fraction in [0, 1).
+	/// Damped scores can't be proven, since the circuit runs undamped iterations.
+	pub fn set_damping(&mut self, numerator: u128, denominator: u128) -> Result<(), EigenError> {
+		if denominator == 0 || numerator >= denominator {
+			return Err(EigenError::ValidationError(
+				"Damping factor must be in [0, 1)".to_string(),
+			));
+		}
+
+		self.damping = if numerator == 0 { None } else { Some((numerator, denominator)) };
+
+		Ok(())
+	}
+
+	/// Gets the convergence damping factor, if any.
+	pub fn damping(&self) -> Option<(u128, u128)> {
+		self.damping
+	}
+
+	/// Sets the aggregation policy of the attestations given by the same attester to the
+	/// same peer. Only scores keeping the latest attestations can be proven.
+	pub fn set_aggregation(&mut self, aggregation: AggregationPolicy) {
+		self.aggregation = aggregation;
+	}
+
+	/// Gets the aggregation policy of the attestations.
+	pub fn aggregation(&self) -> AggregationPolicy {
+		self.aggregation
+	}
+
+	/// Sets the attesters' stakes, such as their on-chain stake or token balance, scaling
+	/// their opinions by their stake relative to the largest one. Attesters without a stake
+	/// aren't scaled. Stake-weighted scores can't be proven.
+	pub fn set_stakes(&mut self, stakes: BTreeMap<Address, u128>) {
+		self.stakes = stakes;
+	}
+
+	/// Gets the attesters' stakes.
+	pub fn stakes(&self) -> &BTreeMap<Address, u128> {
+		&self.stakes
+	}
+
+	/// Sets the scoring parameters of the client's domain, as stored in the domain registry.
+	/// Scores computed with parameters other than the circuit ones can't be proven.
+	pub fn set_domain_params(&mut self, params: DomainParams) -> Result<(), EigenError> {
+		params.validate()?;
+		self.params = params;
+
+		Ok(())
+	}
+
+	/// Gets the scoring parameters of the client's domain.
+	pub fn domain_params(&self) -> DomainParams {
+		self.params
+	}
+
 	/// Gets signer.
 	pub fn get_signer(&self) -> Arc<ClientSigner> {
 		self.signer.clone()
 	}
 
 	/// Submits an attestation to the attestation station.
+	#[instrument(skip_all, fields(about = ?attestation.about))]
 	pub async fn attest(&self, attestation: AttestationRaw) -> Result<(), EigenError> {
+		self.attest_batch(vec![attestation]).await
+	}
+
+	/// Submits the attestations to the attestation station in a single transaction.
+	#[instrument(skip_all, fields(count = attestations.len()))]
+	pub async fn attest_batch(&self, attestations: Vec<AttestationRaw>) -> Result<(), EigenError> {
+		let signed_attestations = attestations
+			.into_iter()
+			.map(|attestation| self.sign_attestation(attestation))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		self.relay(signed_attestations).await
+	}
+
+	/// Signs the attestation off-chain with the client's mnemonic, without submitting it.
+	pub fn sign_attestation(
+		&self, attestation: AttestationRaw,
+	) -> Result<SignedAttestationRaw, EigenError> {
 		let rng = &mut rand::thread_rng();
 		let keypairs = ecdsa_keypairs_from_mnemonic(&self.mnemonic, 1)?;
 
@@ -149,75 +309,614 @@ impl Client {
 
 		let signed_attestation = SignedAttestationEth::new(attestation_eth, signature_eth);
 
-		let as_contract = AttestationStation::new(self.as_address, self.signer.clone());
-
 		// Verify signature is recoverable
 		let recovered_pubkey = signed_attestation.recover_public_key()?;
 		let recovered_address = address_from_ecdsa_key(&recovered_pubkey);
-		assert!(recovered_address == self.signer.address());
+		if recovered_address != address_from_ecdsa_key(&keypairs[0].public_key) {
+			return Err(EigenError::AttestationError(
+				"Signed attestation doesn't recover to the signer".to_string(),
+			));
+		}
 
-		// Stored contract data
-		let (_, about, key, payload) = signed_attestation.to_tx_data()?;
-		let contract_data =
-			ContractAttestationData { about, key: key.to_fixed_bytes(), val: payload };
+		Ok(SignedAttestationRaw::from(signed_attestation))
+	}
 
-		let tx_call = as_contract.attest(vec![contract_data]);
+	/// Submits attestations signed off-chain by their attesters, paying for the transaction
+	/// with the client's signer. Attesters are recovered from the signatures, so they
+	/// don't need any ETH to attest.
+	#[instrument(skip_all, fields(count = signed_attestations.len()))]
+	pub async fn relay(
+		&self, signed_attestations: Vec<SignedAttestationRaw>,
+	) -> Result<(), EigenError> {
+		let contract_data = self.attestation_call_data(signed_attestations)?;
+		let as_contract = AttestationStation::new(self.as_address, self.signer.clone());
+
+		let tx_call = as_contract.attest(contract_data);
 		let tx_res = tx_call.send().await;
-		let tx = tx_res
-			.map_err(|_| EigenError::TransactionError("Transaction send failed".to_string()))?;
-		let res = tx.await.map_err(|_| {
+		let tx = tx_res.map_err(|e| {
+			warn!(error = %e, "Transaction send failed");
+			EigenError::TransactionError("Transaction send failed".to_string())
+		})?;
+		let tx_hash = tx.tx_hash();
+		debug!(?tx_hash, "Transaction sent, waiting for confirmation");
+
+		let res = tx.await.map_err(|e| {
+			warn!(?tx_hash, error = %e, "Transaction resolution failed");
 			EigenError::TransactionError("Transaction resolution failed".to_string())
 		})?;
 
 		if let Some(receipt) = res {
-			info!("Transaction status: {:?}", receipt.status);
+			info!(
+				?tx_hash,
+				status = ?receipt.status,
+				block_number = ?receipt.block_number,
+				gas_used = ?receipt.gas_used,
+				"Transaction confirmed"
+			);
 		}
 
 		Ok(())
 	}
 
-	/// Calculates the EigenTrust global scores.
+	/// Estimates the gas of relaying the signed attestations from the client's signer,
+	/// without submitting them.
+	pub async fn estimate_relay_gas(
+		&self, signed_attestations: Vec<SignedAttestationRaw>,
+	) -> Result<U256, EigenError> {
+		let contract_data = self.attestation_call_data(signed_attestations)?;
+		let as_contract = AttestationStation::new(self.as_address, self.signer.clone());
+
+		as_contract
+			.attest(contract_data)
+			.estimate_gas()
+			.await
+			.map_err(|e| EigenError::TransactionError(format!("Gas estimation failed: {}", e)))
+	}
+
+	/// Returns the call data of the AttestationStation transaction submitting the signed
+	/// attestations, so it can be broadcast from another machine.
+	pub fn attest_call_data(
+		&self, signed_attestations: Vec<SignedAttestationRaw>,
+	) -> Result<Vec<u8>, EigenError> {
+		let contract_data = self.attestation_call_data(signed_attestations)?;
+		let as_contract = AttestationStation::new(self.as_address, self.signer.clone());
+
+		as_contract.attest(contract_data).calldata().map(|data| data.to_vec()).ok_or_else(|| {
+			EigenError::TransactionError("Failed to encode the attestations".to_string())
+		})
+	}
+
+	/// Builds the contract call data of the signed attestations, failing on attestations
+	/// of another domain or with unrecoverable signatures.
+	fn attestation_call_data(
+		&self, signed_attestations: Vec<SignedAttestationRaw>,
+	) -> Result<Vec<ContractAttestationData>, EigenError> {
+		let mut contract_data = Vec::new();
+		for signed_attestation in signed_attestations {
+			if signed_attestation.attestation.domain != self.domain.to_fixed_bytes() {
+				return Err(EigenError::ValidationError(
+					"Relayed attestation domain doesn't match the client domain".to_string(),
+				));
+			}
+
+			// Stored contract data, failing on unrecoverable signatures
+			let signed_attestation_eth = SignedAttestationEth::from(signed_attestation);
+			let (attester, about, key, payload) = signed_attestation_eth.to_tx_data()?;
+			debug!(?attester, ?about, "Relaying attestation");
+
+			contract_data.push(ContractAttestationData {
+				about,
+				key: key.to_fixed_bytes(),
+				val: payload,
+			});
+		}
+
+		Ok(contract_data)
+	}
+
+	/// Calculates the EigenTrust global scores. Participants are ordered by address, and
+	/// sets beyond the circuit capacity are rejected, see `calculate_scores_with_waitlist`.
 	pub fn calculate_scores(
 		&self, att: Vec<SignedAttestationRaw>,
 	) -> Result<Vec<Score>, EigenError> {
+		self.calculate_scores_with_contract_attesters(att, &[])
+	}
+
+	/// Calculates the EigenTrust global scores, using the given contract wallet attester
+	/// of every attestation, if any, as returned by `contract_attesters`.
+	pub fn calculate_scores_with_contract_attesters(
+		&self, att: Vec<SignedAttestationRaw>, contract_attesters: &[Option<[u8; 20]>],
+	) -> Result<Vec<Score>, EigenError> {
+		let et_setup = self.et_circuit_setup_with_attesters(att, contract_attesters)?;
+
+		Ok(Self::build_scores(&et_setup))
+	}
+
+	/// Finds the attestations submitted by contract wallets, whose signatures can't be
+	/// recovered, and verifies them through EIP-1271. Returns the contract attester of every
+	/// attestation, or `None` if it was signed by an externally owned account.
+	/// Unsigned attestations, imported from EAS, are attested by their creator.
+	pub async fn contract_attesters(
+		&self, att: &[SignedAttestationRaw], creators: &[Option<[u8; 20]>],
+	) -> Result<Vec<Option<[u8; 20]>>, EigenError> {
+		let mut is_contract_cache: HashMap<Address, bool> = HashMap::new();
+		let mut attesters = Vec::with_capacity(att.len());
+
+		for (signed_raw, creator) in att.iter().zip(creators) {
+			let creator = match creator {
+				Some(creator) => Address::from(*creator),
+				None => {
+					attesters.push(None);
+					continue;
+				},
+			};
+
+			if signed_raw.is_unsigned() {
+				attesters.push(Some(creator.to_fixed_bytes()));
+				continue;
+			}
+
+			// Attestations signed by their creator are recovered as usual
+			let signed_att = SignedAttestationEth::from(signed_raw.clone());
+			let recovered = signed_att.recover_public_key().map(|pk| address_from_ecdsa_key(&pk));
+			if matches!(recovered, Ok(address) if address == creator) {
+				attesters.push(None);
+				continue;
+			}
+
+			let is_contract = match is_contract_cache.get(&creator) {
+				Some(is_contract) => *is_contract,
+				None => {
+					let code = self
+						.signer
+						.get_code(creator, None)
+						.await
+						.map_err(|e| EigenError::ConnectionError(e.to_string()))?;
+					is_contract_cache.insert(creator, !code.is_empty());
+					!code.is_empty()
+				},
+			};
+
+			if is_contract && self.is_valid_contract_signature(creator, &signed_att).await? {
+				attesters.push(Some(creator.to_fixed_bytes()));
+			} else {
+				attesters.push(None);
+			}
+		}
+
+		Ok(attesters)
+	}
+
+	/// Checks the attestation signature with the EIP-1271 `isValidSignature` method of the
+	/// given contract wallet. The signed message is the big-endian attestation hash.
+	pub async fn is_valid_contract_signature(
+		&self, contract: Address, signed_att: &SignedAttestationEth,
+	) -> Result<bool, EigenError> {
+		let hash = signed_att.eip1271_hash()?;
+		let signature: SignatureRaw = signed_att.signature.clone().into();
+
+		let mut data = EIP1271_MAGIC_VALUE.to_vec();
+		data.extend(encode(&[
+			Token::FixedBytes(hash.as_bytes().to_vec()),
+			Token::Bytes(signature.to_bytes()),
+		]));
+		let tx: TypedTransaction = TransactionRequest::new().to(contract).data(data).into();
+
+		// Wallets reverting the call reject the signature
+		let result = self.signer.call(&tx, None).await;
+
+		Ok(matches!(result, Ok(bytes) if bytes.len() >= 4 && bytes[..4] == EIP1271_MAGIC_VALUE))
+	}
+
+	/// Calculates the EigenTrust global scores of more participants than a set holds, by
+	/// sharding them into sets of the circuit capacity. Participants are ordered by address,
+	/// the latest attestation of every pair wins, and no damping is applied. The sharded
+	/// scores approximate the ones of a single set and can't be proven.
+	pub fn calculate_sharded_scores(
+		&self, att: Vec<SignedAttestationRaw>, contract_attesters: &[Option<[u8; 20]>],
+	) -> Result<Vec<Score>, EigenError> {
+		let attesters = Self::recover_attesters(&att, contract_attesters)?;
+
+		// Latest attestation value of every (origin, destination) pair
+		let mut participants: BTreeSet<Address> = BTreeSet::new();
+		let mut ops: BTreeMap<Address, BTreeMap<Address, u8>> = BTreeMap::new();
+		for (signed_raw, attester) in att.iter().zip(attesters) {
+			let about = Address::from(signed_raw.attestation.about);
+			participants.insert(attester);
+			participants.insert(about);
+			if attester != about {
+				ops.entry(attester).or_default().insert(about, signed_raw.attestation.value);
+			}
+		}
+
+		if participants.len() < MIN_PEER_COUNT {
+			return Err(EigenError::ValidationError(format!(
+				"{} participants are less than the minimum of {}",
+				participants.len(),
+				MIN_PEER_COUNT
+			)));
+		}
+
+		let num_iterations = usize::try_from(self.params.num_iterations)
+			.map_err(|e| EigenError::ConversionError(e.to_string()))?;
+		let mut set = NativeShardedEigenTrust::new(
+			self.get_scalar_domain()?,
+			NUM_NEIGHBOURS,
+			num_iterations,
+			self.params.initial_score,
+		)?;
+
+		let scalars: HashMap<Address, Scalar> = participants
+			.iter()
+			.map(|participant| Ok((*participant, scalar_from_address(participant)?)))
+			.collect::<Result<_, EigenError>>()?;
+		for participant in &participants {
+			set.add_member(scalars[participant])?;
+		}
+		for (origin, op) in ops {
+			let op = op
+				.into_iter()
+				.map(|(about, value)| (scalars[&about], Scalar::from(u64::from(value))))
+				.collect();
+			set.update_op(scalars[&origin], op)?;
+		}
+
+		let scores =
+			info_span!("converge_sharded", shards = set.shards().len()).in_scope(|| {
+				let start_time = Instant::now();
+				let scores = set.converge_rational()?;
+				debug!(elapsed = ?start_time.elapsed(), "Sharded scores converged");
+				Ok::<_, EigenError>(scores)
+			})?;
+
+		Ok(participants
+			.into_iter()
+			.zip(scores)
+			.map(|(participant, score_rat)| {
+				let score_fr = rational_to_fe::<Scalar>(&score_rat);
+				Self::build_score(participant, score_fr, &score_rat)
+			})
+			.collect())
+	}
+
+	/// Calculates the EigenTrust global scores, handling the participants that can't be
+	/// reached from the seed set through positive attestations with the given policy, and
+	/// using the given contract wallet attester of every attestation, if any.
+	/// Only the scores of the seeds' component are covered by an EigenTrust proof.
+	pub fn calculate_scores_from_seeds(
+		&self, att: Vec<SignedAttestationRaw>, contract_attesters: &[Option<[u8; 20]>],
+		seeds: &[[u8; 20]], policy: DisconnectedPolicy,
+	) -> Result<Vec<Score>, EigenError> {
+		let attesters = Self::recover_attesters(&att, contract_attesters)?;
+		let graph = TrustGraph::new(att.iter().zip(attesters.iter()).map(|(signed, attester)| {
+			(
+				*attester,
+				Address::from(signed.attestation.about),
+				signed.attestation.value,
+			)
+		}));
+
+		let seeds: Vec<Address> = seeds.iter().map(|seed| Address::from(*seed)).collect();
+		let connected = graph.reachable(&seeds);
+		let disconnected = graph.disconnected(&seeds);
+
+		if connected.len() < MIN_PEER_COUNT {
+			return Err(EigenError::ValidationError(
+				"Not enough participants connected to the seed set".to_string(),
+			));
+		}
+		if !disconnected.is_empty() {
+			info!(
+				"{} participants are disconnected from the seed set.",
+				disconnected.len()
+			);
+		}
+
+		// Split the attestations by component, dropping the ones across components
+		let (mut connected_att, mut connected_contracts) = (Vec::new(), Vec::new());
+		let (mut disconnected_att, mut disconnected_contracts) = (Vec::new(), Vec::new());
+		let mut separate_participants = BTreeSet::new();
+		for (index, (signed, attester)) in att.into_iter().zip(attesters).enumerate() {
+			let contract = contract_attesters.get(index).copied().flatten();
+			let about = Address::from(signed.attestation.about);
+			match (connected.contains(&attester), connected.contains(&about)) {
+				(true, true) => {
+					connected_att.push(signed);
+					connected_contracts.push(contract);
+				},
+				(false, false) => {
+					separate_participants.insert(attester);
+					separate_participants.insert(about);
+					disconnected_att.push(signed);
+					disconnected_contracts.push(contract);
+				},
+				_ => debug!(?attester, ?about, "Dropping attestation"),
+			}
+		}
+
+		let mut scores =
+			self.calculate_scores_with_contract_attesters(connected_att, &connected_contracts)?;
+
+		match policy {
+			DisconnectedPolicy::Exclude => return Ok(scores),
+			DisconnectedPolicy::ScoreZero => {},
+			DisconnectedPolicy::SeparateRun => {
+				if separate_participants.len() >= MIN_PEER_COUNT {
+					scores.extend(self.calculate_scores_with_contract_attesters(
+						disconnected_att, &disconnected_contracts,
+					)?);
+				} else {
+					warn!("Not enough disconnected participants for a separate run.");
+				}
+			},
+		}
+
+		// Disconnected participants left unscored get a zero score
+		let mut one = [0; 32];
+		one[31] = 1;
+		for participant in disconnected {
+			let address = participant.to_fixed_bytes();
+			if !scores.iter().any(|score| score.address == address) {
+				scores.push(Score {
+					address,
+					score_fr: [0; 32],
+					score_rat: ([0; 32], one),
+					score_hex: [0; 32],
+				});
+			}
+		}
+
+		Ok(scores)
+	}
+
+	/// Calculates the EigenTrust global scores of the participants admitted by the waitlist,
+	/// using the given contract wallet attester of every attestation, if any.
+	/// The attestations of waitlisted participants, given or received, are left out of the
+	/// run until they're admitted. Returns the scores along with the admission.
+	pub fn calculate_scores_with_waitlist(
+		&self, att: Vec<SignedAttestationRaw>, contract_attesters: &[Option<[u8; 20]>],
+		waitlist: &Waitlist,
+	) -> Result<(Vec<Score>, Admission), EigenError> {
+		let attesters = Self::recover_attesters(&att, contract_attesters)?;
+		let pairs: Vec<(Address, Address)> = att
+			.iter()
+			.zip(attesters.iter())
+			.map(|(signed, attester)| (*attester, Address::from(signed.attestation.about)))
+			.collect();
+
+		let admission = waitlist.admit(&pairs);
+		if !admission.waitlisted.is_empty() {
+			info!(
+				waitlisted = admission.waitlisted.len(),
+				policy = ?waitlist.policy(),
+				"Set capacity reached, participants waitlisted."
+			);
+		}
+
+		let (admitted_att, admitted_contracts): (Vec<SignedAttestationRaw>, Vec<_>) = att
+			.into_iter()
+			.zip(pairs)
+			.enumerate()
+			.filter(|(_, (_, (attester, about)))| {
+				admission.is_admitted(attester) && admission.is_admitted(about)
+			})
+			.map(|(index, (signed, _))| (signed, contract_attesters.get(index).copied().flatten()))
+			.unzip();
+
+		let scores =
+			self.calculate_scores_with_contract_attesters(admitted_att, &admitted_contracts)?;
+
+		Ok((scores, admission))
+	}
+
+	/// Replays the scoring run described by the manifest over the given attestations,
+	/// keeping the participants' scores of every convergence iteration.
+	pub fn replay_scores(
+		&self, att: Vec<SignedAttestationRaw>, manifest: &ScoringManifest,
+	) -> Result<ScoreTrace, EigenError> {
+		// The native set only replays runs made with the client's parameters
+		let expected = self.manifest(&[]);
+		if manifest.domain != expected.domain {
+			return Err(EigenError::ValidationError(
+				"Manifest domain does not match the client domain".to_string(),
+			));
+		}
+		manifest.check_params(&expected)?;
+
 		let et_setup = self.et_circuit_setup(att)?;
 
-		// Construct scores vec
-		let scores: Vec<Score> = et_setup
+		// Check the replayed scores against the manifest
+		let scores = Self::build_scores(&et_setup);
+		let matches_manifest = self.manifest(&scores) == *manifest;
+		if !matches_manifest {
+			warn!("Replayed scores do not match the manifest scores");
+		}
+
+		Ok(ScoreTrace::new(
+			et_setup.address_set, et_setup.score_trace, matches_manifest,
+		))
+	}
+
+	/// Returns the manifest of the given scores, computed with the client's parameters.
+	pub fn manifest(&self, scores: &[Score]) -> ScoringManifest {
+		ScoringManifest::new(self.domain.to_fixed_bytes(), scores)
+			.with_damping(self.damping)
+			.with_aggregation(self.aggregation)
+			.with_stakes(
+				self.stakes
+					.iter()
+					.map(|(address, stake)| (address.to_fixed_bytes(), *stake))
+					.collect(),
+			)
+			.with_params(self.params)
+	}
+
+	/// Signs a certificate of every score with the client's signer, binding it to the epoch
+	/// and the client's scoring parameters.
+	pub fn certify_scores(
+		&self, scores: &[Score], epoch: u64,
+	) -> Result<Vec<ScoreCertificate>, EigenError> {
+		let params_hash = self.manifest(&[]).params_hash();
+		scores
+			.iter()
+			.map(|score| ScoreCertificate::sign(self.signer.signer(), epoch, score, params_hash))
+			.collect()
+	}
+
+	/// Checks that the client's scores of the attestations with the given contract wallet
+	/// attesters, as returned by `contract_attesters`, can be proven by the circuit.
+	fn ensure_provable(&self, contract_attesters: &[Option<[u8; 20]>]) -> Result<(), EigenError> {
+		if contract_attesters.iter().any(Option::is_some) {
+			return Err(EigenError::ProvingError(
+				"Attestations of contract wallets or imported from EAS can't be proven by the \
+				 EigenTrust circuit"
+					.to_string(),
+			));
+		}
+		if self.damping.is_some() {
+			return Err(EigenError::ProvingError(
+				"Damped scores can't be proven by the EigenTrust circuit".to_string(),
+			));
+		}
+		if self.aggregation != AggregationPolicy::LatestWins {
+			return Err(EigenError::ProvingError(format!(
+				"Scores aggregated with the {:?} policy can't be proven by the EigenTrust circuit",
+				self.aggregation
+			)));
+		}
+		if !self.stakes.is_empty() {
+			return Err(EigenError::ProvingError(
+				"Stake-weighted scores can't be proven by the EigenTrust circuit".to_string(),
+			));
+		}
+		if !self.params.is_circuit() {
+			return Err(EigenError::ProvingError(format!(
+				"Domain parameters {:?} don't match the EigenTrust circuit parameters {:?}",
+				self.params,
+				DomainParams::circuit()
+			)));
+		}
+
+		Ok(())
+	}
+
+	/// Builds the attestation graph export, using the given contract wallet attester of
+	/// every attestation, if any, as returned by `contract_attesters`, and the score
+	/// records of the participants.
+	pub fn attestation_graph(
+		att: &[SignedAttestationRaw], contract_attesters: &[Option<[u8; 20]>],
+		scores: &[ScoreRecord],
+	) -> Result<GraphExport, EigenError> {
+		Ok(GraphExport::new(
+			Self::attestation_edges(att, contract_attesters)?,
+			scores,
+		))
+	}
+
+	/// Reports the potential sybil rings of the attestation graph: the clusters keeping at
+	/// least `min_internal_share` of their members' trust among themselves, see
+	/// `TrustGraph::sybil_report`. Uses the given contract wallet attester of every
+	/// attestation, if any, as returned by `contract_attesters`.
+	pub fn sybil_report(
+		att: &[SignedAttestationRaw], contract_attesters: &[Option<[u8; 20]>],
+		min_internal_share: f64,
+	) -> Result<SybilReport, EigenError> {
+		let graph = TrustGraph::new(Self::attestation_edges(att, contract_attesters)?);
+		Ok(graph.sybil_report(min_internal_share))
+	}
+
+	/// Returns the (attester, about, value) triples of the attestations, using the given
+	/// contract wallet attester of every attestation, if any.
+	fn attestation_edges(
+		att: &[SignedAttestationRaw], contract_attesters: &[Option<[u8; 20]>],
+	) -> Result<Vec<(Address, Address, u8)>, EigenError> {
+		// Contract wallet signatures can't be recovered
+		att.iter()
+			.zip(contract_attesters.iter().chain(std::iter::repeat(&None)))
+			.map(|(signed_raw, contract_attester)| {
+				let attester = match contract_attester {
+					Some(contract_attester) => Address::from(*contract_attester),
+					None => {
+						let signed_att = SignedAttestationEth::from(signed_raw.clone());
+						address_from_ecdsa_key(&signed_att.recover_public_key()?)
+					},
+				};
+				let attestation = &signed_raw.attestation;
+
+				Ok((
+					attester,
+					Address::from(attestation.about),
+					attestation.value,
+				))
+			})
+			.collect()
+	}
+
+	/// Recovers the attesters' addresses of the given attestations, using the given contract
+	/// wallet attester of every attestation, if any, as returned by `contract_attesters`.
+	#[instrument(skip_all, fields(attestations = att.len()))]
+	fn recover_attesters(
+		att: &[SignedAttestationRaw], contract_attesters: &[Option<[u8; 20]>],
+	) -> Result<Vec<Address>, EigenError> {
+		att.par_iter()
+			.enumerate()
+			.map(
+				|(index, signed_raw)| match contract_attesters.get(index).copied().flatten() {
+					Some(contract) => Ok(Address::from(contract)),
+					None => {
+						let signed_att = SignedAttestationEth::from(signed_raw.clone());
+						let pub_key = signed_att.recover_public_key()?;
+						Ok(address_from_ecdsa_key(&pub_key))
+					},
+				},
+			)
+			.collect()
+	}
+
+	/// Builds the participants' scores from the circuit setup.
+	fn build_scores(et_setup: &ETSetup) -> Vec<Score> {
+		et_setup
 			.address_set
 			.iter()
 			.zip(et_setup.pub_inputs.scores.iter())
 			.zip(et_setup.rational_scores.iter())
 			.map(|((&participant, &score_fr), score_rat)| {
-				let address = participant.to_fixed_bytes();
+				Self::build_score(participant, score_fr, score_rat)
+			})
+			.collect()
+	}
 
-				let mut scalar = score_fr.to_bytes();
-				scalar.reverse();
+	/// Builds the score of a participant from its field and rational values.
+	fn build_score(participant: Address, score_fr: Scalar, score_rat: &BigRational) -> Score {
+		let address = participant.to_fixed_bytes();
 
-				let num_bytes = score_rat.numer().to_bytes_be().1;
-				let den_bytes = score_rat.denom().to_bytes_be().1;
-				let score_bytes = score_rat.to_integer().to_bytes_be().1;
+		let mut scalar = score_fr.to_bytes();
+		scalar.reverse();
 
-				let mut numerator: [u8; 32] = [0; 32];
-				numerator[32 - num_bytes.len()..].copy_from_slice(&num_bytes);
+		let num_bytes = score_rat.numer().to_bytes_be().1;
+		let den_bytes = score_rat.denom().to_bytes_be().1;
+		let score_bytes = score_rat.to_integer().to_bytes_be().1;
 
-				let mut denominator: [u8; 32] = [0; 32];
-				denominator[32 - den_bytes.len()..].copy_from_slice(&den_bytes);
+		let mut numerator: [u8; 32] = [0; 32];
+		numerator[32 - num_bytes.len()..].copy_from_slice(&num_bytes);
 
-				let mut score_hex: [u8; 32] = [0; 32];
-				score_hex[32 - score_bytes.len()..].copy_from_slice(&score_bytes);
+		let mut denominator: [u8; 32] = [0; 32];
+		denominator[32 - den_bytes.len()..].copy_from_slice(&den_bytes);
 
-				Score { address, score_fr: scalar, score_rat: (numerator, denominator), score_hex }
-			})
-			.collect();
+		let mut score_hex: [u8; 32] = [0; 32];
+		score_hex[32 - score_bytes.len()..].copy_from_slice(&score_bytes);
 
-		Ok(scores)
+		Score { address, score_fr: scalar, score_rat: (numerator, denominator), score_hex }
 	}
 
-	/// Generates an EigenTrust circuit proof.
+	/// Generates an EigenTrust circuit proof, given the contract wallet attester of every
+	/// attestation, if any, as returned by `contract_attesters`. The proof is rejected when
+	/// any attestation comes from a contract wallet, as the circuit can't verify it.
 	pub fn generate_et_proof(
-		&self, att: Vec<SignedAttestationRaw>, raw_kzg_params: Vec<u8>, raw_prov_key: Vec<u8>,
+		&self, att: Vec<SignedAttestationRaw>, contract_attesters: &[Option<[u8; 20]>],
+		raw_kzg_params: Vec<u8>, raw_prov_key: Vec<u8>,
 	) -> Result<ETReport, EigenError> {
+		self.ensure_provable(contract_attesters)?;
 		let rng = &mut rand::thread_rng();
 		let et_setup = self.et_circuit_setup(att)?;
 
@@ -239,12 +938,15 @@ impl Client {
 		Ok(ETReport { pub_inputs: et_setup.pub_inputs, proof })
 	}
 
-	/// Generates Threshold circuit proof for the selected participant.
+	/// Generates Threshold circuit proof for the selected participant, given the contract
+	/// wallet attester of every attestation, if any, as for `generate_et_proof`.
+	#[allow(clippy::too_many_arguments)]
 	pub fn generate_th_proof(
-		&self, att: Vec<SignedAttestationRaw>, raw_et_kzg_params: Vec<u8>,
-		raw_th_kzg_params: Vec<u8>, raw_proving_key: Vec<u8>, threshold: u32,
-		participant: [u8; 20],
+		&self, att: Vec<SignedAttestationRaw>, contract_attesters: &[Option<[u8; 20]>],
+		raw_et_kzg_params: Vec<u8>, raw_th_kzg_params: Vec<u8>, raw_proving_key: Vec<u8>,
+		threshold: u32, participant: [u8; 20],
 	) -> Result<ThReport, EigenError> {
+		self.ensure_provable(contract_attesters)?;
 		let rng = &mut thread_rng();
 		let th_setup = self.th_circuit_setup(att, raw_et_kzg_params, threshold, participant)?;
 
@@ -308,39 +1010,80 @@ impl Client {
 
 	/// Returns a built eigen trust circuit and relevant circuit data.
 	pub fn et_circuit_setup(&self, att: Vec<SignedAttestationRaw>) -> Result<ETSetup, EigenError> {
+		self.et_circuit_setup_with_attesters(att, &[])
+	}
+
+	/// Returns a built eigen trust circuit and relevant circuit data, using the given
+	/// contract wallet attesters, verified through EIP-1271, instead of recovering the
+	/// attesters' public keys. Contract attesters' opinions aren't covered by the circuit.
+	#[instrument(skip_all, fields(attestations = att.len()))]
+	fn et_circuit_setup_with_attesters(
+		&self, att: Vec<SignedAttestationRaw>, contract_attesters: &[Option<[u8; 20]>],
+	) -> Result<ETSetup, EigenError> {
 		// Get signed attestations
 		let attestations: Vec<SignedAttestationEth> =
 			att.into_iter().map(|signed_raw| signed_raw.into()).collect();
 
+		// Recover the attesters' public keys and build the scalar attestations in parallel
+		let recovered: Vec<(Address, Option<ECDSAPublicKey>, SignedAttestationScalar)> =
+			attestations
+				.par_iter()
+				.enumerate()
+				.map(|(index, signed_att)| {
+					let scalar_att: SignedAttestationScalar =
+						signed_att.to_signed_signature_fr()?;
+
+					// Contract wallets have no public key, their address is used directly
+					if let Some(contract) = contract_attesters.get(index).copied().flatten() {
+						return Ok((Address::from(contract), None, scalar_att));
+					}
+
+					let pub_key: ECDSAPublicKey = signed_att.recover_public_key()?;
+					Ok((address_from_ecdsa_key(&pub_key), Some(pub_key), scalar_att))
+				})
+				.collect::<Result<Vec<_>, EigenError>>()?;
+
 		// Initialize set to get participants
 		let mut btree_set: BTreeSet<Address> = BTreeSet::new();
 
 		// Create (Address -> ECDSA Public Key) map
 		let mut pub_key_map = HashMap::new();
 
-		for signed_att in &attestations {
-			let pub_key: ECDSAPublicKey = signed_att.recover_public_key()?;
-			let att_origin: Address = address_from_ecdsa_key(&pub_key);
+		// Contract attesters set
+		let mut contract_set: BTreeSet<Address> = BTreeSet::new();
 
-			pub_key_map.insert(att_origin, pub_key);
+		for (signed_att, (att_origin, pub_key, _)) in attestations.iter().zip(recovered.iter()) {
+			if let Some(pub_key) = pub_key {
+				pub_key_map.insert(*att_origin, pub_key.clone());
+			} else {
+				contract_set.insert(*att_origin);
+			}
 			btree_set.insert(signed_att.attestation.about);
-			btree_set.insert(att_origin);
+			btree_set.insert(*att_origin);
 		}
 
 		// Build participants set
-		// The participants' set order defines the attestations' order
+		// The participants' set is sorted by address, its order defines the attestations' order
 		let address_set: Vec<Address> = btree_set.clone().into_iter().collect();
+		let set_index: HashMap<Address, usize> =
+			address_set.iter().enumerate().map(|(index, address)| (*address, index)).collect();
 
 		// Verify that the participants set is not larger than the maximum number of participants
-		assert!(
-			address_set.len() <= NUM_NEIGHBOURS,
-			"Number of participants exceeds maximum number of neighbours"
-		);
+		if address_set.len() > NUM_NEIGHBOURS {
+			return Err(EigenError::ValidationError(format!(
+				"{} participants exceed the set capacity of {}, use a waitlist to admit them",
+				address_set.len(),
+				NUM_NEIGHBOURS
+			)));
+		}
 		// Verify that the number of participants is greater than the minimum number of participants
-		assert!(
-			address_set.len() >= MIN_PEER_COUNT,
-			"Number of participants is less than the minimum number of neighbours"
-		);
+		if address_set.len() < MIN_PEER_COUNT {
+			return Err(EigenError::ValidationError(format!(
+				"{} participants are less than the minimum of {}",
+				address_set.len(),
+				MIN_PEER_COUNT
+			)));
+		}
 
 		// Build Scalar set
 		let mut scalar_set: Vec<Scalar> = btree_set
@@ -372,18 +1115,20 @@ impl Client {
 		let mut attestation_matrix: Vec<OpinionVector> =
 			vec![vec![None; NUM_NEIGHBOURS]; NUM_NEIGHBOURS];
 
-		// Populate the attestation matrix with the attestations data
-		for signed_att in &attestations {
-			let pub_key: ECDSAPublicKey = signed_att.recover_public_key()?;
-			let att_origin: Address = address_from_ecdsa_key(&pub_key);
+		// Attestation values of every (origin, destination) pair, in attestation order
+		let mut pair_values: BTreeMap<(usize, usize), Vec<u8>> = BTreeMap::new();
 
+		// Populate the attestation matrix with the attestations data
+		for (signed_att, (att_origin, _, scalar_att)) in attestations.iter().zip(recovered) {
 			// Get attestation origin and destination indexes in the set
-			let origin_index = address_set.iter().position(|&r| r == att_origin).unwrap();
-			let dest_index =
-				address_set.iter().position(|&r| r == signed_att.attestation.about).unwrap();
-
-			// Get scalar signed attestations
-			let scalar_att: SignedAttestationScalar = signed_att.to_signed_signature_fr()?;
+			let origin_index = set_index[&att_origin];
+			let dest_index = set_index[&signed_att.attestation.about];
+			if origin_index != dest_index {
+				pair_values
+					.entry((origin_index, dest_index))
+					.or_default()
+					.push(signed_att.attestation.value);
+			}
 
 			// Fill matrix
 			attestation_matrix[origin_index][dest_index] = Some(scalar_att);
@@ -398,18 +1143,33 @@ impl Client {
 			//  Hence, we add the self-attestation here, for temporary patch.
 			//
 			let self_scalar_att = Some(SignedAttestationScalar::empty_with_about(
-				scalar_from_address(&att_origin).unwrap(),
-				scalar_domain,
+				scalar_set[origin_index], scalar_domain,
 			));
 			attestation_matrix[origin_index][origin_index] = self_scalar_att;
 		}
 
 		// Initialize Native Set
 		let mut native_et = NativeEigenTrust4::new(scalar_domain);
+		if !self.params.is_circuit() {
+			let num_iterations = usize::try_from(self.params.num_iterations)
+				.map_err(|e| EigenError::ConversionError(e.to_string()))?;
+			native_et.set_params(num_iterations, self.params.initial_score)?;
+		}
 
 		// Add participants to native set
 		for i in 0..address_set.len() {
-			native_et.add_member(scalar_set[i]);
+			native_et.add_member(scalar_set[i])?;
+		}
+
+		if let Some((numerator, denominator)) = self.damping {
+			native_et.set_damping(numerator, denominator)?;
+		}
+
+		// Weigh the opinions of the staked participants
+		for (index, participant) in address_set.iter().enumerate() {
+			if let Some(stake) = self.stakes.get(participant) {
+				native_et.set_stake(scalar_set[index], *stake)?;
+			}
 		}
 
 		// Declare defaults
@@ -427,7 +1187,7 @@ impl Client {
 		for (origin_index, member) in address_set.clone().into_iter().enumerate() {
 			if let Some(pub_key) = pub_key_map.get(&member) {
 				let opinion = attestation_matrix[origin_index].clone();
-				op_hashes.push(native_et.update_op(pub_key.clone(), opinion.clone()));
+				op_hashes.push(native_et.update_op(pub_key.clone(), opinion.clone())?);
 			} else {
 				let op = Opinion4::new(PublicKey::default(), default_op.clone(), scalar_domain);
 				let (_, _, op_hash) = op.validate(default_scalar_set.clone());
@@ -435,26 +1195,80 @@ impl Client {
 			}
 		}
 
+		// Submit the contract attesters' opinions, already verified through EIP-1271
+		for (origin_index, member) in address_set.iter().enumerate() {
+			if !contract_set.contains(member) {
+				continue;
+			}
+
+			let scores = attestation_matrix[origin_index]
+				.iter()
+				.enumerate()
+				.map(|(dest_index, att)| match att {
+					Some(att) if dest_index != origin_index => att.attestation.value,
+					_ => Scalar::zero(),
+				})
+				.collect();
+			native_et.update_verified_op(scalar_set[origin_index], scores)?;
+		}
+
+		// Replace the latest attestations by their aggregate, after the signatures were checked
+		if self.aggregation != AggregationPolicy::LatestWins {
+			let mut aggregated: BTreeMap<usize, Vec<Scalar>> = BTreeMap::new();
+			for ((origin_index, dest_index), values) in &pair_values {
+				let value = u64::from(self.aggregation.aggregate(values));
+				let scores = aggregated
+					.entry(*origin_index)
+					.or_insert_with(|| vec![Scalar::zero(); NUM_NEIGHBOURS]);
+				scores[*dest_index] = Scalar::from(value);
+			}
+
+			for (origin_index, scores) in aggregated {
+				native_et.update_verified_op(scalar_set[origin_index], scores)?;
+			}
+		}
+
+		// Report the participants whose score is distributed evenly, without any valid opinion
+		let empty_opinions: Vec<Address> = native_et
+			.empty_opinions()
+			.iter()
+			.filter_map(|addr| scalar_set.iter().position(|x| x == addr))
+			.map(|i| address_set[i])
+			.collect();
+		if !empty_opinions.is_empty() {
+			info!(
+				participants = ?empty_opinions,
+				"Participants without any valid opinion score distribute theirs evenly"
+			);
+		}
+
 		// Generate opinions' sponge hash.
 		let mut sponge = PoseidonNativeSponge::new();
 		sponge.update(&op_hashes);
 		let opinions_hash = sponge.squeeze();
 
 		// Calculate scores
-		let rational_scores = native_et.converge_rational();
-		let scalar_scores: Vec<Scalar> = native_et.converge();
+		let (score_trace, scalar_scores) = info_span!("converge").in_scope(|| {
+			let start_time = Instant::now();
+			let (scalar_scores, score_trace) = native_et.converge_with_rational_trace()?;
+			debug!(elapsed = ?start_time.elapsed(), "Scores converged");
+
+			Ok::<_, EigenError>((score_trace, scalar_scores))
+		})?;
+		let rational_scores = score_trace.last().cloned().unwrap_or_default();
 
 		// Verify that the scores vectors are of equal length
-		assert_eq!(
-			scalar_scores.len(),
-			rational_scores.len(),
-			"Scores vectors are not of equal length"
-		);
+		if scalar_scores.len() != rational_scores.len() {
+			return Err(EigenError::ValidationError(
+				"Scores vectors are not of equal length".to_string(),
+			));
+		}
 		// Verify that the scores vector is at least as long as the participants vector
-		assert!(
-			scalar_scores.len() >= address_set.len(),
-			"There are more participants than scores"
-		);
+		if scalar_scores.len() < address_set.len() {
+			return Err(EigenError::ValidationError(
+				"There are more participants than scores".to_string(),
+			));
+		}
 
 		// Build public inputs
 		let pub_inputs =
@@ -469,6 +1283,7 @@ impl Client {
 
 		Ok(ETSetup::new(
 			address_set, attestation_matrix, circuit, ecdsa_pub_keys, pub_inputs, rational_scores,
+			score_trace,
 		))
 	}
 
@@ -544,6 +1359,7 @@ impl Client {
 	pub fn generate_et_pk(
 		&self, att: Vec<SignedAttestationRaw>, raw_et_kzg_params: Vec<u8>,
 	) -> Result<Vec<u8>, EigenError> {
+		self.ensure_provable(&[])?;
 		let kzg_params = KZGParams::read_params(&mut raw_et_kzg_params.as_slice())
 			.map_err(|e| EigenError::ReadWriteError(format!("Failed to read KZG params: {}", e)))?;
 		let et_setup: ETSetup = self.et_circuit_setup(att)?;
@@ -555,7 +1371,7 @@ impl Client {
 		let elapsed_time = start_time.elapsed();
 
 		info!("Proving key generated.");
-		debug!("Proving key generation time: {:?}", elapsed_time);
+		debug!(?elapsed_time, "Proving key generation time");
 
 		Ok(proving_key.to_bytes(SerdeFormat::Processed))
 	}
@@ -565,6 +1381,7 @@ impl Client {
 		&self, att: Vec<SignedAttestationRaw>, raw_et_kzg_params: Vec<u8>,
 		raw_th_kzg_params: Vec<u8>,
 	) -> Result<Vec<u8>, EigenError> {
+		self.ensure_provable(&[])?;
 		let th_kzg_params =
 			KZGParams::read_params(&mut raw_th_kzg_params.as_slice()).map_err(|e| {
 				EigenError::ReadWriteError(format!("Failed to read TH KZG params: {}", e))
@@ -582,7 +1399,7 @@ impl Client {
 
 		let elapsed_time = start_time.elapsed();
 		info!("Proving key generated.");
-		debug!("Proving key generation time: {:?}", elapsed_time);
+		debug!(?elapsed_time, "Proving key generation time");
 
 		Ok(proving_key.to_bytes(SerdeFormat::Processed))
 	}
@@ -596,7 +1413,7 @@ impl Client {
 		let elapsed_time = start_time.elapsed();
 
 		info!("KZG parameters generated.");
-		debug!("KZG parameters generation time: {:?}", elapsed_time);
+		debug!(?elapsed_time, "KZG parameters generation time");
 
 		let mut buffer: Vec<u8> = Vec::new();
 		params.write(&mut buffer).map_err(|e| {
@@ -608,53 +1425,379 @@ impl Client {
 
 	/// Fetches attestations from the contract.
 	pub async fn get_attestations(&self) -> Result<Vec<SignedAttestationRaw>, EigenError> {
-		let att_logs: Result<Vec<AttestationCreatedFilter>, EigenError> = self
-			.get_logs()
-			.await?
-			.iter()
-			.map(|log| {
-				let raw_log = RawLog::from((log.topics.clone(), log.data.to_vec()));
-				AttestationCreatedFilter::decode_log(&raw_log)
-					.map_err(|e| EigenError::ParsingError(e.to_string()))
-			})
-			.collect();
+		let indexed_attestations = self.get_indexed_attestations(0).await?;
 
-		// Convert logs into signed attestations
-		let signed_attestations: Result<Vec<SignedAttestationRaw>, _> = att_logs?
-			.into_iter()
-			.map(|log| {
-				let att_raw: AttestationRaw = log.clone().try_into()?;
-				let sig_raw: SignatureRaw = log.try_into()?;
-				Ok(SignedAttestationRaw::new(att_raw, sig_raw))
-			})
-			.collect();
+		Ok(indexed_attestations.into_iter().map(SignedAttestationRaw::from).collect())
+	}
+
+	/// Fetches attestations from the contract starting at the given block,
+	/// along with the blocks that included them.
+	#[instrument(skip(self))]
+	pub async fn get_indexed_attestations(
+		&self, from_block: u64,
+	) -> Result<Vec<IndexedAttestation>, EigenError> {
+		let logs = self.get_logs_from(from_block).await?;
 
-		signed_attestations
+		Ok(Self::decode_attestations(&logs))
 	}
 
-	/// Fetches "AttestationCreated" event logs from the contract, filtered by domain.
-	pub async fn get_logs(&self) -> Result<Vec<Log>, EigenError> {
-		let as_contract = AttestationStation::new(self.as_address, self.get_signer());
+	/// Fetches the attestations submitted after the last block scanned by the given sync
+	/// state, or from the first block if there is none or it was saved for another network,
+	/// contract or domain. Returns them along with the updated state, whose last scanned
+	/// block is the latest block at the time of the call.
+	#[instrument(skip_all, fields(last_block = state.map(SyncState::last_block)))]
+	pub async fn get_attestations_since(
+		&self, state: Option<&SyncState>,
+	) -> Result<(Vec<IndexedAttestation>, SyncState), EigenError> {
+		let matches_client = |state: &SyncState| {
+			state.matches(
+				self.signer.signer().chain_id(),
+				self.as_address.to_fixed_bytes(),
+				self.domain.to_fixed_bytes(),
+			)
+		};
+		let from_block = match state {
+			Some(state) if matches_client(state) => state.last_block() + 1,
+			Some(_) => {
+				warn!("Sync state saved for another network, contract or domain, rescanning");
+				0
+			},
+			None => 0,
+		};
+		let latest_block = self
+			.signer
+			.get_block_number()
+			.await
+			.map_err(|e| EigenError::ConnectionError(e.to_string()))?
+			.as_u64();
+
+		let attestations = if from_block > latest_block {
+			Vec::new()
+		} else {
+			let filter = self.attestation_filter(from_block).to_block(latest_block);
+			Self::decode_attestations(&self.fetch_logs(&filter).await?)
+		};
 
-		// Set filter
-		let filter = as_contract
-			.attestation_created_filter()
-			.filter
-			.topic3(build_att_key(self.domain))
-			.from_block(0);
+		Ok((attestations, self.sync_state(latest_block)))
+	}
 
-		// Fetch logs matching the filter.
-		self.signer.get_logs(&filter).await.map_err(|e| EigenError::ParsingError(e.to_string()))
+	/// Returns the sync state of the client's network, contract and domain, scanned up to
+	/// the given block.
+	pub fn sync_state(&self, last_block: u64) -> SyncState {
+		SyncState::new(
+			self.signer.signer().chain_id(),
+			self.as_address.to_fixed_bytes(),
+			self.domain.to_fixed_bytes(),
+			last_block,
+		)
 	}
 
-	/// Gets the domain as BN256 scalar.
-	pub fn get_scalar_domain(&self) -> Result<Scalar, EigenError> {
-		let domain_bytes_256 = H256::from(self.domain);
+	/// Fetches the attestations selected by the query, filtering the logs by their
+	/// indexed topics and block range on the node.
+	#[instrument(skip(self))]
+	pub async fn query_attestations(
+		&self, query: &AttestationQuery,
+	) -> Result<Vec<IndexedAttestation>, EigenError> {
+		query.validate()?;
+
+		let mut filter = self.attestation_filter(query.from_block);
+		if let Some(creator) = query.creator {
+			filter = filter.topic1(H256::from(Address::from(creator)));
+		}
+		if let Some(about) = query.about {
+			filter = filter.topic2(H256::from(Address::from(about)));
+		}
+		if let Some(to_block) = query.to_block {
+			filter = filter.to_block(to_block);
+		}
+		let logs = self.fetch_logs(&filter).await?;
 
-		let mut domain = *domain_bytes_256.as_fixed_bytes();
-		domain.reverse();
+		Ok(Self::decode_attestations(&logs))
+	}
 
-		let domain_opt = Scalar::from_bytes(&domain);
+	/// Fetches the active attestations of the given schema from the EAS registry at the
+	/// given address, converted into unsigned attestations of the client's domain.
+	/// Attestations whose schema data doesn't hold a score are skipped.
+	#[instrument(skip(self))]
+	pub async fn get_eas_attestations(
+		&self, eas_address: [u8; 20], schema: [u8; 32], from_block: u64,
+	) -> Result<Vec<IndexedAttestation>, EigenError> {
+		let eas_address = Address::from(eas_address);
+		let filter = Filter::new()
+			.address(eas_address)
+			.topic0(AttestedFilter::signature())
+			.topic3(H256::from(schema))
+			.from_block(from_block);
+		let logs = self.fetch_logs(&filter).await?;
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+		let mut attestations = Vec::new();
+		for log in logs {
+			let raw_log = RawLog::from((log.topics.clone(), log.data.to_vec()));
+			let event = AttestedFilter::decode_log(&raw_log)
+				.map_err(|e| EigenError::ParsingError(format!("Invalid EAS event: {}", e)))?;
+			let block_number = log.block_number.unwrap_or_default().as_u64();
+			let block_hash = log.block_hash.unwrap_or_default().to_fixed_bytes();
+			let transaction = log
+				.transaction_hash
+				.zip(log.log_index)
+				.map(|(hash, index)| (hash.to_fixed_bytes(), index.low_u64()));
+
+			// The event doesn't carry the attestation data
+			let tx: TypedTransaction = TransactionRequest::new()
+				.to(eas_address)
+				.data(EasAttestation::call_data(event.uid))
+				.into();
+			let output = self
+				.signer
+				.call(&tx, None)
+				.await
+				.map_err(|e| EigenError::ContractError(e.to_string()))?;
+			let eas_attestation = EasAttestation::decode(&output)?;
+			if !eas_attestation.is_active(now) {
+				debug!(block_number, "Skipping revoked or expired EAS attestation");
+				continue;
+			}
+
+			match eas_attestation.to_indexed_attestation(
+				self.domain.to_fixed_bytes(),
+				block_number,
+				block_hash,
+			) {
+				Ok(indexed) => attestations.push(match transaction {
+					Some((hash, index)) => indexed.with_transaction(hash, index),
+					None => indexed,
+				}),
+				Err(e) => {
+					warn!(block_number, error = ?e, "Skipping EAS attestation without a score")
+				},
+			}
+		}
+
+		Ok(attestations)
+	}
+
+	/// Fetches the attestations about the given address, or submitted by it, using the
+	/// indexed event topics instead of scanning all logs.
+	/// Attestations submitted by a relayer are only found through their `about` address.
+	#[instrument(skip(self), fields(address = ?Address::from(address)))]
+	pub async fn get_attestations_for(
+		&self, address: [u8; 20],
+	) -> Result<Vec<IndexedAttestation>, EigenError> {
+		let topic = H256::from(Address::from(address));
+		let mut logs = self.fetch_logs(&self.attestation_filter(0).topic2(topic)).await?;
+		logs.extend(self.fetch_logs(&self.attestation_filter(0).topic1(topic)).await?);
+
+		// Self-attestations match both queries
+		logs.sort_by_key(|log| (log.block_number, log.log_index));
+		logs.dedup_by_key(|log| (log.transaction_hash, log.log_index));
+
+		Ok(Self::decode_attestations(&logs))
+	}
+
+	/// Fetches the attestations about the given address.
+	#[instrument(skip(self), fields(address = ?Address::from(address)))]
+	pub async fn get_attestations_about(
+		&self, address: [u8; 20],
+	) -> Result<Vec<IndexedAttestation>, EigenError> {
+		let topic = H256::from(Address::from(address));
+		let logs = self.fetch_logs(&self.attestation_filter(0).topic2(topic)).await?;
+
+		Ok(Self::decode_attestations(&logs))
+	}
+
+	/// Decodes the attestations of the given logs, skipping other events.
+	fn decode_attestations(logs: &[Log]) -> Vec<IndexedAttestation> {
+		let _span = info_span!("decode_logs", logs = logs.len()).entered();
+		let mut attestations = Vec::new();
+		for log in logs {
+			let station_log = StationLog::decode(log);
+			match station_log.to_indexed_attestation() {
+				Some(Ok(indexed)) => attestations.push(indexed),
+				// Malformed attestations can't be scored, but shouldn't fail the fetch
+				Some(Err(e)) => warn!(
+					block_number = station_log.block_number(),
+					error = ?e,
+					"Skipping malformed attestation"
+				),
+				None => debug!(
+					block_number = station_log.block_number(),
+					signature = ?station_log.event().signature(),
+					"Skipping non-attestation event"
+				),
+			}
+		}
+
+		attestations
+	}
+
+	/// Fetches and decodes all the events emitted by the contract starting at the
+	/// given block, whatever their domain.
+	#[instrument(skip(self))]
+	pub async fn get_events_from(&self, from_block: u64) -> Result<Vec<StationLog>, EigenError> {
+		let filter = Filter::new().address(self.as_address).from_block(from_block);
+		let logs = self.fetch_logs(&filter).await?;
+
+		Ok(logs.iter().map(StationLog::decode).collect())
+	}
+
+	/// Fetches the attestations about the given peer in chronological order,
+	/// along with their attesters and the blocks that included them.
+	pub async fn attestation_history(
+		&self, address: [u8; 20],
+	) -> Result<Vec<AttestationHistoryEntry>, EigenError> {
+		let mut history = Vec::new();
+		for indexed in self.get_attestations_about(address).await? {
+			let signed_attestation = indexed.signed_attestation();
+
+			// Recover the attester from the signature
+			let signed_attestation_eth = SignedAttestationEth::from(signed_attestation.clone());
+			let public_key = signed_attestation_eth.recover_public_key()?;
+			let attester = address_from_ecdsa_key(&public_key);
+
+			history.push(AttestationHistoryEntry::new(
+				attester.to_fixed_bytes(),
+				signed_attestation.attestation.clone(),
+				indexed.block_number(),
+			));
+		}
+
+		// Logs are returned in order, the stable sort only guards against unordered providers
+		history.sort_by_key(|entry| entry.block_number());
+
+		Ok(history)
+	}
+
+	/// Fetches "AttestationCreated" event logs from the contract, filtered by domain.
+	pub async fn get_logs(&self) -> Result<Vec<Log>, EigenError> {
+		self.get_logs_from(0).await
+	}
+
+	/// Fetches "AttestationCreated" event logs from the contract starting at the given block,
+	/// filtered by domain.
+	#[instrument(skip(self))]
+	pub async fn get_logs_from(&self, from_block: u64) -> Result<Vec<Log>, EigenError> {
+		self.fetch_logs(&self.attestation_filter(from_block)).await
+	}
+
+	/// Returns the filter of the "AttestationCreated" events of the client domain,
+	/// starting at the given block.
+	fn attestation_filter(&self, from_block: u64) -> Filter {
+		let as_contract = AttestationStation::new(self.as_address, self.get_signer());
+
+		as_contract
+			.attestation_created_filter()
+			.filter
+			.topic3(build_att_key(self.domain))
+			.from_block(from_block)
+	}
+
+	/// Fetches the logs matching the filter.
+	async fn fetch_logs(&self, filter: &Filter) -> Result<Vec<Log>, EigenError> {
+		let start_time = Instant::now();
+		let logs = self.signer.provider().get_logs(filter).await.map_err(|e| {
+			warn!(error = %e, "Failed to fetch logs");
+			EigenError::from(e)
+		})?;
+		debug!(logs = logs.len(), elapsed = ?start_time.elapsed(), "Fetched logs");
+
+		Ok(logs)
+	}
+
+	/// Checks the given (number, hash) pairs of indexed blocks against the canonical chain.
+	/// Returns the block from which attestations must be refetched if a reorg is detected.
+	/// Blocks are checked from the newest one back to the first canonical one, so only the
+	/// reorganized blocks cost a request besides it.
+	pub async fn find_fork_point(
+		&self, indexed_blocks: &[(u64, [u8; 32])],
+	) -> Result<Option<u64>, EigenError> {
+		let mut blocks = indexed_blocks.to_vec();
+		blocks.sort();
+		blocks.dedup();
+
+		let mut reorganized = false;
+		for (number, hash) in blocks.into_iter().rev() {
+			let block = self
+				.signer
+				.get_block(BlockNumber::Number(number.into()))
+				.await
+				.map_err(|e| EigenError::ConnectionError(e.to_string()))?;
+			let canonical_hash = block.and_then(|block| block.hash);
+
+			if canonical_hash == Some(H256::from(hash)) {
+				return Ok(reorganized.then_some(number + 1));
+			}
+
+			warn!(
+				"Indexed block {} is no longer part of the canonical chain",
+				number
+			);
+			reorganized = true;
+		}
+
+		Ok(reorganized.then_some(0))
+	}
+
+	/// Exports the client state: all the indexed attestations of the domain, the last
+	/// indexed block and the given scores, computed from those attestations.
+	#[instrument(skip_all)]
+	pub async fn export_state(&self, scores: &[Score]) -> Result<StateSnapshot, EigenError> {
+		let attestations = self.get_indexed_attestations(0).await?;
+		info!(attestations = attestations.len(), "Exporting state");
+
+		Ok(StateSnapshot::new(
+			self.signer.signer().chain_id(),
+			self.as_address.to_fixed_bytes(),
+			self.domain.to_fixed_bytes(),
+			attestations,
+			scores,
+		))
+	}
+
+	/// Imports a state snapshot, checking that it was exported for the client's network,
+	/// contract and domain. Attestations from blocks that are no longer part of the
+	/// canonical chain are dropped along with the scores, which must then be recomputed.
+	#[instrument(skip_all, fields(version = snapshot.version))]
+	pub async fn import_state(
+		&self, mut snapshot: StateSnapshot,
+	) -> Result<StateSnapshot, EigenError> {
+		snapshot.check_version()?;
+
+		let matches_client = snapshot.chain_id == self.signer.signer().chain_id()
+			&& str_to_20_byte_array(&snapshot.as_address)? == self.as_address.to_fixed_bytes()
+			&& str_to_20_byte_array(&snapshot.domain)? == self.domain.to_fixed_bytes();
+		if !matches_client {
+			return Err(EigenError::ValidationError(
+				"Snapshot network, contract or domain does not match the client".to_string(),
+			));
+		}
+
+		let blocks = snapshot.indexed_blocks()?;
+		if let Some(fork_point) = self.find_fork_point(&blocks).await? {
+			warn!(
+				fork_point,
+				"Snapshot includes non-canonical blocks, truncating"
+			);
+			snapshot.truncate(fork_point)?;
+		}
+
+		info!(
+			attestations = snapshot.attestations.len(),
+			scores = snapshot.scores.len(),
+			"Imported state"
+		);
+
+		Ok(snapshot)
+	}
+
+	/// Gets the domain as BN256 scalar.
+	pub fn get_scalar_domain(&self) -> Result<Scalar, EigenError> {
+		let domain_bytes_256 = H256::from(self.domain);
+
+		let mut domain = *domain_bytes_256.as_fixed_bytes();
+		domain.reverse();
+
+		let domain_opt = Scalar::from_bytes(&domain);
 
 		match domain_opt.is_some().into() {
 			true => Ok(domain_opt.unwrap()),
@@ -679,16 +1822,29 @@ impl Client {
 #[cfg(test)]
 mod lib_tests {
 	use crate::{
-		att_station::AttestationStation,
-		attestation::{AttestationRaw, DOMAIN_PREFIX, DOMAIN_PREFIX_LEN},
+		att_station::{AttestCall, AttestationStation},
+		attestation::{
+			AggregationPolicy, AttestationQuery, AttestationRaw, SignedAttestationRaw,
+			DOMAIN_PREFIX, DOMAIN_PREFIX_LEN,
+		},
+		circuit::Score,
+		error::EigenError,
 		eth::deploy_as,
+		graph::DisconnectedPolicy,
+		storage::SyncState,
 		Client, ContractAttestationData,
 	};
+	use eigentrust_zk::{
+		circuits::NUM_NEIGHBOURS,
+		halo2::halo2curves::{bn256::Fr as Scalar, ff::PrimeField},
+	};
 	use ethers::{
+		abi::AbiDecode,
+		contract::EthCall,
 		types::{Address, Bytes, H160},
 		utils::Anvil,
 	};
-	use std::str::FromStr;
+	use std::{collections::BTreeMap, str::FromStr};
 
 	const TEST_MNEMONIC: &'static str =
 		"test test test test test test test test test test test junk";
@@ -726,6 +1882,97 @@ mod lib_tests {
 		drop(anvil);
 	}
 
+	#[tokio::test]
+	async fn test_attest_batch() {
+		let anvil = Anvil::new().spawn();
+		let node_url = anvil.endpoint().to_string();
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url.clone(),
+		);
+		let as_address = deploy_as(client.get_signer()).await.unwrap();
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			as_address.to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url,
+		);
+
+		let attestations = vec![
+			AttestationRaw::new([1; 20], [0; 20], 5, [0; 32]),
+			AttestationRaw::new([2; 20], [0; 20], 7, [0; 32]),
+		];
+		client.attest_batch(attestations).await.unwrap();
+
+		// Both attestations are included in the same transaction
+		let indexed = client.get_indexed_attestations(0).await.unwrap();
+		assert_eq!(indexed.len(), 2);
+		assert_eq!(indexed[0].block_number(), indexed[1].block_number());
+
+		drop(anvil);
+	}
+
+	#[tokio::test]
+	async fn test_estimate_relay_gas() {
+		let anvil = Anvil::new().spawn();
+		let node_url = anvil.endpoint().to_string();
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url.clone(),
+		);
+		let as_address = deploy_as(client.get_signer()).await.unwrap();
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			as_address.to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url,
+		);
+
+		let attestation = AttestationRaw::new([1; 20], [0; 20], 5, [0; 32]);
+		let signed = client.sign_attestation(attestation).unwrap();
+		let gas = client.estimate_relay_gas(vec![signed.clone()]).await.unwrap();
+		assert!(!gas.is_zero());
+
+		// Nothing is submitted
+		assert!(client.get_indexed_attestations(0).await.unwrap().is_empty());
+
+		// Attestations of another domain are rejected before estimating
+		let mut other_domain = signed;
+		other_domain.attestation.domain = [1; 20];
+		assert!(client.estimate_relay_gas(vec![other_domain]).await.is_err());
+
+		drop(anvil);
+	}
+
+	#[tokio::test]
+	async fn test_offline_client() {
+		let client = Client::offline(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+		);
+
+		// Attestations are signed and encoded without a node
+		let attestation = AttestationRaw::new([1; 20], [0; 20], 5, [0; 32]);
+		let signed = client.sign_attestation(attestation).unwrap();
+		let call_data = client.attest_call_data(vec![signed]).unwrap();
+		assert_eq!(call_data[..4], AttestCall::selector());
+		let call = AttestCall::decode(&call_data).unwrap();
+		assert_eq!(call.attestations.len(), 1);
+		assert_eq!(call.attestations[0].about, Address::from([1; 20]));
+
+		assert!(client.get_indexed_attestations(0).await.is_err());
+	}
+
 	#[tokio::test]
 	async fn test_get_attestations() {
 		let anvil = Anvil::new().spawn();
@@ -779,6 +2026,20 @@ mod lib_tests {
 
 		assert_eq!(attestations.len(), 1);
 
+		// Resuming from the returned state only scans the new blocks
+		let (indexed, state) = client.get_attestations_since(None).await.unwrap();
+		assert_eq!(indexed.len(), 1);
+		let (indexed, resumed) = client.get_attestations_since(Some(&state)).await.unwrap();
+		assert!(indexed.is_empty());
+		assert_eq!(resumed.last_block(), state.last_block());
+
+		// States of other contracts are ignored
+		let other = SyncState::new(1, [0; 20], domain_input, state.last_block());
+		assert_eq!(
+			client.get_attestations_since(Some(&other)).await.unwrap().0.len(),
+			1
+		);
+
 		let fetched_att = attestations[0].clone().attestation;
 
 		// Check that the attestations match
@@ -790,6 +2051,504 @@ mod lib_tests {
 		drop(anvil);
 	}
 
+	#[tokio::test]
+	async fn test_find_fork_point() {
+		let anvil = Anvil::new().spawn();
+		let node_url = anvil.endpoint().to_string();
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url.clone(),
+		);
+
+		// Deploy attestation station
+		let as_address = deploy_as(client.get_signer()).await.unwrap();
+
+		// Update config with new addresses and instantiate client
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			as_address.to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url,
+		);
+
+		let attestation = AttestationRaw::new([0; 20], [0; 20], 5, [0; 32]);
+		client.attest(attestation).await.unwrap();
+
+		let indexed = client.get_indexed_attestations(0).await.unwrap();
+		let blocks: Vec<(u64, [u8; 32])> =
+			indexed.iter().map(|att| (att.block_number(), att.block_hash())).collect();
+
+		// Canonical blocks are not reorganized
+		assert_eq!(client.find_fork_point(&blocks).await.unwrap(), None);
+
+		// An unknown block hash is treated as orphaned
+		let orphaned = vec![(blocks[0].0, [1; 32])];
+		assert_eq!(client.find_fork_point(&orphaned).await.unwrap(), Some(0));
+
+		// Attestations are refetched from the block after the newest canonical one
+		let reorganized = vec![blocks[0], (blocks[0].0 + 1, [1; 32]), (blocks[0].0 + 2, [2; 32])];
+		assert_eq!(
+			client.find_fork_point(&reorganized).await.unwrap(),
+			Some(blocks[0].0 + 1)
+		);
+
+		drop(anvil);
+	}
+
+	#[tokio::test]
+	async fn test_export_import_state() {
+		let anvil = Anvil::new().spawn();
+		let node_url = anvil.endpoint().to_string();
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url.clone(),
+		);
+
+		// Deploy attestation station
+		let as_address = deploy_as(client.get_signer()).await.unwrap();
+
+		// Update config with new addresses and instantiate client
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			as_address.to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url.clone(),
+		);
+
+		let attestation = AttestationRaw::new([0; 20], [0; 20], 5, [0; 32]);
+		client.attest(attestation).await.unwrap();
+
+		let snapshot = client.export_state(&[]).await.unwrap();
+		assert_eq!(snapshot.attestations.len(), 1);
+		assert!(snapshot.checkpoint.is_some());
+
+		let imported = client.import_state(snapshot.clone()).await.unwrap();
+		assert_eq!(imported.attestations.len(), 1);
+		assert_eq!(imported.checkpoint, snapshot.checkpoint);
+
+		// Snapshots of other domains are rejected
+		let other_domain = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			as_address.to_fixed_bytes(),
+			[1; 20],
+			node_url,
+		);
+		assert!(other_domain.import_state(snapshot).await.is_err());
+
+		drop(anvil);
+	}
+
+	#[tokio::test]
+	async fn test_attestation_history() {
+		let anvil = Anvil::new().spawn();
+		let node_url = anvil.endpoint().to_string();
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url.clone(),
+		);
+
+		// Deploy attestation station
+		let as_address = deploy_as(client.get_signer()).await.unwrap();
+
+		// Update config with new addresses and instantiate client
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			as_address.to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url,
+		);
+
+		let peer = [1; 20];
+		let first = AttestationRaw::new(peer, [0; 20], 5, [0; 32]);
+		let other = AttestationRaw::new([2; 20], [0; 20], 7, [0; 32]);
+		let second = AttestationRaw::new(peer, [0; 20], 9, [0; 32]);
+		client.attest(first.clone()).await.unwrap();
+		client.attest(other).await.unwrap();
+		client.attest(second.clone()).await.unwrap();
+
+		let history = client.attestation_history(peer).await.unwrap();
+
+		assert_eq!(history.len(), 2);
+		assert_eq!(history[0].attestation(), &first);
+		assert_eq!(history[1].attestation(), &second);
+		assert!(history[0].block_number() < history[1].block_number());
+		assert_eq!(
+			history[0].attester(),
+			client.get_signer().address().to_fixed_bytes()
+		);
+
+		drop(anvil);
+	}
+
+	#[tokio::test]
+	async fn test_get_attestations_for() {
+		let anvil = Anvil::new().spawn();
+		let node_url = anvil.endpoint().to_string();
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url.clone(),
+		);
+
+		// Deploy attestation station
+		let as_address = deploy_as(client.get_signer()).await.unwrap();
+
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			as_address.to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url,
+		);
+
+		let signer = client.get_signer().address().to_fixed_bytes();
+		client.attest(AttestationRaw::new([1; 20], [0; 20], 5, [0; 32])).await.unwrap();
+		client.attest(AttestationRaw::new([2; 20], [0; 20], 7, [0; 32])).await.unwrap();
+		client.attest(AttestationRaw::new(signer, [0; 20], 9, [0; 32])).await.unwrap();
+
+		let about_peer = client.get_attestations_for([1; 20]).await.unwrap();
+		assert_eq!(about_peer.len(), 1);
+		assert_eq!(about_peer[0].signed_attestation().attestation.value, 5);
+
+		// The self-attestation is only returned once
+		let from_signer = client.get_attestations_for(signer).await.unwrap();
+		assert_eq!(from_signer.len(), 3);
+
+		drop(anvil);
+	}
+
+	#[tokio::test]
+	async fn test_query_attestations() {
+		let anvil = Anvil::new().spawn();
+		let node_url = anvil.endpoint().to_string();
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url.clone(),
+		);
+
+		// Deploy attestation station
+		let as_address = deploy_as(client.get_signer()).await.unwrap();
+
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			as_address.to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url,
+		);
+
+		let signer = client.get_signer().address().to_fixed_bytes();
+		client.attest(AttestationRaw::new([1; 20], [0; 20], 5, [0; 32])).await.unwrap();
+		client.attest(AttestationRaw::new([2; 20], [0; 20], 7, [0; 32])).await.unwrap();
+		client.attest(AttestationRaw::new([1; 20], [0; 20], 9, [0; 32])).await.unwrap();
+
+		let all = client.query_attestations(&AttestationQuery::default()).await.unwrap();
+		assert_eq!(all.len(), 3);
+
+		let query =
+			AttestationQuery { creator: Some(signer), about: Some([1; 20]), ..Default::default() };
+		assert_eq!(client.query_attestations(&query).await.unwrap().len(), 2);
+
+		let query = AttestationQuery { creator: Some([3; 20]), ..Default::default() };
+		assert!(client.query_attestations(&query).await.unwrap().is_empty());
+
+		// Block bounds are inclusive
+		let second_block = all[1].block_number();
+		let query = AttestationQuery {
+			from_block: second_block,
+			to_block: Some(second_block),
+			..Default::default()
+		};
+		let in_range = client.query_attestations(&query).await.unwrap();
+		assert_eq!(in_range.len(), 1);
+		assert_eq!(in_range[0].signed_attestation().attestation.value, 7);
+
+		drop(anvil);
+	}
+
+	#[tokio::test]
+	async fn test_relay() {
+		let anvil = Anvil::new().spawn();
+		let node_url = anvil.endpoint().to_string();
+		let relayer = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url.clone(),
+		);
+
+		// Deploy attestation station
+		let as_address = deploy_as(relayer.get_signer()).await.unwrap();
+
+		let relayer = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			as_address.to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url.clone(),
+		);
+
+		// The attester account holds no ETH
+		let attester = Client::new(
+			"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+				.to_string(),
+			TEST_CHAIN_ID,
+			as_address.to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			node_url,
+		);
+
+		let attestation = AttestationRaw::new([1; 20], [0; 20], 5, [0; 32]);
+		let signed_attestation = attester.sign_attestation(attestation.clone()).unwrap();
+		relayer.relay(vec![signed_attestation]).await.unwrap();
+
+		let history = relayer.attestation_history([1; 20]).await.unwrap();
+
+		assert_eq!(history.len(), 1);
+		assert_eq!(history[0].attestation(), &attestation);
+		assert_eq!(
+			history[0].attester(),
+			attester.get_signer().address().to_fixed_bytes()
+		);
+
+		// Attestations from other domains are rejected
+		let other_domain = AttestationRaw::new([1; 20], [2; 20], 5, [0; 32]);
+		let signed_other = attester.sign_attestation(other_domain).unwrap();
+		assert!(relayer.relay(vec![signed_other]).await.is_err());
+
+		drop(anvil);
+	}
+
+	#[test]
+	fn test_calculate_scores_capacity() {
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			"http://localhost:8545".to_string(),
+		);
+
+		// The attester and every attested peer exceed the set capacity
+		let attestations: Vec<SignedAttestationRaw> = (1..=NUM_NEIGHBOURS as u8)
+			.map(|i| {
+				let attestation = AttestationRaw::new([i; 20], [0; 20], 5, [0; 32]);
+				client.sign_attestation(attestation).unwrap()
+			})
+			.collect();
+
+		let result = client.calculate_scores(attestations.clone());
+		assert!(matches!(result, Err(EigenError::ValidationError(_))));
+
+		// Results don't depend on the attestations' order
+		let fitting = attestations[..NUM_NEIGHBOURS - 1].to_vec();
+		let mut reversed = fitting.clone();
+		reversed.reverse();
+		let scores = client.calculate_scores(fitting).unwrap();
+		let reversed_scores = client.calculate_scores(reversed).unwrap();
+		assert_eq!(
+			scores.iter().map(|score| (score.address, score.score_fr)).collect::<Vec<_>>(),
+			reversed_scores.iter().map(|score| (score.address, score.score_fr)).collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn test_calculate_sharded_scores() {
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			"http://localhost:8545".to_string(),
+		);
+
+		// The attester and the attested peers fill several sets
+		let attestations: Vec<SignedAttestationRaw> = (1..=3 * NUM_NEIGHBOURS as u8)
+			.map(|i| {
+				let attestation = AttestationRaw::new([i; 20], [0; 20], 5, [0; 32]);
+				client.sign_attestation(attestation).unwrap()
+			})
+			.collect();
+
+		let scores = client.calculate_sharded_scores(attestations, &[]).unwrap();
+		assert_eq!(scores.len(), 3 * NUM_NEIGHBOURS + 1);
+
+		// Participants are ordered by address, and the total score is kept
+		assert!(scores.windows(2).all(|pair| pair[0].address < pair[1].address));
+		let total: Scalar = scores
+			.iter()
+			.map(|score| {
+				let mut bytes = score.score_fr;
+				bytes.reverse();
+				Scalar::from_bytes(&bytes).unwrap()
+			})
+			.sum();
+		let initial_score = Scalar::from_u128(client.params.initial_score);
+		assert_eq!(total, initial_score * Scalar::from(scores.len() as u64));
+	}
+
+	#[test]
+	fn test_calculate_scores_from_seeds() {
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			"http://localhost:8545".to_string(),
+		);
+
+		// Peers 1 to 3 trust each other and peer 6, who attests no one, peers 4 and 5 only
+		// trust each other. Attestations are credited to contract attesters to have
+		// several attesters.
+		let pairs = [(1, 2), (2, 3), (3, 1), (1, 6), (4, 5), (5, 4)];
+		let attestations: Vec<SignedAttestationRaw> = pairs
+			.iter()
+			.map(|&(_, about)| {
+				let attestation = AttestationRaw::new([about; 20], [0; 20], 5, [0; 32]);
+				client.sign_attestation(attestation).unwrap()
+			})
+			.collect();
+		let contract_attesters: Vec<Option<[u8; 20]>> =
+			pairs.iter().map(|&(attester, _)| Some([attester; 20])).collect();
+		let score_of = |scores: &[Score], peer: u8| {
+			scores.iter().find(|score| score.address == [peer; 20]).map(|score| score.score_fr)
+		};
+
+		// The uniform prior lets peer 6 hand its trust over to peers 4 and 5
+		let uniform = client
+			.calculate_scores_with_contract_attesters(attestations.clone(), &contract_attesters)
+			.unwrap();
+		assert_eq!(uniform.len(), 6);
+		assert_ne!(score_of(&uniform, 4), Some([0; 32]));
+
+		// Seeded runs keep the trust within the seeds' component
+		let seeded = client
+			.calculate_scores_from_seeds(
+				attestations.clone(),
+				&contract_attesters,
+				&[[1; 20]],
+				DisconnectedPolicy::Exclude,
+			)
+			.unwrap();
+		assert_eq!(seeded.len(), 4);
+		assert_eq!(score_of(&seeded, 4), None);
+		assert_ne!(score_of(&seeded, 1), score_of(&uniform, 1));
+
+		let zeroed = client
+			.calculate_scores_from_seeds(
+				attestations,
+				&contract_attesters,
+				&[[1; 20]],
+				DisconnectedPolicy::ScoreZero,
+			)
+			.unwrap();
+		assert_eq!(zeroed.len(), 6);
+		assert_eq!(score_of(&zeroed, 4), Some([0; 32]));
+		assert_eq!(score_of(&zeroed, 1), score_of(&seeded, 1));
+	}
+
+	#[test]
+	fn test_aggregation_policy() {
+		let mut client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			"http://localhost:8545".to_string(),
+		);
+		let sign = |about: u8, value: u8| {
+			let attestation = AttestationRaw::new([about; 20], [0; 20], value, [0; 32]);
+			client.sign_attestation(attestation).unwrap()
+		};
+		let attestations = vec![sign(1, 10), sign(2, 20), sign(1, 30)];
+		let latest = vec![sign(2, 20), sign(1, 30)];
+		let averaged = vec![sign(1, 20), sign(2, 20)];
+		let score_frs =
+			|scores: Vec<Score>| scores.iter().map(|score| score.score_fr).collect::<Vec<_>>();
+
+		// The latest attestation wins by default
+		assert_eq!(
+			score_frs(client.calculate_scores(attestations.clone()).unwrap()),
+			score_frs(client.calculate_scores(latest.clone()).unwrap())
+		);
+
+		client.set_aggregation(AggregationPolicy::Average);
+		let scores = score_frs(client.calculate_scores(attestations).unwrap());
+		assert_ne!(scores, score_frs(client.calculate_scores(latest).unwrap()));
+		assert_eq!(
+			scores,
+			score_frs(client.calculate_scores(averaged).unwrap())
+		);
+		assert!(client.ensure_provable(&[]).is_err());
+	}
+
+	#[test]
+	fn test_stake_weighted_scores() {
+		let mut client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			"http://localhost:8545".to_string(),
+		);
+		let attestations: Vec<SignedAttestationRaw> = [1, 2]
+			.into_iter()
+			.map(|about| {
+				let attestation = AttestationRaw::new([about; 20], [0; 20], 5, [0; 32]);
+				client.sign_attestation(attestation).unwrap()
+			})
+			.collect();
+		let score_frs =
+			|scores: Vec<Score>| scores.iter().map(|score| score.score_fr).collect::<Vec<_>>();
+		let unweighted = score_frs(client.calculate_scores(attestations.clone()).unwrap());
+
+		// The attester holds a quarter of the largest stake
+		let attester = client.get_signer().address();
+		let stakes = BTreeMap::from([(attester, 100), (Address::from([1; 20]), 400)]);
+		client.set_stakes(stakes);
+
+		let scores = score_frs(client.calculate_scores(attestations).unwrap());
+		assert_ne!(scores, unweighted);
+		assert_eq!(client.manifest(&[]).stakes.len(), 2);
+		assert!(client.ensure_provable(&[]).is_err());
+	}
+
+	#[test]
+	fn test_contract_attesters_not_provable() {
+		let client = Client::new(
+			TEST_MNEMONIC.to_string(),
+			TEST_CHAIN_ID,
+			Address::from_str(TEST_AS_ADDRESS).unwrap().to_fixed_bytes(),
+			H160::zero().to_fixed_bytes(),
+			"http://localhost:8545".to_string(),
+		);
+
+		assert!(client.ensure_provable(&[None, None]).is_ok());
+		assert!(matches!(
+			client.ensure_provable(&[None, Some([1; 20])]),
+			Err(EigenError::ProvingError(_))
+		));
+	}
+
 	#[tokio::test]
 	async fn test_get_logs() {
 		let anvil = Anvil::new().spawn();
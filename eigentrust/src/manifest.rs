@@ -0,0 +1,464 @@
+//! # Manifest Module.
+//!
+//! This module provides the scoring manifest, a compact commitment to the
+//! parameters and results of a scoring run, the provenance metadata embedded
+//! in the score exports, and the score certificates signed by the scorer.
+
+use crate::{
+	attestation::AggregationPolicy, circuit::Score, domain::DomainParams, error::EigenError,
+};
+use eigentrust_zk::circuits::{INITIAL_SCORE, NUM_ITERATIONS, NUM_NEIGHBOURS};
+use ethers::{
+	signers::LocalWallet,
+	types::{RecoveryMessage, Signature, H256},
+	utils::{hex, keccak256},
+};
+use serde::{Deserialize, Serialize};
+
+/// First header line of the exports carrying provenance metadata.
+pub const PROVENANCE_HEADER: &str = "eigentrust-provenance";
+/// Domain separation tag of the signed score certificates.
+const CERTIFICATE_DST: &[u8] = b"EIGENTRUST_SCORE_CERTIFICATE";
+
+/// Scoring run manifest.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoringManifest {
+	/// Attestation domain.
+	pub domain: [u8; 20],
+	/// Maximum number of participants in the set.
+	pub num_neighbours: u64,
+	/// Number of convergence iterations.
+	pub num_iterations: u64,
+	/// Initial score of each participant.
+	pub initial_score: u128,
+	/// Participants' addresses and scalar scores.
+	pub scores: Vec<([u8; 20], [u8; 32])>,
+	/// Convergence damping factor (numerator, denominator), if any.
+	#[serde(default)]
+	pub damping: Option<(u128, u128)>,
+	/// Aggregation policy of the attestations given to the same peer.
+	#[serde(default)]
+	pub aggregation: AggregationPolicy,
+	/// Attesters' stakes weighing their opinions, sorted by address.
+	#[serde(default)]
+	pub stakes: Vec<([u8; 20], u128)>,
+}
+
+impl ScoringManifest {
+	/// Creates a new manifest for the given domain and scores, using the circuit parameters.
+	pub fn new(domain: [u8; 20], scores: &[Score]) -> Self {
+		Self {
+			domain,
+			num_neighbours: NUM_NEIGHBOURS as u64,
+			num_iterations: NUM_ITERATIONS as u64,
+			initial_score: INITIAL_SCORE,
+			scores: scores.iter().map(|score| (score.address, score.score_fr)).collect(),
+			damping: None,
+			aggregation: AggregationPolicy::default(),
+			stakes: Vec::new(),
+		}
+	}
+
+	/// Sets the convergence damping factor of the run.
+	pub fn with_damping(mut self, damping: Option<(u128, u128)>) -> Self {
+		self.damping = damping;
+		self
+	}
+
+	/// Sets the aggregation policy of the attestations of the run.
+	pub fn with_aggregation(mut self, aggregation: AggregationPolicy) -> Self {
+		self.aggregation = aggregation;
+		self
+	}
+
+	/// Sets the attesters' stakes weighing their opinions in the run.
+	pub fn with_stakes(mut self, mut stakes: Vec<([u8; 20], u128)>) -> Self {
+		stakes.sort();
+		self.stakes = stakes;
+		self
+	}
+
+	/// Sets the scoring parameters of the run's domain.
+	pub fn with_params(mut self, params: DomainParams) -> Self {
+		self.num_iterations = params.num_iterations;
+		self.initial_score = params.initial_score;
+		self
+	}
+
+	/// Checks that both manifests' scores were computed with the same parameters, so they
+	/// can be compared or combined.
+	pub fn check_params(&self, other: &ScoringManifest) -> Result<(), EigenError> {
+		let params = (
+			self.num_neighbours, self.num_iterations, self.initial_score, self.damping,
+			self.aggregation, &self.stakes,
+		);
+		let other_params = (
+			other.num_neighbours, other.num_iterations, other.initial_score, other.damping,
+			other.aggregation, &other.stakes,
+		);
+
+		if params != other_params {
+			return Err(EigenError::ValidationError(format!(
+				"Scoring parameters {:?} do not match {:?}",
+				params, other_params
+			)));
+		}
+
+		Ok(())
+	}
+
+	/// Returns the keccak256 hash of the manifest's parameters, leaving out the scores, so
+	/// that it's the same for every score of the run.
+	pub fn params_hash(&self) -> [u8; 32] {
+		let mut bytes = Vec::new();
+		bytes.extend(self.domain);
+		bytes.extend(self.num_neighbours.to_be_bytes());
+		bytes.extend(self.num_iterations.to_be_bytes());
+		bytes.extend(self.initial_score.to_be_bytes());
+
+		if let Some((numerator, denominator)) = self.damping {
+			bytes.extend(numerator.to_be_bytes());
+			bytes.extend(denominator.to_be_bytes());
+		}
+
+		match self.aggregation {
+			AggregationPolicy::LatestWins => {},
+			AggregationPolicy::Average => bytes.push(1),
+			AggregationPolicy::RecencyWeighted => bytes.push(2),
+		}
+
+		// Unweighted runs add no stakes
+		for (address, stake) in &self.stakes {
+			bytes.extend(address);
+			bytes.extend(stake.to_be_bytes());
+		}
+
+		keccak256(bytes)
+	}
+
+	/// Returns the keccak256 hash of the manifest.
+	pub fn hash(&self) -> [u8; 32] {
+		let mut bytes = Vec::new();
+		bytes.extend(self.domain);
+		bytes.extend(self.num_neighbours.to_be_bytes());
+		bytes.extend(self.num_iterations.to_be_bytes());
+		bytes.extend(self.initial_score.to_be_bytes());
+
+		for (address, score) in &self.scores {
+			bytes.extend(address);
+			bytes.extend(score);
+		}
+
+		// Undamped runs keep their original hash
+		if let Some((numerator, denominator)) = self.damping {
+			bytes.extend(numerator.to_be_bytes());
+			bytes.extend(denominator.to_be_bytes());
+		}
+
+		// So do runs keeping the latest attestations
+		match self.aggregation {
+			AggregationPolicy::LatestWins => {},
+			AggregationPolicy::Average => bytes.push(1),
+			AggregationPolicy::RecencyWeighted => bytes.push(2),
+		}
+
+		// And unweighted runs
+		for (address, stake) in &self.stakes {
+			bytes.extend(address);
+			bytes.extend(stake.to_be_bytes());
+		}
+
+		keccak256(bytes)
+	}
+}
+
+/// Provenance of a score export, tracing it back to its verifiable origin.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+	/// Attestation domain.
+	pub domain: String,
+	/// Hash of the scoring run manifest.
+	pub manifest_hash: String,
+	/// Address of the account that computed the scores.
+	pub scorer: String,
+	/// Address of the verifier contract, if any.
+	pub verifier: String,
+	/// Last block of the scored attestations.
+	pub epoch: u64,
+}
+
+impl Provenance {
+	/// Creates the provenance of the scores of the given manifest.
+	pub fn new(
+		manifest: &ScoringManifest, scorer: [u8; 20], verifier: Option<[u8; 20]>, epoch: u64,
+	) -> Self {
+		Self {
+			domain: format!("0x{}", hex::encode(manifest.domain)),
+			manifest_hash: format!("0x{}", hex::encode(manifest.hash())),
+			scorer: format!("0x{}", hex::encode(scorer)),
+			verifier: verifier.map(|v| format!("0x{}", hex::encode(v))).unwrap_or_default(),
+			epoch,
+		}
+	}
+
+	/// Returns the provenance as `key: value` header lines.
+	pub fn to_header(&self) -> Vec<String> {
+		vec![
+			PROVENANCE_HEADER.to_string(),
+			format!("domain: {}", self.domain),
+			format!("manifest_hash: {}", self.manifest_hash),
+			format!("scorer: {}", self.scorer),
+			format!("verifier: {}", self.verifier),
+			format!("epoch: {}", self.epoch),
+		]
+	}
+
+	/// Parses the provenance from its header lines.
+	pub fn from_header(header: &[String]) -> Result<Self, EigenError> {
+		if header.first().map(String::as_str) != Some(PROVENANCE_HEADER) {
+			return Err(EigenError::ParsingError(
+				"Missing provenance header".to_string(),
+			));
+		}
+
+		let mut provenance = Self::default();
+		for line in &header[1..] {
+			let (key, value) = line.split_once(':').ok_or_else(|| {
+				EigenError::ParsingError(format!("Invalid provenance line: {}", line))
+			})?;
+			let value = value.trim().to_string();
+
+			match key.trim() {
+				"domain" => provenance.domain = value,
+				"manifest_hash" => provenance.manifest_hash = value,
+				"scorer" => provenance.scorer = value,
+				"verifier" => provenance.verifier = value,
+				"epoch" => {
+					provenance.epoch = value.parse().map_err(|_| {
+						EigenError::ParsingError("Failed to parse epoch.".to_string())
+					})?
+				},
+				// Unknown keys are kept for forward compatibility
+				_ => {},
+			}
+		}
+
+		Ok(provenance)
+	}
+}
+
+/// Export with embedded provenance, used as the frontmatter of JSON exports.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvenanceExport<T> {
+	/// Provenance of the exported data.
+	pub provenance: Provenance,
+	/// Exported data.
+	pub data: T,
+}
+
+impl<T> ProvenanceExport<T> {
+	/// Creates a new export.
+	pub fn new(provenance: Provenance, data: T) -> Self {
+		Self { provenance, data }
+	}
+}
+
+/// Score of a participant signed by the account that computed it, so that the consumers
+/// of the published scores can check their provenance without recomputing them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreCertificate {
+	/// Last block of the scored attestations.
+	pub epoch: u64,
+	/// Participant address.
+	pub member: String,
+	/// Scalar score, as in the score exports.
+	pub score: String,
+	/// Hash of the scoring parameters, see `ScoringManifest::params_hash`.
+	pub params_hash: String,
+	/// Scorer's ECDSA signature over the certificate hash.
+	pub signature: String,
+}
+
+impl ScoreCertificate {
+	/// Signs the participant's score with the scorer's wallet.
+	pub fn sign(
+		wallet: &LocalWallet, epoch: u64, score: &Score, params_hash: [u8; 32],
+	) -> Result<Self, EigenError> {
+		let hash = certificate_hash(epoch, score.address, score.score_fr, params_hash);
+		let signature = wallet.sign_hash(H256::from(hash)).map_err(|e| {
+			EigenError::KeysError(format!("Failed to sign the score certificate: {}", e))
+		})?;
+
+		Ok(Self {
+			epoch,
+			member: format!("0x{}", hex::encode(score.address)),
+			score: format!("0x{}", hex::encode(score.score_fr)),
+			params_hash: format!("0x{}", hex::encode(params_hash)),
+			signature: format!("0x{}", hex::encode(signature.to_vec())),
+		})
+	}
+
+	/// Returns the hash signed by the scorer.
+	pub fn hash(&self) -> Result<[u8; 32], EigenError> {
+		Ok(certificate_hash(
+			self.epoch,
+			parse_bytes(&self.member, "member")?,
+			parse_bytes(&self.score, "score")?,
+			parse_bytes(&self.params_hash, "params hash")?,
+		))
+	}
+
+	/// Recovers the address of the scorer that signed the certificate.
+	pub fn signer(&self) -> Result<[u8; 20], EigenError> {
+		let bytes = hex::decode(self.signature.trim_start_matches("0x")).map_err(|_| {
+			EigenError::ParsingError("Failed to parse the certificate signature".to_string())
+		})?;
+		let signature = Signature::try_from(bytes.as_slice()).map_err(|e| {
+			EigenError::ParsingError(format!("Invalid certificate signature: {}", e))
+		})?;
+
+		let message = RecoveryMessage::Hash(H256::from(self.hash()?));
+		let signer = signature.recover(message).map_err(|e| {
+			EigenError::RecoveryError(format!("Failed to recover the certificate signer: {}", e))
+		})?;
+
+		Ok(signer.to_fixed_bytes())
+	}
+
+	/// Verifies that the certificate was signed by the given scorer.
+	pub fn verify(&self, scorer: [u8; 20]) -> Result<(), EigenError> {
+		if self.signer()? != scorer {
+			return Err(EigenError::VerificationError(format!(
+				"Certificate of {} wasn't signed by 0x{}",
+				self.member,
+				hex::encode(scorer)
+			)));
+		}
+
+		Ok(())
+	}
+
+	/// Verifies that the certificate was signed by the given scorer and certifies the
+	/// given exported score, as found in the score CSV files.
+	pub fn verify_score(
+		&self, scorer: [u8; 20], epoch: u64, peer_address: &str, score_fr: &str,
+	) -> Result<(), EigenError> {
+		self.verify(scorer)?;
+
+		let matches = self.epoch == epoch
+			&& self.member.eq_ignore_ascii_case(peer_address)
+			&& self.score.eq_ignore_ascii_case(score_fr);
+		if !matches {
+			return Err(EigenError::VerificationError(format!(
+				"Certificate of {} doesn't certify the score {} at epoch {}",
+				self.member, score_fr, epoch
+			)));
+		}
+
+		Ok(())
+	}
+}
+
+/// Returns the keccak256 hash of a score certificate's fields.
+fn certificate_hash(
+	epoch: u64, member: [u8; 20], score: [u8; 32], params_hash: [u8; 32],
+) -> [u8; 32] {
+	let mut bytes = CERTIFICATE_DST.to_vec();
+	bytes.extend(epoch.to_be_bytes());
+	bytes.extend(member);
+	bytes.extend(score);
+	bytes.extend(params_hash);
+
+	keccak256(bytes)
+}
+
+/// Parses the fixed-length hex field of a certificate.
+fn parse_bytes<const N: usize>(value: &str, field: &str) -> Result<[u8; N], EigenError> {
+	let bytes = hex::decode(value.trim_start_matches("0x"))
+		.map_err(|_| EigenError::ParsingError(format!("Failed to parse the {}", field)))?;
+	bytes
+		.try_into()
+		.map_err(|_| EigenError::ParsingError(format!("Invalid {} length", field)))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::manifest::*;
+	use ethers::signers::Signer;
+
+	#[test]
+	fn test_manifest_hash_binds_scores() {
+		let manifest = ScoringManifest {
+			scores: vec![([1; 20], [2; 32]), ([3; 20], [4; 32])],
+			..Default::default()
+		};
+
+		let mut tampered = manifest.clone();
+		tampered.scores[1].1 = [5; 32];
+
+		assert_eq!(manifest.hash(), manifest.clone().hash());
+		assert_ne!(manifest.hash(), tampered.hash());
+
+		let damped = manifest.clone().with_damping(Some((1, 2)));
+		assert_ne!(manifest.hash(), damped.hash());
+		assert!(manifest.check_params(&damped).is_err());
+
+		let averaged = manifest.clone().with_aggregation(AggregationPolicy::Average);
+		assert_ne!(manifest.hash(), averaged.hash());
+		assert!(manifest.check_params(&averaged).is_err());
+
+		let staked = manifest.clone().with_stakes(vec![([3; 20], 10), ([1; 20], 20)]);
+		assert_eq!(staked.stakes[0], ([1; 20], 20));
+		assert_ne!(manifest.hash(), staked.hash());
+		assert_ne!(manifest.params_hash(), staked.params_hash());
+		assert!(manifest.check_params(&staked).is_err());
+
+		let overridden = manifest.clone().with_params(DomainParams::new(5, 500));
+		assert_ne!(manifest.hash(), overridden.hash());
+		assert!(manifest.check_params(&overridden).is_err());
+		assert!(manifest.check_params(&tampered).is_ok());
+	}
+
+	#[test]
+	fn test_provenance_header() {
+		let manifest = ScoringManifest { domain: [1; 20], ..Default::default() };
+		let provenance = Provenance::new(&manifest, [2; 20], None, 42);
+
+		assert_eq!(
+			provenance.manifest_hash,
+			format!("0x{}", hex::encode(manifest.hash()))
+		);
+		assert_eq!(provenance.verifier, "");
+
+		let header = provenance.to_header();
+		assert_eq!(Provenance::from_header(&header).unwrap(), provenance);
+		assert!(Provenance::from_header(&header[1..]).is_err());
+	}
+
+	#[test]
+	fn test_score_certificate() {
+		let wallet = LocalWallet::new(&mut rand::thread_rng());
+		let scorer = wallet.address().to_fixed_bytes();
+		let score = Score {
+			address: [1; 20],
+			score_fr: [2; 32],
+			score_rat: ([0; 32], [1; 32]),
+			score_hex: [0; 32],
+		};
+		let params_hash = ScoringManifest::default().params_hash();
+
+		let certificate = ScoreCertificate::sign(&wallet, 42, &score, params_hash).unwrap();
+		assert_eq!(certificate.signer().unwrap(), scorer);
+		assert!(certificate.verify(scorer).is_ok());
+		assert!(certificate.verify([3; 20]).is_err());
+
+		let (member, score_fr) = (certificate.member.clone(), certificate.score.clone());
+		assert!(certificate.verify_score(scorer, 42, &member, &score_fr).is_ok());
+		assert!(certificate.verify_score(scorer, 43, &member, &score_fr).is_err());
+
+		// Tampering with the score invalidates the signature
+		let mut tampered = certificate.clone();
+		tampered.score = format!("0x{}", hex::encode([5; 32]));
+		assert!(tampered.verify(scorer).is_err());
+	}
+}
@@ -0,0 +1,219 @@
+//! # SDK Module.
+//!
+//! This module provides a typed client for the HTTP API of a remote scorer, so
+//! applications can read scores and attestations, and submit attestations through the
+//! scorer's relayer, without running the indexer themselves.
+//!
+//! Available behind the `sdk` feature.
+
+use crate::{
+	attestation::{RelayPayload, SignedAttestationRaw},
+	error::EigenError,
+	graph::SybilReport,
+	storage::{AttestationRecord, NormalizedScoreRecord, ScoreRecord},
+};
+use eigentrust_zk::circuits::dynamic_sets::native::ScoreNormalization;
+use ethers::utils::hex;
+use reqwest::{
+	header::{HeaderValue, CONTENT_TYPE},
+	Client, Response,
+};
+use serde::de::DeserializeOwned;
+
+/// Path of the scores endpoint.
+pub const SCORES_PATH: &str = "/scores";
+/// Path of the attestations endpoint.
+pub const ATTESTATIONS_PATH: &str = "/attestations";
+/// Path of the sybil report endpoint.
+pub const SYBIL_PATH: &str = "/sybil";
+/// Path of the relay endpoint.
+pub const RELAY_PATH: &str = "/relay";
+
+/// Client of a remote scorer API.
+#[derive(Clone, Debug)]
+pub struct ScorerClient {
+	base_url: String,
+	relay_token: Option<String>,
+	client: Client,
+}
+
+impl ScorerClient {
+	/// Creates a client for the scorer API at the given base URL.
+	pub fn new(base_url: &str) -> Self {
+		Self {
+			base_url: base_url.trim_end_matches('/').to_string(),
+			relay_token: None,
+			client: Client::new(),
+		}
+	}
+
+	/// Sets the bearer token authorizing the attestations submitted to the scorer's relayer.
+	pub fn with_relay_token(mut self, token: &str) -> Self {
+		self.relay_token = Some(token.to_string());
+		self
+	}
+
+	/// Returns the base URL.
+	pub fn base_url(&self) -> &str {
+		&self.base_url
+	}
+
+	/// Fetches the global scores of all the participants.
+	pub async fn get_scores(&self) -> Result<Vec<ScoreRecord>, EigenError> {
+		self.get(SCORES_PATH).await
+	}
+
+	/// Fetches the global scores saved for the epoch ending at the given block.
+	pub async fn get_epoch_scores(&self, epoch: u64) -> Result<Vec<ScoreRecord>, EigenError> {
+		self.get(&format!("{}?epoch={}", SCORES_PATH, epoch)).await
+	}
+
+	/// Fetches the global scores of all the participants along with their normalized value.
+	pub async fn get_normalized_scores(
+		&self, normalization: ScoreNormalization,
+	) -> Result<Vec<NormalizedScoreRecord>, EigenError> {
+		self.get(&format!(
+			"{}?normalize={}",
+			SCORES_PATH,
+			normalization.name()
+		))
+		.await
+	}
+
+	/// Fetches the global score of the given participant.
+	pub async fn get_score(&self, address: [u8; 20]) -> Result<ScoreRecord, EigenError> {
+		self.get(&format!("{}/0x{}", SCORES_PATH, hex::encode(address))).await
+	}
+
+	/// Fetches all the attestations indexed by the scorer.
+	pub async fn get_attestations(&self) -> Result<Vec<AttestationRecord>, EigenError> {
+		self.get(ATTESTATIONS_PATH).await
+	}
+
+	/// Fetches the attestations about the given participant.
+	pub async fn get_attestations_about(
+		&self, address: [u8; 20],
+	) -> Result<Vec<AttestationRecord>, EigenError> {
+		self.get(&format!("{}/0x{}", ATTESTATIONS_PATH, hex::encode(address))).await
+	}
+
+	/// Fetches the report of the potential sybil rings, flagging the clusters keeping at
+	/// least the given share of their trust among themselves, or the scorer's default.
+	pub async fn get_sybil_report(
+		&self, min_internal_share: Option<f64>,
+	) -> Result<SybilReport, EigenError> {
+		match min_internal_share {
+			Some(share) => self.get(&format!("{}?share={}", SYBIL_PATH, share)).await,
+			None => self.get(SYBIL_PATH).await,
+		}
+	}
+
+	/// Submits an attestation signed off-chain to the scorer's relayer.
+	pub async fn submit_attestation(
+		&self, signed_attestation: &SignedAttestationRaw,
+	) -> Result<(), EigenError> {
+		let payload = RelayPayload::from_signed(signed_attestation)?;
+		let body = serde_json::to_string(&payload)?;
+
+		let mut request = self
+			.client
+			.post(self.url(RELAY_PATH))
+			.header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+			.body(body);
+		if let Some(token) = &self.relay_token {
+			request = request.bearer_auth(token);
+		}
+
+		let response = request.send().await.map_err(|e| EigenError::RequestError(e.to_string()))?;
+		Self::check_status(response).await?;
+
+		Ok(())
+	}
+
+	/// Sends a GET request to the given path and parses the JSON response.
+	async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, EigenError> {
+		let response = self
+			.client
+			.get(self.url(path))
+			.send()
+			.await
+			.map_err(|e| EigenError::RequestError(e.to_string()))?;
+		let body = Self::check_status(response).await?;
+
+		Ok(serde_json::from_str(&body)?)
+	}
+
+	/// Returns the response body, or an error if the request failed.
+	async fn check_status(response: Response) -> Result<String, EigenError> {
+		let status = response.status();
+		let body = response.text().await.map_err(|e| EigenError::RequestError(e.to_string()))?;
+
+		if !status.is_success() {
+			return Err(EigenError::RequestError(format!(
+				"Scorer API returned {}: {}",
+				status, body
+			)));
+		}
+
+		Ok(body)
+	}
+
+	/// Returns the URL of the given path.
+	fn url(&self, path: &str) -> String {
+		format!("{}{}", self.base_url, path)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::sdk::*;
+	use std::{
+		io::{Read, Write},
+		net::TcpListener,
+		thread,
+	};
+
+	/// Serves a single HTTP response, returning the server URL.
+	fn serve_once(status: &'static str, body: String) -> String {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let url = format!("http://{}", listener.local_addr().unwrap());
+
+		thread::spawn(move || {
+			let (mut stream, _) = listener.accept().unwrap();
+			let mut request = [0; 1024];
+			let _ = stream.read(&mut request).unwrap();
+
+			let response = format!(
+				"HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+				status,
+				body.len(),
+				body
+			);
+			stream.write_all(response.as_bytes()).unwrap();
+		});
+
+		url
+	}
+
+	#[tokio::test]
+	async fn test_get_scores() {
+		let record = ScoreRecord::new(
+			"0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+			"0x00".to_string(),
+			"1500".to_string(),
+			"1".to_string(),
+			"1500".to_string(),
+		);
+		let body = serde_json::to_string(&vec![record]).unwrap();
+
+		let client = ScorerClient::new(&serve_once("200 OK", body));
+		let scores = client.get_scores().await.unwrap();
+		assert_eq!(scores.len(), 1);
+		assert_eq!(scores[0].score(), "1500");
+
+		// Failed requests carry the response status
+		let client = ScorerClient::new(&serve_once("404 Not Found", "{}".to_string()));
+		let result = client.get_score([1; 20]).await;
+		assert!(matches!(result, Err(EigenError::RequestError(e)) if e.contains("404")));
+	}
+}
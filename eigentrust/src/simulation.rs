@@ -0,0 +1,292 @@
+//! # Simulation Module.
+//!
+//! This module provides synthetic trust networks and a floating-point run of the
+//! EigenTrust convergence over them, for tuning the scoring parameters before going
+//! on-chain. Synthetic networks aren't bound by the circuit set size, so their scores
+//! only approximate the native set ones and can't be proven.
+
+use crate::{domain::DomainParams, error::EigenError};
+use eigentrust_zk::circuits::MIN_PEER_COUNT;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::Serialize;
+use std::str::FromStr;
+
+/// Relative score change under which the scores are considered converged.
+pub const CONVERGENCE_TOLERANCE: f64 = 1e-6;
+
+/// Distribution of the synthetic attestation scores.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoreDistribution {
+	/// Scores drawn uniformly between 1 and 255.
+	#[default]
+	Uniform,
+	/// Every attestation gives the maximum score.
+	Constant,
+	/// Mostly low scores, with a few high ones.
+	Skewed,
+}
+
+impl ScoreDistribution {
+	/// Draws an attestation score.
+	pub fn sample<R: Rng>(&self, rng: &mut R) -> u8 {
+		match self {
+			ScoreDistribution::Uniform => rng.gen_range(1..=u8::MAX),
+			ScoreDistribution::Constant => u8::MAX,
+			ScoreDistribution::Skewed => {
+				let x: f64 = rng.gen();
+				(x * x * f64::from(u8::MAX)).ceil().clamp(1.0, f64::from(u8::MAX)) as u8
+			},
+		}
+	}
+}
+
+impl FromStr for ScoreDistribution {
+	type Err = EigenError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"uniform" => Ok(ScoreDistribution::Uniform),
+			"constant" => Ok(ScoreDistribution::Constant),
+			"skewed" => Ok(ScoreDistribution::Skewed),
+			_ => Err(EigenError::ParsingError(format!(
+				"Invalid score distribution: {}",
+				s
+			))),
+		}
+	}
+}
+
+/// Synthetic trust network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyntheticNetwork {
+	/// Number of peers.
+	pub peers: usize,
+	/// Attestations, as (attester, attested, score) triples of peer indices.
+	pub edges: Vec<(usize, usize, u8)>,
+}
+
+impl SyntheticNetwork {
+	/// Generates a network where every peer attests to every other one with the given
+	/// probability, drawing the scores from the distribution. Networks generated from the
+	/// same seed are identical.
+	pub fn generate(
+		peers: usize, density: f64, distribution: ScoreDistribution, seed: Option<u64>,
+	) -> Result<Self, EigenError> {
+		if peers < MIN_PEER_COUNT {
+			return Err(EigenError::ValidationError(format!(
+				"At least {} peers are required",
+				MIN_PEER_COUNT
+			)));
+		}
+		if density.is_nan() || density <= 0.0 || density > 1.0 {
+			return Err(EigenError::ValidationError(
+				"The edge density must be in (0, 1]".to_string(),
+			));
+		}
+
+		let mut rng = match seed {
+			Some(seed) => StdRng::seed_from_u64(seed),
+			None => StdRng::from_entropy(),
+		};
+		let mut edges = Vec::new();
+		for from in 0..peers {
+			for to in (0..peers).filter(|&to| to != from) {
+				if rng.gen_bool(density) {
+					edges.push((from, to, distribution.sample(&mut rng)));
+				}
+			}
+		}
+
+		Ok(Self { peers, edges })
+	}
+
+	/// Runs the EigenTrust convergence with the given parameters. Opinions are handled
+	/// like in the native set: self-attestations are ignored and peers without any
+	/// attestation distribute their score evenly to the others.
+	pub fn converge(&self, params: DomainParams) -> Result<SimulationReport, EigenError> {
+		params.validate()?;
+		let num_iterations = usize::try_from(params.num_iterations)
+			.map_err(|e| EigenError::ConversionError(e.to_string()))?;
+
+		// Normalized opinions of every peer
+		let mut opinions: Vec<Vec<(usize, f64)>> = vec![Vec::new(); self.peers];
+		for &(from, to, score) in &self.edges {
+			if from == to || score == 0 {
+				continue;
+			}
+			match opinions[from].iter_mut().find(|(peer, _)| *peer == to) {
+				Some(opinion) => opinion.1 = f64::from(score),
+				None => opinions[from].push((to, f64::from(score))),
+			}
+		}
+		for opinion in opinions.iter_mut() {
+			let sum: f64 = opinion.iter().map(|(_, score)| score).sum();
+			opinion.iter_mut().for_each(|(_, score)| *score /= sum);
+		}
+
+		let initial_score = params.initial_score as f64;
+		let total = initial_score * self.peers as f64;
+		let others = (self.peers - 1) as f64;
+
+		let mut scores = vec![initial_score; self.peers];
+		let mut deltas = Vec::with_capacity(num_iterations);
+		for _ in 0..num_iterations {
+			// Scores of the peers without opinions, spread to all the others
+			let spread: f64 = (0..self.peers)
+				.filter(|&peer| opinions[peer].is_empty())
+				.map(|peer| scores[peer])
+				.sum();
+
+			let mut new_scores: Vec<f64> = (0..self.peers)
+				.map(|peer| {
+					let own = if opinions[peer].is_empty() { scores[peer] } else { 0.0 };
+					(spread - own) / others
+				})
+				.collect();
+			for (from, opinion) in opinions.iter().enumerate() {
+				for &(to, weight) in opinion {
+					new_scores[to] += weight * scores[from];
+				}
+			}
+
+			let delta: f64 =
+				scores.iter().zip(&new_scores).map(|(old, new)| (new - old).abs()).sum();
+			deltas.push(delta / total);
+			scores = new_scores;
+		}
+
+		let converged_at =
+			deltas.iter().position(|&delta| delta <= CONVERGENCE_TOLERANCE).map(|i| i + 1);
+
+		Ok(SimulationReport {
+			peers: self.peers,
+			edges: self.edges.len(),
+			iterations: num_iterations,
+			converged_at,
+			final_delta: deltas.last().copied().unwrap_or_default(),
+			stats: ScoreStats::new(&scores),
+			deltas,
+		})
+	}
+}
+
+/// Summary of the scores of a simulation.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ScoreStats {
+	/// Lowest score.
+	pub min: f64,
+	/// Highest score.
+	pub max: f64,
+	/// Mean score, the initial score.
+	pub mean: f64,
+	/// Median score.
+	pub median: f64,
+	/// Standard deviation of the scores.
+	pub std_dev: f64,
+	/// Share of the total score held by the top 10% of the peers.
+	pub top_decile_share: f64,
+}
+
+impl ScoreStats {
+	/// Computes the summary of the given scores.
+	pub fn new(scores: &[f64]) -> Self {
+		if scores.is_empty() {
+			return Self::default();
+		}
+
+		let mut sorted = scores.to_vec();
+		sorted.sort_by(f64::total_cmp);
+
+		let count = sorted.len() as f64;
+		let total: f64 = sorted.iter().sum();
+		let mean = total / count;
+		let variance = sorted.iter().map(|score| (score - mean).powi(2)).sum::<f64>() / count;
+
+		let middle = sorted.len() / 2;
+		let median = if sorted.len() % 2 == 0 {
+			(sorted[middle - 1] + sorted[middle]) / 2.0
+		} else {
+			sorted[middle]
+		};
+
+		let top_count = (sorted.len() + 9) / 10;
+		let top_total: f64 = sorted.iter().rev().take(top_count).sum();
+		let top_decile_share = if total > 0.0 { top_total / total } else { 0.0 };
+
+		Self {
+			min: sorted[0],
+			max: sorted[sorted.len() - 1],
+			mean,
+			median,
+			std_dev: variance.sqrt(),
+			top_decile_share,
+		}
+	}
+}
+
+/// Outcome of a simulation.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SimulationReport {
+	/// Number of peers.
+	pub peers: usize,
+	/// Number of attestations.
+	pub edges: usize,
+	/// Number of iterations run.
+	pub iterations: usize,
+	/// First iteration whose relative score change was under `CONVERGENCE_TOLERANCE`,
+	/// if any.
+	pub converged_at: Option<usize>,
+	/// Relative score change of the last iteration.
+	pub final_delta: f64,
+	/// Summary of the final scores.
+	pub stats: ScoreStats,
+	/// Relative score change of every iteration.
+	pub deltas: Vec<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::simulation::*;
+
+	#[test]
+	fn test_synthetic_network() {
+		// Complete networks have an edge between every pair of peers
+		let network =
+			SyntheticNetwork::generate(5, 1.0, ScoreDistribution::Constant, None).unwrap();
+		assert_eq!(network.edges.len(), 20);
+		assert!(network.edges.iter().all(|&(from, to, score)| from != to && score == u8::MAX));
+
+		// Symmetric networks keep the initial scores
+		let report = network.converge(DomainParams::new(10, 1000)).unwrap();
+		assert_eq!(report.iterations, 10);
+		assert_eq!(report.converged_at, Some(1));
+		assert!((report.stats.min - 1000.0).abs() < 1e-9);
+		assert!((report.stats.max - 1000.0).abs() < 1e-9);
+
+		// Scores are conserved
+		let network =
+			SyntheticNetwork::generate(50, 0.2, ScoreDistribution::Skewed, Some(7)).unwrap();
+		assert_eq!(
+			SyntheticNetwork::generate(50, 0.2, ScoreDistribution::Skewed, Some(7)).unwrap(),
+			network
+		);
+		let report = network.converge(DomainParams::new(20, 1000)).unwrap();
+		assert!((report.stats.mean - 1000.0).abs() < 1e-6);
+		assert_eq!(report.deltas.len(), 20);
+
+		assert!(SyntheticNetwork::generate(1, 0.5, ScoreDistribution::Uniform, None).is_err());
+		assert!(SyntheticNetwork::generate(5, 0.0, ScoreDistribution::Uniform, None).is_err());
+		assert!("normal".parse::<ScoreDistribution>().is_err());
+	}
+
+	#[test]
+	fn test_score_stats() {
+		let stats = ScoreStats::new(&[4.0, 1.0, 3.0, 2.0]);
+		assert_eq!(stats.min, 1.0);
+		assert_eq!(stats.max, 4.0);
+		assert_eq!(stats.mean, 2.5);
+		assert_eq!(stats.median, 2.5);
+		assert_eq!(stats.top_decile_share, 0.4);
+	}
+}
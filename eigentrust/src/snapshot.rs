@@ -0,0 +1,219 @@
+//! # Snapshot Module.
+//!
+//! This module provides the state snapshot of a client: the indexed attestations, the
+//! checkpoint of the last indexed block and the computed scores, bundled in a single
+//! versioned document that can be moved between machines or published. Score snapshots
+//! only carry the computed scores, for distributing them without the attestations.
+
+use crate::{
+	attestation::{IndexedAttestation, SignedAttestationRaw},
+	circuit::Score,
+	domain::DomainParams,
+	error::EigenError,
+	storage::{str_to_32_byte_array, AttestationRecord, ScoreRecord},
+};
+use ethers::utils::hex;
+use serde::{Deserialize, Serialize};
+
+/// Current snapshot format version.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Last indexed block.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncCheckpoint {
+	/// Block number.
+	pub block_number: u64,
+	/// Block hash.
+	pub block_hash: String,
+}
+
+impl SyncCheckpoint {
+	/// Creates the checkpoint of the given block.
+	pub fn new(block_number: u64, block_hash: [u8; 32]) -> Self {
+		Self { block_number, block_hash: format!("0x{}", hex::encode(block_hash)) }
+	}
+
+	/// Returns the block as a (number, hash) pair.
+	pub fn block(&self) -> Result<(u64, [u8; 32]), EigenError> {
+		Ok((self.block_number, str_to_32_byte_array(&self.block_hash)?))
+	}
+}
+
+/// Versioned state snapshot.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StateSnapshot {
+	/// Snapshot format version.
+	pub version: u32,
+	/// Network chain ID.
+	pub chain_id: u64,
+	/// AttestationStation contract address.
+	pub as_address: String,
+	/// Attestation domain.
+	pub domain: String,
+	/// Last indexed block, if any attestation was indexed.
+	pub checkpoint: Option<SyncCheckpoint>,
+	/// Indexed attestations.
+	pub attestations: Vec<AttestationRecord>,
+	/// Computed scores.
+	pub scores: Vec<ScoreRecord>,
+}
+
+impl StateSnapshot {
+	/// Creates a snapshot of the given attestations and scores.
+	pub fn new(
+		chain_id: u64, as_address: [u8; 20], domain: [u8; 20],
+		attestations: Vec<IndexedAttestation>, scores: &[Score],
+	) -> Self {
+		let checkpoint = attestations
+			.iter()
+			.max_by_key(|indexed| indexed.block_number())
+			.map(|indexed| SyncCheckpoint::new(indexed.block_number(), indexed.block_hash()));
+
+		Self {
+			version: SNAPSHOT_VERSION,
+			chain_id,
+			as_address: format!("0x{}", hex::encode(as_address)),
+			domain: format!("0x{}", hex::encode(domain)),
+			checkpoint,
+			attestations: attestations.into_iter().map(AttestationRecord::from).collect(),
+			scores: scores.iter().cloned().map(ScoreRecord::from_score).collect(),
+		}
+	}
+
+	/// Checks that the snapshot can be read by this version of the library.
+	pub fn check_version(&self) -> Result<(), EigenError> {
+		if self.version != SNAPSHOT_VERSION {
+			return Err(EigenError::ValidationError(format!(
+				"Unsupported snapshot version {}, expected {}",
+				self.version, SNAPSHOT_VERSION
+			)));
+		}
+
+		Ok(())
+	}
+
+	/// Returns the (number, hash) pairs of the blocks including the attestations.
+	pub fn indexed_blocks(&self) -> Result<Vec<(u64, [u8; 32])>, EigenError> {
+		self.attestations
+			.iter()
+			.map(|record| {
+				record.block()?.ok_or_else(|| {
+					EigenError::ValidationError(
+						"Snapshot attestation without block data".to_string(),
+					)
+				})
+			})
+			.collect()
+	}
+
+	/// Returns the signed attestations.
+	pub fn signed_attestations(&self) -> Result<Vec<SignedAttestationRaw>, EigenError> {
+		self.attestations.iter().cloned().map(SignedAttestationRaw::try_from).collect()
+	}
+
+	/// Drops the attestations included from the given block onwards, along with the
+	/// scores computed from them.
+	pub fn truncate(&mut self, from_block: u64) -> Result<(), EigenError> {
+		let blocks = self.indexed_blocks()?;
+		let mut kept = Vec::new();
+		for (record, (number, _)) in self.attestations.drain(..).zip(blocks) {
+			if number < from_block {
+				kept.push(record);
+			}
+		}
+		self.attestations = kept;
+
+		self.checkpoint = self
+			.indexed_blocks()?
+			.into_iter()
+			.max_by_key(|(number, _)| *number)
+			.map(|(number, hash)| SyncCheckpoint::new(number, hash));
+		self.scores.clear();
+
+		Ok(())
+	}
+}
+
+/// Versioned snapshot of the scores computed at a block.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScoreSnapshot {
+	/// Snapshot format version.
+	pub version: u32,
+	/// Network chain ID.
+	pub chain_id: u64,
+	/// AttestationStation contract address.
+	pub as_address: String,
+	/// Attestation domain.
+	pub domain: String,
+	/// Last block of the attestations the scores were computed from.
+	pub block_number: u64,
+	/// Scoring parameters.
+	pub params: DomainParams,
+	/// Computed scores.
+	pub scores: Vec<ScoreRecord>,
+}
+
+impl ScoreSnapshot {
+	/// Creates a snapshot of the given scores.
+	pub fn new(
+		chain_id: u64, as_address: [u8; 20], domain: [u8; 20], block_number: u64,
+		params: DomainParams, scores: &[Score],
+	) -> Self {
+		Self {
+			version: SNAPSHOT_VERSION,
+			chain_id,
+			as_address: format!("0x{}", hex::encode(as_address)),
+			domain: format!("0x{}", hex::encode(domain)),
+			block_number,
+			params,
+			scores: scores.iter().cloned().map(ScoreRecord::from_score).collect(),
+		}
+	}
+
+	/// Checks that the snapshot can be read by this version of the library.
+	pub fn check_version(&self) -> Result<(), EigenError> {
+		if self.version != SNAPSHOT_VERSION {
+			return Err(EigenError::ValidationError(format!(
+				"Unsupported snapshot version {}, expected {}",
+				self.version, SNAPSHOT_VERSION
+			)));
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::attestation::{AttestationRaw, SignatureRaw};
+	use crate::snapshot::*;
+
+	fn indexed(block_number: u64) -> IndexedAttestation {
+		let signed = SignedAttestationRaw::new(
+			AttestationRaw { about: [1; 20], domain: [2; 20], value: 5, message: [0; 32] },
+			SignatureRaw { sig_r: [3; 32], sig_s: [4; 32], rec_id: 0 },
+		);
+
+		IndexedAttestation::new(signed, [5; 20], block_number, [block_number as u8; 32])
+	}
+
+	#[test]
+	fn test_snapshot_roundtrip() {
+		let snapshot =
+			StateSnapshot::new(31337, [6; 20], [2; 20], vec![indexed(3), indexed(8)], &[]);
+		assert_eq!(snapshot.checkpoint, Some(SyncCheckpoint::new(8, [8; 32])));
+
+		let json = serde_json::to_string(&snapshot).unwrap();
+		let mut restored: StateSnapshot = serde_json::from_str(&json).unwrap();
+		restored.check_version().unwrap();
+		assert_eq!(restored.signed_attestations().unwrap().len(), 2);
+
+		// Truncating moves the checkpoint back
+		restored.truncate(5).unwrap();
+		assert_eq!(restored.attestations.len(), 1);
+		assert_eq!(restored.checkpoint, Some(SyncCheckpoint::new(3, [3; 32])));
+
+		restored.version = SNAPSHOT_VERSION + 1;
+		assert!(restored.check_version().is_err());
+	}
+}
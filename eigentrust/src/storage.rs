@@ -3,23 +3,61 @@
 //! This module contains generic storage traits and implementations.
 
 use crate::{
-	attestation::{AttestationRaw, SignatureRaw, SignedAttestationRaw},
-	circuit::Score,
+	attestation::{
+		AttestationRaw, IndexedAttestation, SignatureRaw, SignedAttestationEth,
+		SignedAttestationRaw,
+	},
+	circuit::{Circuit, ProofArtifact, Score, ScoreTrace},
 	error::EigenError,
+	eth::address_from_ecdsa_key,
+};
+use async_trait::async_trait;
+use chacha20poly1305::{
+	aead::{Aead, KeyInit, Payload},
+	ChaCha20Poly1305, Key, Nonce,
 };
 use csv::{ReaderBuilder, WriterBuilder};
+use eigentrust_zk::circuits::{dynamic_sets::native::ScoreNormalization, RationalScore};
 use ethers::{
+	signers::LocalWallet,
 	types::{H160, H256, U256},
-	utils::hex,
+	utils::{hex, keccak256},
 };
-use log::debug;
+use flate2::{read::MultiGzDecoder, write::GzEncoder};
+use rand::Rng;
 use serde::Deserialize;
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::{from_reader, to_string};
-use std::io::{BufReader, Read, Write};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::marker::PhantomData;
-use std::path::PathBuf;
-use std::{fs::File, str::FromStr};
+use std::path::{Path, PathBuf};
+use std::{
+	fs::{self, File, OpenOptions},
+	process,
+	str::FromStr,
+};
+use tracing::{debug, warn};
+
+/// Prefix of the CSV comment lines.
+pub const CSV_COMMENT_PREFIX: char = '#';
+/// Magic bytes starting the framed binary files.
+pub const FRAME_MAGIC: [u8; 4] = *b"ETBF";
+/// Current framed binary format version.
+pub const FRAME_VERSION: u16 = 1;
+/// Length of the framed binary header: magic, version, kind, payload length and checksum.
+pub const FRAME_HEADER_LEN: usize = 4 + 2 + 1 + 8 + 32;
+/// Magic bytes starting the encrypted files.
+pub const ENCRYPTION_MAGIC: [u8; 4] = *b"ETEC";
+/// Current encrypted file format version.
+pub const ENCRYPTION_VERSION: u16 = 1;
+/// Length of the encrypted file header: magic, version and nonce.
+pub const ENCRYPTION_HEADER_LEN: usize = 4 + 2 + 12;
+/// Context of the storage keys derived from keystore secrets.
+pub const STORAGE_KEY_CONTEXT: &[u8] = b"EIGENTRUST_STORAGE_KEY";
+/// Decimals of the replayed iteration residuals.
+pub const RESIDUAL_DECIMALS: usize = 6;
 
 /// The main trait to be implemented by different storage types.
 pub trait Storage<T> {
@@ -32,8 +70,263 @@ pub trait Storage<T> {
 	fn save(&mut self, data: T) -> Result<(), Self::Err>;
 }
 
+/// Async variant of the `Storage` trait, for remote backends such as databases, object
+/// stores or IPFS. Every `Storage` implements it, running its operations in place.
+#[async_trait]
+pub trait AsyncStorage<T: Send> {
+	/// The error type.
+	type Err;
+
+	/// Loads data from storage.
+	async fn load_async(&self) -> Result<T, Self::Err>;
+	/// Saves data to storage.
+	async fn save_async(&mut self, data: T) -> Result<(), Self::Err>;
+}
+
+#[async_trait]
+impl<T, S> AsyncStorage<T> for S
+where
+	T: Send + 'static,
+	S: Storage<T> + Send + Sync,
+	S::Err: Send,
+{
+	type Err = S::Err;
+
+	async fn load_async(&self) -> Result<T, Self::Err> {
+		self.load()
+	}
+
+	async fn save_async(&mut self, data: T) -> Result<(), Self::Err> {
+		self.save(data)
+	}
+}
+
+/// Indexed queries of the stored attestations, implemented by the attestation storages so
+/// callers don't have to load every record to find a few of them.
+pub trait AttestationIndex {
+	/// The error type.
+	type Err;
+
+	/// Returns the attestations about the given address.
+	fn attestations_about(&self, address: [u8; 20]) -> Result<Vec<AttestationRecord>, Self::Err>;
+	/// Returns the attestations of the given attester, see `AttestationRecord::attester`.
+	fn attestations_by(&self, address: [u8; 20]) -> Result<Vec<AttestationRecord>, Self::Err>;
+	/// Returns the attestations included in the given inclusive block range. Records
+	/// without block data are left out.
+	fn attestations_in_range(
+		&self, from_block: u64, to_block: u64,
+	) -> Result<Vec<AttestationRecord>, Self::Err>;
+}
+
+/// Async variant of the `AttestationIndex` trait, for remote backends such as databases.
+/// Every `AttestationIndex` implements it, running its queries in place.
+#[async_trait]
+pub trait AsyncAttestationIndex {
+	/// The error type.
+	type Err;
+
+	/// Returns the attestations about the given address.
+	async fn attestations_about_async(
+		&self, address: [u8; 20],
+	) -> Result<Vec<AttestationRecord>, Self::Err>;
+	/// Returns the attestations of the given attester, see `AttestationRecord::attester`.
+	async fn attestations_by_async(
+		&self, address: [u8; 20],
+	) -> Result<Vec<AttestationRecord>, Self::Err>;
+	/// Returns the attestations included in the given inclusive block range. Records
+	/// without block data are left out.
+	async fn attestations_in_range_async(
+		&self, from_block: u64, to_block: u64,
+	) -> Result<Vec<AttestationRecord>, Self::Err>;
+}
+
+#[async_trait]
+impl<S> AsyncAttestationIndex for S
+where
+	S: AttestationIndex + Sync,
+	S::Err: Send,
+{
+	type Err = S::Err;
+
+	async fn attestations_about_async(
+		&self, address: [u8; 20],
+	) -> Result<Vec<AttestationRecord>, Self::Err> {
+		self.attestations_about(address)
+	}
+
+	async fn attestations_by_async(
+		&self, address: [u8; 20],
+	) -> Result<Vec<AttestationRecord>, Self::Err> {
+		self.attestations_by(address)
+	}
+
+	async fn attestations_in_range_async(
+		&self, from_block: u64, to_block: u64,
+	) -> Result<Vec<AttestationRecord>, Self::Err> {
+		self.attestations_in_range(from_block, to_block)
+	}
+}
+
+/// Compression of a storage file, given by its extension: `.gz` files are compressed with
+/// gzip and `.zst` files with Zstandard, any other file is left uncompressed. Appending to a
+/// compressed file adds a new gzip member or Zstandard frame, read back as a single stream.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+	/// Uncompressed file.
+	#[default]
+	None,
+	/// Gzip compression.
+	Gzip,
+	/// Zstandard compression.
+	Zstd,
+}
+
+impl Compression {
+	/// Returns the compression of the file at the given path.
+	pub fn from_path(path: &Path) -> Self {
+		match path.extension().and_then(|extension| extension.to_str()) {
+			Some("gz") => Compression::Gzip,
+			Some("zst") => Compression::Zstd,
+			_ => Compression::None,
+		}
+	}
+
+	/// Returns the file extension of the compression, if any.
+	pub fn extension(&self) -> Option<&'static str> {
+		match self {
+			Compression::None => None,
+			Compression::Gzip => Some("gz"),
+			Compression::Zstd => Some("zst"),
+		}
+	}
+
+	/// Opens the file for reading its decompressed content.
+	fn reader(path: &Path) -> Result<Box<dyn Read + Send>, EigenError> {
+		let file = File::open(path).map_err(EigenError::IOError)?;
+
+		Ok(match Self::from_path(path) {
+			Compression::None => Box::new(file),
+			Compression::Gzip => Box::new(MultiGzDecoder::new(BufReader::new(file))),
+			Compression::Zstd => {
+				Box::new(zstd::stream::read::Decoder::new(file).map_err(EigenError::IOError)?)
+			},
+		})
+	}
+}
+
+impl FromStr for Compression {
+	type Err = EigenError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"none" => Ok(Compression::None),
+			"gzip" => Ok(Compression::Gzip),
+			"zstd" => Ok(Compression::Zstd),
+			_ => Err(EigenError::ParsingError(format!(
+				"Invalid compression: {}",
+				s
+			))),
+		}
+	}
+}
+
+/// Writer of a storage file, compressing the content according to the file extension.
+enum FileWriter {
+	Plain(File),
+	Gzip(GzEncoder<File>),
+	Zstd(zstd::stream::write::Encoder<'static, File>),
+}
+
+impl FileWriter {
+	/// Wraps the file opened at the given path.
+	fn new(file: File, path: &Path) -> Result<Self, EigenError> {
+		Ok(match Compression::from_path(path) {
+			Compression::None => FileWriter::Plain(file),
+			Compression::Gzip => {
+				FileWriter::Gzip(GzEncoder::new(file, flate2::Compression::default()))
+			},
+			Compression::Zstd => FileWriter::Zstd(
+				zstd::stream::write::Encoder::new(file, 0).map_err(EigenError::IOError)?,
+			),
+		})
+	}
+
+	/// Writes the end of the compressed stream, if any, returning the file.
+	fn finish(self) -> Result<File, EigenError> {
+		match self {
+			FileWriter::Plain(mut file) => file.flush().map(|_| file),
+			FileWriter::Gzip(encoder) => encoder.finish(),
+			FileWriter::Zstd(encoder) => encoder.finish(),
+		}
+		.map_err(EigenError::IOError)
+	}
+}
+
+impl Write for FileWriter {
+	fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+		match self {
+			FileWriter::Plain(file) => file.write(buf),
+			FileWriter::Gzip(encoder) => encoder.write(buf),
+			FileWriter::Zstd(encoder) => encoder.write(buf),
+		}
+	}
+
+	fn flush(&mut self) -> std::io::Result<()> {
+		match self {
+			FileWriter::Plain(file) => file.flush(),
+			FileWriter::Gzip(encoder) => encoder.flush(),
+			FileWriter::Zstd(encoder) => encoder.flush(),
+		}
+	}
+}
+
+/// Writes the header lines as comments, then the records, and finishes the file.
+fn write_csv<T: Serialize>(
+	mut file: FileWriter, header: &[String], data: &[T], has_headers: bool,
+) -> Result<File, EigenError> {
+	for line in header {
+		writeln!(file, "{} {}", CSV_COMMENT_PREFIX, line).map_err(EigenError::IOError)?;
+	}
+
+	let mut writer = WriterBuilder::new().has_headers(has_headers).from_writer(file);
+	for record in data {
+		writer.serialize(record)?;
+	}
+	writer.flush()?;
+
+	writer.into_inner().map_err(|e| EigenError::IOError(e.into_error()))?.finish()
+}
+
+/// Returns the path of the temporary file written before replacing the given file.
+fn temp_path(path: &Path) -> PathBuf {
+	let mut file_name = path.file_name().map(OsString::from).unwrap_or_default();
+	file_name.push(format!(".{}.tmp", process::id()));
+	path.with_file_name(file_name)
+}
+
+/// Replaces the file atomically: the content is written to a temporary file next to it,
+/// synced, then renamed over it, so a crash mid-write leaves either the old or the new
+/// file but never a truncated one.
+fn write_atomic(
+	path: &Path, write: impl FnOnce(File) -> Result<File, EigenError>,
+) -> Result<(), EigenError> {
+	let temp_path = temp_path(path);
+	let result = File::create(&temp_path)
+		.map_err(EigenError::IOError)
+		.and_then(write)
+		.and_then(|file| file.sync_all().map_err(EigenError::IOError))
+		.and_then(|_| fs::rename(&temp_path, path).map_err(EigenError::IOError));
+
+	if result.is_err() {
+		let _ = fs::remove_file(&temp_path);
+	}
+
+	result
+}
+
 /// The `CSVFileStorage` struct provides a mechanism for persisting
-/// and retrieving structured data to and from CSV files.
+/// and retrieving structured data to and from CSV files, compressed according to their
+/// extension, see `Compression`.
 ///
 /// # Examples
 ///
@@ -62,53 +355,157 @@ pub trait Storage<T> {
 /// ```
 pub struct CSVFileStorage<T> {
 	filepath: PathBuf,
+	header: Vec<String>,
 	phantom: PhantomData<T>,
 }
 
 impl<T> CSVFileStorage<T> {
 	/// Creates a new CSVFileStorage.
 	pub fn new(filepath: PathBuf) -> Self {
-		Self { filepath, phantom: PhantomData }
+		Self { filepath, header: Vec::new(), phantom: PhantomData }
+	}
+
+	/// Sets the header lines written as `#` comments before the records.
+	/// Comment lines are skipped when loading the records.
+	pub fn with_header(mut self, header: Vec<String>) -> Self {
+		self.header = header;
+		self
 	}
 
 	/// Returns the path to the file.
 	pub fn filepath(&self) -> &PathBuf {
 		&self.filepath
 	}
+
+	/// Loads the header lines of the file, without the comment prefix.
+	pub fn load_header(&self) -> Result<Vec<String>, EigenError> {
+		let reader = Compression::reader(&self.filepath)?;
+
+		let mut header = Vec::new();
+		for line in BufReader::new(reader).lines() {
+			let line = line.map_err(EigenError::IOError)?;
+			match line.strip_prefix(CSV_COMMENT_PREFIX) {
+				Some(comment) => header.push(comment.trim_start().to_string()),
+				None => break,
+			}
+		}
+
+		Ok(header)
+	}
+}
+
+impl<T: DeserializeOwned> CSVFileStorage<T> {
+	/// Returns an iterator reading the records one at a time, so large files can be processed
+	/// without loading all their records in memory.
+	pub fn load_iter(&self) -> Result<impl Iterator<Item = Result<T, EigenError>>, EigenError> {
+		let reader = ReaderBuilder::new()
+			.comment(Some(CSV_COMMENT_PREFIX as u8))
+			.from_reader(BufReader::new(Compression::reader(&self.filepath)?));
+
+		Ok(reader.into_deserialize().map(|result| result.map_err(EigenError::from)))
+	}
+}
+
+impl<T: Serialize + DeserializeOwned + Clone> CSVFileStorage<T> {
+	/// Appends the records to the file, leaving the existing ones untouched. Missing or
+	/// empty files are created with the header lines and the column names first.
+	pub fn append(&mut self, data: Vec<T>) -> Result<(), EigenError> {
+		let file = OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(&self.filepath)
+			.map_err(EigenError::IOError)?;
+		let is_empty = file.metadata().map_err(EigenError::IOError)?.len() == 0;
+		let header = if is_empty { self.header.as_slice() } else { &[] };
+
+		write_csv(
+			FileWriter::new(file, &self.filepath)?,
+			header,
+			&data,
+			is_empty,
+		)?;
+
+		Ok(())
+	}
+
+	/// Appends the records whose key is neither in the file nor in a previous record.
+	/// Returns the number of appended records.
+	pub fn append_unique<K: Ord>(
+		&mut self, data: Vec<T>, key: impl Fn(&T) -> K,
+	) -> Result<usize, EigenError> {
+		let mut keys: BTreeSet<K> = if self.filepath.exists() {
+			self.load_iter()?
+				.map(|record| record.map(|record| key(&record)))
+				.collect::<Result<_, _>>()?
+		} else {
+			BTreeSet::new()
+		};
+		let unique: Vec<T> = data.into_iter().filter(|record| keys.insert(key(record))).collect();
+		let appended = unique.len();
+
+		self.append(unique)?;
+
+		Ok(appended)
+	}
 }
 
 impl<T: Serialize + DeserializeOwned + Clone> Storage<Vec<T>> for CSVFileStorage<T> {
 	type Err = EigenError;
 
 	fn load(&self) -> Result<Vec<T>, EigenError> {
-		let file = File::open(&self.filepath).map_err(EigenError::IOError)?;
-		let mut reader = ReaderBuilder::new().from_reader(BufReader::new(file));
-
-		reader
-			.deserialize()
-			.map(|result| result.map_err(|e| EigenError::FileIOError(e.to_string())))
-			.collect()
+		self.load_iter()?.collect()
 	}
 
 	fn save(&mut self, data: Vec<T>) -> Result<(), EigenError> {
-		let mut writer = WriterBuilder::new()
-			.from_path(&self.filepath)
-			.map_err(|e| EigenError::FileIOError(e.to_string()))?;
+		write_atomic(&self.filepath, |file| {
+			write_csv(
+				FileWriter::new(file, &self.filepath)?,
+				&self.header,
+				&data,
+				true,
+			)
+		})
+	}
+}
 
-		// Loop over content and write each item
-		for record in &data {
-			writer.serialize(record).map_err(|e| EigenError::FileIOError(e.to_string()))?;
-		}
+impl CSVFileStorage<AttestationRecord> {
+	/// Loads the attestation records, deduplicated according to the given policy.
+	pub fn load_deduplicated(
+		&self, policy: DedupPolicy,
+	) -> Result<Vec<AttestationRecord>, EigenError> {
+		Ok(policy.apply(self.load()?))
+	}
 
-		// Flush buffer
-		writer.flush().map_err(|e| EigenError::FileIOError(e.to_string()))?;
+	/// Streams the file, keeping the records matching the predicate.
+	fn load_matching(
+		&self, predicate: impl Fn(&AttestationRecord) -> bool,
+	) -> Result<Vec<AttestationRecord>, EigenError> {
+		self.load_iter()?.filter(|record| record.as_ref().map_or(true, &predicate)).collect()
+	}
+}
 
-		Ok(())
+impl AttestationIndex for CSVFileStorage<AttestationRecord> {
+	type Err = EigenError;
+
+	fn attestations_about(&self, address: [u8; 20]) -> Result<Vec<AttestationRecord>, Self::Err> {
+		let address = format!("0x{}", hex::encode(address));
+		self.load_matching(|record| record.about.eq_ignore_ascii_case(&address))
+	}
+
+	fn attestations_by(&self, address: [u8; 20]) -> Result<Vec<AttestationRecord>, Self::Err> {
+		self.load_matching(|record| record.attester() == Some(address))
+	}
+
+	fn attestations_in_range(
+		&self, from_block: u64, to_block: u64,
+	) -> Result<Vec<AttestationRecord>, Self::Err> {
+		self.load_matching(|record| record.is_in_range(from_block, to_block))
 	}
 }
 
 /// The `JSONFileStorage` struct provides a mechanism for persisting
-/// and retrieving structured data to and from JSON files.
+/// and retrieving structured data to and from JSON files, compressed according to their
+/// extension, see `Compression`.
 pub struct JSONFileStorage<T> {
 	filepath: PathBuf,
 	phantom: PhantomData<T>,
@@ -130,16 +527,18 @@ impl<T: Serialize + DeserializeOwned + Clone> Storage<T> for JSONFileStorage<T>
 	type Err = EigenError;
 
 	fn load(&self) -> Result<T, Self::Err> {
-		let file = File::open(&self.filepath).map_err(EigenError::IOError)?;
-		let reader = BufReader::new(file);
-		from_reader(reader).map_err(|e| EigenError::ParsingError(e.to_string()))
+		let reader = BufReader::new(Compression::reader(&self.filepath)?);
+		Ok(from_reader(reader)?)
 	}
 
 	fn save(&mut self, data: T) -> Result<(), Self::Err> {
-		let json_str = to_string(&data).map_err(|e| EigenError::ParsingError(e.to_string()))?;
+		let json_str = to_string(&data)?;
 
-		let mut file = File::create(&self.filepath).map_err(EigenError::IOError)?;
-		file.write_all(json_str.as_bytes()).map_err(EigenError::IOError)
+		write_atomic(&self.filepath, |file| {
+			let mut writer = FileWriter::new(file, &self.filepath)?;
+			writer.write_all(json_str.as_bytes()).map_err(EigenError::IOError)?;
+			writer.finish()
+		})
 	}
 }
 
@@ -174,8 +573,440 @@ impl Storage<Vec<u8>> for BinFileStorage {
 
 	fn save(&mut self, data: Vec<u8>) -> Result<(), Self::Err> {
 		debug!("Saving file to: {:?}", &self.filepath);
-		let mut file = File::create(&self.filepath).map_err(EigenError::IOError)?;
-		file.write_all(&data).map_err(EigenError::IOError)
+		write_atomic(&self.filepath, |mut file| {
+			file.write_all(&data).map_err(EigenError::IOError)?;
+			Ok(file)
+		})
+	}
+}
+
+/// The `MemoryStorage` struct keeps the data in memory, for tests and interactive
+/// sessions that don't need to persist it.
+#[derive(Clone, Debug)]
+pub struct MemoryStorage<T> {
+	data: Option<T>,
+}
+
+impl<T> MemoryStorage<T> {
+	/// Creates a new empty MemoryStorage.
+	pub fn new() -> Self {
+		Self { data: None }
+	}
+
+	/// Creates a new MemoryStorage holding the given data.
+	pub fn with_data(data: T) -> Self {
+		Self { data: Some(data) }
+	}
+
+	/// Returns the stored data, if any.
+	pub fn data(&self) -> Option<&T> {
+		self.data.as_ref()
+	}
+
+	/// Removes the stored data, returning it.
+	pub fn take(&mut self) -> Option<T> {
+		self.data.take()
+	}
+}
+
+impl MemoryStorage<Vec<AttestationRecord>> {
+	/// Returns the stored records matching the predicate.
+	fn matching(&self, predicate: impl Fn(&AttestationRecord) -> bool) -> Vec<AttestationRecord> {
+		self.data.iter().flatten().filter(|record| predicate(record)).cloned().collect()
+	}
+}
+
+impl AttestationIndex for MemoryStorage<Vec<AttestationRecord>> {
+	type Err = EigenError;
+
+	fn attestations_about(&self, address: [u8; 20]) -> Result<Vec<AttestationRecord>, Self::Err> {
+		let address = format!("0x{}", hex::encode(address));
+		Ok(self.matching(|record| record.about.eq_ignore_ascii_case(&address)))
+	}
+
+	fn attestations_by(&self, address: [u8; 20]) -> Result<Vec<AttestationRecord>, Self::Err> {
+		Ok(self.matching(|record| record.attester() == Some(address)))
+	}
+
+	fn attestations_in_range(
+		&self, from_block: u64, to_block: u64,
+	) -> Result<Vec<AttestationRecord>, Self::Err> {
+		Ok(self.matching(|record| record.is_in_range(from_block, to_block)))
+	}
+}
+
+impl<T: Clone> Storage<T> for MemoryStorage<T> {
+	type Err = EigenError;
+
+	fn load(&self) -> Result<T, Self::Err> {
+		self.data.clone().ok_or_else(|| {
+			EigenError::ResourceUnavailableError("No data in memory storage".to_string())
+		})
+	}
+
+	fn save(&mut self, data: T) -> Result<(), Self::Err> {
+		self.data = Some(data);
+		Ok(())
+	}
+}
+
+/// Attestation indexing state, persisted between runs so indexing resumes from the last
+/// scanned block instead of rescanning the whole chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncState {
+	/// Network chain ID.
+	chain_id: u64,
+	/// AttestationStation contract address.
+	as_address: String,
+	/// Attestation domain.
+	domain: String,
+	/// Last scanned block.
+	last_block: u64,
+}
+
+impl SyncState {
+	/// Creates a new sync state.
+	pub fn new(chain_id: u64, as_address: [u8; 20], domain: [u8; 20], last_block: u64) -> Self {
+		Self {
+			chain_id,
+			as_address: format!("0x{}", hex::encode(as_address)),
+			domain: format!("0x{}", hex::encode(domain)),
+			last_block,
+		}
+	}
+
+	/// Returns the last scanned block.
+	pub fn last_block(&self) -> u64 {
+		self.last_block
+	}
+
+	/// Returns whether the state was saved for the given network, contract and domain.
+	pub fn matches(&self, chain_id: u64, as_address: [u8; 20], domain: [u8; 20]) -> bool {
+		self.chain_id == chain_id
+			&& str_to_20_byte_array(&self.as_address).map_or(false, |address| address == as_address)
+			&& str_to_20_byte_array(&self.domain)
+				.map_or(false, |state_domain| state_domain == domain)
+	}
+}
+
+/// Kind of the artifact stored in a framed binary file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArtifactKind {
+	/// KZG parameters.
+	KzgParams,
+	/// Proving key.
+	ProvingKey,
+	/// Proof.
+	Proof,
+	/// Proof public inputs.
+	PublicInputs,
+}
+
+impl ArtifactKind {
+	/// Returns the byte identifying the kind in the frame header.
+	fn to_byte(self) -> u8 {
+		match self {
+			ArtifactKind::KzgParams => 0,
+			ArtifactKind::ProvingKey => 1,
+			ArtifactKind::Proof => 2,
+			ArtifactKind::PublicInputs => 3,
+		}
+	}
+
+	/// Returns the name of the kind.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			ArtifactKind::KzgParams => "KZG parameters",
+			ArtifactKind::ProvingKey => "proving key",
+			ArtifactKind::Proof => "proof",
+			ArtifactKind::PublicInputs => "public inputs",
+		}
+	}
+}
+
+/// The `FramedBinFileStorage` struct persists binary artifacts, such as KZG parameters,
+/// proving keys, proofs and public inputs, behind a header holding the format version,
+/// the artifact kind, the payload length and its Keccak-256 checksum, all checked on load.
+/// Files without header, written before the format was introduced, are loaded as is.
+pub struct FramedBinFileStorage {
+	filepath: PathBuf,
+	kind: ArtifactKind,
+}
+
+impl FramedBinFileStorage {
+	/// Creates a new FramedBinFileStorage for artifacts of the given kind.
+	pub fn new(filepath: PathBuf, kind: ArtifactKind) -> Self {
+		Self { filepath, kind }
+	}
+
+	/// Returns the path to the file.
+	pub fn filepath(&self) -> &PathBuf {
+		&self.filepath
+	}
+
+	/// Frames the payload.
+	pub fn encode(&self, payload: &[u8]) -> Vec<u8> {
+		let mut data = Vec::with_capacity(FRAME_HEADER_LEN + payload.len());
+		data.extend(FRAME_MAGIC);
+		data.extend(FRAME_VERSION.to_be_bytes());
+		data.push(self.kind.to_byte());
+		data.extend((payload.len() as u64).to_be_bytes());
+		data.extend(keccak256(payload));
+		data.extend(payload);
+
+		data
+	}
+
+	/// Checks the frame and returns its payload.
+	pub fn decode(&self, mut data: Vec<u8>) -> Result<Vec<u8>, EigenError> {
+		if !data.starts_with(&FRAME_MAGIC) {
+			warn!(
+				"No header found in \"{}\", skipping integrity checks.",
+				self.filepath.display()
+			);
+			return Ok(data);
+		}
+		if data.len() < FRAME_HEADER_LEN {
+			return Err(self.corrupted("truncated header"));
+		}
+
+		let (header, payload) = data.split_at(FRAME_HEADER_LEN);
+		let version = u16::from_be_bytes([header[4], header[5]]);
+		if version != FRAME_VERSION {
+			return Err(EigenError::ValidationError(format!(
+				"Unsupported {} format version {}, expected {}",
+				self.kind.as_str(),
+				version,
+				FRAME_VERSION
+			)));
+		}
+		if header[6] != self.kind.to_byte() {
+			return Err(EigenError::ValidationError(format!(
+				"\"{}\" doesn't hold {}",
+				self.filepath.display(),
+				self.kind.as_str()
+			)));
+		}
+
+		let mut length = [0; 8];
+		length.copy_from_slice(&header[7..15]);
+		if u64::from_be_bytes(length) != payload.len() as u64 {
+			return Err(self.corrupted("length mismatch"));
+		}
+		if keccak256(payload)[..] != header[15..] {
+			return Err(self.corrupted("checksum mismatch"));
+		}
+
+		Ok(data.split_off(FRAME_HEADER_LEN))
+	}
+
+	/// Returns the error of a corrupted file.
+	fn corrupted(&self, reason: &str) -> EigenError {
+		EigenError::ValidationError(format!(
+			"Corrupted {} file \"{}\": {}",
+			self.kind.as_str(),
+			self.filepath.display(),
+			reason
+		))
+	}
+}
+
+impl Storage<Vec<u8>> for FramedBinFileStorage {
+	type Err = EigenError;
+
+	fn load(&self) -> Result<Vec<u8>, Self::Err> {
+		self.decode(BinFileStorage::new(self.filepath.clone()).load()?)
+	}
+
+	fn save(&mut self, data: Vec<u8>) -> Result<(), Self::Err> {
+		BinFileStorage::new(self.filepath.clone()).save(self.encode(&data))
+	}
+}
+
+/// The `ProofStorage` struct persists generated proofs along with the inputs they were
+/// generated from, see `ProofArtifact`, and only loads them back for matching inputs.
+pub struct ProofStorage {
+	storage: JSONFileStorage<ProofArtifact>,
+}
+
+impl ProofStorage {
+	/// Creates a new ProofStorage.
+	pub fn new(filepath: PathBuf) -> Self {
+		Self { storage: JSONFileStorage::new(filepath) }
+	}
+
+	/// Returns the path to the file.
+	pub fn filepath(&self) -> &PathBuf {
+		&self.storage.filepath
+	}
+
+	/// Loads the proof, checking that it belongs to the given circuit and was generated with
+	/// the given parameters and, if given, from the given attestations.
+	pub fn load_checked(
+		&self, circuit: &Circuit, params_hash: [u8; 32], attestations_hash: Option<[u8; 32]>,
+	) -> Result<ProofArtifact, EigenError> {
+		let artifact = self.storage.load()?;
+		artifact.validate(circuit, params_hash, attestations_hash)?;
+
+		Ok(artifact)
+	}
+}
+
+impl Storage<ProofArtifact> for ProofStorage {
+	type Err = EigenError;
+
+	fn load(&self) -> Result<ProofArtifact, Self::Err> {
+		self.storage.load()
+	}
+
+	fn save(&mut self, data: ProofArtifact) -> Result<(), Self::Err> {
+		self.storage.save(data)
+	}
+}
+
+/// Symmetric key encrypting files at rest.
+#[derive(Clone)]
+pub struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+	/// Creates a key from its bytes.
+	pub fn new(key: [u8; 32]) -> Self {
+		Self(key)
+	}
+
+	/// Parses a hex-encoded 32-byte key.
+	pub fn from_hex(key: &str) -> Result<Self, EigenError> {
+		let bytes = hex::decode(key.trim().trim_start_matches("0x"))
+			.map_err(|e| EigenError::KeysError(format!("Invalid encryption key: {}", e)))?;
+		let key = bytes.try_into().map_err(|_| {
+			EigenError::KeysError("The encryption key must be 32 bytes long".to_string())
+		})?;
+
+		Ok(Self(key))
+	}
+
+	/// Derives a key from the secret key of the given Ethereum keystore, decrypted with the
+	/// password. The signing key itself is never used to encrypt.
+	pub fn from_keystore(path: &Path, password: &str) -> Result<Self, EigenError> {
+		let wallet = LocalWallet::decrypt_keystore(path, password)
+			.map_err(|e| EigenError::KeysError(format!("Failed to decrypt keystore: {}", e)))?;
+
+		Ok(Self::derive(&wallet.signer().to_bytes()))
+	}
+
+	/// Derives a key from the secret, under the storage key context.
+	pub fn derive(secret: &[u8]) -> Self {
+		Self(keccak256([STORAGE_KEY_CONTEXT, secret].concat()))
+	}
+}
+
+/// The `EncryptedJSONFileStorage` struct persists structured data to JSON files encrypted
+/// with ChaCha20-Poly1305, for files holding sensitive data. Encrypted files start with a
+/// versioned header, so files written without a key, or before encryption was enabled,
+/// are still loaded as plain JSON. Loading such a file with a key logs a warning, since
+/// anyone able to write it can swap in plain content, and the next save encrypts it.
+pub struct EncryptedJSONFileStorage<T> {
+	filepath: PathBuf,
+	key: Option<EncryptionKey>,
+	phantom: PhantomData<T>,
+}
+
+impl<T> EncryptedJSONFileStorage<T> {
+	/// Creates a new EncryptedJSONFileStorage. Data is saved in plain JSON without a key.
+	pub fn new(filepath: PathBuf, key: Option<EncryptionKey>) -> Self {
+		Self { filepath, key, phantom: PhantomData }
+	}
+
+	/// Returns the path to the file.
+	pub fn filepath(&self) -> &PathBuf {
+		&self.filepath
+	}
+
+	/// Encrypts the plaintext, prepending the header.
+	pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, EigenError> {
+		let mut data = Vec::with_capacity(ENCRYPTION_HEADER_LEN + plaintext.len());
+		data.extend(ENCRYPTION_MAGIC);
+		data.extend(ENCRYPTION_VERSION.to_be_bytes());
+		let mut nonce = [0; 12];
+		rand::thread_rng().fill(&mut nonce);
+		data.extend(nonce);
+
+		// The header is authenticated along with the content
+		let payload = Payload { msg: plaintext, aad: &data };
+		let ciphertext = self
+			.cipher()?
+			.encrypt(Nonce::from_slice(&nonce), payload)
+			.map_err(|_| EigenError::ReadWriteError("Failed to encrypt the file".to_string()))?;
+		data.extend(ciphertext);
+
+		Ok(data)
+	}
+
+	/// Decrypts the data if it starts with the header, returning it as is otherwise.
+	pub fn decrypt(&self, data: Vec<u8>) -> Result<Vec<u8>, EigenError> {
+		if !data.starts_with(&ENCRYPTION_MAGIC) {
+			if self.key.is_some() {
+				warn!(
+					"\"{}\" isn't encrypted although a key is set, encrypting it on the next save.",
+					self.filepath.display()
+				);
+			}
+			return Ok(data);
+		}
+		if data.len() < ENCRYPTION_HEADER_LEN {
+			return Err(EigenError::ValidationError(format!(
+				"Corrupted encrypted file \"{}\": truncated header",
+				self.filepath.display()
+			)));
+		}
+
+		let (header, ciphertext) = data.split_at(ENCRYPTION_HEADER_LEN);
+		let version = u16::from_be_bytes([header[4], header[5]]);
+		if version != ENCRYPTION_VERSION {
+			return Err(EigenError::ValidationError(format!(
+				"Unsupported encrypted file format version {}, expected {}",
+				version, ENCRYPTION_VERSION
+			)));
+		}
+
+		let payload = Payload { msg: ciphertext, aad: header };
+		self.cipher()?.decrypt(Nonce::from_slice(&header[6..]), payload).map_err(|_| {
+			EigenError::VerificationError(format!(
+				"Failed to decrypt \"{}\": wrong key or corrupted file",
+				self.filepath.display()
+			))
+		})
+	}
+
+	/// Returns the cipher of the key.
+	fn cipher(&self) -> Result<ChaCha20Poly1305, EigenError> {
+		let key = self.key.as_ref().ok_or_else(|| {
+			EigenError::KeysError(format!(
+				"\"{}\" is encrypted, but no encryption key was given",
+				self.filepath.display()
+			))
+		})?;
+
+		Ok(ChaCha20Poly1305::new(Key::from_slice(&key.0)))
+	}
+}
+
+impl<T: Serialize + DeserializeOwned + Clone> Storage<T> for EncryptedJSONFileStorage<T> {
+	type Err = EigenError;
+
+	fn load(&self) -> Result<T, Self::Err> {
+		let data = BinFileStorage::new(self.filepath.clone()).load()?;
+		Ok(serde_json::from_slice(&self.decrypt(data)?)?)
+	}
+
+	fn save(&mut self, data: T) -> Result<(), Self::Err> {
+		let json = serde_json::to_vec(&data)?;
+		let data = match self.key {
+			Some(_) => self.encrypt(&json)?,
+			None => json,
+		};
+
+		BinFileStorage::new(self.filepath.clone()).save(data)
 	}
 }
 
@@ -192,6 +1023,9 @@ pub struct ScoreRecord {
 	denominator: String,
 	/// Score.
 	score: String,
+	/// Epoch of the score: the last block of the attestations it was computed from.
+	#[serde(default)]
+	epoch: Option<u64>,
 }
 
 impl ScoreRecord {
@@ -200,7 +1034,13 @@ impl ScoreRecord {
 		peer_address: String, score_fr: String, numerator: String, denominator: String,
 		score: String,
 	) -> Self {
-		Self { peer_address, score_fr, numerator, denominator, score }
+		Self { peer_address, score_fr, numerator, denominator, score, epoch: None }
+	}
+
+	/// Sets the epoch of the score.
+	pub fn with_epoch(mut self, epoch: u64) -> Self {
+		self.epoch = Some(epoch);
+		self
 	}
 
 	/// Creates a new score record from a score.
@@ -240,6 +1080,160 @@ impl ScoreRecord {
 	pub fn score(&self) -> &String {
 		&self.score
 	}
+
+	/// Returns the epoch of the score, if known.
+	pub fn epoch(&self) -> Option<u64> {
+		self.epoch
+	}
+
+	/// Returns the exact score, the numerator divided by the denominator.
+	pub fn rational_score(&self) -> Result<RationalScore, EigenError> {
+		RationalScore::from_str(&format!("{}/{}", self.numerator, self.denominator))
+			.map_err(|e| EigenError::ParsingError(format!("Failed to parse score: {}", e)))
+	}
+}
+
+/// Score of a participant along with its user-facing value.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NormalizedScoreRecord {
+	/// The peer's address.
+	pub peer_address: String,
+	/// Score.
+	pub score: String,
+	/// Normalized score.
+	pub value: f64,
+}
+
+/// Returns the user-facing values of the score records, in the same order, see
+/// `ScoreNormalization`.
+pub fn normalize_scores(
+	records: &[ScoreRecord], normalization: ScoreNormalization,
+) -> Result<Vec<NormalizedScoreRecord>, EigenError> {
+	let scores = records.iter().map(ScoreRecord::rational_score).collect::<Result<Vec<_>, _>>()?;
+	let values = normalization.apply(&scores);
+
+	Ok(records
+		.iter()
+		.zip(values)
+		.map(|(record, value)| NormalizedScoreRecord {
+			peer_address: record.peer_address.clone(),
+			score: record.score.clone(),
+			value,
+		})
+		.collect())
+}
+
+/// Parses a score normalization by its name: `raw`, `share`, `percentile` or `log`.
+pub fn parse_normalization(s: &str) -> Result<ScoreNormalization, EigenError> {
+	let normalizations = [
+		ScoreNormalization::Raw,
+		ScoreNormalization::Share,
+		ScoreNormalization::Percentile,
+		ScoreNormalization::Log,
+	];
+	normalizations
+		.into_iter()
+		.find(|normalization| normalization.name() == s)
+		.ok_or_else(|| EigenError::ParsingError(format!("Invalid score normalization: {}", s)))
+}
+
+/// Score record of a single convergence iteration in a replayed scoring run.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReplayRecord {
+	/// Convergence iteration, 0 being the initial scores.
+	iteration: String,
+	/// The peer's address.
+	peer_address: String,
+	/// Score numerator.
+	numerator: String,
+	/// Score denominator.
+	denominator: String,
+	/// Score.
+	score: String,
+	/// Largest score change of the iteration, in decimal form.
+	#[serde(default)]
+	residual: String,
+}
+
+impl ReplayRecord {
+	/// Creates a new replay record.
+	pub fn new(
+		iteration: String, peer_address: String, numerator: String, denominator: String,
+		score: String,
+	) -> Self {
+		Self { iteration, peer_address, numerator, denominator, score, residual: String::new() }
+	}
+
+	/// Sets the residual of the record's iteration.
+	pub fn with_residual(mut self, residual: String) -> Self {
+		self.residual = residual;
+		self
+	}
+
+	/// Creates the replay records of every iteration from a score trace, along with the
+	/// iteration residuals, so the convergence can be plotted.
+	pub fn from_trace(trace: &ScoreTrace) -> Vec<Self> {
+		let residuals = trace.residuals();
+		trace
+			.iterations
+			.iter()
+			.zip(residuals)
+			.enumerate()
+			.flat_map(|(iteration, (scores, residual))| {
+				let residual = to_decimal(&residual, RESIDUAL_DECIMALS);
+				trace.address_set.iter().zip(scores).map(move |(address, score)| {
+					Self::new(
+						iteration.to_string(),
+						format!("0x{}", hex::encode(address)),
+						score.numer().to_string(),
+						score.denom().to_string(),
+						score.to_integer().to_string(),
+					)
+					.with_residual(residual.clone())
+				})
+			})
+			.collect()
+	}
+
+	/// Returns the convergence iteration.
+	pub fn iteration(&self) -> &String {
+		&self.iteration
+	}
+
+	/// Returns the peer's address.
+	pub fn peer_address(&self) -> &String {
+		&self.peer_address
+	}
+
+	/// Returns the score numerator.
+	pub fn numerator(&self) -> &String {
+		&self.numerator
+	}
+
+	/// Returns the score denominator.
+	pub fn denominator(&self) -> &String {
+		&self.denominator
+	}
+
+	/// Returns the score.
+	pub fn score(&self) -> &String {
+		&self.score
+	}
+
+	/// Returns the residual of the record's iteration.
+	pub fn residual(&self) -> &String {
+		&self.residual
+	}
+}
+
+/// Rounds the non-negative ratio down to the given number of decimals.
+fn to_decimal(ratio: &RationalScore, decimals: usize) -> String {
+	let ten = RationalScore::from_integer(10.into());
+	let scaled = (0..decimals).fold(ratio.clone(), |scaled, _| scaled * &ten);
+	let digits = format!("{:0>width$}", scaled.to_integer(), width = decimals + 1);
+	let (integer, fraction) = digits.split_at(digits.len() - decimals);
+
+	format!("{}.{}", integer, fraction)
 }
 
 /// Attestation record.
@@ -259,6 +1253,279 @@ pub struct AttestationRecord {
 	sig_s: String,
 	/// Recovery id of the ECDSA signature.
 	rec_id: String,
+	/// Account that submitted the attestation.
+	#[serde(default)]
+	creator: Option<String>,
+	/// Number of the block including the attestation.
+	#[serde(default)]
+	block_number: Option<u64>,
+	/// Hash of the block including the attestation.
+	#[serde(default)]
+	block_hash: Option<String>,
+	/// Hash of the transaction emitting the attestation.
+	#[serde(default)]
+	transaction_hash: Option<String>,
+	/// Index of the attestation log in its block.
+	#[serde(default)]
+	log_index: Option<u64>,
+}
+
+impl AttestationRecord {
+	/// Returns the address of the attested peer.
+	pub fn about(&self) -> &String {
+		&self.about
+	}
+
+	/// Returns the given rating.
+	pub fn value(&self) -> &String {
+		&self.value
+	}
+
+	/// Returns the number and hash of the block including the attestation, if known.
+	pub fn block(&self) -> Result<Option<(u64, [u8; 32])>, EigenError> {
+		match (self.block_number, &self.block_hash) {
+			(Some(number), Some(hash)) => Ok(Some((number, str_to_32_byte_array(hash)?))),
+			_ => Ok(None),
+		}
+	}
+
+	/// Returns the number of the block including the attestation, if known.
+	pub fn block_number(&self) -> Option<u64> {
+		self.block_number
+	}
+
+	/// Returns the hash of the transaction and the index of the log emitting the
+	/// attestation, if known.
+	pub fn transaction(&self) -> Result<Option<([u8; 32], u64)>, EigenError> {
+		match (&self.transaction_hash, self.log_index) {
+			(Some(hash), Some(index)) => Ok(Some((str_to_32_byte_array(hash)?, index))),
+			_ => Ok(None),
+		}
+	}
+
+	/// Returns the account that submitted the attestation, if known.
+	pub fn creator(&self) -> Result<Option<[u8; 20]>, EigenError> {
+		self.creator.as_deref().map(str_to_20_byte_array).transpose()
+	}
+
+	/// Returns whether the attestation was included in the given inclusive block range.
+	fn is_in_range(&self, from_block: u64, to_block: u64) -> bool {
+		self.block_number.map_or(false, |number| (from_block..=to_block).contains(&number))
+	}
+
+	/// Returns whether the attestation belongs to the epoch ending at the given block,
+	/// inclusive. Attestations without block data belong to every epoch.
+	pub fn is_in_epoch(&self, epoch: u64) -> bool {
+		self.block_number.map_or(true, |number| number <= epoch)
+	}
+
+	/// Returns the attester: the signer of the attestation, or its creator if it is
+	/// unsigned or its signer can't be recovered. Returns `None` for malformed records.
+	pub fn attester(&self) -> Option<[u8; 20]> {
+		let signed_raw = SignedAttestationRaw::try_from(self.clone()).ok()?;
+		let signer = if signed_raw.is_unsigned() {
+			None
+		} else {
+			SignedAttestationEth::from(signed_raw)
+				.recover_public_key()
+				.ok()
+				.map(|public_key| address_from_ecdsa_key(&public_key).to_fixed_bytes())
+		};
+
+		signer.or(self.creator().ok().flatten())
+	}
+
+	/// Checks the record against the given domain, recovering its signer. Returns the
+	/// issues found, if any.
+	pub fn validate(&self, domain: [u8; 20]) -> Vec<RecordIssue> {
+		let mut issues = Vec::new();
+
+		match str_to_20_byte_array(&self.domain) {
+			Ok(record_domain) if record_domain != domain => {
+				issues.push(RecordIssue::DomainMismatch)
+			},
+			Ok(_) => {},
+			Err(_) => issues.push(RecordIssue::Malformed("domain".to_string())),
+		}
+		if self.value.parse::<u8>().is_err() {
+			issues.push(RecordIssue::ValueOutOfRange(self.value.clone()));
+		}
+		let fields = [
+			("about", str_to_20_byte_array(&self.about).is_ok()),
+			("message", str_to_32_byte_array(&self.message).is_ok()),
+			("sig_r", str_to_32_byte_array(&self.sig_r).is_ok()),
+			("sig_s", str_to_32_byte_array(&self.sig_s).is_ok()),
+		];
+		for (field, _) in fields.iter().filter(|(_, is_valid)| !is_valid) {
+			issues.push(RecordIssue::Malformed(field.to_string()));
+		}
+		if self.rec_id.parse::<u8>().is_err() {
+			issues.push(RecordIssue::Malformed("rec_id".to_string()));
+		}
+		let creator = match self.creator() {
+			Ok(creator) => creator,
+			Err(_) => {
+				issues.push(RecordIssue::Malformed("creator".to_string()));
+				None
+			},
+		};
+
+		// The signature can only be checked on well-formed records
+		let signed_raw = match SignedAttestationRaw::try_from(self.clone()) {
+			Ok(signed_raw) if issues.is_empty() => signed_raw,
+			_ => return issues,
+		};
+		// Unsigned records, imported from EAS, are attested by their creator
+		let recovered = match creator {
+			Some(creator) if signed_raw.is_unsigned() => Ok(creator),
+			_ => SignedAttestationEth::from(signed_raw.clone())
+				.recover_public_key()
+				.map(|public_key| address_from_ecdsa_key(&public_key).to_fixed_bytes()),
+		};
+		let attester = match recovered {
+			Ok(attester) => attester,
+			Err(e) => {
+				issues.push(RecordIssue::InvalidSignature(e.to_string()));
+				return issues;
+			},
+		};
+
+		if attester == signed_raw.attestation.about {
+			issues.push(RecordIssue::SelfAttestation);
+		}
+		if matches!(creator, Some(creator) if creator != attester) {
+			issues.push(RecordIssue::CreatorMismatch);
+		}
+
+		issues
+	}
+}
+
+/// Issue found when validating an attestation record.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "detail", rename_all = "snake_case")]
+pub enum RecordIssue {
+	/// The given field can't be parsed.
+	Malformed(String),
+	/// The value isn't in the 0-255 range.
+	ValueOutOfRange(String),
+	/// The domain isn't the configured one, so the attestation key wouldn't be
+	/// `DOMAIN_PREFIX` followed by the configured domain.
+	DomainMismatch,
+	/// The signer can't be recovered from the signature.
+	InvalidSignature(String),
+	/// The signer attested to itself.
+	SelfAttestation,
+	/// The signer isn't the account that submitted the attestation. Attestations
+	/// submitted by contract wallets, verified through EIP-1271, are reported as well.
+	CreatorMismatch,
+}
+
+impl std::fmt::Display for RecordIssue {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			RecordIssue::Malformed(field) => write!(f, "malformed {}", field),
+			RecordIssue::ValueOutOfRange(value) => write!(f, "value {} out of range", value),
+			RecordIssue::DomainMismatch => write!(f, "domain mismatch"),
+			RecordIssue::InvalidSignature(e) => write!(f, "invalid signature: {}", e),
+			RecordIssue::SelfAttestation => write!(f, "self-attestation"),
+			RecordIssue::CreatorMismatch => write!(f, "signer isn't the creator"),
+		}
+	}
+}
+
+/// Deduplication policy of the attestation records. Attestations sharing the same
+/// attester, attested peer and domain, i.e. the same AttestationStation key, override each
+/// other on-chain, so replays and re-attestations would otherwise be counted several times.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DedupPolicy {
+	/// Keeps the most recent attestation of every key.
+	#[default]
+	Latest,
+	/// Keeps the first attestation of every key.
+	Earliest,
+	/// Keeps every attestation.
+	KeepAll,
+}
+
+impl DedupPolicy {
+	/// Deduplicates the records, keeping their order. Records are ordered in time by block
+	/// number and log index, records without block data coming last in file order.
+	/// Malformed records, whose attester is unknown, are all kept.
+	pub fn apply(&self, records: Vec<AttestationRecord>) -> Vec<AttestationRecord> {
+		if let DedupPolicy::KeepAll = self {
+			return records;
+		}
+
+		// Kept record of every key, along with its position in time
+		let mut kept: BTreeMap<([u8; 20], String, String), ((bool, u64, u64), usize)> =
+			BTreeMap::new();
+		let mut unkeyed = BTreeSet::new();
+		for (index, record) in records.iter().enumerate() {
+			let attester = match record.attester() {
+				Some(attester) => attester,
+				None => {
+					unkeyed.insert(index);
+					continue;
+				},
+			};
+			let key = (
+				attester,
+				record.about.to_lowercase(),
+				record.domain.to_lowercase(),
+			);
+			let position = (
+				record.block_number.is_none(),
+				record.block_number.unwrap_or_default(),
+				record.log_index.unwrap_or_default(),
+			);
+
+			let replace = match kept.get(&key) {
+				Some(current) => match self {
+					DedupPolicy::Latest => (position, index) > *current,
+					_ => (position, index) < *current,
+				},
+				None => true,
+			};
+			if replace {
+				kept.insert(key, (position, index));
+			}
+		}
+
+		let kept_indices: BTreeSet<usize> =
+			kept.into_values().map(|(_, index)| index).chain(unkeyed).collect();
+		let total = records.len();
+		let records: Vec<AttestationRecord> = records
+			.into_iter()
+			.enumerate()
+			.filter(|(index, _)| kept_indices.contains(index))
+			.map(|(_, record)| record)
+			.collect();
+		if records.len() < total {
+			debug!(
+				dropped = total - records.len(),
+				"Deduplicated attestation records"
+			);
+		}
+
+		records
+	}
+}
+
+impl FromStr for DedupPolicy {
+	type Err = EigenError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"latest" => Ok(DedupPolicy::Latest),
+			"earliest" => Ok(DedupPolicy::Earliest),
+			"keep-all" => Ok(DedupPolicy::KeepAll),
+			_ => Err(EigenError::ParsingError(format!(
+				"Invalid dedup policy: {}",
+				s
+			))),
+		}
+	}
 }
 
 impl From<SignedAttestationRaw> for AttestationRecord {
@@ -275,6 +1542,33 @@ impl From<SignedAttestationRaw> for AttestationRecord {
 			sig_r: format!("0x{}", hex::encode(sig_r)),
 			sig_s: format!("0x{}", hex::encode(sig_s)),
 			rec_id: rec_id.to_string(),
+			creator: None,
+			block_number: None,
+			block_hash: None,
+			transaction_hash: None,
+			log_index: None,
+		}
+	}
+}
+
+impl From<IndexedAttestation> for AttestationRecord {
+	fn from(indexed: IndexedAttestation) -> Self {
+		let IndexedAttestation {
+			signed_attestation,
+			creator,
+			block_number,
+			block_hash,
+			transaction,
+		} = indexed;
+		let (transaction_hash, log_index) = transaction.unzip();
+
+		Self {
+			creator: Some(format!("0x{}", hex::encode(creator))),
+			block_number: Some(block_number),
+			block_hash: Some(format!("0x{}", hex::encode(block_hash))),
+			transaction_hash: transaction_hash.map(|hash| format!("0x{}", hex::encode(hash))),
+			log_index,
+			..Self::from(signed_attestation)
 		}
 	}
 }
@@ -283,7 +1577,7 @@ impl TryFrom<AttestationRecord> for SignedAttestationRaw {
 	type Error = EigenError;
 
 	fn try_from(record: AttestationRecord) -> Result<Self, Self::Error> {
-		let AttestationRecord { about, domain, value, message, sig_r, sig_s, rec_id } = record;
+		let AttestationRecord { about, domain, value, message, sig_r, sig_s, rec_id, .. } = record;
 
 		let attestation = AttestationRaw {
 			about: str_to_20_byte_array(&about)?,
@@ -322,7 +1616,9 @@ pub fn str_to_32_byte_array(hex: &str) -> Result<[u8; 32], EigenError> {
 
 #[cfg(test)]
 mod tests {
-	use crate::storage::*;
+	use crate::{attestation::AttestationEth, storage::*, SecpScalar};
+	use eigentrust_zk::circuits::{ECDSAKeypair, PoseidonNativeHasher, HASHER_WIDTH};
+	use num_rational::BigRational;
 	use serde::{Deserialize, Serialize};
 	use std::{env::current_dir, fs};
 
@@ -359,6 +1655,452 @@ mod tests {
 		fs::remove_file(filepath).unwrap();
 	}
 
+	#[test]
+	fn test_csv_file_storage_header() {
+		let filepath = current_dir().unwrap().join("test_header.csv");
+		let header = vec!["domain: 0x00".to_string(), "epoch: 7".to_string()];
+		let mut csv_storage =
+			CSVFileStorage::<Record>::new(filepath.clone()).with_header(header.clone());
+
+		let content = vec![Record {
+			peer_address: "0x70997970c51812dc3a010c7d01b50e0d17dc7666".to_string(),
+			score: 1000,
+		}];
+		csv_storage.save(content.clone()).unwrap();
+
+		assert_eq!(csv_storage.load().unwrap(), content);
+		assert_eq!(csv_storage.load_header().unwrap(), header);
+
+		fs::remove_file(filepath).unwrap();
+	}
+
+	#[test]
+	fn test_atomic_save() {
+		let filepath = current_dir().unwrap().join("test_atomic.csv");
+		let content = vec![Record { peer_address: "0x01".to_string(), score: 1000 }];
+		CSVFileStorage::<Record>::new(filepath.clone()).save(content.clone()).unwrap();
+		assert!(!temp_path(&filepath).exists());
+
+		// A failed write leaves the previous file untouched
+		#[derive(Clone, Serialize, Deserialize)]
+		struct Nested {
+			values: Vec<u32>,
+		}
+		let mut nested_storage = CSVFileStorage::<Nested>::new(filepath.clone());
+		assert!(nested_storage.save(vec![Nested { values: vec![1, 2] }]).is_err());
+		assert!(!temp_path(&filepath).exists());
+		assert_eq!(
+			CSVFileStorage::<Record>::new(filepath.clone()).load().unwrap(),
+			content
+		);
+
+		fs::remove_file(filepath).unwrap();
+	}
+
+	#[test]
+	fn test_csv_file_storage_append() {
+		let filepath = current_dir().unwrap().join("test_append.csv");
+		let header = vec!["epoch: 7".to_string()];
+		let mut csv_storage =
+			CSVFileStorage::<Record>::new(filepath.clone()).with_header(header.clone());
+		let record = |score: u32| Record { peer_address: format!("0x{:02}", score), score };
+
+		// Missing files are created with their header
+		csv_storage.append(vec![record(1)]).unwrap();
+		csv_storage.append(vec![record(2), record(3)]).unwrap();
+		assert_eq!(
+			csv_storage.load().unwrap(),
+			vec![record(1), record(2), record(3)]
+		);
+		assert_eq!(csv_storage.load_header().unwrap(), header);
+
+		// Records already stored or repeated are skipped
+		let appended = csv_storage
+			.append_unique(vec![record(3), record(4), record(4)], |r| {
+				r.peer_address.clone()
+			})
+			.unwrap();
+		assert_eq!(appended, 1);
+		assert_eq!(csv_storage.load().unwrap().len(), 4);
+
+		// Records are streamed in order
+		let scores: Vec<u32> =
+			csv_storage.load_iter().unwrap().map(|record| record.unwrap().score).collect();
+		assert_eq!(scores, vec![1, 2, 3, 4]);
+
+		fs::remove_file(filepath).unwrap();
+	}
+
+	#[test]
+	fn test_compressed_storage() {
+		let record = |score: u32| Record { peer_address: format!("0x{:02}", score), score };
+		for extension in ["csv.gz", "csv.zst"] {
+			let filepath = current_dir().unwrap().join(format!("test_compressed.{}", extension));
+			let header = vec!["epoch: 7".to_string()];
+			let mut csv_storage =
+				CSVFileStorage::<Record>::new(filepath.clone()).with_header(header.clone());
+
+			// Appended records are read back as a single stream
+			csv_storage.save(vec![record(1)]).unwrap();
+			csv_storage.append(vec![record(2)]).unwrap();
+			assert_eq!(csv_storage.load().unwrap(), vec![record(1), record(2)]);
+			assert_eq!(csv_storage.load_header().unwrap(), header);
+
+			// Files are actually compressed
+			let raw = fs::read(&filepath).unwrap();
+			assert!(!raw.starts_with(b"# epoch"));
+
+			fs::remove_file(filepath).unwrap();
+		}
+
+		let filepath = current_dir().unwrap().join("test_compressed.json.gz");
+		let mut json_storage = JSONFileStorage::<Vec<u32>>::new(filepath.clone());
+		json_storage.save(vec![1, 2, 3]).unwrap();
+		assert_eq!(json_storage.load().unwrap(), vec![1, 2, 3]);
+		fs::remove_file(filepath).unwrap();
+
+		assert_eq!(
+			Compression::from_path(Path::new("scores.csv.zst")),
+			Compression::Zstd
+		);
+		assert_eq!(
+			Compression::from_path(Path::new("scores.csv")),
+			Compression::None
+		);
+		assert!("lz4".parse::<Compression>().is_err());
+	}
+
+	#[tokio::test]
+	async fn test_async_storage() {
+		let mut storage = MemoryStorage::<Vec<u32>>::new();
+
+		storage.save_async(vec![1, 2, 3]).await.unwrap();
+		assert_eq!(storage.load_async().await.unwrap(), vec![1, 2, 3]);
+	}
+
+	#[test]
+	fn test_memory_storage() {
+		let mut storage = MemoryStorage::<Vec<Record>>::new();
+		assert!(storage.load().is_err());
+
+		let records = vec![Record { peer_address: "0x01".to_string(), score: 1 }];
+		storage.save(records.clone()).unwrap();
+		assert_eq!(storage.load().unwrap(), records);
+		assert_eq!(storage.data(), Some(&records));
+
+		assert_eq!(storage.take(), Some(records));
+		assert!(storage.load().is_err());
+	}
+
+	#[test]
+	fn test_attestation_record_block() {
+		let filepath = current_dir().unwrap().join("test_indexed.csv");
+		let mut csv_storage = CSVFileStorage::<AttestationRecord>::new(filepath.clone());
+
+		let indexed = IndexedAttestation::new(SignedAttestationRaw::default(), [2; 20], 7, [1; 32])
+			.with_transaction([3; 32], 4);
+		let records = vec![
+			AttestationRecord::from(indexed),
+			AttestationRecord::from(SignedAttestationRaw::default()),
+		];
+
+		csv_storage.save(records).unwrap();
+		let loaded = csv_storage.load().unwrap();
+
+		assert_eq!(loaded[0].block().unwrap(), Some((7, [1; 32])));
+		assert_eq!(loaded[1].block().unwrap(), None);
+		assert_eq!(loaded[0].creator().unwrap(), Some([2; 20]));
+		assert_eq!(loaded[1].creator().unwrap(), None);
+		assert_eq!(loaded[0].transaction().unwrap(), Some(([3; 32], 4)));
+		assert_eq!(loaded[1].transaction().unwrap(), None);
+		assert!(loaded[0].is_in_epoch(7) && !loaded[0].is_in_epoch(6));
+		assert!(loaded[1].is_in_epoch(0));
+
+		// Clean up
+		fs::remove_file(filepath).unwrap();
+	}
+
+	#[test]
+	fn test_attestation_record_validate() {
+		let rng = &mut rand::thread_rng();
+		let keypair = ECDSAKeypair::generate_keypair(rng);
+		let signer = address_from_ecdsa_key(&keypair.public_key).to_fixed_bytes();
+		let domain = [1; 20];
+
+		let mut sign = |about: [u8; 20]| {
+			let attestation = AttestationRaw::new(about, domain, 5, [0; 32]);
+			let attestation_fr =
+				AttestationEth::from(attestation.clone()).to_attestation_fr().unwrap();
+			let message = attestation_fr.hash::<HASHER_WIDTH, PoseidonNativeHasher>().to_bytes();
+			let signature = keypair.sign(SecpScalar::from_bytes(&message).unwrap(), rng);
+			AttestationRecord::from(SignedAttestationRaw::new(
+				attestation,
+				SignatureRaw::from(signature),
+			))
+		};
+
+		let record = sign([2; 20]);
+		assert!(record.validate(domain).is_empty());
+		assert_eq!(record.validate([3; 20]), vec![RecordIssue::DomainMismatch]);
+
+		// Records submitted by another account don't match their signer
+		let submitted = AttestationRecord {
+			creator: Some(format!("0x{}", hex::encode([4; 20]))),
+			..record.clone()
+		};
+		assert_eq!(
+			submitted.validate(domain),
+			vec![RecordIssue::CreatorMismatch]
+		);
+
+		assert_eq!(
+			sign(signer).validate(domain),
+			vec![RecordIssue::SelfAttestation]
+		);
+
+		let corrupted =
+			AttestationRecord { value: "256".to_string(), sig_r: "0x12".to_string(), ..record };
+		assert_eq!(
+			corrupted.validate(domain),
+			vec![
+				RecordIssue::ValueOutOfRange("256".to_string()),
+				RecordIssue::Malformed("sig_r".to_string())
+			]
+		);
+	}
+
+	#[test]
+	fn test_dedup_policy() {
+		let record = |creator: u8, value: u8, block_number: Option<u64>| {
+			let attestation = AttestationRaw::new([2; 20], [1; 20], value, [0; 32]);
+			let signed = SignedAttestationRaw::new(attestation, SignatureRaw::default());
+			AttestationRecord {
+				creator: Some(format!("0x{}", hex::encode([creator; 20]))),
+				block_number,
+				..AttestationRecord::from(signed)
+			}
+		};
+		let values = |records: Vec<AttestationRecord>| {
+			records.iter().map(|record| record.value().clone()).collect::<Vec<_>>()
+		};
+		let records = vec![
+			record(3, 1, Some(9)),
+			record(3, 2, Some(4)),
+			record(4, 3, Some(5)),
+			record(3, 4, None),
+		];
+
+		// Records without block data are the most recent ones
+		assert_eq!(
+			values(DedupPolicy::Latest.apply(records.clone())),
+			vec!["3", "4"]
+		);
+		assert_eq!(
+			values(DedupPolicy::Earliest.apply(records.clone())),
+			vec!["2", "3"]
+		);
+		assert_eq!(DedupPolicy::KeepAll.apply(records.clone()).len(), 4);
+
+		// Malformed records are all kept
+		let malformed = AttestationRecord { value: "256".to_string(), ..records[0].clone() };
+		assert_eq!(
+			DedupPolicy::Latest.apply(vec![malformed.clone(), malformed]).len(),
+			2
+		);
+		assert!("newest".parse::<DedupPolicy>().is_err());
+	}
+
+	#[test]
+	fn test_attestation_query() {
+		let record = |creator: u8, about: u8, block_number: Option<u64>| {
+			let attestation = AttestationRaw::new([about; 20], [1; 20], 5, [0; 32]);
+			let signed = SignedAttestationRaw::new(attestation, SignatureRaw::default());
+			AttestationRecord {
+				creator: Some(format!("0x{}", hex::encode([creator; 20]))),
+				block_number,
+				..AttestationRecord::from(signed)
+			}
+		};
+		let records = vec![record(2, 3, Some(4)), record(3, 2, Some(8)), record(2, 4, None)];
+
+		let filepath = current_dir().unwrap().join("test_query.csv");
+		let mut csv_storage = CSVFileStorage::<AttestationRecord>::new(filepath.clone());
+		csv_storage.save(records.clone()).unwrap();
+		let memory_storage = MemoryStorage::with_data(records);
+
+		for storage in [&csv_storage as &dyn AttestationIndex<Err = EigenError>, &memory_storage] {
+			let about = storage.attestations_about([2; 20]).unwrap();
+			assert_eq!(about.len(), 1);
+			assert_eq!(about[0].creator().unwrap(), Some([3; 20]));
+
+			assert_eq!(storage.attestations_by([2; 20]).unwrap().len(), 2);
+			assert_eq!(storage.attestations_in_range(4, 7).unwrap().len(), 1);
+			assert!(storage.attestations_in_range(9, 10).unwrap().is_empty());
+		}
+
+		fs::remove_file(filepath).unwrap();
+	}
+
+	#[test]
+	fn test_sync_state() {
+		let filepath = current_dir().unwrap().join("test_sync_state.json");
+		let mut storage = JSONFileStorage::<SyncState>::new(filepath.clone());
+
+		storage.save(SyncState::new(31337, [1; 20], [2; 20], 42)).unwrap();
+		let state = storage.load().unwrap();
+		assert_eq!(state.last_block(), 42);
+		assert!(state.matches(31337, [1; 20], [2; 20]));
+		assert!(!state.matches(1, [1; 20], [2; 20]));
+		assert!(!state.matches(31337, [3; 20], [2; 20]));
+
+		fs::remove_file(filepath).unwrap();
+	}
+
+	#[test]
+	fn test_framed_bin_file_storage() {
+		let filepath = current_dir().unwrap().join("test_framed.bin");
+		let mut storage = FramedBinFileStorage::new(filepath.clone(), ArtifactKind::Proof);
+
+		storage.save(vec![1, 2, 3]).unwrap();
+		assert_eq!(storage.load().unwrap(), vec![1, 2, 3]);
+
+		// Artifacts of another kind are rejected
+		let params = FramedBinFileStorage::new(filepath.clone(), ArtifactKind::KzgParams);
+		assert!(params.load().is_err());
+
+		// Corrupted payloads are detected
+		let mut data = fs::read(&filepath).unwrap();
+		data[FRAME_HEADER_LEN] ^= 1;
+		assert!(storage.decode(data.clone()).is_err());
+		data.pop();
+		assert!(storage.decode(data).is_err());
+
+		// Files without header are loaded as is
+		fs::write(&filepath, [4, 5, 6]).unwrap();
+		assert_eq!(storage.load().unwrap(), vec![4, 5, 6]);
+
+		fs::remove_file(filepath).unwrap();
+	}
+
+	#[test]
+	fn test_encrypted_json_file_storage() {
+		let filepath = current_dir().unwrap().join("test_encrypted.json");
+		let content = Record { peer_address: "0x01".to_string(), score: 1000 };
+		let key = EncryptionKey::new([1; 32]);
+
+		let mut storage =
+			EncryptedJSONFileStorage::<Record>::new(filepath.clone(), Some(key.clone()));
+		storage.save(content.clone()).unwrap();
+		let data = fs::read(&filepath).unwrap();
+		assert!(data.starts_with(&ENCRYPTION_MAGIC));
+		assert!(!String::from_utf8_lossy(&data).contains("peer_address"));
+		assert_eq!(storage.load().unwrap(), content);
+
+		// Encrypted files require the right key
+		assert!(EncryptedJSONFileStorage::<Record>::new(filepath.clone(), None).load().is_err());
+		let wrong_key = Some(EncryptionKey::from_hex(&hex::encode([2; 32])).unwrap());
+		assert!(
+			EncryptedJSONFileStorage::<Record>::new(filepath.clone(), wrong_key).load().is_err()
+		);
+
+		// Plain files are still loaded, and encrypted on the next save
+		JSONFileStorage::<Record>::new(filepath.clone()).save(content.clone()).unwrap();
+		assert_eq!(storage.load().unwrap(), content);
+		storage.save(content.clone()).unwrap();
+		assert!(fs::read(&filepath).unwrap().starts_with(&ENCRYPTION_MAGIC));
+
+		// Derived keys depend on the context, not only on the secret
+		assert_ne!(EncryptionKey::derive(&[1; 32]).0, [1; 32]);
+		assert!(EncryptionKey::from_hex("0x01").is_err());
+
+		fs::remove_file(filepath).unwrap();
+	}
+
+	#[test]
+	fn test_proof_storage() {
+		let filepath = current_dir().unwrap().join("test_proof_artifact.json");
+		let mut storage = ProofStorage::new(filepath.clone());
+		let params_hash = [1; 32];
+		let artifact = ProofArtifact::new(&Circuit::Threshold, &[2], &[3], params_hash, [4; 32]);
+
+		storage.save(artifact.clone()).unwrap();
+		assert_eq!(
+			storage.load_checked(&Circuit::Threshold, params_hash, Some([4; 32])).unwrap(),
+			artifact
+		);
+
+		// Proofs of other inputs are refused
+		assert!(storage.load_checked(&Circuit::EigenTrust, params_hash, None).is_err());
+		assert!(storage.load_checked(&Circuit::Threshold, [0; 32], None).is_err());
+		assert!(storage.load_checked(&Circuit::Threshold, params_hash, Some([0; 32])).is_err());
+
+		fs::remove_file(filepath).unwrap();
+	}
+
+	#[test]
+	fn test_replay_records_from_trace() {
+		let address_set = vec![H160::from([1; 20]), H160::from([2; 20])];
+		let initial = BigRational::from_integer(1000.into());
+		let iterations = vec![
+			vec![initial.clone(), initial],
+			vec![BigRational::new(1500.into(), 1.into()), BigRational::new(1001.into(), 2.into())],
+		];
+		let trace = ScoreTrace::new(address_set, iterations, true);
+
+		let records = ReplayRecord::from_trace(&trace);
+
+		assert_eq!(records.len(), 4);
+		assert_eq!(records[3].iteration(), "1");
+		assert_eq!(
+			records[3].peer_address(),
+			"0x0202020202020202020202020202020202020202"
+		);
+		assert_eq!(records[3].numerator(), "1001");
+		assert_eq!(records[3].denominator(), "2");
+		assert_eq!(records[3].score(), "500");
+		assert_eq!(records[0].residual(), "0.000000");
+		assert_eq!(records[3].residual(), "500.000000");
+		assert_eq!(
+			to_decimal(&BigRational::new(1.into(), 3.into()), 4),
+			"0.3333"
+		);
+	}
+
+	#[test]
+	fn test_normalize_scores() {
+		let record = |address: &str, numerator: &str, denominator: &str| {
+			ScoreRecord::new(
+				address.to_string(),
+				"0x00".to_string(),
+				numerator.to_string(),
+				denominator.to_string(),
+				"0".to_string(),
+			)
+		};
+		let records = vec![
+			record("0x01", "1000", "2"),
+			record("0x02", "1500", "1"),
+			record("0x03", "0", "1"),
+		];
+
+		let shares = normalize_scores(&records, parse_normalization("share").unwrap()).unwrap();
+		assert_eq!(shares[0].peer_address, "0x01");
+		assert_eq!(
+			shares.iter().map(|share| share.value).collect::<Vec<_>>(),
+			vec![0.25, 0.75, 0.]
+		);
+
+		let percentiles =
+			normalize_scores(&records, parse_normalization("percentile").unwrap()).unwrap();
+		assert_eq!(
+			percentiles.iter().map(|percentile| percentile.value).collect::<Vec<_>>(),
+			vec![50., 100., 0.]
+		);
+
+		assert!(parse_normalization("linear").is_err());
+		assert!(normalize_scores(&[record("0x01", "1", "x")], ScoreNormalization::Raw).is_err());
+	}
+
 	#[test]
 	fn test_json_file_storage() {
 		// Create the JSON file
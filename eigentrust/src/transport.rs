@@ -0,0 +1,139 @@
+//! # Transport Module.
+//!
+//! This module provides the JSON-RPC transports of the client. Besides HTTP, the client
+//! can talk to a local node over IPC, which is considerably faster when indexing the
+//! attestation history, or through any pre-built JSON-RPC client.
+
+use crate::error::EigenError;
+use async_trait::async_trait;
+use ethers::providers::{Http, Ipc, JsonRpcClient, ProviderError};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{fmt::Debug, str::FromStr, sync::Arc};
+
+/// Prefix of the IPC endpoints given as URLs.
+pub const IPC_PREFIX: &str = "ipc://";
+
+/// Object-safe JSON-RPC client, used to plug custom transports.
+#[async_trait]
+pub trait DynJsonRpcClient: Debug + Send + Sync {
+	/// Sends a request with already serialized parameters.
+	async fn request_value(&self, method: &str, params: Value) -> Result<Value, ProviderError>;
+}
+
+#[async_trait]
+impl<C> DynJsonRpcClient for C
+where
+	C: JsonRpcClient + 'static,
+	C::Error: Into<ProviderError>,
+{
+	async fn request_value(&self, method: &str, params: Value) -> Result<Value, ProviderError> {
+		self.request(method, params).await.map_err(Into::into)
+	}
+}
+
+/// Client transport.
+#[derive(Clone, Debug)]
+pub enum ClientTransport {
+	/// HTTP transport.
+	Http(Http),
+	/// IPC transport, to a local node.
+	Ipc(Ipc),
+	/// Custom pre-built transport.
+	Custom(Arc<dyn DynJsonRpcClient>),
+	/// No node connection, failing every request. Used to sign on air-gapped machines.
+	Offline,
+}
+
+impl ClientTransport {
+	/// Connects to the given endpoint. HTTP(S) URLs use the HTTP transport, while
+	/// `ipc://` URLs and `.ipc` file paths use the IPC transport.
+	pub async fn connect(endpoint: &str) -> Result<Self, EigenError> {
+		match Self::ipc_path(endpoint) {
+			Some(path) => {
+				let ipc = Ipc::connect(path)
+					.await
+					.map_err(|e| EigenError::ConnectionError(e.to_string()))?;
+				Ok(Self::Ipc(ipc))
+			},
+			None => Self::http(endpoint),
+		}
+	}
+
+	/// Creates an HTTP transport for the given URL.
+	pub fn http(url: &str) -> Result<Self, EigenError> {
+		let http = Http::from_str(url).map_err(|e| EigenError::ParsingError(e.to_string()))?;
+		Ok(Self::Http(http))
+	}
+
+	/// Wraps a pre-built JSON-RPC client.
+	pub fn custom<C: DynJsonRpcClient + 'static>(client: C) -> Self {
+		Self::Custom(Arc::new(client))
+	}
+
+	/// Returns the IPC path of the endpoint, if it is an IPC endpoint.
+	pub fn ipc_path(endpoint: &str) -> Option<&str> {
+		endpoint
+			.strip_prefix(IPC_PREFIX)
+			.or_else(|| endpoint.ends_with(".ipc").then_some(endpoint))
+	}
+}
+
+#[async_trait]
+impl JsonRpcClient for ClientTransport {
+	type Error = ProviderError;
+
+	async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+	where
+		T: Debug + Serialize + Send + Sync,
+		R: DeserializeOwned + Send,
+	{
+		match self {
+			Self::Http(http) => http.request(method, params).await.map_err(Into::into),
+			Self::Ipc(ipc) => ipc.request(method, params).await.map_err(Into::into),
+			Self::Custom(client) => {
+				let params = serde_json::to_value(params)?;
+				let response = client.request_value(method, params).await?;
+				Ok(serde_json::from_value(response)?)
+			},
+			Self::Offline => Err(ProviderError::CustomError(format!(
+				"No node connection for {}, the client is offline",
+				method
+			))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::transport::*;
+	use ethers::providers::{MockProvider, Provider};
+	use ethers::{providers::Middleware, types::U64};
+
+	#[test]
+	fn test_ipc_path() {
+		assert_eq!(
+			ClientTransport::ipc_path("ipc:///tmp/geth.ipc"),
+			Some("/tmp/geth.ipc")
+		);
+		assert_eq!(
+			ClientTransport::ipc_path("/tmp/geth.ipc"),
+			Some("/tmp/geth.ipc")
+		);
+		assert_eq!(ClientTransport::ipc_path("http://localhost:8545"), None);
+	}
+
+	#[tokio::test]
+	async fn test_custom_transport() {
+		let mock = MockProvider::new();
+		mock.push(U64::from(42)).unwrap();
+
+		let provider = Provider::new(ClientTransport::custom(mock));
+		let block_number = provider.get_block_number().await.unwrap();
+
+		assert_eq!(block_number, U64::from(42));
+
+		let offline = Provider::new(ClientTransport::Offline);
+		assert!(offline.get_block_number().await.is_err());
+	}
+}
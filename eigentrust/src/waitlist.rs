@@ -0,0 +1,197 @@
+//! # Waitlist Module.
+//!
+//! This module handles the participants beyond the capacity of the EigenTrust circuit.
+//! Participants are ranked by an admission policy and the ones that don't fit in the set
+//! are kept in a waitlist, so the admitted set never depends on the order in which the
+//! participants happen to be collected.
+
+use crate::error::EigenError;
+use eigentrust_zk::circuits::NUM_NEIGHBOURS;
+use ethers::types::Address;
+use std::{
+	cmp::Reverse,
+	collections::{BTreeMap, BTreeSet},
+	str::FromStr,
+};
+
+/// Policy ranking the participants competing for the set's free slots.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AdmissionPolicy {
+	/// Admits the participants in order of first appearance in the attestations.
+	#[default]
+	Fifo,
+	/// Admits first the participants attested by the highest weighted attester.
+	AttesterScore,
+	/// Admits first the participants with the highest weight, their stake.
+	Stake,
+}
+
+impl FromStr for AdmissionPolicy {
+	type Err = EigenError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"fifo" => Ok(Self::Fifo),
+			"attester-score" => Ok(Self::AttesterScore),
+			"stake" => Ok(Self::Stake),
+			_ => Err(EigenError::ParsingError(format!(
+				"Invalid admission policy: {}",
+				s
+			))),
+		}
+	}
+}
+
+/// Result of the admission of the participants.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Admission {
+	/// Admitted participants, sorted by address.
+	pub admitted: BTreeSet<Address>,
+	/// Waitlisted participants, in admission order.
+	pub waitlisted: Vec<Address>,
+}
+
+impl Admission {
+	/// Returns whether the given participant was admitted.
+	pub fn is_admitted(&self, participant: &Address) -> bool {
+		self.admitted.contains(participant)
+	}
+
+	/// Returns the waitlist position of the given participant, starting from 0.
+	pub fn position(&self, participant: &Address) -> Option<usize> {
+		self.waitlisted.iter().position(|waitlisted| waitlisted == participant)
+	}
+}
+
+/// Waitlist admitting participants up to the set capacity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Waitlist {
+	capacity: usize,
+	policy: AdmissionPolicy,
+	members: BTreeSet<Address>,
+	weights: BTreeMap<Address, u128>,
+}
+
+impl Waitlist {
+	/// Creates a waitlist for the circuit capacity with the given policy.
+	pub fn new(policy: AdmissionPolicy) -> Self {
+		Self::with_capacity(NUM_NEIGHBOURS, policy)
+	}
+
+	/// Creates a waitlist for the given capacity with the given policy.
+	pub fn with_capacity(capacity: usize, policy: AdmissionPolicy) -> Self {
+		Self { capacity, policy, members: BTreeSet::new(), weights: BTreeMap::new() }
+	}
+
+	/// Sets the current members, admitted before any newcomer.
+	pub fn with_members(mut self, members: impl IntoIterator<Item = Address>) -> Self {
+		self.members = members.into_iter().collect();
+		self
+	}
+
+	/// Sets the participants' weights: the attesters' scores with the `AttesterScore`
+	/// policy, or the participants' stakes with the `Stake` policy.
+	/// Participants without a weight have a zero weight.
+	pub fn with_weights(mut self, weights: impl IntoIterator<Item = (Address, u128)>) -> Self {
+		self.weights = weights.into_iter().collect();
+		self
+	}
+
+	/// Returns the capacity.
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+
+	/// Returns the admission policy.
+	pub fn policy(&self) -> AdmissionPolicy {
+		self.policy
+	}
+
+	/// Admits the participants of the given (attester, about) pairs, in attestation order.
+	/// Current members come first, then newcomers ranked by the policy. Ties are broken by
+	/// first appearance, then by address.
+	pub fn admit(&self, attestations: &[(Address, Address)]) -> Admission {
+		// First appearance of every participant
+		let mut first_seen: BTreeMap<Address, usize> = BTreeMap::new();
+		let mut sponsor_weight: BTreeMap<Address, u128> = BTreeMap::new();
+		for (attester, about) in attestations {
+			for participant in [attester, about] {
+				let next = first_seen.len();
+				first_seen.entry(*participant).or_insert(next);
+			}
+
+			let weight = self.weight(attester);
+			let sponsor = sponsor_weight.entry(*about).or_default();
+			*sponsor = (*sponsor).max(weight);
+		}
+
+		let mut ranked: Vec<Address> = first_seen.keys().cloned().collect();
+		ranked.sort_by_key(|participant| {
+			let priority = match self.policy {
+				AdmissionPolicy::Fifo => 0,
+				AdmissionPolicy::AttesterScore => {
+					sponsor_weight.get(participant).copied().unwrap_or_default()
+				},
+				AdmissionPolicy::Stake => self.weight(participant),
+			};
+
+			(
+				!self.members.contains(participant),
+				Reverse(priority),
+				first_seen[participant],
+				*participant,
+			)
+		});
+
+		let waitlisted = ranked.split_off(self.capacity.min(ranked.len()));
+
+		Admission { admitted: ranked.into_iter().collect(), waitlisted }
+	}
+
+	/// Returns the weight of the given participant.
+	fn weight(&self, participant: &Address) -> u128 {
+		self.weights.get(participant).copied().unwrap_or_default()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::waitlist::*;
+
+	#[test]
+	fn test_waitlist_admission() {
+		let (a, b, c, d, e) = (
+			Address::from([1; 20]),
+			Address::from([2; 20]),
+			Address::from([3; 20]),
+			Address::from([4; 20]),
+			Address::from([5; 20]),
+		);
+		let attestations = vec![(c, a), (a, b), (d, e), (b, d)];
+
+		// FIFO admits by first appearance
+		let admission = Waitlist::with_capacity(3, AdmissionPolicy::Fifo).admit(&attestations);
+		assert_eq!(admission.admitted, BTreeSet::from([c, a, b]));
+		assert_eq!(admission.waitlisted, vec![d, e]);
+		assert_eq!(admission.position(&e), Some(1));
+
+		// Current members are never evicted
+		let admission = Waitlist::with_capacity(3, AdmissionPolicy::Fifo)
+			.with_members([e])
+			.admit(&attestations);
+		assert_eq!(admission.admitted, BTreeSet::from([e, c, a]));
+
+		// Participants attested by heavier attesters come first
+		let admission = Waitlist::with_capacity(2, AdmissionPolicy::AttesterScore)
+			.with_weights([(d, 10), (b, 5)])
+			.admit(&attestations);
+		assert_eq!(admission.admitted, BTreeSet::from([e, d]));
+		assert_eq!(admission.waitlisted, vec![c, a, b]);
+
+		// Stakes rank the participants themselves
+		let admission = Waitlist::with_capacity(2, AdmissionPolicy::Stake)
+			.with_weights([(b, 1), (e, 2)])
+			.admit(&attestations);
+		assert_eq!(admission.admitted, BTreeSet::from([e, b]));
+	}
+}